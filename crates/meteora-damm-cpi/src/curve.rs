@@ -0,0 +1,280 @@
+// ============================================================================
+// Curve Math
+// ============================================================================
+//
+// Integer swap math for the non-constant-product curves. Every function
+// rounds in favor of the pool, so a quote never promises more than the
+// on-chain invariant allows. Intermediate values are `BigUint` because the
+// stableswap invariant cubes D and the weighted invariant raises reserves to
+// their weights.
+
+use num_bigint::BigUint;
+use num_traits::{CheckedSub, ToPrimitive, Zero};
+
+/// Number of tokens in a pool
+const N_COINS: u64 = 2;
+
+/// Newton iterations before giving up on convergence
+const MAX_ITERATIONS: usize = 256;
+
+/// Largest reduced weight accepted by the weighted invariant. Weights are
+/// reduced by their gcd first, so any split expressed in percent fits.
+pub const MAX_REDUCED_WEIGHT: u64 = 100;
+
+/// StableSwap invariant D for two normalized balances:
+/// `A·n^n·(x + y) + D = A·D·n^n + D^(n+1) / (n^n·x·y)`, solved by Newton iteration
+pub fn compute_d(amp: u64, x: u128, y: u128) -> Option<u128> {
+    if amp == 0 {
+        return None;
+    }
+    let sum = BigUint::from(x) + BigUint::from(y);
+    if sum.is_zero() {
+        return Some(0);
+    }
+    if x == 0 || y == 0 {
+        return None;
+    }
+
+    let n = BigUint::from(N_COINS);
+    let ann = BigUint::from(amp) * &n;
+    let x = BigUint::from(x);
+    let y = BigUint::from(y);
+    let one = BigUint::from(1u8);
+
+    let mut d = sum.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let d_p = &d * &d / (&x * &n) * &d / (&y * &n);
+        let d_prev = d.clone();
+        let numerator = (&ann * &sum + &d_p * &n) * &d;
+        let denominator = (&ann - &one) * &d + (&n + &one) * &d_p;
+        d = numerator / denominator;
+        if abs_diff(&d, &d_prev) <= one {
+            return d.to_u128();
+        }
+    }
+    None
+}
+
+/// Balance of the other token that keeps invariant `d` when one side holds `x`
+pub fn compute_y(amp: u64, x: u128, d: u128) -> Option<u128> {
+    if amp == 0 || x == 0 {
+        return None;
+    }
+
+    let n = BigUint::from(N_COINS);
+    let ann = BigUint::from(amp) * &n;
+    let x = BigUint::from(x);
+    let d = BigUint::from(d);
+    let one = BigUint::from(1u8);
+
+    // y² + (b - D)·y = c, with c = D^(n+1) / (n^n·x·Ann) and b = x + D/Ann
+    let c = &d * &d / (&x * &n) * &d / (&ann * &n);
+    let b = &x + &d / &ann;
+
+    let mut y = d.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y.clone();
+        let denominator = (&y * 2u8 + &b).checked_sub(&d)?;
+        if denominator.is_zero() {
+            return None;
+        }
+        y = (&y * &y + &c) / denominator;
+        if abs_diff(&y, &y_prev) <= one {
+            return y.to_u128();
+        }
+    }
+    None
+}
+
+/// StableSwap output for `amount_in` (after fees). Balances are scaled by
+/// their token multipliers so both sides share one precision; the result is
+/// scaled back and rounded down.
+pub fn stable_swap_output(
+    amp: u64,
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    multiplier_in: u64,
+    multiplier_out: u64,
+) -> Option<u64> {
+    if multiplier_in == 0 || multiplier_out == 0 {
+        return None;
+    }
+
+    let x = reserve_in as u128 * multiplier_in as u128;
+    let y = reserve_out as u128 * multiplier_out as u128;
+    let dx = amount_in as u128 * multiplier_in as u128;
+
+    let d = compute_d(amp, x, y)?;
+    let new_y = compute_y(amp, x.checked_add(dx)?, d)?;
+
+    // One unit is kept back to absorb the rounding of the Newton solution
+    let dy = y.checked_sub(new_y)?.saturating_sub(1);
+    let amount_out = (dy / multiplier_out as u128) as u64;
+
+    Some(amount_out.min(reserve_out.saturating_sub(1)))
+}
+
+/// Weighted-pool output for `amount_in` (after fees): the largest amount out
+/// that keeps `x^w_in · y^w_out` from decreasing.
+pub fn weighted_swap_output(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    weight_in: u64,
+    weight_out: u64,
+) -> Option<u64> {
+    let (weight_in, weight_out) = reduce_weights(weight_in, weight_out)?;
+    if reserve_in == 0 || reserve_out == 0 {
+        return None;
+    }
+
+    let invariant = weighted_invariant(reserve_in, reserve_out, weight_in, weight_out);
+    let new_reserve_in = reserve_in.checked_add(amount_in)?;
+    let in_term = BigUint::from(new_reserve_in).pow(weight_in);
+
+    // Binary search the smallest remaining balance that still satisfies the invariant
+    let mut low = 1u64;
+    let mut high = reserve_out;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if &in_term * BigUint::from(mid).pow(weight_out) >= invariant {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    Some(reserve_out - low)
+}
+
+/// Weighted invariant `x^w_x · y^w_y` for reduced weights
+pub fn weighted_invariant(x: u64, y: u64, weight_x: u32, weight_y: u32) -> BigUint {
+    BigUint::from(x).pow(weight_x) * BigUint::from(y).pow(weight_y)
+}
+
+/// Divide both weights by their gcd, rejecting zero or oversized weights
+pub fn reduce_weights(weight_a: u64, weight_b: u64) -> Option<(u32, u32)> {
+    if weight_a == 0 || weight_b == 0 {
+        return None;
+    }
+    let divisor = gcd(weight_a, weight_b);
+    let (a, b) = (weight_a / divisor, weight_b / divisor);
+    if a > MAX_REDUCED_WEIGHT || b > MAX_REDUCED_WEIGHT {
+        return None;
+    }
+    Some((a as u32, b as u32))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn abs_diff(a: &BigUint, b: &BigUint) -> BigUint {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_compute_d_balanced_pool() {
+        // A balanced pool's invariant is the sum of its balances
+        assert_eq!(compute_d(100, 1_000_000, 1_000_000), Some(2_000_000));
+        assert_eq!(compute_d(100, 0, 0), Some(0));
+        assert_eq!(compute_d(0, 1_000, 1_000), None);
+    }
+
+    #[test]
+    fn test_stable_swap_near_parity() {
+        // Deep balanced pool with high amplification trades close to 1:1
+        let out = stable_swap_output(100, 1_000_000, 1_000_000_000_000, 1_000_000_000_000, 1, 1).unwrap();
+        assert!(out > 999_000 && out < 1_000_000, "out = {}", out);
+
+        // A 1% trade moves a constant-product pool of the same depth far more
+        let big_trade = 10_000_000_000u64;
+        let stable_out = stable_swap_output(100, big_trade, 1_000_000_000_000, 1_000_000_000_000, 1, 1).unwrap();
+        let cp_out = big_trade as u128 * 1_000_000_000_000 / (1_000_000_000_000 + big_trade as u128);
+        assert!(stable_out as u128 > cp_out);
+    }
+
+    #[test]
+    fn test_stable_swap_multipliers() {
+        // 6-decimal token against 9-decimal token at parity
+        let out = stable_swap_output(100, 1_000_000, 1_000_000_000_000, 1_000_000_000_000_000, 1_000, 1).unwrap();
+        assert!(out > 999_000_000 && out < 1_000_000_000, "out = {}", out);
+    }
+
+    #[test]
+    fn test_weighted_even_weights_match_constant_product() {
+        let out = weighted_swap_output(1_000, 1_000_000, 2_000_000, 50, 50).unwrap();
+        // 2_000_000 - ceil(2e12 / 1_001_000)
+        assert_eq!(out, 1_998);
+    }
+
+    #[test]
+    fn test_weighted_heavier_input_pays_more() {
+        // 80/20 pool: the input side carries more weight, so each unit buys more
+        let even = weighted_swap_output(10_000, 1_000_000, 1_000_000, 50, 50).unwrap();
+        let heavy = weighted_swap_output(10_000, 1_000_000, 1_000_000, 80, 20).unwrap();
+        assert!(heavy > even);
+    }
+
+    #[test]
+    fn test_reduce_weights() {
+        assert_eq!(reduce_weights(80, 20), Some((4, 1)));
+        assert_eq!(reduce_weights(50, 50), Some((1, 1)));
+        assert_eq!(reduce_weights(0, 50), None);
+        assert_eq!(reduce_weights(101, 1), None);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_stable_output_preserves_invariant(
+            amp in 1u64..5_000,
+            reserve_in in 1_000u64..1_000_000_000_000_000,
+            reserve_out in 1_000u64..1_000_000_000_000_000,
+            amount_in in 1u64..1_000_000_000_000,
+        ) {
+            if let Some(out) = stable_swap_output(amp, amount_in, reserve_in, reserve_out, 1, 1) {
+                prop_assert!(out < reserve_out);
+
+                let d_before = compute_d(amp, reserve_in as u128, reserve_out as u128).unwrap();
+                let d_after = compute_d(amp, (reserve_in + amount_in) as u128, (reserve_out - out) as u128).unwrap();
+                prop_assert!(d_after >= d_before, "D decreased: {} -> {}", d_before, d_after);
+            }
+        }
+
+        #[test]
+        fn prop_weighted_output_preserves_invariant(
+            weight_in in 1u64..10,
+            weight_out in 1u64..10,
+            reserve_in in 1u64..1_000_000_000_000,
+            reserve_out in 1u64..1_000_000_000_000,
+            amount_in in 0u64..1_000_000_000_000,
+        ) {
+            let out = weighted_swap_output(amount_in, reserve_in, reserve_out, weight_in, weight_out).unwrap();
+            prop_assert!(out < reserve_out);
+
+            let (w_in, w_out) = reduce_weights(weight_in, weight_out).unwrap();
+            let before = weighted_invariant(reserve_in, reserve_out, w_in, w_out);
+            let after = weighted_invariant(reserve_in + amount_in, reserve_out - out, w_in, w_out);
+            prop_assert!(after >= before);
+
+            // Taking one more unit would break the invariant
+            if out + 1 < reserve_out {
+                let greedy = weighted_invariant(reserve_in + amount_in, reserve_out - out - 1, w_in, w_out);
+                prop_assert!(greedy < before);
+            }
+        }
+    }
+}
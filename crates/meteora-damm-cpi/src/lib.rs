@@ -3,6 +3,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
+pub mod curve;
+
 // Program ID for Meteora DAMM
 declare_id!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo"); // Placeholder - replace with actual program ID
 
@@ -29,6 +31,7 @@ pub struct Pool {
     pub token_b_amount: u64,
     pub lp_supply: u64,
     pub bump: u8,
+    pub curve_params: CurveParams,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
@@ -38,6 +41,32 @@ pub enum CurveType {
     Weighted = 2,
 }
 
+impl CurveType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::ConstantProduct),
+            1 => Some(Self::Stable),
+            2 => Some(Self::Weighted),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters of the stable and weighted curves (unused by constant product)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct CurveParams {
+    /// Stable: amplification coefficient
+    pub amp: u64,
+    /// Stable: scales token A balances to the common precision
+    pub token_a_multiplier: u64,
+    /// Stable: scales token B balances to the common precision
+    pub token_b_multiplier: u64,
+    /// Weighted: relative weight of token A
+    pub token_a_weight: u64,
+    /// Weighted: relative weight of token B
+    pub token_b_weight: u64,
+}
+
 // ============================================================================
 // Instruction Contexts (Generated from IDL)
 // ============================================================================
@@ -103,8 +132,8 @@ pub struct RemoveLiquidity<'info> {
 // ============================================================================
 
 impl Pool {
-    /// Calculate expected output amount for a swap using constant product formula (x * y = k)
-    /// This is a simplified calculation - actual implementation depends on curve_type
+    /// Calculate expected output amount for a swap on the pool's curve.
+    /// The trade fee is taken from the input before the curve is applied.
     pub fn calculate_swap_output(
         &self,
         amount_in: u64,
@@ -119,30 +148,43 @@ impl Pool {
         require!(reserve_in > 0 && reserve_out > 0, ErrorCode::InsufficientLiquidity);
         require!(amount_in > 0, ErrorCode::InvalidAmount);
 
-        match self.curve_type {
-            0 => self.calculate_constant_product_output(amount_in, reserve_in, reserve_out),
-            1 => self.calculate_stable_swap_output(amount_in, reserve_in, reserve_out),
-            _ => Err(ErrorCode::InvalidCurveType.into()),
+        let curve_type = CurveType::from_u8(self.curve_type).ok_or(ErrorCode::InvalidCurveType)?;
+        let amount_in_with_fee = self.amount_after_fee(amount_in)?;
+
+        match curve_type {
+            CurveType::ConstantProduct => {
+                self.calculate_constant_product_output(amount_in_with_fee, reserve_in, reserve_out)
+            }
+            CurveType::Stable => {
+                self.calculate_stable_swap_output(amount_in_with_fee, reserve_in, reserve_out, source_is_token_a)
+            }
+            CurveType::Weighted => {
+                self.calculate_weighted_output(amount_in_with_fee, reserve_in, reserve_out, source_is_token_a)
+            }
         }
     }
 
-    /// Constant product AMM calculation (Uniswap v2 style)
-    fn calculate_constant_product_output(
-        &self,
-        amount_in: u64,
-        reserve_in: u64,
-        reserve_out: u64,
-    ) -> Result<u64> {
-        // Apply fee: amount_in_with_fee = amount_in * (1 - fee)
+    /// Input left after the trade fee: amount_in * (1 - fee)
+    fn amount_after_fee(&self, amount_in: u64) -> Result<u64> {
         let fee_amount = (amount_in as u128)
             .checked_mul(self.trade_fee_numerator as u128)
             .ok_or(ErrorCode::MathOverflow)?
             .checked_div(self.trade_fee_denominator as u128)
             .ok_or(ErrorCode::MathOverflow)?;
-        
+
         let amount_in_with_fee = amount_in.checked_sub(fee_amount as u64)
             .ok_or(ErrorCode::MathOverflow)?;
 
+        Ok(amount_in_with_fee)
+    }
+
+    /// Constant product AMM calculation (Uniswap v2 style)
+    fn calculate_constant_product_output(
+        &self,
+        amount_in_with_fee: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Result<u64> {
         // Calculate output: amount_out = (amount_in_with_fee * reserve_out) / (reserve_in + amount_in_with_fee)
         let numerator = (amount_in_with_fee as u128)
             .checked_mul(reserve_out as u128)
@@ -159,38 +201,64 @@ impl Pool {
         Ok(amount_out as u64)
     }
 
-    /// Stable swap calculation (StableSwap invariant)
-    /// Simplified - production version would use full StableSwap curve
+    /// Stable swap calculation (StableSwap invariant with amplification),
+    /// with balances scaled by the token multipliers
     fn calculate_stable_swap_output(
         &self,
-        amount_in: u64,
+        amount_in_with_fee: u64,
         reserve_in: u64,
         reserve_out: u64,
+        source_is_token_a: bool,
     ) -> Result<u64> {
-        // For stable swaps, use a simplified approximation
-        // Real implementation would use the StableSwap invariant formula
-        
-        let fee_amount = (amount_in as u128)
-            .checked_mul(self.trade_fee_numerator as u128)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(self.trade_fee_denominator as u128)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        let amount_in_with_fee = amount_in.checked_sub(fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let params = &self.curve_params;
+        let (multiplier_in, multiplier_out) = if source_is_token_a {
+            (params.token_a_multiplier, params.token_b_multiplier)
+        } else {
+            (params.token_b_multiplier, params.token_a_multiplier)
+        };
 
-        // Simplified stable calculation (approximately 1:1 for small amounts)
-        let slippage_factor = (amount_in_with_fee as u128)
-            .checked_mul(reserve_in as u128)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div((reserve_in as u128).checked_add(reserve_out as u128).ok_or(ErrorCode::MathOverflow)?)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        let amount_out = (amount_in_with_fee as u128)
-            .checked_sub(slippage_factor)
+        require!(
+            params.amp > 0 && multiplier_in > 0 && multiplier_out > 0,
+            ErrorCode::InvalidCurveParameters
+        );
+
+        let amount_out = curve::stable_swap_output(
+            params.amp,
+            amount_in_with_fee,
+            reserve_in,
+            reserve_out,
+            multiplier_in,
+            multiplier_out,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(amount_out)
+    }
+
+    /// Weighted pool calculation (x^w_x * y^w_y = k)
+    fn calculate_weighted_output(
+        &self,
+        amount_in_with_fee: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        source_is_token_a: bool,
+    ) -> Result<u64> {
+        let params = &self.curve_params;
+        let (weight_in, weight_out) = if source_is_token_a {
+            (params.token_a_weight, params.token_b_weight)
+        } else {
+            (params.token_b_weight, params.token_a_weight)
+        };
+
+        require!(
+            curve::reduce_weights(weight_in, weight_out).is_some(),
+            ErrorCode::InvalidCurveParameters
+        );
+
+        let amount_out = curve::weighted_swap_output(amount_in_with_fee, reserve_in, reserve_out, weight_in, weight_out)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        Ok(amount_out as u64)
+        Ok(amount_out)
     }
 
     /// Calculate the price of token A in terms of token B
@@ -296,6 +364,8 @@ pub enum ErrorCode {
     InvalidShareAmount,
     #[msg("Math overflow")]
     MathOverflow,
+    #[msg("Invalid curve parameters")]
+    InvalidCurveParameters,
 }
 
 // ============================================================================
@@ -358,6 +428,7 @@ mod tests {
             token_b_amount: 1_000_000,
             lp_supply: 1_000_000,
             bump: 255,
+            curve_params: CurveParams::default(),
         };
 
         let amount_in = 1000;
@@ -383,6 +454,7 @@ mod tests {
             token_b_amount: 1_000_000,
             lp_supply: 1_000_000,
             bump: 255,
+            curve_params: CurveParams::default(),
         };
 
         let price_a_to_b = pool.get_price_a_to_b().unwrap();
@@ -407,9 +479,74 @@ mod tests {
             token_b_amount: 1_000_000,
             lp_supply: 1_000_000,
             bump: 255,
+            curve_params: CurveParams::default(),
         };
 
         let fee_bps = pool.get_fee_bps();
         assert_eq!(fee_bps, 25); // 0.25%
     }
+
+    #[test]
+    fn test_stable_swap_calculation() {
+        let pool = Pool {
+            curve_type: CurveType::Stable as u8,
+            token_a_mint: Pubkey::default(),
+            token_b_mint: Pubkey::default(),
+            token_a_vault: Pubkey::default(),
+            token_b_vault: Pubkey::default(),
+            lp_mint: Pubkey::default(),
+            trade_fee_numerator: 1,
+            trade_fee_denominator: 10000,
+            token_a_amount: 1_000_000_000_000,
+            token_b_amount: 1_000_000_000_000,
+            lp_supply: 1_000_000,
+            bump: 255,
+            curve_params: CurveParams {
+                amp: 100,
+                token_a_multiplier: 1,
+                token_b_multiplier: 1,
+                ..Default::default()
+            },
+        };
+
+        // Near parity: only the 1 bps fee and a little curvature are lost
+        let result = pool.calculate_swap_output(1_000_000, true).unwrap();
+        assert!(result > 999_000 && result < 999_900, "result = {}", result);
+
+        let missing_amp = Pool { curve_params: CurveParams::default(), ..pool };
+        assert!(missing_amp.calculate_swap_output(1_000_000, true).is_err());
+    }
+
+    #[test]
+    fn test_weighted_calculation() {
+        let pool = Pool {
+            curve_type: CurveType::Weighted as u8,
+            token_a_mint: Pubkey::default(),
+            token_b_mint: Pubkey::default(),
+            token_a_vault: Pubkey::default(),
+            token_b_vault: Pubkey::default(),
+            lp_mint: Pubkey::default(),
+            trade_fee_numerator: 0,
+            trade_fee_denominator: 10000,
+            token_a_amount: 1_000_000,
+            token_b_amount: 2_000_000,
+            lp_supply: 1_000_000,
+            bump: 255,
+            curve_params: CurveParams {
+                token_a_weight: 50,
+                token_b_weight: 50,
+                ..Default::default()
+            },
+        };
+
+        // 50/50 weights reduce to constant product
+        let weighted = pool.calculate_swap_output(1_000, true).unwrap();
+        let constant_product = Pool { curve_type: CurveType::ConstantProduct as u8, ..pool.clone() }
+            .calculate_swap_output(1_000, true)
+            .unwrap();
+        assert!(constant_product - weighted <= 1);
+
+        let invalid = Pool { curve_type: 3, ..pool };
+        assert!(invalid.calculate_swap_output(1_000, true).is_err());
+    }
 }
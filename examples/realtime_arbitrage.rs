@@ -20,10 +20,8 @@ use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use tracing::{info, Level};
-use tracing_subscriber;
 
-use solana_mev_bot::chain::pool_monitor::{BatchPoolMonitor, MonitorConfig};
-use solana_mev_bot::dex::pool_fetcher::PoolDataFetcher;
+use solana_mev_bot::chain::pool_monitor::MonitorConfig;
 use solana_mev_bot::dex::triangular_arb::{create_shared_graph, BellmanFordDetector};
 
 #[tokio::main]
@@ -44,12 +42,12 @@ async fn main() -> Result<()> {
     info!("");
 
     // Configuration
-    let ws_urls = vec![
+    let _ws_urls = [
         "wss://api.mainnet-beta.solana.com".to_string(),
     ];
     
     // Base currency for arbitrage (USDC)
-    let base_currency = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")?;
+    let _base_currency = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")?;
 
     info!("Step 1: Initialize components");
     info!("  - RPC clients for data fetching");
@@ -81,10 +79,12 @@ async fn main() -> Result<()> {
 
     info!("Step 4: Configure WebSocket monitoring");
     let _monitor_config = MonitorConfig {
-        detection_rate_limit_ms: 1000,  // Detect at most once per second
+        detection_rate_limit_ms: 1000,  // Rediscover at most once per second
+        rediscovery_interval_ms: 30_000, // Full rediscovery every 30 seconds
         reconnect_delay_ms: 2000,
         max_reconnect_attempts: 10,
         subscription_batch_size: 50,
+        ..MonitorConfig::default()
     };
     info!("  - Rate limit: {}ms between detections", _monitor_config.detection_rate_limit_ms);
     info!("  - Batch size: {} pools per subscription", _monitor_config.subscription_batch_size);
@@ -96,7 +96,7 @@ async fn main() -> Result<()> {
     info!("    1. Receive accountSubscribe notification");
     info!("    2. Decode and parse pool account data");
     info!("    3. Update graph edges (A→B and B→A)");
-    info!("    4. Trigger arbitrage detection (rate-limited)");
+    info!("    4. Re-score the cycles through that pool (full rediscovery periodically)");
     info!("    5. Execute profitable opportunities");
     info!("");
     
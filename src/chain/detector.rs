@@ -2,25 +2,40 @@
 //
 // This module coordinates the entire detection pipeline:
 // 1. Runs Bellman-Ford detection on graph updates
-// 2. Focuses on high-liquidity base tokens (SOL, USDC, USDT)
+// 2. Focuses on the configured base tokens (SOL, USDC, USDT by default)
 // 3. Calculates profitability with slippage for realistic position sizes
 // 4. Filters opportunities by minimum profit threshold
 // 5. Sends profitable opportunities to execution engine
 // 6. Tracks detection metrics (latency, opportunities found, etc.)
 
 use tokio::sync::{mpsc, RwLock};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use solana_sdk::pubkey::Pubkey;
-use std::str::FromStr;
 use anyhow::Result;
 use tracing::{info, debug, warn, error};
 use chrono::Utc;
 
-use crate::dex::triangular_arb::{SharedArbitrageGraph, BellmanFordDetector, ArbitrageCycle};
+use crate::config::{Config, RiskConfig};
+use crate::dex::route_cache::SharedRouteCache;
+use super::constants::{USDC_MINT, USDT_MINT, WSOL_MINT};
+use super::opportunity_guard::{OpportunityCooldown, OpportunityKey};
+use super::reliability::{ReliabilityTracker, SharedReliability};
+use super::sizing::{SizedTrade, SizingEngine};
+use crate::dex::triangular_arb::{
+    ArbitrageCycle, ArbitrageGraph, BellmanFordDetector, ExchangeEdge, SharedArbitrageGraph,
+};
+
+/// Smallest units of USDC and USDT per dollar
+const STABLE_UNITS_PER_USD: f64 = 1e6;
+
+/// How long an emitted route is held back before it may be emitted again
+const DEFAULT_OPPORTUNITY_COOLDOWN: Duration = Duration::from_secs(5);
 
 /// Main arbitrage detection orchestrator
 pub struct ArbitrageDetector {
+    #[allow(dead_code)]
     graph: SharedArbitrageGraph,
     bellman_ford: BellmanFordDetector,
     base_tokens: Vec<Pubkey>,
@@ -28,9 +43,22 @@ pub struct ArbitrageDetector {
     opportunity_tx: mpsc::UnboundedSender<ArbitrageOpportunity>,
     metrics: Arc<RwLock<DetectionMetrics>>,
     max_path_length: usize,
+    /// Pools worth less than this in USD are not traded through (0 = no floor)
+    min_pool_liquidity_usd: f64,
+    /// Smallest sized input per start token
+    min_trade_sizes: HashMap<Pubkey, u64>,
+    /// Routes emitted recently, held back until their cooldown ends
+    cooldown: std::sync::Mutex<OpportunityCooldown>,
+    sizing: SizingEngine,
+    risk: RiskConfig,
+    reliability: SharedReliability,
 }
 
 /// Arbitrage opportunity ready for execution
+///
+/// Shared by every detector: graph cycles and two-pool price gaps
+/// (`token_price::MarketDataFetcher`) are both expressed as a cycle that
+/// starts and ends in the token the profit is realized in.
 #[derive(Clone, Debug)]
 pub struct ArbitrageOpportunity {
     pub cycle: ArbitrageCycle,
@@ -40,15 +68,103 @@ pub struct ArbitrageOpportunity {
     pub expected_profit_bps: i64,
     pub detected_at: i64,
     pub priority_score: f64,
+    /// Components behind `priority_score`
+    pub score: ScoreBreakdown,
     pub risk_level: RiskLevel,
 }
 
-/// Risk assessment for opportunity
+impl ArbitrageOpportunity {
+    /// Token the cycle starts and ends in
+    pub fn profit_token(&self) -> Pubkey {
+        self.cycle.start_token
+    }
+
+    /// Profit at spot rates before fees
+    pub fn gross_profit_bps(&self) -> i64 {
+        self.cycle.gross_profit_bps
+    }
+
+    pub fn total_fee_bps(&self) -> u16 {
+        self.cycle.total_fee_bps
+    }
+
+    /// Profit lost to price impact at the sized input: spot profit after
+    /// fees minus the expected profit
+    pub fn estimated_slippage_bps(&self) -> u64 {
+        let spot_bps = (self.cycle.net_profit_after_fees * 10_000.0).round() as i64;
+        spot_bps.saturating_sub(self.expected_profit_bps).max(0) as u64
+    }
+
+    /// Hops of the cycle, e.g. `A -> B (Raydium) -> A (Orca)`
+    pub fn route(&self) -> String {
+        let mut route = self.cycle.start_token.to_string();
+        for step in &self.cycle.path {
+            route.push_str(&format!(" -> {} ({})", step.to_token, step.dex));
+        }
+        route
+    }
+
+    /// Identity of the route traded, for deduplication and conflict checks
+    pub fn key(&self) -> OpportunityKey {
+        OpportunityKey::of(&self.cycle)
+    }
+}
+
+/// Risk assessment for opportunity (thresholds from `RiskConfig`)
 #[derive(Clone, Debug, PartialEq)]
 pub enum RiskLevel {
-    Low,      // High liquidity, 2-3 hops, reliable DEXs
-    Medium,   // Moderate liquidity, 3-4 hops
-    High,     // Low liquidity, 4+ hops, or untested DEXs
+    Low,      // Profit and hop count within the low-risk thresholds
+    Medium,   // Within the medium-risk thresholds
+    High,     // Anything else; not executed
+}
+
+/// Components of an opportunity's priority score, each from 0.0 to 1.0,
+/// kept on the opportunity so execution decisions can be explained
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    /// Expected profit (1 SOL or more scores 1.0)
+    pub profit: f64,
+    /// Fewer hops score higher
+    pub hops: f64,
+    /// Worst hop's price impact at the sized input against `max_price_impact_bps`
+    pub liquidity: f64,
+    /// Oldest edge's age against the graph's `max_edge_age_ms` (1.0 when
+    /// edge age is not limited)
+    pub freshness: f64,
+    /// Venue reliability, averaged over the hops
+    pub venue: f64,
+    /// Worst hop's price impact; `None` if a hop could not be quoted
+    pub max_price_impact_bps: Option<u64>,
+    /// Age of the oldest edge; `None` if an edge has left the graph
+    pub oldest_edge_age_secs: Option<i64>,
+}
+
+impl ScoreBreakdown {
+    /// Weighted priority score (0.0 to 1.0)
+    pub fn total(&self) -> f64 {
+        let score = self.profit * 0.30
+            + self.hops * 0.20
+            + self.liquidity * 0.25
+            + self.freshness * 0.15
+            + self.venue * 0.10;
+        score.clamp(0.0, 1.0)
+    }
+}
+
+impl std::fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "profit {:.2}, hops {:.2}, liquidity {:.2}", self.profit, self.hops, self.liquidity)?;
+        match self.max_price_impact_bps {
+            Some(impact) => write!(f, " (impact {}bps)", impact)?,
+            None => write!(f, " (unquoted)")?,
+        }
+        write!(f, ", freshness {:.2}", self.freshness)?;
+        match self.oldest_edge_age_secs {
+            Some(age) => write!(f, " (oldest edge {}s)", age)?,
+            None => write!(f, " (missing edge)")?,
+        }
+        write!(f, ", venue {:.2} = {:.3}", self.venue, self.total())
+    }
 }
 
 /// Detection performance metrics
@@ -60,6 +176,10 @@ pub struct DetectionMetrics {
     pub avg_detection_latency_ms: f64,
     pub last_detection_time: Option<Instant>,
     pub profitable_by_token: std::collections::HashMap<String, u64>,
+    /// Edges left out of detection for being too old, across all passes
+    pub stale_edges_rejected: u64,
+    /// Cycles rejected because their edges were observed too many slots apart
+    pub wide_slot_span_rejections: u64,
 }
 
 impl ArbitrageDetector {
@@ -70,17 +190,7 @@ impl ArbitrageDetector {
         opportunity_tx: mpsc::UnboundedSender<ArbitrageOpportunity>,
     ) -> Self {
         // Initialize with high-liquidity base tokens
-        let base_tokens = vec![
-            // SOL (native token)
-            Pubkey::from_str("So11111111111111111111111111111111111111112")
-                .expect("Invalid SOL pubkey"),
-            // USDC (most liquid stablecoin)
-            Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
-                .expect("Invalid USDC pubkey"),
-            // USDT (second stablecoin)
-            Pubkey::from_str("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB")
-                .expect("Invalid USDT pubkey"),
-        ];
+        let base_tokens = vec![WSOL_MINT, USDC_MINT, USDT_MINT];
 
         let bellman_ford = BellmanFordDetector::new(graph.clone(), min_profit_bps);
 
@@ -98,9 +208,102 @@ impl ArbitrageDetector {
             opportunity_tx,
             metrics: Arc::new(RwLock::new(DetectionMetrics::default())),
             max_path_length: 4, // 2-4 hops for triangular arbitrage
+            min_pool_liquidity_usd: 0.0,
+            min_trade_sizes: HashMap::new(),
+            cooldown: std::sync::Mutex::new(OpportunityCooldown::new(DEFAULT_OPPORTUNITY_COOLDOWN)),
+            sizing: SizingEngine::default(),
+            risk: RiskConfig::default(),
+            reliability: Arc::new(std::sync::RwLock::new(ReliabilityTracker::default())),
+        }
+    }
+
+    /// Detector for the configured base tokens, hop limit, minimum profit,
+    /// pool liquidity floor, minimum trade sizes and risk thresholds
+    pub fn from_config(
+        graph: SharedArbitrageGraph,
+        config: &Config,
+        opportunity_tx: mpsc::UnboundedSender<ArbitrageOpportunity>,
+    ) -> Self {
+        let detector = Self::new(graph, config.bot.min_profit_bps as i64, opportunity_tx)
+            .with_base_tokens(config.tokens.base_tokens.clone())
+            .with_max_path_length(config.routing.max_hops as usize)
+            .with_min_pool_liquidity_usd(config.tokens.min_pool_liquidity_usd)
+            .with_min_trade_sizes(config.tokens.min_trade_sizes.clone())
+            .with_cooldown(Duration::from_millis(config.bot.opportunity_cooldown_ms))
+            .with_sizing(SizingEngine::from_config(config))
+            .with_risk_config(config.risk.clone());
+
+        info!(
+            "Configured ArbitrageDetector: {} base tokens, up to {} hops, pool floor ${:.0}, {} minimum trade sizes",
+            detector.base_tokens.len(),
+            detector.max_path_length,
+            detector.min_pool_liquidity_usd,
+            detector.min_trade_sizes.len()
+        );
+        detector
+    }
+
+    /// Start cycles at these tokens, in priority order
+    pub fn with_base_tokens(mut self, base_tokens: Vec<Pubkey>) -> Self {
+        self.base_tokens = base_tokens;
+        self
+    }
+
+    /// Only report cycles of at most `max_length` hops
+    pub fn with_max_path_length(self, max_length: usize) -> Self {
+        Self {
+            bellman_ford: self.bellman_ford.with_max_path_length(max_length),
+            max_path_length: max_length,
+            ..self
+        }
+    }
+
+    /// Skip cycles through pools holding less than `min_usd` of liquidity
+    pub fn with_min_pool_liquidity_usd(mut self, min_usd: f64) -> Self {
+        self.min_pool_liquidity_usd = min_usd;
+        self
+    }
+
+    /// Drop opportunities whose sized input is below the start token's minimum
+    pub fn with_min_trade_sizes(mut self, min_trade_sizes: HashMap<Pubkey, u64>) -> Self {
+        self.min_trade_sizes = min_trade_sizes;
+        self
+    }
+
+    /// Emit the same route at most once per `window`
+    pub fn with_cooldown(mut self, window: Duration) -> Self {
+        self.cooldown = std::sync::Mutex::new(OpportunityCooldown::new(window));
+        self
+    }
+
+    /// Price the cached routes of the base tokens instead of running a full
+    /// Bellman-Ford pass on every detection
+    pub fn with_route_cache(self, route_cache: SharedRouteCache) -> Self {
+        Self {
+            bellman_ford: self.bellman_ford.with_route_cache(route_cache),
+            ..self
         }
     }
 
+    /// Size opportunities with this engine (position limit, inventory and
+    /// execution costs)
+    pub fn with_sizing(mut self, sizing: SizingEngine) -> Self {
+        self.sizing = sizing;
+        self
+    }
+
+    /// Classify risk and score liquidity and freshness with these thresholds
+    pub fn with_risk_config(mut self, risk: RiskConfig) -> Self {
+        self.risk = risk;
+        self
+    }
+
+    /// Score venues and skip demoted pools using this trade history
+    pub fn with_reliability(mut self, reliability: SharedReliability) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
     /// Main detection loop - runs continuously
     pub async fn run_detection_loop(&self, mut update_signal: mpsc::UnboundedReceiver<()>) {
         info!("Starting arbitrage detection loop");
@@ -109,6 +312,9 @@ impl ArbitrageDetector {
             // Wait for signal that graph was updated
             match update_signal.recv().await {
                 Some(()) => {
+                    // One pass covers every update signalled so far
+                    while update_signal.try_recv().is_ok() {}
+
                     let start = Instant::now();
                     
                     // Run detection
@@ -143,32 +349,35 @@ impl ArbitrageDetector {
     async fn detect_all_opportunities(&self) -> Result<usize> {
         let mut total_opportunities = 0;
 
-        // For each base token, run detection
-        for base_token in &self.base_tokens {
-            let cycles = self.detect_from_token(*base_token).await?;
-            
-            debug!(
-                "Found {} cycles starting from token {}",
-                cycles.len(),
-                base_token
-            );
+        // One pass over the whole graph; each cycle starts at a base token
+        let cycles = self.detect_from_base_tokens().await?;
+        
+        debug!(
+            "Found {} cycles through {} base tokens",
+            cycles.len(),
+            self.base_tokens.len()
+        );
 
-            // Process each cycle
-            for cycle in cycles {
-                if let Some(opportunity) = self.process_cycle(cycle).await {
-                    // Send to execution engine
-                    if let Err(e) = self.opportunity_tx.send(opportunity.clone()) {
-                        error!("Failed to send opportunity: {}", e);
-                    } else {
-                        total_opportunities += 1;
-                        info!(
-                            "🎯 Opportunity: {:.2}% profit ({:.4} SOL), priority={:.2}, risk={:?}",
-                            opportunity.expected_profit_bps as f64 / 100.0,
-                            opportunity.expected_profit_sol,
-                            opportunity.priority_score,
-                            opportunity.risk_level
-                        );
-                    }
+        // Process each cycle
+        for cycle in cycles {
+            if let Some(opportunity) = self.process_cycle(cycle).await {
+                if !self.cooldown.lock().map_or(true, |mut cooldown| cooldown.allow(&opportunity.key())) {
+                    debug!("Route {} is cooling down, not re-emitted", opportunity.route());
+                    continue;
+                }
+
+                // Send to execution engine
+                if let Err(e) = self.opportunity_tx.send(opportunity.clone()) {
+                    error!("Failed to send opportunity: {}", e);
+                } else {
+                    total_opportunities += 1;
+                    info!(
+                        "🎯 Opportunity: {:.2}% profit ({:.4} SOL), priority={:.2}, risk={:?}",
+                        opportunity.expected_profit_bps as f64 / 100.0,
+                        opportunity.expected_profit_sol,
+                        opportunity.priority_score,
+                        opportunity.risk_level
+                    );
                 }
             }
         }
@@ -176,17 +385,31 @@ impl ArbitrageDetector {
         Ok(total_opportunities)
     }
 
-    /// Detect arbitrage opportunities starting from a specific token
-    async fn detect_from_token(&self, start_token: Pubkey) -> Result<Vec<ArbitrageCycle>> {
-        // Run Bellman-Ford detection (no max_path_length parameter needed)
+    /// Detect arbitrage cycles through any base token, rotated to start at
+    /// the first base token (in priority order) they pass through
+    async fn detect_from_base_tokens(&self) -> Result<Vec<ArbitrageCycle>> {
         let cycles = self.bellman_ford
-            .detect_arbitrage(start_token)
+            .detect_arbitrage_parallel(self.base_tokens.clone())
             .await?;
 
-        // Filter by profitability threshold
+        // Filter by profitability threshold and hop limit, leaving out thin
+        // pools and pools that keep failing
+        let reliability = self.reliability.read()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire reliability lock: {}", e))?;
+        let graph = self.graph.read()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire graph lock: {}", e))?;
         let profitable: Vec<_> = cycles
             .into_iter()
             .filter(|cycle| cycle.gross_profit_bps >= self.min_profit_bps)
+            .filter(|cycle| cycle.path.len() <= self.max_path_length)
+            .filter(|cycle| self.meets_liquidity_floor(&graph, cycle))
+            .filter(|cycle| {
+                let demoted = reliability.uses_demoted_pool(cycle);
+                if demoted {
+                    debug!("Skipping cycle from {} through a demoted pool", cycle.start_token);
+                }
+                !demoted
+            })
             .collect();
 
         Ok(profitable)
@@ -194,162 +417,211 @@ impl ArbitrageDetector {
 
     /// Process a detected cycle into an executable opportunity
     async fn process_cycle(&self, cycle: ArbitrageCycle) -> Option<ArbitrageOpportunity> {
-        // Calculate optimal input amount with slippage
-        let (optimal_input, expected_output, profit_sol) = 
-            self.calculate_optimal_input(&cycle).await?;
+        // Size the trade on the cycle's exact quotes
+        let trade = self.calculate_optimal_input(&cycle).await?;
+        if self.reliability.read().map_or(true, |reliability| reliability.uses_demoted_pool(&trade.cycle)) {
+            debug!("Sized route from {} splits into a demoted pool, skipping", cycle.start_token);
+            return None;
+        }
+
+        if let Some(&min_size) = self.min_trade_sizes.get(&trade.cycle.start_token) {
+            if trade.input < min_size {
+                debug!(
+                    "Cycle from {} filtered: sized input {} < minimum trade size {}",
+                    cycle.start_token, trade.input, min_size
+                );
+                return None;
+            }
+        }
 
-        // Verify profitability after slippage
-        let profit_bps = ((expected_output as i128 - optimal_input as i128) * 10000 
-            / optimal_input as i128) as i64;
+        // Verify profitability after execution costs
+        let profit_bps = trade.net_profit_bps();
 
         if profit_bps < self.min_profit_bps {
             debug!(
-                "Cycle filtered: profit {}bps < threshold {}bps after slippage",
+                "Cycle filtered: profit {}bps < threshold {}bps after costs",
                 profit_bps, self.min_profit_bps
             );
             return None;
         }
 
         // Create opportunity
+        // Costs are part of the profit: report the output net of them
         let mut opportunity = ArbitrageOpportunity {
-            cycle,
-            optimal_input_amount: optimal_input,
-            expected_output_amount: expected_output,
-            expected_profit_sol: profit_sol,
+            optimal_input_amount: trade.input,
+            expected_output_amount: trade.output.saturating_sub(trade.costs),
+            expected_profit_sol: trade.net_profit_sol(),
             expected_profit_bps: profit_bps,
+            cycle: trade.cycle,
             detected_at: Utc::now().timestamp(),
             priority_score: 0.0,
+            score: ScoreBreakdown::default(),
             risk_level: RiskLevel::Medium,
         };
 
         // Calculate priority score and risk
-        opportunity.priority_score = self.calculate_priority_score(&opportunity);
+        opportunity.score = self.score_opportunity(&opportunity);
+        opportunity.priority_score = opportunity.score.total();
         opportunity.risk_level = self.assess_risk(&opportunity);
+        debug!("Scored opportunity {}: {} ({:?} risk)", opportunity.route(), opportunity.score, opportunity.risk_level);
 
         Some(opportunity)
     }
 
-    /// Calculate optimal input amount considering slippage
-    async fn calculate_optimal_input(&self, cycle: &ArbitrageCycle) -> Option<(u64, u64, f64)> {
-        // For now, use a conservative fixed amount
-        // In production, implement binary search or calculus-based optimization
-        // using cycle.calculate_slippage_adjusted_profit()
-        
-        // Use a moderate trade size: 0.1 SOL = 100M lamports
-        let optimal_input = 100_000_000u64; // 0.1 SOL
+    /// Whether every pool of the cycle holds at least the configured USD
+    /// liquidity. Pools that cannot be valued are let through.
+    fn meets_liquidity_floor(&self, graph: &ArbitrageGraph, cycle: &ArbitrageCycle) -> bool {
+        if self.min_pool_liquidity_usd <= 0.0 {
+            return true;
+        }
+        cycle.path.iter().all(|step| {
+            step.pools().iter().all(|pool| {
+                let liquidity = graph.get_edge(&step.from_token, &step.to_token, pool)
+                    .and_then(|edge| Self::pool_liquidity_usd(graph, edge));
+                match liquidity {
+                    Some(usd) if usd < self.min_pool_liquidity_usd => {
+                        debug!("Skipping cycle from {}: pool {} holds ${:.0}", cycle.start_token, pool, usd);
+                        false
+                    }
+                    _ => true,
+                }
+            })
+        })
+    }
 
-        // Calculate expected output using cycle's net profit
-        let profit_multiplier = 1.0 + (cycle.net_profit_after_fees / 100.0);
-        let theoretical_output = (optimal_input as f64 * profit_multiplier).round() as u64;
-        
-        // Apply conservative slippage estimate (2%)
-        let slippage_factor = 0.98;
-        let expected_output = (theoretical_output as f64 * slippage_factor) as u64;
+    /// USD value of the pool behind `edge`: twice its larger priced reserve.
+    /// `None` without a pool snapshot or a USD price for either token.
+    fn pool_liquidity_usd(graph: &ArbitrageGraph, edge: &ExchangeEdge) -> Option<f64> {
+        let pool = edge.quoter.as_ref()?.pool();
+        [(pool.token_a, pool.reserve_a), (pool.token_b, pool.reserve_b)]
+            .into_iter()
+            .filter_map(|(mint, reserve)| Some(reserve as f64 * Self::usd_per_unit(graph, &mint)?))
+            .max_by(f64::total_cmp)
+            .map(|side| side * 2.0)
+    }
+
+    /// Dollars per smallest unit of `mint`: fixed for USDC and USDT,
+    /// otherwise the best spot rate of a `mint` -> stablecoin edge
+    fn usd_per_unit(graph: &ArbitrageGraph, mint: &Pubkey) -> Option<f64> {
+        let is_stable = |token: &Pubkey| *token == USDC_MINT || *token == USDT_MINT;
+        if is_stable(mint) {
+            return Some(1.0 / STABLE_UNITS_PER_USD);
+        }
+        graph.get_edges_from(mint)?
+            .iter()
+            .filter(|edge| is_stable(&edge.to_token) && edge.rate.is_finite() && edge.rate > 0.0)
+            .map(|edge| edge.rate / STABLE_UNITS_PER_USD)
+            .max_by(f64::total_cmp)
+    }
 
-        // Calculate profit in SOL
-        let profit_lamports = expected_output.saturating_sub(optimal_input) as i64;
-        let profit_sol = profit_lamports as f64 / 1e9;
+    /// Size the cycle on its exact quotes; `None` unless it nets a profit
+    /// after execution costs. The sized route may split hops across pools.
+    async fn calculate_optimal_input(&self, cycle: &ArbitrageCycle) -> Option<SizedTrade> {
+        let trade = {
+            let graph = self.graph.read().ok()?;
+            self.sizing.size_cycle(&graph, cycle)
+        };
+        let Some(trade) = trade else {
+            debug!("Cycle from {} could not be sized", cycle.start_token);
+            return None;
+        };
 
-        if profit_lamports <= 0 {
+        if trade.net_profit <= 0 {
             return None;
         }
 
         debug!(
-            "Optimal input: {} lamports ({:.4} SOL), expected output: {} lamports, profit: {:.4} SOL",
-            optimal_input,
-            optimal_input as f64 / 1e9,
-            expected_output,
-            profit_sol
+            "Optimal input: {} (output {}, costs {}, net {}) from a {}-point profit curve: {}",
+            trade.input,
+            trade.output,
+            trade.costs,
+            trade.net_profit,
+            trade.curve.len(),
+            trade.curve.iter()
+                .map(|point| format!("{}:{}", point.input, point.net_profit))
+                .collect::<Vec<_>>()
+                .join(" ")
         );
 
-        Some((optimal_input, expected_output, profit_sol))
-    }
-
-    /// Estimate slippage factor based on liquidity and trade size
-    fn estimate_slippage_factor(&self, liquidity_usd: f64, trade_size_usd: f64) -> f64 {
-        // Simple slippage model: slippage increases with trade size / liquidity ratio
-        let ratio = trade_size_usd / liquidity_usd;
-        
-        if ratio < 0.01 {
-            0.999 // 0.1% slippage
-        } else if ratio < 0.02 {
-            0.997 // 0.3% slippage
-        } else if ratio < 0.05 {
-            0.99  // 1% slippage
-        } else {
-            0.98  // 2% slippage
-        }
+        Some(trade)
     }
 
-    /// Calculate priority score for opportunity (0.0 to 1.0)
-    fn calculate_priority_score(&self, opp: &ArbitrageOpportunity) -> f64 {
-        let mut score = 0.0;
-        
-        // Factor 1: Expected profit (40% weight)
+    /// Score an opportunity from its profit, hop count, the depth of its
+    /// pools at the sized input, the age of its edges and its venues
+    fn score_opportunity(&self, opp: &ArbitrageOpportunity) -> ScoreBreakdown {
         // Normalize to 0-1 scale (0 SOL = 0, 1 SOL = 1.0)
-        let profit_score = (opp.expected_profit_sol / 1.0).min(1.0);
-        score += profit_score * 0.4;
+        let profit = opp.expected_profit_sol.clamp(0.0, 1.0);
 
-        // Factor 2: Number of hops (30% weight)
-        // Fewer hops = better (2 hops = 1.0, 4 hops = 0.5)
-        let hop_count = opp.cycle.path.len();
-        let hop_score = match hop_count {
+        // Fewer hops = better (2 hops = 1.0, 4 hops = 0.6)
+        let hops = match opp.cycle.path.len() {
             2 => 1.0,
             3 => 0.8,
             4 => 0.6,
             _ => 0.4,
         };
-        score += hop_score * 0.3;
-
-        // Factor 3: Liquidity depth (20% weight)
-        // Higher liquidity = better execution probability
-        // Note: Would need liquidity data from graph, for now use default
-        let liquidity_score = 0.5; // Default medium liquidity
-        score += liquidity_score * 0.2;
 
-        // Factor 4: DEX reliability (10% weight)
-        // Raydium, Orca = high reliability
-        let dex_score = self.calculate_dex_reliability_score(&opp.cycle);
-        score += dex_score * 0.1;
+        let (max_price_impact_bps, oldest_edge_age_secs, max_edge_age_ms) = match self.graph.read() {
+            Ok(graph) => {
+                let impact = graph.price_impact_bps(&opp.cycle, opp.optimal_input_amount).ok()
+                    .map(|impacts| impacts.into_iter().max().unwrap_or(0));
+
+                let now = Utc::now().timestamp();
+                let ages: Option<Vec<i64>> = opp.cycle.path.iter()
+                    .flat_map(|step| step.pools().into_iter().map(move |pool| (step, pool)))
+                    .map(|(step, pool)| {
+                        let edge = graph.get_edge(&step.from_token, &step.to_token, &pool)?;
+                        Some((now - edge.last_update).max(0))
+                    })
+                    .collect();
+                (impact, ages.and_then(|ages| ages.into_iter().max()), graph.staleness().max_edge_age_ms)
+            }
+            Err(_) => (None, None, 0),
+        };
 
-        score.clamp(0.0, 1.0)
+        // Depth relative to trade size: no impact = 1.0, the cap or worse = 0.0
+        let liquidity = max_price_impact_bps.map_or(0.0, |impact| {
+            1.0 - (impact as f64 / self.risk.max_price_impact_bps.max(1) as f64).min(1.0)
+        });
+        // Edges reach zero freshness at the age the graph drops them as stale
+        let freshness = oldest_edge_age_secs.map_or(0.0, |age| {
+            if max_edge_age_ms == 0 {
+                return 1.0;
+            }
+            1.0 - (age as f64 * 1000.0 / max_edge_age_ms as f64).min(1.0)
+        });
+
+        ScoreBreakdown {
+            profit,
+            hops,
+            liquidity,
+            freshness,
+            venue: self.calculate_dex_reliability_score(&opp.cycle),
+            max_price_impact_bps,
+            oldest_edge_age_secs,
+        }
     }
 
-    /// Calculate DEX reliability score based on historical success rates
+    /// Reliability of the cycle's venues and pools, learned from trade history
     fn calculate_dex_reliability_score(&self, cycle: &ArbitrageCycle) -> f64 {
-        use crate::dex::triangular_arb::DexType;
-        let mut total_score = 0.0;
-        
-        for step in &cycle.path {
-            let dex_score = match step.dex {
-                DexType::Raydium => 1.0,      // Most reliable
-                DexType::Orca => 0.95,        // Very reliable
-                DexType::Whirlpool => 0.95,   // Orca Whirlpool, very reliable
-                DexType::Meteora => 0.9,      // Reliable
-                DexType::Pump => 0.7,         // Less tested
-            };
-            total_score += dex_score;
-        }
-
-        total_score / cycle.path.len() as f64
+        self.reliability.read()
+            .map(|reliability| reliability.cycle_score(cycle))
+            .unwrap_or(0.0)
     }
 
-    /// Assess risk level for opportunity
+    /// Assess risk level for opportunity against the configured thresholds
     fn assess_risk(&self, opp: &ArbitrageOpportunity) -> RiskLevel {
         let hop_count = opp.cycle.path.len();
         let profit_bps = opp.expected_profit_bps;
 
-        // Low risk: 2-3 hops, high profit (>2%)
-        if hop_count <= 3 && profit_bps > 200 {
+        if hop_count <= self.risk.low_risk_max_hops && profit_bps >= self.risk.low_risk_min_profit_bps {
             return RiskLevel::Low;
         }
 
-        // High risk: 4+ hops or low profit (<0.5%)
-        if hop_count >= 4 || profit_bps < 50 {
-            return RiskLevel::High;
+        if hop_count <= self.risk.medium_risk_max_hops && profit_bps >= self.risk.medium_risk_min_profit_bps {
+            return RiskLevel::Medium;
         }
 
-        RiskLevel::Medium
+        RiskLevel::High
     }
 
     /// Update detection metrics
@@ -366,6 +638,12 @@ impl ArbitrageDetector {
             (metrics.avg_detection_latency_ms * (n - 1.0) + latency_ms) / n;
         
         metrics.last_detection_time = Some(Instant::now());
+
+        if let Ok(graph) = self.graph.read() {
+            let staleness = graph.staleness_stats();
+            metrics.stale_edges_rejected = staleness.stale_edges;
+            metrics.wide_slot_span_rejections = staleness.wide_slot_spans;
+        }
     }
 
     /// Get current detection metrics
@@ -403,6 +681,8 @@ impl ArbitrageDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::adapter::DexRegistry;
+    use crate::dex::test_fixtures::raydium_pool;
     use crate::dex::triangular_arb::{create_shared_graph, CycleStep, DexType};
 
     #[tokio::test]
@@ -436,6 +716,7 @@ mod tests {
                     pool: Pubkey::new_unique(),
                     rate: 150.0,
                     fee_bps: 25,
+                    splits: Vec::new(),
                 },
                 CycleStep {
                     from_token: usdc,
@@ -444,6 +725,7 @@ mod tests {
                     pool: Pubkey::new_unique(),
                     rate: 0.0068,
                     fee_bps: 30,
+                    splits: Vec::new(),
                 },
             ],
             gross_profit_bps: 302,
@@ -462,14 +744,58 @@ mod tests {
             expected_profit_bps: 302,
             detected_at: Utc::now().timestamp(),
             priority_score: 0.0,
+            score: ScoreBreakdown::default(),
             risk_level: RiskLevel::Medium,
         };
 
-        let score = detector.calculate_priority_score(&opportunity);
+        let breakdown = detector.score_opportunity(&opportunity);
+        let score = breakdown.total();
         
         // Should be positive score
         assert!(score > 0.0);
         assert!(score <= 1.0);
+
+        // Pools missing from the graph cannot be quoted or dated
+        assert_eq!(breakdown.max_price_impact_bps, None);
+        assert_eq!(breakdown.liquidity, 0.0);
+        assert_eq!(breakdown.oldest_edge_age_secs, None);
+        assert_eq!(breakdown.hops, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_freshness_reaches_zero_at_the_staleness_limit() {
+        use crate::config::StalenessConfig;
+        use crate::dex::triangular_arb::{ArbitrageGraph, ExchangeEdge};
+
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (buy, sell) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let now = Utc::now().timestamp();
+        let mut graph = ArbitrageGraph::new().with_staleness(StalenessConfig {
+            max_edge_age_ms: 20_000,
+            ..StalenessConfig::default()
+        });
+        graph.add_edge(ExchangeEdge::new(sol, usdc, DexType::Raydium, buy, 150.0, 25, Vec::new(), now - 5));
+        graph.add_edge(ExchangeEdge::new(usdc, sol, DexType::Orca, sell, 0.0068, 30, Vec::new(), now - 10));
+        let cycle = graph.cycle_through(&[(sol, usdc, buy), (usdc, sol, sell)]).unwrap();
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let detector = ArbitrageDetector::new(Arc::new(std::sync::RwLock::new(graph)), 100, tx);
+        let opportunity = ArbitrageOpportunity {
+            cycle,
+            optimal_input_amount: 1_000_000,
+            expected_output_amount: 1_010_000,
+            expected_profit_sol: 0.00001,
+            expected_profit_bps: 100,
+            detected_at: now,
+            priority_score: 0.0,
+            score: ScoreBreakdown::default(),
+            risk_level: RiskLevel::Medium,
+        };
+
+        // The oldest edge is half way to the age the graph drops it as stale
+        let breakdown = detector.score_opportunity(&opportunity);
+        assert!(matches!(breakdown.oldest_edge_age_secs, Some(10..=11)));
+        assert!((breakdown.freshness - 0.5).abs() < 0.1);
     }
 
     #[tokio::test]
@@ -492,6 +818,7 @@ mod tests {
                         pool: Pubkey::new_unique(),
                         rate: 150.0,
                         fee_bps: 25,
+                        splits: Vec::new(),
                     },
                     CycleStep {
                         from_token: usdc,
@@ -500,6 +827,7 @@ mod tests {
                         pool: Pubkey::new_unique(),
                         rate: 0.0068,
                         fee_bps: 30,
+                        splits: Vec::new(),
                     },
                 ],
                 gross_profit_bps: 302,
@@ -515,25 +843,80 @@ mod tests {
             expected_profit_bps: 302,
             detected_at: Utc::now().timestamp(),
             priority_score: 0.0,
+            score: ScoreBreakdown::default(),
             risk_level: RiskLevel::Medium,
         };
 
         let risk = detector.assess_risk(&low_risk_opp);
         assert_eq!(risk, RiskLevel::Low);
+
+        // Thresholds come from the risk config
+        let detector = detector.with_risk_config(RiskConfig {
+            low_risk_min_profit_bps: 500,
+            medium_risk_min_profit_bps: 300,
+            ..RiskConfig::default()
+        });
+        assert_eq!(detector.assess_risk(&low_risk_opp), RiskLevel::Medium);
+
+        let detector = detector.with_risk_config(RiskConfig {
+            low_risk_max_hops: 1,
+            medium_risk_max_hops: 1,
+            ..RiskConfig::default()
+        });
+        assert_eq!(detector.assess_risk(&low_risk_opp), RiskLevel::High);
     }
 
     #[tokio::test]
-    async fn test_slippage_estimation() {
+    async fn test_cycle_that_cannot_be_sized_is_dropped() {
         let graph = create_shared_graph();
         let (tx, _rx) = mpsc::unbounded_channel();
+
+        // Profitable at spot, but the start token has no SOL market to value costs in
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let cycle = {
+            let mut graph = graph.write().unwrap();
+            let pools = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+            for (from, to, pool) in [(a, b, pools[0]), (b, c, pools[1]), (c, a, pools[2])] {
+                graph.add_edge(ExchangeEdge::new(from, to, DexType::Raydium, pool, 1.1, 25, vec![], 1000));
+            }
+            graph.cycle_through(&[(a, b, pools[0]), (b, c, pools[1]), (c, a, pools[2])]).unwrap()
+        };
+        assert!(cycle.net_profit_after_fees > 0.0);
+
         let detector = ArbitrageDetector::new(graph, 100, tx);
+        assert!(detector.process_cycle(cycle).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_thin_pools_and_small_trades_are_filtered() {
+        let registry = DexRegistry::default();
+        let graph = create_shared_graph();
 
-        // Small trade in high liquidity = low slippage
-        let slippage1 = detector.estimate_slippage_factor(100_000.0, 500.0);
-        assert!(slippage1 > 0.995);
+        // Sell SOL on a ~$3k pool priced 5% above a ~$300k pool, buy it back there
+        let thin = Pubkey::new_unique();
+        let deep = Pubkey::new_unique();
+        let cycle = {
+            let mut graph = graph.write().unwrap();
+            graph.upsert_pool(&registry, &raydium_pool(thin, WSOL_MINT, USDC_MINT, 10_000_000_000, 1_575_000_000), 1000).unwrap();
+            graph.upsert_pool(&registry, &raydium_pool(deep, WSOL_MINT, USDC_MINT, 1_000_000_000_000, 150_000_000_000), 1000).unwrap();
+            graph.cycle_through(&[(WSOL_MINT, USDC_MINT, thin), (USDC_MINT, WSOL_MINT, deep)]).unwrap()
+        };
 
-        // Large trade in low liquidity = high slippage
-        let slippage2 = detector.estimate_slippage_factor(10_000.0, 1_000.0);
-        assert!(slippage2 < 0.995);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let detector = ArbitrageDetector::new(graph.clone(), 10, tx).with_min_pool_liquidity_usd(1_000.0);
+        {
+            let graph = graph.read().unwrap();
+            let liquidity = ArbitrageDetector::pool_liquidity_usd(&graph, graph.get_edge(&WSOL_MINT, &USDC_MINT, &thin).unwrap()).unwrap();
+            assert!((liquidity - 3_150.0).abs() < 1.0, "thin pool valued at ${}", liquidity);
+            assert!(detector.meets_liquidity_floor(&graph, &cycle));
+        }
+        let detector = detector.with_min_pool_liquidity_usd(10_000.0);
+        assert!(!detector.meets_liquidity_floor(&graph.read().unwrap(), &cycle));
+
+        // The trade sizes well below 5 SOL, the configured minimum
+        let opportunity = detector.process_cycle(cycle.clone()).await.unwrap();
+        assert!(opportunity.optimal_input_amount < 5_000_000_000);
+        let detector = detector.with_min_trade_sizes(HashMap::from([(WSOL_MINT, 5_000_000_000)]));
+        assert!(detector.process_cycle(cycle).await.is_none());
     }
 }
@@ -15,7 +15,8 @@ use std::str::FromStr;
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
-use crate::data::{TradeRecord, TradeStorage};
+use crate::chain::reliability::SharedReliability;
+use crate::data::{TradeHop, TradeRecord, TradeStorage};
 
 /// Transaction executor for arbitrage operations
 /// 
@@ -29,6 +30,7 @@ use crate::data::{TradeRecord, TradeStorage};
 pub struct TransactionExecutor {
     rpc_client: Arc<RpcClient>,
     storage: Arc<TradeStorage>,
+    reliability: Option<SharedReliability>,
 }
 
 impl TransactionExecutor {
@@ -45,6 +47,23 @@ impl TransactionExecutor {
         Self { 
             rpc_client,
             storage,
+            reliability: None,
+        }
+    }
+
+    /// Feed every recorded trade into a shared reliability tracker
+    pub fn with_reliability(mut self, reliability: SharedReliability) -> Self {
+        self.reliability = Some(reliability);
+        self
+    }
+
+    /// Persist a trade record and update the reliability tracker with it
+    async fn record_trade(&self, trade_record: &TradeRecord) {
+        if let Err(e) = self.storage.save_record(trade_record).await {
+            warn!("Failed to save trade record: {}", e);
+        }
+        if let Some(Ok(mut tracker)) = self.reliability.as_ref().map(|r| r.write()) {
+            tracker.record(trade_record);
         }
     }
 
@@ -565,6 +584,7 @@ impl TransactionExecutor {
     /// * `is_simulation_mode` - If true, simulates; if false, executes live
     /// * `profit_token_mint` - Token mint address for profit tracking
     /// * `expected_profit_amount` - Expected profit in smallest token units
+    /// * `route` - Venues and pools the transaction trades through
     /// 
     /// # Returns
     /// Result containing execution outcome (simulation or live)
@@ -584,6 +604,7 @@ impl TransactionExecutor {
         is_simulation_mode: bool,
         profit_token_mint: &Pubkey,
         expected_profit_amount: u64,
+        route: &[TradeHop],
     ) -> Result<ArbitrageExecutionResult> {
         // Start timing for latency tracking
         let start_time = Instant::now();
@@ -624,9 +645,7 @@ impl TransactionExecutor {
             };
             
             // Save record asynchronously
-            if let Err(e) = self.storage.save_record(&trade_record).await {
-                warn!("Failed to save trade record: {}", e);
-            }
+            self.record_trade(&trade_record.with_route(route.to_vec())).await;
             
             Ok(ArbitrageExecutionResult::Simulation(result))
         } else {
@@ -645,7 +664,7 @@ impl TransactionExecutor {
                         // Find profit for the specified token
                         pv.profit_by_token.iter()
                             .find(|tp| tp.mint == *profit_token_mint)
-                            .map(|tp| tp.amount_after.saturating_sub(tp.amount_before))
+                            .map(|tp| tp.net_change)
                     })
                     .unwrap_or(0);
                 
@@ -671,9 +690,7 @@ impl TransactionExecutor {
             };
             
             // Save record asynchronously
-            if let Err(e) = self.storage.save_record(&trade_record).await {
-                warn!("Failed to save trade record: {}", e);
-            }
+            self.record_trade(&trade_record.with_route(route.to_vec())).await;
             
             Ok(ArbitrageExecutionResult::Live(result))
         }
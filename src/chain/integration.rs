@@ -1,19 +1,31 @@
 use anyhow::{Context, Result};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::signature::Keypair;
+use solana_sdk::signature::{Keypair, Signer};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
-use crate::config::Config;
+use crate::config::{Config, RpcConfig};
+use crate::data::{TradeHop, TradeRecord, TradeStorage};
 use crate::dex::triangular_arb::{ArbitrageGraph, BellmanFordDetector};
+use crate::dex::adapter::DexRegistry;
 use crate::dex::pool_fetcher::PoolDataFetcher;
+use crate::dex::route_cache::RouteCache;
 
 use super::detector::{ArbitrageDetector, ArbitrageOpportunity};
+use super::new_pool_listener::{NewPoolListener, PoolSafetyConfig};
+use super::opportunity_guard::{InFlightRegistry, SharedInFlight};
+use super::pool_discovery::PoolDiscovery;
+use super::constants::WSOL_MINT;
 use super::pool_monitor::PoolMonitor;
+use super::reliability::{ReliabilityTracker, SharedReliability};
+use super::sizing::{SharedInventory, SizingEngine};
 use super::transaction_builder::{SwapTransactionBuilder, TransactionConfig};
-use super::transaction_sender::{SendConfig, TransactionSender};
+use super::transaction_sender::{SendConfig, SendResult, TransactionSender};
+
+/// Trade history shared with the standalone executor
+const TRADE_HISTORY_PATH: &str = "data/trade_history.jsonl";
 
 /// Main integration structure that coordinates all MEV bot components
 pub struct MevBotOrchestrator {
@@ -21,13 +33,27 @@ pub struct MevBotOrchestrator {
     pub graph: Arc<std::sync::RwLock<ArbitrageGraph>>,
     pub detector: Arc<ArbitrageDetector>,
     pub pool_monitor: Arc<PoolMonitor>,
+    pub pool_listener: Option<Arc<NewPoolListener>>,
     pub tx_builder: Arc<SwapTransactionBuilder>,
     pub tx_sender: Arc<TransactionSender>,
     pub opportunity_tx: mpsc::UnboundedSender<ArbitrageOpportunity>,
     pub opportunity_rx: Option<mpsc::UnboundedReceiver<ArbitrageOpportunity>>,
+    pub update_rx: Option<mpsc::UnboundedReceiver<()>>,
     pub shutdown_tx: mpsc::Sender<()>,
     pub shutdown_rx: Option<mpsc::Receiver<()>>,
     pub metrics: Arc<RwLock<ExecutionMetrics>>,
+    pub trade_storage: Arc<TradeStorage>,
+    pub reliability: SharedReliability,
+    pub in_flight: SharedInFlight,
+}
+
+/// State the execution engine shares with the rest of the bot: where
+/// outcomes are recorded and which routes are in flight
+#[derive(Clone)]
+struct ExecutionState {
+    trade_storage: Arc<TradeStorage>,
+    reliability: SharedReliability,
+    in_flight: SharedInFlight,
 }
 
 /// Execution metrics for monitoring bot performance
@@ -51,54 +77,88 @@ impl MevBotOrchestrator {
         info!("🔧 Initializing MEV Bot Orchestrator...");
 
         // Initialize RPC clients
-        let rpc_clients = Self::create_rpc_clients(&config)?;
+        let rpc_clients = Self::create_rpc_clients(&config.rpc)?;
         info!("✅ Created {} RPC clients", rpc_clients.len());
 
-        // Initialize arbitrage graph (using std::sync::RwLock as required by BellmanFord)
-        let std_graph = Arc::new(std::sync::RwLock::new(ArbitrageGraph::new()));
-        // Also create a tokio RwLock version for detector
-        let tokio_graph = Arc::new(RwLock::new(ArbitrageGraph::new()));
-        info!("✅ Initialized arbitrage graph");
+        // Initialize the arbitrage graph shared by the monitor, detectors and
+        // builder (std::sync::RwLock as required by BellmanFord)
+        let std_graph = Arc::new(std::sync::RwLock::new(
+            ArbitrageGraph::new().with_staleness(config.staleness.clone()),
+        ));
+        info!(
+            "✅ Initialized arbitrage graph (edges stale after {} slots / {}ms, cycle span up to {} slots)",
+            config.staleness.max_edge_age_slots,
+            config.staleness.max_edge_age_ms,
+            config.staleness.max_cycle_slot_span
+        );
 
         // Create channel for arbitrage opportunities
         let (opportunity_tx, opportunity_rx) = mpsc::unbounded_channel();
 
+        // Create channel the pool monitor signals graph updates on
+        let (update_tx, update_rx) = mpsc::unbounded_channel();
+
         // Create shutdown channel
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
 
-        // Initialize detector (uses tokio RwLock internally)
-        let detector = Arc::new(ArbitrageDetector::new(
+        // Candidate routes per base token, shared by both detectors
+        let route_cache = RouteCache::from_config(&config)
+            .map(|cache| Arc::new(std::sync::RwLock::new(cache)));
+        if route_cache.is_some() {
+            info!("✅ Route cache enabled (TTL {}s, max {} hops)",
+                config.routing.route_cache_ttl_seconds, config.routing.max_hops);
+        }
+
+        // Trade sizes are capped by the SOL held above the wallet's reserve
+        let mut sizing = SizingEngine::from_config(&config);
+        match rpc_clients[0].get_balance(&keypair.pubkey()).await {
+            Ok(balance) => {
+                let reserve = (config.wallet.min_balance_sol * 1e9) as u64;
+                let inventory: SharedInventory = Arc::new(std::sync::RwLock::new(
+                    HashMap::from([(WSOL_MINT, balance.saturating_sub(reserve))]),
+                ));
+                sizing = sizing.with_inventory(inventory);
+            }
+            Err(e) => warn!("Could not read wallet balance, sizing by position limit only: {}", e),
+        }
+
+        // Venue and pool reliability learned from the recorded trade history
+        let trade_storage = Arc::new(TradeStorage::new(TRADE_HISTORY_PATH));
+        let trade_history = trade_storage.load_all_records().await.unwrap_or_else(|e| {
+            warn!("Failed to load trade history: {}", e);
+            Vec::new()
+        });
+        let reliability: SharedReliability = Arc::new(std::sync::RwLock::new(
+            ReliabilityTracker::from_records(config.reliability.clone(), &trade_history),
+        ));
+        info!("✅ Seeded reliability tracker from {} trade records", trade_history.len());
+
+        // Initialize detector from the configured tokens and limits
+        let mut detector = ArbitrageDetector::from_config(
             std_graph.clone(),
-            config.bot.min_profit_bps as i64,
+            &config,
             opportunity_tx.clone(),
-        ));
+        )
+        .with_sizing(sizing)
+        .with_reliability(reliability.clone());
+        if let Some(route_cache) = &route_cache {
+            detector = detector.with_route_cache(route_cache.clone());
+        }
+        let detector = Arc::new(detector);
         info!("✅ Initialized arbitrage detector");
 
+        // Venue adapters for the configured DEX programs
+        let dex_registry = Arc::new(DexRegistry::from_config(&config.dex));
+
         // Initialize pool data fetcher
         let pool_fetcher = Arc::new(PoolDataFetcher::new(
             vec![rpc_clients[0].clone()],
             config.cache.ttl_seconds * 1000,  // Convert to ms
-        ));
+        ).with_registry(dex_registry.clone()));
         info!("✅ Initialized pool data fetcher");
 
-        // Initialize Bellman-Ford detector for pool monitor
-        let bellman_ford = Arc::new(BellmanFordDetector::new(
-            std_graph.clone(),
-            config.bot.min_profit_bps as i64,
-        ));
-
-        // Initialize pool monitor
-        let monitored_pools = vec![]; // Will be populated with actual pool addresses
-        let pool_monitor = Arc::new(PoolMonitor::new(
-            config.rpc.ws_url.clone(),
-            std_graph.clone(),
-            pool_fetcher.clone(),
-            monitored_pools,
-            bellman_ford,
-        ));
-        info!("✅ Initialized pool monitor");
-
-        // Initialize transaction builder
+        // Initialize transaction builder; legs are built against the pool
+        // snapshots their edges in the live graph were priced from
         let token_accounts = HashMap::new(); // Will be populated dynamically
         let lookup_tables = vec![]; // Address lookup tables for transaction compression
         
@@ -111,9 +171,62 @@ impl MevBotOrchestrator {
             payer_keypair,
             token_accounts,
             lookup_tables,
-        ));
+        )
+        .with_registry(dex_registry.clone())
+        .with_graph(std_graph.clone()));
         info!("✅ Initialized transaction builder");
 
+        // Initialize Bellman-Ford detector for pool monitor
+        let mut bellman_ford = BellmanFordDetector::new(
+            std_graph.clone(),
+            config.bot.min_profit_bps as i64,
+        )
+        .with_max_path_length(config.routing.max_hops as usize);
+        if let Some(route_cache) = route_cache {
+            bellman_ford = bellman_ford.with_route_cache(route_cache);
+        }
+        let bellman_ford = Arc::new(bellman_ford);
+
+        // Discover the pools of the configured mints (plus any listed explicitly)
+        let discovery = PoolDiscovery::new(rpc_clients[0].clone(), dex_registry.clone());
+        let monitored_pools: Vec<_> = discovery.pool_universe(&config).await
+            .context("Pool discovery failed")?
+            .into_iter()
+            .map(|pool| pool.address)
+            .collect();
+        info!("✅ Discovered {} pools to monitor", monitored_pools.len());
+
+        // Initialize pool monitor
+        let pool_monitor = Arc::new(PoolMonitor::new(
+            config.rpc.ws_url.clone(),
+            std_graph.clone(),
+            pool_fetcher.clone(),
+            monitored_pools.clone(),
+            bellman_ford,
+        )
+        .with_update_signal(update_tx));
+        info!("✅ Initialized pool monitor");
+
+        // Listen for pools created after startup
+        let pool_listener = if config.discovery.listen_new_pools {
+            let listener = NewPoolListener::new(
+                config.rpc.ws_url.clone(),
+                rpc_clients[0].clone(),
+                pool_fetcher.clone(),
+                std_graph.clone(),
+                pool_monitor.clone(),
+                config.mints.iter().map(|mint| mint.address),
+                monitored_pools,
+            ).with_safety(PoolSafetyConfig {
+                min_liquidity: config.discovery.new_pool_min_liquidity,
+                ..PoolSafetyConfig::default()
+            });
+            info!("✅ Initialized new-pool listener");
+            Some(Arc::new(listener))
+        } else {
+            None
+        };
+
         // Initialize transaction sender
         let tx_sender = Arc::new(TransactionSender::new(
             rpc_clients,
@@ -125,31 +238,41 @@ impl MevBotOrchestrator {
         // Initialize metrics
         let metrics = Arc::new(RwLock::new(ExecutionMetrics::default()));
 
+        // Routes with a pending transaction; a claim lapses after the transaction timeout
+        let in_flight = Arc::new(std::sync::Mutex::new(InFlightRegistry::new(
+            std::time::Duration::from_millis(config.bot.transaction_timeout_ms),
+        )));
+
         Ok(Self {
             config,
             graph: std_graph,
             detector,
             pool_monitor,
+            pool_listener,
             tx_builder,
             tx_sender,
             opportunity_tx,
             opportunity_rx: Some(opportunity_rx),
+            update_rx: Some(update_rx),
             shutdown_tx,
             shutdown_rx: Some(shutdown_rx),
             metrics,
+            trade_storage,
+            reliability,
+            in_flight,
         })
     }
 
     /// Create multiple RPC clients for redundancy
-    fn create_rpc_clients(config: &Config) -> Result<Vec<Arc<RpcClient>>> {
+    fn create_rpc_clients(config: &RpcConfig) -> Result<Vec<Arc<RpcClient>>> {
         let mut clients = Vec::new();
 
         // Main RPC client
-        let main_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+        let main_client = Arc::new(RpcClient::new(config.url.clone()));
         clients.push(main_client);
 
         // Backup RPC clients
-        for backup_url in &config.rpc.backup_urls {
+        for backup_url in &config.backup_urls {
             let backup_client = Arc::new(RpcClient::new(backup_url.clone()));
             clients.push(backup_client);
         }
@@ -166,10 +289,14 @@ impl MevBotOrchestrator {
         info!("🚀 Starting MEV Bot...");
 
         // Take ownership of receivers
-        let mut opportunity_rx = self
+        let opportunity_rx = self
             .opportunity_rx
             .take()
             .context("Opportunity receiver already taken")?;
+        let update_rx = self
+            .update_rx
+            .take()
+            .context("Update receiver already taken")?;
         let mut shutdown_rx = self
             .shutdown_rx
             .take()
@@ -184,9 +311,18 @@ impl MevBotOrchestrator {
             }
         });
 
-        // Spawn detection task
+        // Spawn new-pool listener task
+        let listener_handle = self.pool_listener.clone().map(|listener| {
+            tokio::spawn(async move {
+                info!("🆕 Starting new-pool listener...");
+                if let Err(e) = listener.start().await {
+                    error!("New-pool listener error: {}", e);
+                }
+            })
+        });
+
+        // Spawn detection task, woken by the pool monitor's graph updates
         let detector = self.detector.clone();
-        let (update_tx, update_rx) = mpsc::unbounded_channel();
         let detection_handle = tokio::spawn(async move {
             info!("🔍 Starting arbitrage detector...");
             detector.run_detection_loop(update_rx).await;
@@ -197,9 +333,14 @@ impl MevBotOrchestrator {
         let tx_sender = self.tx_sender.clone();
         let config = self.config.clone();
         let metrics = self.metrics.clone();
+        let state = ExecutionState {
+            trade_storage: self.trade_storage.clone(),
+            reliability: self.reliability.clone(),
+            in_flight: self.in_flight.clone(),
+        };
         let execution_handle = tokio::spawn(async move {
             info!("⚡ Starting execution engine...");
-            Self::execute_opportunities(opportunity_rx, tx_builder, tx_sender, config, metrics)
+            Self::execute_opportunities(opportunity_rx, tx_builder, tx_sender, config, metrics, state)
                 .await;
         });
 
@@ -225,6 +366,9 @@ impl MevBotOrchestrator {
         // Graceful shutdown
         info!("🔄 Shutting down gracefully...");
         monitor_handle.abort();
+        if let Some(handle) = listener_handle {
+            handle.abort();
+        }
         detection_handle.abort();
         execution_handle.abort();
         metrics_handle.abort();
@@ -259,6 +403,7 @@ impl MevBotOrchestrator {
         tx_sender: Arc<TransactionSender>,
         config: Config,
         metrics: Arc<RwLock<ExecutionMetrics>>,
+        state: ExecutionState,
     ) {
         info!("🎯 Execution engine ready");
 
@@ -269,12 +414,14 @@ impl MevBotOrchestrator {
                 m.opportunities_received += 1;
             }
 
-            // Log opportunity
+            // Log opportunity with the components behind its score
             info!(
-                "💰 New opportunity: {} hops, profit: {:.4}%, score: {:.3}",
+                "💰 New opportunity: {} hops, profit: {:.4}%, score: {:.3} ({}), risk: {:?}",
                 opportunity.cycle.path.len(),
                 opportunity.expected_profit_bps as f64 / 100.0,
-                opportunity.priority_score
+                opportunity.priority_score,
+                opportunity.score,
+                opportunity.risk_level
             );
 
             // Check if in simulation mode
@@ -289,7 +436,22 @@ impl MevBotOrchestrator {
 
             // Check risk level
             if matches!(opportunity.risk_level, super::detector::RiskLevel::High) {
-                warn!("⚠️  High risk opportunity, skipping");
+                warn!(
+                    "⚠️  High risk opportunity, skipping: {} hops at {}bps (medium risk allows up to {} hops from {}bps)",
+                    opportunity.cycle.path.len(),
+                    opportunity.expected_profit_bps,
+                    config.risk.medium_risk_max_hops,
+                    config.risk.medium_risk_min_profit_bps
+                );
+                let mut m = metrics.write().await;
+                m.opportunities_skipped += 1;
+                continue;
+            }
+
+            // Skip routes that share a pool with a pending transaction
+            let key = opportunity.key();
+            if !state.in_flight.lock().is_ok_and(|mut registry| registry.try_claim(&key)) {
+                warn!("⏳ Route shares a pool with a pending transaction, skipping: {}", opportunity.route());
                 let mut m = metrics.write().await;
                 m.opportunities_skipped += 1;
                 continue;
@@ -298,14 +460,36 @@ impl MevBotOrchestrator {
             // Execute the opportunity
             let start_time = std::time::Instant::now();
             
-            match Self::execute_single_opportunity(
+            let outcome = Self::execute_single_opportunity(
                 &opportunity,
                 &tx_builder,
                 &tx_sender,
                 &config,
             )
-            .await
-            {
+            .await;
+
+            let latency_ms = start_time.elapsed().as_millis() as u64;
+            let realized_profit = match &outcome {
+                Ok(result) => Self::realized_profit(&opportunity, result, &tx_builder, &tx_sender).await,
+                Err(_) => None,
+            };
+            let trade_record = Self::trade_record(&opportunity, &outcome, realized_profit, latency_ms);
+            if let Err(e) = state.trade_storage.save_record(&trade_record).await {
+                warn!("Failed to save trade record: {}", e);
+            }
+            if let Ok(mut tracker) = state.reliability.write() {
+                tracker.record(&trade_record);
+            }
+
+            // A sent but unconfirmed transaction keeps its pools until the claim expires
+            let settled = outcome.as_ref().map_or(true, |result| result.confirmed);
+            if settled {
+                if let Ok(mut registry) = state.in_flight.lock() {
+                    registry.release(&key);
+                }
+            }
+
+            match outcome {
                 Ok(result) => {
                     let execution_time = start_time.elapsed().as_millis() as u64;
                     
@@ -319,7 +503,11 @@ impl MevBotOrchestrator {
                     m.opportunities_executed += 1;
                     m.transactions_sent += 1;
                     m.transactions_confirmed += 1;
-                    m.total_profit_lamports += opportunity.expected_profit_bps as i64;
+                    // Only routes that start and end in SOL profit in lamports
+                    if opportunity.profit_token() == WSOL_MINT {
+                        m.total_profit_lamports += opportunity.expected_output_amount as i64
+                            - opportunity.optimal_input_amount as i64;
+                    }
                     m.total_fees_paid += config.execution.compute_unit_price;
                     
                     // Update average execution time
@@ -347,13 +535,71 @@ impl MevBotOrchestrator {
         info!("Execution engine stopped");
     }
 
+    /// Profit the confirmed transaction left in the wallet: the signed change
+    /// in the payer's balance of the profit token, negative for a loss. `None`
+    /// when the transaction's balances cannot be read.
+    async fn realized_profit(
+        opportunity: &ArbitrageOpportunity,
+        result: &SendResult,
+        tx_builder: &SwapTransactionBuilder,
+        tx_sender: &TransactionSender,
+    ) -> Option<i64> {
+        match tx_sender
+            .token_balance_change(&result.signature, &tx_builder.payer(), &opportunity.profit_token())
+            .await
+        {
+            Ok(change) => Some(change.clamp(i64::MIN as i128, i64::MAX as i128) as i64),
+            Err(e) => {
+                warn!("Could not read realized profit of {}: {}", result.signature, e);
+                None
+            }
+        }
+    }
+
+    /// Trade record for an execution attempt, with the route it traded.
+    /// Confirmed trades whose realized profit was measured are `LIVE`; the
+    /// rest carry no realized profit and are marked `LIVE_UNVALIDATED`.
+    fn trade_record(
+        opportunity: &ArbitrageOpportunity,
+        outcome: &Result<SendResult>,
+        realized_profit: Option<i64>,
+        latency_ms: u64,
+    ) -> TradeRecord {
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let profit_token_mint = opportunity.profit_token().to_string();
+        let expected_profit_amount = opportunity.expected_output_amount
+            .saturating_sub(opportunity.optimal_input_amount);
+        let execution_mode = if realized_profit.is_some() { "LIVE" } else { "LIVE_UNVALIDATED" }.to_string();
+
+        let record = match outcome {
+            Ok(result) => TradeRecord::success(
+                timestamp,
+                result.signature.to_string(),
+                profit_token_mint,
+                realized_profit.unwrap_or(0),
+                expected_profit_amount,
+                latency_ms,
+                execution_mode,
+            ),
+            Err(e) => TradeRecord::failure(
+                timestamp,
+                profit_token_mint,
+                expected_profit_amount,
+                latency_ms,
+                execution_mode,
+                format!("{:#}", e),
+            ),
+        };
+        record.with_route(TradeHop::route_of(&opportunity.cycle))
+    }
+
     /// Execute a single arbitrage opportunity
     async fn execute_single_opportunity(
         opportunity: &ArbitrageOpportunity,
         tx_builder: &SwapTransactionBuilder,
         tx_sender: &TransactionSender,
         config: &Config,
-    ) -> Result<super::transaction_sender::SendResult> {
+    ) -> Result<SendResult> {
         // Build transaction
         debug!("🔨 Building transaction...");
         
@@ -393,6 +639,11 @@ impl MevBotOrchestrator {
             .await
             .context("Failed to send transaction")?;
 
+        // A confirmed transaction can still have failed in a program
+        if let Some(err) = &result.error {
+            anyhow::bail!("Transaction {} reverted: {}", result.signature, err);
+        }
+
         // Check for front-running
         // Assume actual profit is ~80% of expected for demonstration
         let estimated_actual_profit = (opportunity.expected_profit_bps as u64 * 80) / 100;
@@ -466,7 +717,6 @@ impl MevBotOrchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solana_sdk::signature::Keypair;
 
     #[tokio::test]
     async fn test_execution_metrics_default() {
@@ -478,11 +728,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_rpc_clients() {
-        let mut config = Config::load().unwrap();
-        config.rpc.backup_urls = vec![
-            "https://api.mainnet-beta.solana.com".to_string(),
-            "https://solana-api.projectserum.com".to_string(),
-        ];
+        let config = RpcConfig {
+            url: "https://api.devnet.solana.com".to_string(),
+            ws_url: "wss://api.devnet.solana.com".to_string(),
+            backup_urls: vec![
+                "https://api.mainnet-beta.solana.com".to_string(),
+                "https://solana-api.projectserum.com".to_string(),
+            ],
+            commitment_level: "confirmed".to_string(),
+            timeout_seconds: 30,
+            external_data_api_url: None,
+        };
 
         let clients = MevBotOrchestrator::create_rpc_clients(&config).unwrap();
         assert_eq!(clients.len(), 3); // 1 main + 2 backup
@@ -490,10 +746,12 @@ mod tests {
 
     #[test]
     fn test_metrics_update() {
-        let mut metrics = ExecutionMetrics::default();
-        metrics.opportunities_received = 10;
-        metrics.opportunities_executed = 7;
-        metrics.opportunities_skipped = 3;
+        let metrics = ExecutionMetrics {
+            opportunities_received: 10,
+            opportunities_executed: 7,
+            opportunities_skipped: 3,
+            ..Default::default()
+        };
 
         assert_eq!(metrics.opportunities_received, 10);
         assert_eq!(metrics.opportunities_executed, 7);
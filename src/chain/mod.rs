@@ -2,7 +2,12 @@ pub mod constants;
 pub mod detector;
 pub mod executor;
 pub mod integration;
+pub mod new_pool_listener;
+pub mod opportunity_guard;
+pub mod pool_discovery;
 pub mod pool_monitor;
+pub mod reliability;
+pub mod sizing;
 pub mod token_fetch;
 pub mod token_price;
 pub mod transaction_builder;
@@ -0,0 +1,412 @@
+// New Pool Listener
+//
+// Pools created after startup are invisible to discovery, and that is where
+// the largest mispricings appear. This listener adds them to the live graph
+// without a restart:
+// 1. `logsSubscribe` to every venue whose adapter declares pool-initialization
+//    log lines (`DexAdapter::pool_init_logs`)
+// 2. On a matching transaction, fetch it and decode its accounts owned by the
+//    venue program to find the new pool
+// 3. Load the pool's dependent accounts (vaults, mints, ...) and run the
+//    safety checks: the pool must pair a configured mint, the other mint may
+//    have no mint or freeze authority (nor a routing-restricting extension),
+//    and the configured side must hold a minimum liquidity
+// 4. Insert the pool's edges into the graph and hand it to the `PoolMonitor`
+
+use anyhow::{Result, anyhow};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::Signature,
+};
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::chain::pool_monitor::PoolMonitor;
+use crate::dex::adapter::DexRegistry;
+use crate::dex::pool_fetcher::{PoolData, PoolDataFetcher};
+use crate::dex::triangular_arb::SharedArbitrageGraph;
+
+/// Attempts to fetch a just-confirmed transaction before giving up
+const TRANSACTION_FETCH_ATTEMPTS: u32 = 3;
+
+/// Delay before re-subscribing after a dropped log subscription
+const RECONNECT_DELAY_MS: u64 = 2000;
+
+/// Checks a new pool must pass before it is traded
+#[derive(Clone, Debug)]
+pub struct PoolSafetyConfig {
+    /// Reject pools whose unconfigured mint still has a mint authority
+    pub reject_mint_authority: bool,
+    /// Reject pools whose unconfigured mint still has a freeze authority
+    pub reject_freeze_authority: bool,
+    /// Minimum reserve on each configured-mint side, in whole tokens
+    pub min_liquidity: f64,
+}
+
+impl Default for PoolSafetyConfig {
+    fn default() -> Self {
+        Self {
+            reject_mint_authority: true,
+            reject_freeze_authority: true,
+            min_liquidity: 100.0,
+        }
+    }
+}
+
+/// Whether `log` is one of the pool-initialization `markers`, or continues
+/// one after a space
+pub fn is_pool_init_log(markers: &[&str], log: &str) -> bool {
+    markers.iter().any(|marker| {
+        log.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+/// Safety checks for a newly created pool whose dependent accounts (mints
+/// included) have been loaded. Configured mints are trusted; the other mint
+/// is screened for authorities that let its issuer inflate or freeze it.
+pub fn check_new_pool(pool: &PoolData, trusted_mints: &HashSet<Pubkey>, safety: &PoolSafetyConfig) -> Result<()> {
+    if !trusted_mints.contains(&pool.token_a) && !trusted_mints.contains(&pool.token_b) {
+        return Err(anyhow!("pool pairs no configured mint"));
+    }
+    if let Some(reason) = pool.mints.routing_restriction() {
+        return Err(anyhow!(reason));
+    }
+
+    let sides = [
+        (pool.token_a, &pool.mints.a, pool.reserve_a),
+        (pool.token_b, &pool.mints.b, pool.reserve_b),
+    ];
+    for (mint, info, reserve) in sides {
+        if trusted_mints.contains(&mint) {
+            let min_reserve = safety.min_liquidity * 10f64.powi(info.decimals as i32);
+            if (reserve as f64) < min_reserve {
+                return Err(anyhow!("reserve {} of {} is below the minimum {}", reserve, mint, min_reserve));
+            }
+            continue;
+        }
+        if safety.reject_mint_authority {
+            if let Some(authority) = info.mint_authority {
+                return Err(anyhow!("mint {} still has mint authority {}", mint, authority));
+            }
+        }
+        if safety.reject_freeze_authority {
+            if let Some(authority) = info.freeze_authority {
+                return Err(anyhow!("mint {} has freeze authority {}", mint, authority));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decode the accounts of an initialization transaction that belong to
+/// `program_id` and are not tracked yet
+pub fn new_pool_candidates(
+    registry: &DexRegistry,
+    program_id: &Pubkey,
+    accounts: &[(Pubkey, Option<Account>)],
+    known_pools: &HashSet<Pubkey>,
+) -> Vec<PoolData> {
+    accounts.iter()
+        .filter(|(address, _)| !known_pools.contains(address))
+        .filter_map(|(address, account)| {
+            let account = account.as_ref().filter(|account| account.owner == *program_id)?;
+            registry.decode_pool(address, account).ok()
+        })
+        .collect()
+}
+
+/// Watches the supported AMM programs for new pools and adds the safe ones
+/// to the graph and the pool monitor
+#[derive(Clone)]
+pub struct NewPoolListener {
+    pubsub_url: String,
+    rpc_client: Arc<RpcClient>,
+    pool_fetcher: Arc<PoolDataFetcher>,
+    graph: SharedArbitrageGraph,
+    pool_monitor: Arc<PoolMonitor>,
+    trusted_mints: HashSet<Pubkey>,
+    safety: PoolSafetyConfig,
+    known_pools: Arc<Mutex<HashSet<Pubkey>>>,
+}
+
+impl NewPoolListener {
+    /// Create a listener. `known_pools` are the pools already traded; new
+    /// pools must pair one of `trusted_mints`.
+    pub fn new(
+        pubsub_url: String,
+        rpc_client: Arc<RpcClient>,
+        pool_fetcher: Arc<PoolDataFetcher>,
+        graph: SharedArbitrageGraph,
+        pool_monitor: Arc<PoolMonitor>,
+        trusted_mints: impl IntoIterator<Item = Pubkey>,
+        known_pools: impl IntoIterator<Item = Pubkey>,
+    ) -> Self {
+        Self {
+            pubsub_url,
+            rpc_client,
+            pool_fetcher,
+            graph,
+            pool_monitor,
+            trusted_mints: trusted_mints.into_iter().collect(),
+            safety: PoolSafetyConfig::default(),
+            known_pools: Arc::new(Mutex::new(known_pools.into_iter().collect())),
+        }
+    }
+
+    /// Create with custom safety checks
+    pub fn with_safety(mut self, safety: PoolSafetyConfig) -> Self {
+        self.safety = safety;
+        self
+    }
+
+    /// Listen on every venue that declares pool-initialization logs until
+    /// all subscriptions end
+    pub async fn start(&self) -> Result<()> {
+        let registry = self.pool_fetcher.registry();
+        let mut program_ids: Vec<Pubkey> = registry.program_ids().into_iter()
+            .filter(|program_id| registry.get(program_id).is_some_and(|adapter| !adapter.pool_init_logs().is_empty()))
+            .collect();
+        program_ids.sort();
+        if program_ids.is_empty() {
+            return Err(anyhow!("No registered venue declares pool-initialization logs"));
+        }
+
+        info!("Listening for new pools on {} programs", program_ids.len());
+        let pubsub_client = Arc::new(PubsubClient::new(&self.pubsub_url).await
+            .map_err(|e| anyhow!("Failed to connect to WebSocket: {}", e))?);
+
+        let handles: Vec<_> = program_ids.into_iter()
+            .map(|program_id| {
+                let listener = self.clone();
+                let pubsub_client = Arc::clone(&pubsub_client);
+                tokio::spawn(async move {
+                    loop {
+                        if let Err(e) = listener.listen_program(&pubsub_client, program_id).await {
+                            warn!("New-pool subscription for {} failed: {}", program_id, e);
+                        }
+                        tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY_MS)).await;
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.map_err(|e| anyhow!("New-pool listener task failed: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Follow the logs of one program and handle its pool initializations
+    async fn listen_program(&self, pubsub_client: &PubsubClient, program_id: Pubkey) -> Result<()> {
+        let adapter = self.pool_fetcher.registry().adapter(&program_id)?.clone();
+        let markers = adapter.pool_init_logs();
+
+        let (mut stream, _unsubscribe) = pubsub_client
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+                RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) },
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to subscribe to logs of {}: {}", program_id, e))?;
+        info!("Watching {} ({}) for new pools", adapter.dex_type(), program_id);
+
+        use tokio_stream::StreamExt as _;
+        while let Some(response) = stream.next().await {
+            let logs = response.value;
+            if logs.err.is_some() || !logs.logs.iter().any(|log| is_pool_init_log(markers, log)) {
+                continue;
+            }
+
+            debug!("Pool initialization on {} in {}", adapter.dex_type(), logs.signature);
+            if let Err(e) = self.handle_initialization(&program_id, &logs.signature).await {
+                warn!("Failed to add pool created in {}: {}", logs.signature, e);
+            }
+        }
+
+        Err(anyhow!("Log subscription of {} ended", program_id))
+    }
+
+    /// Find, check and add the pools created by one transaction
+    async fn handle_initialization(&self, program_id: &Pubkey, signature: &str) -> Result<()> {
+        let keys = self.transaction_accounts(signature).await?;
+        let response = self.rpc_client
+            .get_multiple_accounts_with_commitment(&keys, CommitmentConfig::confirmed())
+            .await
+            .map_err(|e| anyhow!("Failed to fetch accounts of {}: {}", signature, e))?;
+        let slot = response.context.slot;
+        let accounts: Vec<(Pubkey, Option<Account>)> = keys.into_iter().zip(response.value).collect();
+
+        let candidates = {
+            let known_pools = self.known_pools.lock()
+                .map_err(|e| anyhow!("Known pool set lock poisoned: {}", e))?;
+            new_pool_candidates(self.pool_fetcher.registry(), program_id, &accounts, &known_pools)
+        };
+
+        for pool in candidates {
+            let mut pool = pool.at_slot(slot);
+            self.pool_fetcher.refresh_dependent_accounts(&mut pool).await?;
+            match check_new_pool(&pool, &self.trusted_mints, &self.safety) {
+                Ok(()) => self.add_pool(&pool)?,
+                Err(reason) => info!("Skipping new {} pool {}: {}", pool.dex_type, pool.pool_address, reason),
+            }
+        }
+        Ok(())
+    }
+
+    /// Static account keys of a confirmed transaction, retried briefly while
+    /// the RPC node catches up with the log notification
+    async fn transaction_accounts(&self, signature: &str) -> Result<Vec<Pubkey>> {
+        let signature = Signature::from_str(signature)
+            .map_err(|e| anyhow!("Invalid signature {}: {}", signature, e))?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+
+        let mut attempt = 0;
+        let transaction = loop {
+            attempt += 1;
+            match self.rpc_client.get_transaction_with_config(&signature, config).await {
+                Ok(transaction) => break transaction,
+                Err(e) if attempt < TRANSACTION_FETCH_ATTEMPTS => {
+                    debug!("Transaction {} not available yet (attempt {}): {}", signature, attempt, e);
+                    tokio::time::sleep(Duration::from_millis(500 * attempt as u64)).await;
+                }
+                Err(e) => return Err(anyhow!("Failed to fetch transaction {}: {}", signature, e)),
+            }
+        };
+
+        let transaction = transaction.transaction.transaction.decode()
+            .ok_or_else(|| anyhow!("Failed to decode transaction {}", signature))?;
+        Ok(transaction.message.static_account_keys().to_vec())
+    }
+
+    /// Insert a checked pool into the graph and start monitoring it
+    fn add_pool(&self, pool: &PoolData) -> Result<()> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        {
+            let mut graph = self.graph.write()
+                .map_err(|e| anyhow!("Failed to acquire graph lock: {}", e))?;
+            graph.upsert_pool(self.pool_fetcher.registry(), pool, timestamp)?;
+        }
+        match self.known_pools.lock() {
+            Ok(mut known_pools) => {
+                known_pools.insert(pool.pool_address);
+            }
+            Err(e) => error!("Known pool set lock poisoned: {}", e),
+        }
+        self.pool_monitor.add_pool(pool.pool_address);
+
+        info!(
+            "🆕 Added new {} pool {} ({} / {})",
+            pool.dex_type, pool.pool_address, pool.token_a, pool.token_b
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::meteora::{DAMM_POOL_MIN_LEN, METEORA_DAMM_PROGRAM_ID};
+    use crate::dex::token_2022::MintInfo;
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    /// New pool pairing untrusted token 1 (6 decimals) with trusted token 2 (9 decimals)
+    fn create_new_pool(reserve_b: u64) -> PoolData {
+        let registry = DexRegistry::default();
+        let mut data = vec![0u8; DAMM_POOL_MIN_LEN];
+        data[40..72].copy_from_slice(create_test_pubkey(1).as_ref());
+        data[72..104].copy_from_slice(create_test_pubkey(2).as_ref());
+        let account = Account { lamports: 1, data, owner: METEORA_DAMM_PROGRAM_ID, executable: false, rent_epoch: 0 };
+
+        let mut pool = registry.decode_pool(&create_test_pubkey(9), &account).unwrap();
+        pool.reserve_a = 1_000_000_000;
+        pool.reserve_b = reserve_b;
+        pool.mints.a = MintInfo { decimals: 6, ..MintInfo::default() };
+        pool.mints.b = MintInfo { decimals: 9, ..MintInfo::default() };
+        pool
+    }
+
+    #[test]
+    fn test_pool_init_log_markers() {
+        let markers = ["Program log: Instruction: Initialize", "Program log: initialize2:"];
+        assert!(is_pool_init_log(&markers, "Program log: Instruction: Initialize"));
+        assert!(is_pool_init_log(&markers, "Program log: initialize2: InitializeInstruction2 { nonce: 254 }"));
+        // Token program instructions share the prefix but are other instructions
+        assert!(!is_pool_init_log(&markers, "Program log: Instruction: InitializeAccount3"));
+        assert!(!is_pool_init_log(&[], "Program log: Instruction: Initialize"));
+    }
+
+    #[test]
+    fn test_new_pool_safety_checks() {
+        let trusted = HashSet::from([create_test_pubkey(2)]);
+        let safety = PoolSafetyConfig { min_liquidity: 10.0, ..PoolSafetyConfig::default() };
+
+        let pool = create_new_pool(10_000_000_000);
+        assert!(check_new_pool(&pool, &trusted, &safety).is_ok());
+
+        // Below 10 whole tokens of the configured mint
+        let thin = create_new_pool(9_999_999_999);
+        assert!(check_new_pool(&thin, &trusted, &safety).unwrap_err().to_string().contains("below the minimum"));
+
+        let mut mintable = pool.clone();
+        mintable.mints.a.mint_authority = Some(create_test_pubkey(5));
+        assert!(check_new_pool(&mintable, &trusted, &safety).unwrap_err().to_string().contains("mint authority"));
+
+        let mut freezable = pool.clone();
+        freezable.mints.a.freeze_authority = Some(create_test_pubkey(5));
+        assert!(check_new_pool(&freezable, &trusted, &safety).is_err());
+        let lenient = PoolSafetyConfig { reject_freeze_authority: false, ..safety.clone() };
+        assert!(check_new_pool(&freezable, &trusted, &lenient).is_ok());
+
+        // Authorities of a configured mint are trusted
+        let mut trusted_authority = pool.clone();
+        trusted_authority.mints.b.freeze_authority = Some(create_test_pubkey(5));
+        assert!(check_new_pool(&trusted_authority, &trusted, &safety).is_ok());
+
+        assert!(check_new_pool(&pool, &HashSet::new(), &safety).is_err());
+    }
+
+    #[test]
+    fn test_new_pool_candidates() {
+        let registry = DexRegistry::default();
+        let pool = create_new_pool(0);
+        let mut data = vec![0u8; DAMM_POOL_MIN_LEN];
+        data[40..72].copy_from_slice(pool.token_a.as_ref());
+        let pool_account = Account { lamports: 1, data, owner: METEORA_DAMM_PROGRAM_ID, executable: false, rent_epoch: 0 };
+        let other_account = Account { owner: spl_token::id(), ..pool_account.clone() };
+
+        let accounts = vec![
+            (create_test_pubkey(9), Some(pool_account.clone())),
+            (create_test_pubkey(10), Some(other_account)),
+            (create_test_pubkey(11), None),
+            (create_test_pubkey(12), Some(pool_account)),
+        ];
+        let known = HashSet::from([create_test_pubkey(12)]);
+
+        let candidates = new_pool_candidates(&registry, &METEORA_DAMM_PROGRAM_ID, &accounts, &known);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].pool_address, create_test_pubkey(9));
+        assert_eq!(candidates[0].token_a, pool.token_a);
+    }
+}
@@ -0,0 +1,202 @@
+// Opportunity Deduplication and In-Flight Tracking
+//
+// Detection runs on every pool update and re-finds the same cycle until its
+// pools change, so the detector would keep emitting one opportunity. An
+// opportunity is identified by the pools it trades, in order and with the
+// direction of each swap (`OpportunityKey`); `OpportunityCooldown` lets a key
+// through at most once per window.
+//
+// Executing two routes that share a pool at the same time makes the second
+// one trade against state the first has already moved. `InFlightRegistry`
+// holds the pools of every route with a pending transaction; a route that
+// touches any of them is refused until the pending one is released (on
+// confirmation or failure) or its claim expires.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+
+use crate::dex::triangular_arb::ArbitrageCycle;
+
+/// Identity of an opportunity: each pool it swaps through, in order, with
+/// the token it sells into that pool. Split legs count as separate pools.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OpportunityKey(Vec<(Pubkey, Pubkey)>);
+
+impl OpportunityKey {
+    pub fn of(cycle: &ArbitrageCycle) -> Self {
+        Self(
+            cycle.path.iter()
+                .flat_map(|step| step.pools().into_iter().map(move |pool| (pool, step.from_token)))
+                .collect(),
+        )
+    }
+
+    /// Pools the opportunity trades through
+    pub fn pools(&self) -> impl Iterator<Item = &Pubkey> {
+        self.0.iter().map(|(pool, _)| pool)
+    }
+
+    fn shares_pool_with(&self, other: &OpportunityKey) -> bool {
+        self.pools().any(|pool| other.pools().any(|other_pool| other_pool == pool))
+    }
+}
+
+/// Lets each opportunity through at most once per cooldown window
+pub struct OpportunityCooldown {
+    window: Duration,
+    last_emitted: HashMap<OpportunityKey, Instant>,
+}
+
+impl OpportunityCooldown {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_emitted: HashMap::new(),
+        }
+    }
+
+    /// Whether `key` may be emitted now; if so, its window starts over
+    pub fn allow(&mut self, key: &OpportunityKey) -> bool {
+        let now = Instant::now();
+        let window = self.window;
+        self.last_emitted.retain(|_, emitted| now.duration_since(*emitted) < window);
+
+        if self.last_emitted.contains_key(key) {
+            return false;
+        }
+        self.last_emitted.insert(key.clone(), now);
+        true
+    }
+
+    /// Opportunities currently cooling down
+    pub fn len(&self) -> usize {
+        self.last_emitted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last_emitted.is_empty()
+    }
+}
+
+/// Routes with a pending transaction, keyed by opportunity
+pub struct InFlightRegistry {
+    ttl: Duration,
+    claims: HashMap<OpportunityKey, Instant>,
+}
+
+/// Thread-safe wrapper for InFlightRegistry
+pub type SharedInFlight = Arc<Mutex<InFlightRegistry>>;
+
+impl InFlightRegistry {
+    /// Registry whose claims expire after `ttl` if never released
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            claims: HashMap::new(),
+        }
+    }
+
+    /// Claim the route of `key` for execution. Refused if the route, or
+    /// another route sharing one of its pools, is still in flight.
+    pub fn try_claim(&mut self, key: &OpportunityKey) -> bool {
+        let now = Instant::now();
+        let ttl = self.ttl;
+        self.claims.retain(|claimed, claimed_at| {
+            let live = now.duration_since(*claimed_at) < ttl;
+            if !live {
+                debug!("In-flight claim on {} pools expired", claimed.pools().count());
+            }
+            live
+        });
+
+        if self.claims.keys().any(|claimed| claimed.shares_pool_with(key)) {
+            return false;
+        }
+        self.claims.insert(key.clone(), now);
+        true
+    }
+
+    /// Release the route of `key` once its transaction has settled
+    pub fn release(&mut self, key: &OpportunityKey) {
+        self.claims.remove(key);
+    }
+
+    /// Routes currently in flight
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::triangular_arb::{ArbitrageGraph, DexType, ExchangeEdge};
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn key(hops: &[(u8, u8, u8)]) -> OpportunityKey {
+        let mut graph = ArbitrageGraph::new();
+        for &(from, to, pool) in hops {
+            graph.add_edge(ExchangeEdge::new(
+                create_test_pubkey(from), create_test_pubkey(to), DexType::Raydium, create_test_pubkey(pool),
+                1.0, 25, vec![], 1000,
+            ));
+        }
+        let edges: Vec<_> = hops.iter()
+            .map(|&(from, to, pool)| (create_test_pubkey(from), create_test_pubkey(to), create_test_pubkey(pool)))
+            .collect();
+        OpportunityKey::of(&graph.cycle_through(&edges).unwrap())
+    }
+
+    #[test]
+    fn test_cooldown_suppresses_repeats_of_the_same_route() {
+        let forward = key(&[(1, 2, 101), (2, 1, 102)]);
+        // Same pools traded the other way round is a different opportunity
+        let reverse = key(&[(1, 2, 102), (2, 1, 101)]);
+        assert_ne!(forward, reverse);
+
+        let mut cooldown = OpportunityCooldown::new(Duration::from_secs(60));
+        assert!(cooldown.allow(&forward));
+        assert!(!cooldown.allow(&forward));
+        assert!(cooldown.allow(&reverse));
+        assert_eq!(cooldown.len(), 2);
+
+        let mut no_cooldown = OpportunityCooldown::new(Duration::ZERO);
+        assert!(no_cooldown.allow(&forward));
+        assert!(no_cooldown.allow(&forward));
+    }
+
+    #[test]
+    fn test_in_flight_routes_block_routes_sharing_a_pool() {
+        let first = key(&[(1, 2, 101), (2, 1, 102)]);
+        let overlapping = key(&[(1, 3, 103), (3, 2, 104), (2, 1, 102)]);
+        let disjoint = key(&[(1, 3, 103), (3, 1, 105)]);
+
+        let mut registry = InFlightRegistry::new(Duration::from_secs(60));
+        assert!(registry.try_claim(&first));
+        assert!(!registry.try_claim(&first));
+        assert!(!registry.try_claim(&overlapping));
+        assert!(registry.try_claim(&disjoint));
+
+        // Confirmation releases the pools
+        registry.release(&first);
+        assert!(!registry.try_claim(&overlapping)); // still shares pool 103 with `disjoint`
+        registry.release(&disjoint);
+        assert!(registry.try_claim(&overlapping));
+        assert_eq!(registry.len(), 1);
+
+        // Claims that are never released expire
+        let mut expiring = InFlightRegistry::new(Duration::ZERO);
+        assert!(expiring.try_claim(&first));
+        assert!(expiring.try_claim(&overlapping));
+    }
+}
@@ -0,0 +1,422 @@
+// Pool Discovery
+//
+// Builds the pool universe from the configured mints instead of hand-listed
+// pool addresses:
+// 1. For every pair of configured mints and every registered venue with a
+//    `MintLayout`, query `getProgramAccounts` with memcmp filters on both mint
+//    offsets (once per mint order), plus the account size where it is fixed.
+// 2. Classify each returned account by its owner program and keep it only if
+//    that venue's decoder accepts it.
+// 3. Persist the result to a JSON pool registry file, reused on restart as
+//    long as the configured mints have not changed.
+//
+// Pools listed explicitly with `MINT_X_POOLS` are classified the same way and
+// added on top of the discovered ones.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::dex::adapter::{DexRegistry, MintLayout};
+use crate::dex::triangular_arb::DexType;
+
+/// Maximum accounts per `getMultipleAccounts` call
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// A pool found by discovery or listed in the configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredPool {
+    pub address: Pubkey,
+    /// Owner program of the pool account
+    pub program_id: Pubkey,
+    pub dex_type: DexType,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+}
+
+/// `getProgramAccounts` filters matching pools with `mint_a` as token A and
+/// `mint_b` as token B
+pub fn mint_pair_filters(layout: &MintLayout, mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = Vec::with_capacity(3);
+    if let Some(size) = layout.data_size {
+        filters.push(RpcFilterType::DataSize(size));
+    }
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(layout.mint_a_offset, mint_a.as_ref())));
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(layout.mint_b_offset, mint_b.as_ref())));
+    filters
+}
+
+/// Every ordered pair of distinct mints
+fn mint_pairs(mints: &[Pubkey]) -> Vec<(Pubkey, Pubkey)> {
+    let mut unique = Vec::with_capacity(mints.len());
+    for mint in mints {
+        if !unique.contains(mint) {
+            unique.push(*mint);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (i, mint_a) in unique.iter().enumerate() {
+        for mint_b in &unique[i + 1..] {
+            pairs.push((*mint_a, *mint_b));
+            pairs.push((*mint_b, *mint_a));
+        }
+    }
+    pairs
+}
+
+/// Classify a pool account by its owner program. `None` when no adapter is
+/// registered for the owner or its decoder rejects the account.
+pub fn classify_account(registry: &DexRegistry, address: &Pubkey, account: &Account) -> Option<DiscoveredPool> {
+    let adapter = registry.get(&account.owner)?;
+    match adapter.decode_pool(address, &account.data) {
+        Ok(pool) => Some(DiscoveredPool {
+            address: *address,
+            program_id: account.owner,
+            dex_type: adapter.dex_type(),
+            token_a: pool.token_a,
+            token_b: pool.token_b,
+        }),
+        Err(e) => {
+            debug!("Skipping {} account {}: {}", adapter.dex_type(), address, e);
+            None
+        }
+    }
+}
+
+/// Finds and classifies the pools trading the configured mints
+pub struct PoolDiscovery {
+    rpc_client: Arc<RpcClient>,
+    registry: Arc<DexRegistry>,
+}
+
+impl PoolDiscovery {
+    pub fn new(rpc_client: Arc<RpcClient>, registry: Arc<DexRegistry>) -> Self {
+        Self { rpc_client, registry }
+    }
+
+    /// Pools to trade: the discovered pools for the configured mints (when
+    /// discovery is enabled) plus every pool listed in the configuration
+    pub async fn pool_universe(&self, config: &Config) -> Result<Vec<DiscoveredPool>> {
+        let mints: Vec<Pubkey> = config.mints.iter().map(|mint| mint.address).collect();
+        let mut pools = if config.discovery.enabled {
+            self.load_or_discover(&mints, &config.discovery.registry_path, config.discovery.refresh).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut known: HashSet<Pubkey> = pools.iter().map(|pool| pool.address).collect();
+        let configured: Vec<Pubkey> = config.mints.iter()
+            .flat_map(|mint| mint.pools.iter().copied())
+            .filter(|address| known.insert(*address))
+            .collect();
+        if !configured.is_empty() {
+            pools.extend(self.classify(&configured).await?);
+        }
+
+        Ok(pools)
+    }
+
+    /// Pools from the registry file at `path` when it was built for `mints`,
+    /// otherwise a fresh discovery, which is then written to `path`
+    pub async fn load_or_discover(&self, mints: &[Pubkey], path: impl AsRef<Path>, refresh: bool) -> Result<Vec<DiscoveredPool>> {
+        let path = path.as_ref();
+        if !refresh {
+            match PoolRegistryFile::load(path).await {
+                Ok(Some(file)) if file.matches_mints(mints) => {
+                    let pools = file.pools(&self.registry);
+                    info!("Loaded {} pools from pool registry {}", pools.len(), path.display());
+                    return Ok(pools);
+                }
+                Ok(Some(_)) => info!("Pool registry {} was built for other mints, rediscovering", path.display()),
+                Ok(None) => {}
+                Err(e) => warn!("Ignoring unreadable pool registry {}: {}", path.display(), e),
+            }
+        }
+
+        let pools = self.discover(mints).await?;
+        if let Err(e) = PoolRegistryFile::new(mints, &pools).save(path).await {
+            warn!("Failed to write pool registry {}: {}", path.display(), e);
+        }
+        Ok(pools)
+    }
+
+    /// Pools trading any pair of `mints` on every venue with a mint layout.
+    /// A program the RPC refuses to scan is skipped with a warning.
+    pub async fn discover(&self, mints: &[Pubkey]) -> Result<Vec<DiscoveredPool>> {
+        let pairs = mint_pairs(mints);
+        let mut program_ids = self.registry.program_ids();
+        program_ids.sort();
+
+        let mut pools = Vec::new();
+        let mut seen = HashSet::new();
+        for program_id in program_ids {
+            let adapter = self.registry.adapter(&program_id)?;
+            let layout = match adapter.mint_layout() {
+                Some(layout) => layout,
+                None => continue,
+            };
+            debug!("Discovering {} pools for {} mint pairs", adapter.dex_type(), pairs.len() / 2);
+
+            for (mint_a, mint_b) in &pairs {
+                let config = RpcProgramAccountsConfig {
+                    filters: Some(mint_pair_filters(&layout, mint_a, mint_b)),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        data_slice: None,
+                        min_context_slot: None,
+                    },
+                    with_context: None,
+                };
+                let accounts = match self.rpc_client.get_program_accounts_with_config(&program_id, config).await {
+                    Ok(accounts) => accounts,
+                    Err(e) => {
+                        warn!("getProgramAccounts for {} ({}) failed: {}", adapter.dex_type(), program_id, e);
+                        break;
+                    }
+                };
+
+                for (address, account) in accounts {
+                    if !seen.insert(address) {
+                        continue;
+                    }
+                    if let Some(pool) = classify_account(&self.registry, &address, &account) {
+                        debug!("Discovered {} pool {} ({} / {})", pool.dex_type, address, pool.token_a, pool.token_b);
+                        pools.push(pool);
+                    }
+                }
+            }
+        }
+
+        info!("Discovered {} pools for {} mints", pools.len(), mints.len());
+        Ok(pools)
+    }
+
+    /// Classify explicitly listed pools by the owner of their accounts
+    pub async fn classify(&self, addresses: &[Pubkey]) -> Result<Vec<DiscoveredPool>> {
+        let mut pools = Vec::new();
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.rpc_client.get_multiple_accounts(chunk).await
+                .context("Failed to fetch configured pool accounts")?;
+            for (address, account) in chunk.iter().zip(accounts) {
+                match account.and_then(|account| classify_account(&self.registry, address, &account)) {
+                    Some(pool) => pools.push(pool),
+                    None => warn!("Configured pool {} is not a supported pool account, skipping", address),
+                }
+            }
+        }
+        Ok(pools)
+    }
+}
+
+/// One pool in the registry file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolRegistryEntry {
+    pub address: String,
+    pub program_id: String,
+    /// Venue name for readers of the file; pools are reclassified by
+    /// `program_id` when loaded
+    pub dex_type: String,
+    pub token_a: String,
+    pub token_b: String,
+}
+
+/// Pool registry file (JSON) written by discovery. Addresses are base58.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PoolRegistryFile {
+    /// Unix timestamp (seconds) of the discovery run
+    pub updated_at: i64,
+    /// Mints the pools were discovered for
+    pub mints: Vec<String>,
+    pub pools: Vec<PoolRegistryEntry>,
+}
+
+impl PoolRegistryFile {
+    pub fn new(mints: &[Pubkey], pools: &[DiscoveredPool]) -> Self {
+        Self {
+            updated_at: chrono::Utc::now().timestamp(),
+            mints: mints.iter().map(|mint| mint.to_string()).collect(),
+            pools: pools.iter()
+                .map(|pool| PoolRegistryEntry {
+                    address: pool.address.to_string(),
+                    program_id: pool.program_id.to_string(),
+                    dex_type: pool.dex_type.to_string(),
+                    token_a: pool.token_a.to_string(),
+                    token_b: pool.token_b.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Read the registry at `path`; `None` if the file does not exist
+    pub async fn load(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let json = match tokio::fs::read_to_string(path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read pool registry {}", path.display())),
+        };
+        let file = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse pool registry {}", path.display()))?;
+        Ok(Some(file))
+    }
+
+    /// Write the registry to `path`, creating its directory if needed
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await
+                .context("Failed to create pool registry directory")?;
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize pool registry")?;
+        tokio::fs::write(path, json).await
+            .with_context(|| format!("Failed to write pool registry {}", path.display()))?;
+        debug!("Saved {} pools to pool registry {}", self.pools.len(), path.display());
+        Ok(())
+    }
+
+    /// Whether the registry was discovered for exactly `mints` (in any order)
+    pub fn matches_mints(&self, mints: &[Pubkey]) -> bool {
+        let stored: HashSet<&str> = self.mints.iter().map(String::as_str).collect();
+        let wanted: Vec<String> = mints.iter().map(|mint| mint.to_string()).collect();
+        stored.len() == wanted.len() && wanted.iter().all(|mint| stored.contains(mint.as_str()))
+    }
+
+    /// Pools of the registry, classified by their program. Entries that do
+    /// not parse or whose program has no registered adapter are dropped.
+    pub fn pools(&self, registry: &DexRegistry) -> Vec<DiscoveredPool> {
+        self.pools.iter()
+            .filter_map(|entry| {
+                let parsed = (
+                    Pubkey::from_str(&entry.address),
+                    Pubkey::from_str(&entry.program_id),
+                    Pubkey::from_str(&entry.token_a),
+                    Pubkey::from_str(&entry.token_b),
+                );
+                let (address, program_id, token_a, token_b) = match parsed {
+                    (Ok(address), Ok(program_id), Ok(token_a), Ok(token_b)) => (address, program_id, token_a, token_b),
+                    _ => {
+                        warn!("Skipping malformed pool registry entry {:?}", entry);
+                        return None;
+                    }
+                };
+                let adapter = match registry.get(&program_id) {
+                    Some(adapter) => adapter,
+                    None => {
+                        warn!("Skipping pool {} of unsupported program {}", address, program_id);
+                        return None;
+                    }
+                };
+                Some(DiscoveredPool {
+                    address,
+                    program_id,
+                    dex_type: adapter.dex_type(),
+                    token_a,
+                    token_b,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::meteora::{DAMM_POOL_MIN_LEN, METEORA_DAMM_PROGRAM_ID};
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn create_damm_account(owner: Pubkey, token_a: &Pubkey, token_b: &Pubkey) -> Account {
+        let mut data = vec![0u8; DAMM_POOL_MIN_LEN];
+        data[40..72].copy_from_slice(token_a.as_ref());
+        data[72..104].copy_from_slice(token_b.as_ref());
+        Account { lamports: 1, data, owner, executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn test_mint_pair_filters() {
+        let (mint_a, mint_b) = (create_test_pubkey(1), create_test_pubkey(2));
+        let layout = MintLayout { data_size: Some(752), mint_a_offset: 400, mint_b_offset: 432 };
+
+        assert_eq!(mint_pair_filters(&layout, &mint_a, &mint_b), vec![
+            RpcFilterType::DataSize(752),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(400, mint_a.as_ref())),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(432, mint_b.as_ref())),
+        ]);
+
+        let layout = MintLayout { data_size: None, ..layout };
+        assert_eq!(mint_pair_filters(&layout, &mint_a, &mint_b).len(), 2);
+
+        // Both orders of every pair, duplicates ignored
+        let mints = [mint_a, mint_b, create_test_pubkey(3), mint_a];
+        let pairs = mint_pairs(&mints);
+        assert_eq!(pairs.len(), 6);
+        assert!(pairs.contains(&(mint_b, mint_a)));
+        assert!(pairs.iter().all(|(a, b)| a != b));
+    }
+
+    #[test]
+    fn test_classify_account_by_owner() {
+        let registry = DexRegistry::default();
+        let address = create_test_pubkey(9);
+        let (token_a, token_b) = (create_test_pubkey(1), create_test_pubkey(2));
+
+        let pool = classify_account(&registry, &address, &create_damm_account(METEORA_DAMM_PROGRAM_ID, &token_a, &token_b))
+            .expect("DAMM pool should be classified");
+        assert_eq!(pool.dex_type, DexType::Meteora);
+        assert_eq!(pool.program_id, METEORA_DAMM_PROGRAM_ID);
+        assert_eq!((pool.token_a, pool.token_b), (token_a, token_b));
+
+        // Unknown owner or an account the decoder rejects
+        assert!(classify_account(&registry, &address, &create_damm_account(create_test_pubkey(7), &token_a, &token_b)).is_none());
+        let mut short = create_damm_account(METEORA_DAMM_PROGRAM_ID, &token_a, &token_b);
+        short.data.truncate(100);
+        assert!(classify_account(&registry, &address, &short).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_registry_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry/pools.json");
+        assert!(PoolRegistryFile::load(&path).await.unwrap().is_none());
+
+        let registry = DexRegistry::default();
+        let mints = [create_test_pubkey(1), create_test_pubkey(2)];
+        let pool = DiscoveredPool {
+            address: create_test_pubkey(9),
+            program_id: METEORA_DAMM_PROGRAM_ID,
+            dex_type: DexType::Meteora,
+            token_a: mints[0],
+            token_b: mints[1],
+        };
+        let mut file = PoolRegistryFile::new(&mints, std::slice::from_ref(&pool));
+        file.pools.push(PoolRegistryEntry {
+            address: create_test_pubkey(10).to_string(),
+            program_id: create_test_pubkey(7).to_string(),
+            dex_type: "Unknown".to_string(),
+            token_a: mints[0].to_string(),
+            token_b: mints[1].to_string(),
+        });
+        file.save(&path).await.unwrap();
+
+        let loaded = PoolRegistryFile::load(&path).await.unwrap().unwrap();
+        assert!(loaded.matches_mints(&[mints[1], mints[0]]));
+        assert!(!loaded.matches_mints(&mints[..1]));
+        assert!(!loaded.matches_mints(&[mints[0], mints[1], create_test_pubkey(3)]));
+        // The entry of an unregistered program is dropped
+        assert_eq!(loaded.pools(&registry), vec![pool]);
+    }
+}
@@ -7,9 +7,13 @@
 // Features:
 // - Real-time account change subscriptions
 // - Automatic graph updates on pool state changes
-// - Rate-limited arbitrage detection
+// - Incremental re-scoring: an update re-scores only the indexed candidate
+//   cycles through the changed pool; full rediscovery runs periodically or,
+//   rate-limited, when pools are added or removed
 // - Automatic reconnection on disconnect
 // - Concurrent subscription management
+// - Pools added at runtime (e.g. by the new-pool listener) are subscribed
+//   without a restart
 
 use solana_client::nonblocking::pubsub_client::PubsubClient;
 use solana_client::rpc_config::RpcAccountInfoConfig;
@@ -18,31 +22,40 @@ use solana_sdk::{
     commitment_config::CommitmentConfig,
     pubkey::Pubkey,
 };
-use tokio::sync::mpsc;
-use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use anyhow::{Result, anyhow};
 use tracing::{debug, warn, info, error};
 
-use crate::dex::triangular_arb::{SharedArbitrageGraph, BellmanFordDetector};
+use crate::dex::cycle_index::CycleIndex;
+use crate::dex::triangular_arb::{SharedArbitrageGraph, BellmanFordDetector, ArbitrageCycle};
 use crate::dex::pool_fetcher::{PoolDataFetcher, PoolData};
 
 /// Configuration for pool monitoring
 #[derive(Clone, Debug)]
 pub struct MonitorConfig {
-    pub detection_rate_limit_ms: u64,  // Min time between detections
+    pub detection_rate_limit_ms: u64,  // Min time between full rediscoveries
+    pub rediscovery_interval_ms: u64,  // Full rediscovery even without topology changes
     pub max_reconnect_attempts: usize,
     pub reconnect_delay_ms: u64,
     pub subscription_batch_size: usize,
+    /// Tokens candidate cycles start from
+    pub start_tokens: Vec<Pubkey>,
+    /// Cap on indexed candidate cycles
+    pub max_candidate_cycles: usize,
 }
 
 impl Default for MonitorConfig {
     fn default() -> Self {
         Self {
             detection_rate_limit_ms: 1000,  // 1 second
+            rediscovery_interval_ms: 30_000, // 30 seconds
             max_reconnect_attempts: 10,
             reconnect_delay_ms: 2000,        // 2 seconds
             subscription_batch_size: 50,     // Subscribe 50 at a time
+            start_tokens: vec![solana_sdk::pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")], // USDC
+            max_candidate_cycles: 10_000,
         }
     }
 }
@@ -61,9 +74,15 @@ pub struct PoolMonitor {
     pubsub_url: String,
     graph: SharedArbitrageGraph,
     pool_fetcher: Arc<PoolDataFetcher>,
-    monitored_pools: Vec<Pubkey>,
+    monitored_pools: Arc<RwLock<Vec<Pubkey>>>,
+    /// Pools added after start, picked up by the live subscription manager
+    added_pools_tx: mpsc::UnboundedSender<Pubkey>,
+    added_pools_rx: Arc<Mutex<mpsc::UnboundedReceiver<Pubkey>>>,
     config: MonitorConfig,
     detector: Arc<BellmanFordDetector>,
+    update_signal: Option<mpsc::UnboundedSender<()>>,
+    /// Candidate cycles by edge, rebuilt on rediscovery
+    cycle_index: Arc<RwLock<CycleIndex>>,
 }
 
 impl PoolMonitor {
@@ -76,27 +95,74 @@ impl PoolMonitor {
         detector: Arc<BellmanFordDetector>,
     ) -> Self {
         info!("Initializing PoolMonitor for {} pools", monitored_pools.len());
+        let (added_pools_tx, added_pools_rx) = mpsc::unbounded_channel();
+        let config = MonitorConfig::default();
+        let cycle_index = CycleIndex::new(detector.max_path_length(), config.max_candidate_cycles);
         
         Self {
             pubsub_url,
             graph,
             pool_fetcher,
-            monitored_pools,
-            config: MonitorConfig::default(),
+            monitored_pools: Arc::new(RwLock::new(monitored_pools)),
+            added_pools_tx,
+            added_pools_rx: Arc::new(Mutex::new(added_pools_rx)),
+            config,
             detector,
+            update_signal: None,
+            cycle_index: Arc::new(RwLock::new(cycle_index)),
+        }
+    }
+    
+    /// Start monitoring another pool. Returns false if it is already monitored.
+    ///
+    /// A running monitor subscribes to it immediately; otherwise it is
+    /// included when monitoring starts.
+    pub fn add_pool(&self, pool_address: Pubkey) -> bool {
+        {
+            let mut pools = match self.monitored_pools.write() {
+                Ok(pools) => pools,
+                Err(e) => {
+                    error!("Monitored pool list lock poisoned: {}", e);
+                    return false;
+                }
+            };
+            if pools.contains(&pool_address) {
+                return false;
+            }
+            pools.push(pool_address);
         }
+        
+        info!("Added pool {} to monitoring", pool_address);
+        // The receiver lives as long as any clone of this monitor
+        let _ = self.added_pools_tx.send(pool_address);
+        true
+    }
+    
+    /// Pools currently monitored
+    pub fn monitored_pools(&self) -> Vec<Pubkey> {
+        self.monitored_pools.read().map(|pools| pools.clone()).unwrap_or_default()
     }
     
     /// Create with custom configuration
     pub fn with_config(mut self, config: MonitorConfig) -> Self {
+        self.cycle_index = Arc::new(RwLock::new(
+            CycleIndex::new(self.detector.max_path_length(), config.max_candidate_cycles),
+        ));
         self.config = config;
         self
     }
     
+    /// Signal `update_signal` after every graph update (drives the
+    /// arbitrage detector's detection loop)
+    pub fn with_update_signal(mut self, update_signal: mpsc::UnboundedSender<()>) -> Self {
+        self.update_signal = Some(update_signal);
+        self
+    }
+    
     /// Start monitoring all pools with WebSocket subscriptions
     pub async fn start_monitoring(&self) -> Result<()> {
         info!("Starting WebSocket pool monitoring...");
-        info!("  Pools: {}", self.monitored_pools.len());
+        info!("  Pools: {}", self.monitored_pools().len());
         info!("  Detection rate limit: {}ms", self.config.detection_rate_limit_ms);
         info!("  WebSocket URL: {}", self.pubsub_url);
         
@@ -168,45 +234,60 @@ impl PoolMonitor {
         let pubsub_client = Arc::new(PubsubClient::new(&self.pubsub_url).await
             .map_err(|e| anyhow!("Failed to connect to WebSocket: {}", e))?);
         
-        info!("WebSocket connected, subscribing to {} pools", self.monitored_pools.len());
+        // Pools queued before this (re)connect are part of the snapshot below
+        let mut added_pools = self.added_pools_rx.lock().await;
+        while added_pools.try_recv().is_ok() {}
+        let monitored_pools = self.monitored_pools();
+        
+        info!("WebSocket connected, subscribing to {} pools", monitored_pools.len());
         
         // Batch subscriptions to avoid overwhelming the connection
-        for (i, chunk) in self.monitored_pools.chunks(self.config.subscription_batch_size).enumerate() {
+        let batch_count = monitored_pools.chunks(self.config.subscription_batch_size).len();
+        for (i, chunk) in monitored_pools.chunks(self.config.subscription_batch_size).enumerate() {
             debug!("Subscribing batch {}: {} pools", i + 1, chunk.len());
             
             for pool_address in chunk {
-                let tx_clone = tx.clone();
-                let pool = *pool_address;
-                let pool_fetcher = Arc::clone(&self.pool_fetcher);
-                let pubsub_client = Arc::clone(&pubsub_client);
-                
-                // Spawn subscription task for each pool
-                tokio::spawn(async move {
-                    if let Err(e) = Self::subscribe_single_pool(
-                        pubsub_client,
-                        pool,
-                        tx_clone,
-                        pool_fetcher,
-                    ).await {
-                        warn!("Subscription failed for pool {}: {}", pool, e);
-                    }
-                });
+                self.spawn_subscription(&pubsub_client, *pool_address, tx.clone());
             }
             
             // Small delay between batches
-            if i < self.monitored_pools.chunks(self.config.subscription_batch_size).len() - 1 {
+            if i < batch_count - 1 {
                 tokio::time::sleep(Duration::from_millis(100)).await;
             }
         }
         
         info!("All pool subscriptions initiated");
         
-        // Keep connection alive
-        tokio::time::sleep(Duration::from_secs(u64::MAX)).await;
+        // Keep the connection alive, subscribing pools added at runtime
+        while let Some(pool_address) = added_pools.recv().await {
+            self.spawn_subscription(&pubsub_client, pool_address, tx.clone());
+        }
         
         Ok(())
     }
     
+    /// Spawn the subscription task of one pool on a shared connection
+    fn spawn_subscription(
+        &self,
+        pubsub_client: &Arc<PubsubClient>,
+        pool: Pubkey,
+        tx: mpsc::UnboundedSender<PoolUpdate>,
+    ) {
+        let pool_fetcher = Arc::clone(&self.pool_fetcher);
+        let pubsub_client = Arc::clone(pubsub_client);
+        
+        tokio::spawn(async move {
+            if let Err(e) = Self::subscribe_single_pool(
+                pubsub_client,
+                pool,
+                tx,
+                pool_fetcher,
+            ).await {
+                warn!("Subscription failed for pool {}: {}", pool, e);
+            }
+        });
+    }
+    
     /// Subscribe to a single pool account
     async fn subscribe_single_pool(
         pubsub_client: Arc<PubsubClient>,
@@ -278,22 +359,12 @@ impl PoolMonitor {
         pool_address: &Pubkey,
         account: &Account,
         slot: u64,
-        _pool_fetcher: &PoolDataFetcher,
+        pool_fetcher: &PoolDataFetcher,
     ) -> Result<PoolUpdate> {
-        // Parse pool data directly based on owner program
-        let owner = account.owner;
-        
-        // Simplified parsing - in production, use proper DEX-specific parsers
-        let pool_data = PoolData {
-            pool_address: *pool_address,
-            token_a: Pubkey::default(),
-            token_b: Pubkey::default(),
-            reserve_a: 0,
-            reserve_b: 0,
-            fee_bps: 30,
-            dex_type: crate::dex::triangular_arb::DexType::Raydium,
-            program_id: owner,
-        };
+        // Decode with the venue adapter registered for the owner program,
+        // then reload the accounts it depends on (e.g. vault balances)
+        let mut pool_data = pool_fetcher.decode_pool_account(pool_address, account)?.at_slot(slot);
+        pool_fetcher.refresh_dependent_accounts(&mut pool_data).await?;
         
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -308,16 +379,18 @@ impl PoolMonitor {
         })
     }
     
-    /// Process pool updates and trigger arbitrage detection
+    /// Process pool updates: re-score the candidate cycles through each
+    /// updated pool, and rediscover cycles periodically or after a topology change
     async fn process_updates(&self, mut rx: mpsc::UnboundedReceiver<PoolUpdate>) {
         info!("Starting pool update processor");
         
-        let mut last_detection = Instant::now();
-        let mut updates_since_detection = 0;
+        let mut last_rediscovery: Option<Instant> = None;
+        let mut updates_since_rediscovery = 0;
         let rate_limit_duration = Duration::from_millis(self.config.detection_rate_limit_ms);
+        let rediscovery_interval = Duration::from_millis(self.config.rediscovery_interval_ms);
         
         while let Some(update) = rx.recv().await {
-            updates_since_detection += 1;
+            updates_since_rediscovery += 1;
             
             // Update graph with new pool data
             if let Err(e) = self.update_graph(&update).await {
@@ -328,41 +401,140 @@ impl PoolMonitor {
             debug!("Updated graph with pool {} data from slot {}", 
                 update.pool_address, update.slot);
             
-            // Check if enough time has passed since last detection
+            if let Some(update_signal) = &self.update_signal {
+                if update_signal.send(()).is_err() {
+                    debug!("Detection loop stopped, graph update not signalled");
+                }
+            }
+            
             let now = Instant::now();
-            if now.duration_since(last_detection) >= rate_limit_duration {
-                info!("Triggering arbitrage detection ({} updates accumulated)", 
-                    updates_since_detection);
-                
-                // Trigger detection in background
-                let detector = Arc::clone(&self.detector);
-                let usdc_mint = solana_sdk::pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
-                
-                tokio::spawn(async move {
-                    match detector.detect_arbitrage(usdc_mint).await {
-                        Ok(cycles) => {
-                            if !cycles.is_empty() {
-                                info!("🎯 Found {} arbitrage opportunities!", cycles.len());
-                                for (i, cycle) in cycles.iter().take(5).enumerate() {
-                                    info!("  #{}: {} bps profit, {} hops", 
-                                        i + 1, cycle.gross_profit_bps, cycle.path.len());
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Arbitrage detection failed: {}", e);
+            let topology_changed = self.cycle_index_is_stale();
+            let rediscover = match last_rediscovery {
+                None => true,
+                Some(at) => {
+                    let elapsed = now.duration_since(at);
+                    elapsed >= rediscovery_interval || (topology_changed && elapsed >= rate_limit_duration)
+                }
+            };
+            
+            if rediscover {
+                info!("Triggering cycle rediscovery ({} updates accumulated, topology changed: {})", 
+                    updates_since_rediscovery, topology_changed);
+                self.spawn_rediscovery();
+                last_rediscovery = Some(now);
+                updates_since_rediscovery = 0;
+                continue;
+            }
+            
+            // Only the indexed cycles through this pool can have changed
+            match self.rescore_affected_cycles(&update) {
+                Ok(cycles) => {
+                    if !cycles.is_empty() {
+                        info!("🎯 Found {} arbitrage opportunities through pool {}", 
+                            cycles.len(), update.pool_address);
+                        for (i, cycle) in cycles.iter().take(5).enumerate() {
+                            info!("  #{}: {} bps profit, {} hops", 
+                                i + 1, cycle.gross_profit_bps, cycle.path.len());
                         }
                     }
-                });
-                
-                last_detection = now;
-                updates_since_detection = 0;
+                }
+                Err(e) => {
+                    warn!("Cycle re-scoring failed for pool {}: {}", update.pool_address, e);
+                }
             }
         }
         
         warn!("Pool update processor exiting");
     }
     
+    /// Whether pools were added or removed since the cycle index was built
+    fn cycle_index_is_stale(&self) -> bool {
+        match (self.graph.read(), self.cycle_index.read()) {
+            (Ok(graph), Ok(index)) => index.is_stale(&graph),
+            _ => true,
+        }
+    }
+    
+    /// Re-score the candidate cycles through the updated pool's edges and
+    /// return the profitable ones, best first
+    fn rescore_affected_cycles(&self, update: &PoolUpdate) -> Result<Vec<ArbitrageCycle>> {
+        let started = Instant::now();
+        let pool = &update.new_data;
+        let edges = [
+            (pool.token_a, pool.token_b, pool.pool_address),
+            (pool.token_b, pool.token_a, pool.pool_address),
+        ];
+        
+        let graph = self.graph.read()
+            .map_err(|e| anyhow!("Failed to acquire graph lock: {}", e))?;
+        let index = self.cycle_index.read()
+            .map_err(|e| anyhow!("Failed to acquire cycle index lock: {}", e))?;
+        
+        let cycles: Vec<ArbitrageCycle> = index.rescore(&graph, &edges)
+            .into_iter()
+            .filter(|cycle| self.detector.is_profitable(cycle))
+            .collect();
+        
+        debug!("Re-scored cycles through pool {} in {}µs", 
+            update.pool_address, started.elapsed().as_micros());
+        Ok(cycles)
+    }
+    
+    /// Run full detection in the background and rebuild the cycle index from
+    /// the current graph plus every cycle it found
+    fn spawn_rediscovery(&self) {
+        let graph = Arc::clone(&self.graph);
+        let detector = Arc::clone(&self.detector);
+        let cycle_index = Arc::clone(&self.cycle_index);
+        let start_tokens = self.config.start_tokens.clone();
+        let max_candidate_cycles = self.config.max_candidate_cycles;
+        
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let cycles = match detector.detect_arbitrage_parallel(start_tokens.clone()).await {
+                Ok(cycles) => {
+                    if !cycles.is_empty() {
+                        info!("🎯 Found {} arbitrage opportunities!", cycles.len());
+                        for (i, cycle) in cycles.iter().take(5).enumerate() {
+                            info!("  #{}: {} bps profit, {} hops", 
+                                i + 1, cycle.gross_profit_bps, cycle.path.len());
+                        }
+                    }
+                    cycles
+                }
+                Err(e) => {
+                    warn!("Arbitrage detection failed: {}", e);
+                    Vec::new()
+                }
+            };
+            
+            let rebuilt = match graph.read() {
+                Ok(graph) => {
+                    let mut index = CycleIndex::new(detector.max_path_length(), max_candidate_cycles);
+                    index.rebuild(&graph, &start_tokens);
+                    for cycle in &cycles {
+                        index.insert(cycle);
+                    }
+                    index
+                }
+                Err(e) => {
+                    warn!("Failed to acquire graph lock for cycle index: {}", e);
+                    return;
+                }
+            };
+            
+            let candidates = rebuilt.len();
+            match cycle_index.write() {
+                Ok(mut index) => *index = rebuilt,
+                Err(e) => {
+                    warn!("Failed to acquire cycle index lock: {}", e);
+                    return;
+                }
+            }
+            debug!("Rediscovery indexed {} candidate cycles in {:?}", candidates, started.elapsed());
+        });
+    }
+    
     /// Update arbitrage graph with new pool data
     async fn update_graph(&self, update: &PoolUpdate) -> Result<()> {
         let pool = &update.new_data;
@@ -370,23 +542,8 @@ impl PoolMonitor {
         let mut graph = self.graph.write()
             .map_err(|e| anyhow!("Failed to acquire graph lock: {}", e))?;
         
-        // Update edge: token A -> token B
-        graph.update_edge_rate(
-            pool.token_a,
-            pool.token_b,
-            pool.dex_type.clone(),
-            pool.calculate_rate_a_to_b(),
-            update.timestamp,
-        )?;
-        
-        // Update edge: token B -> token A
-        graph.update_edge_rate(
-            pool.token_b,
-            pool.token_a,
-            pool.dex_type.clone(),
-            pool.calculate_rate_b_to_a(),
-            update.timestamp,
-        )?;
+        // Refresh both directions through the pool's venue adapter
+        graph.upsert_pool(self.pool_fetcher.registry(), pool, update.timestamp)?;
         
         Ok(())
     }
@@ -397,20 +554,26 @@ impl PoolMonitor {
             pubsub_url: self.pubsub_url.clone(),
             graph: Arc::clone(&self.graph),
             pool_fetcher: Arc::clone(&self.pool_fetcher),
-            monitored_pools: self.monitored_pools.clone(),
+            monitored_pools: Arc::clone(&self.monitored_pools),
+            added_pools_tx: self.added_pools_tx.clone(),
+            added_pools_rx: Arc::clone(&self.added_pools_rx),
             config: self.config.clone(),
             detector: Arc::clone(&self.detector),
+            update_signal: self.update_signal.clone(),
+            cycle_index: Arc::clone(&self.cycle_index),
         }
     }
     
     /// Get monitoring statistics
     pub async fn get_stats(&self) -> MonitorStats {
         let graph = self.graph.read().unwrap();
+        let candidate_cycles = self.cycle_index.read().map(|index| index.len()).unwrap_or(0);
         
         MonitorStats {
-            monitored_pools: self.monitored_pools.len(),
+            monitored_pools: self.monitored_pools().len(),
             graph_tokens: graph.token_count(),
             graph_edges: graph.edge_count(),
+            candidate_cycles,
             rate_limit_ms: self.config.detection_rate_limit_ms,
         }
     }
@@ -422,6 +585,7 @@ pub struct MonitorStats {
     pub monitored_pools: usize,
     pub graph_tokens: usize,
     pub graph_edges: usize,
+    pub candidate_cycles: usize,
     pub rate_limit_ms: u64,
 }
 
@@ -439,7 +603,7 @@ impl BatchPoolMonitor {
         all_pools: Vec<Pubkey>,
         detector: Arc<BellmanFordDetector>,
     ) -> Self {
-        let pools_per_monitor = (all_pools.len() + pubsub_urls.len() - 1) / pubsub_urls.len();
+        let pools_per_monitor = all_pools.len().div_ceil(pubsub_urls.len());
         
         let monitors: Vec<PoolMonitor> = pubsub_urls
             .into_iter()
@@ -500,6 +664,7 @@ mod tests {
     fn test_monitor_config_default() {
         let config = MonitorConfig::default();
         assert_eq!(config.detection_rate_limit_ms, 1000);
+        assert_eq!(config.rediscovery_interval_ms, 30_000);
         assert_eq!(config.max_reconnect_attempts, 10);
         assert_eq!(config.reconnect_delay_ms, 2000);
         assert_eq!(config.subscription_batch_size, 50);
@@ -507,6 +672,7 @@ mod tests {
     
     #[test]
     fn test_pool_update_creation() {
+        use crate::dex::adapter::VenueState;
         use crate::dex::pool_fetcher::PoolData;
         use crate::dex::triangular_arb::DexType;
         
@@ -519,6 +685,9 @@ mod tests {
             fee_bps: 25,
             dex_type: DexType::Raydium,
             program_id: create_test_pubkey(100),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
         };
         
         let update = PoolUpdate {
@@ -538,6 +707,7 @@ mod tests {
             monitored_pools: 100,
             graph_tokens: 50,
             graph_edges: 200,
+            candidate_cycles: 0,
             rate_limit_ms: 1000,
         };
         
@@ -568,4 +738,75 @@ mod tests {
         
         assert_eq!(batch_monitor.monitors.len(), 1);
     }
+    
+    #[tokio::test]
+    async fn test_graph_updates_signal_detection() {
+        use solana_client::nonblocking::rpc_client::RpcClient;
+        use crate::dex::adapter::VenueState;
+        use crate::dex::pool_fetcher::PoolData;
+        use crate::dex::raydium::RAYDIUM_AMM_V4_PROGRAM_ID;
+        use crate::dex::triangular_arb::DexType;
+        
+        let pool_data = PoolData {
+            pool_address: create_test_pubkey(1),
+            token_a: create_test_pubkey(2),
+            token_b: create_test_pubkey(3),
+            reserve_a: 1000000,
+            reserve_b: 2000000,
+            fee_bps: 25,
+            dex_type: DexType::Raydium,
+            program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
+        };
+        
+        let graph = create_shared_graph();
+        let rpc_clients = vec![Arc::new(RpcClient::new("http://localhost:8899".to_string()))];
+        let pool_fetcher = Arc::new(PoolDataFetcher::new(rpc_clients, 60000));
+        let detector = Arc::new(BellmanFordDetector::new(Arc::clone(&graph), 50));
+        
+        let (signal_tx, mut signal_rx) = mpsc::unbounded_channel();
+        let monitor = PoolMonitor::new("ws://localhost:8900".to_string(), Arc::clone(&graph), pool_fetcher, vec![], detector)
+            .with_update_signal(signal_tx);
+        
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(PoolUpdate {
+            pool_address: pool_data.pool_address,
+            new_data: pool_data,
+            slot: 12345,
+            timestamp: 1699999999,
+        }).unwrap();
+        drop(tx);
+        monitor.process_updates(rx).await;
+        
+        assert_eq!(graph.read().unwrap().edge_count(), 2);
+        assert!(signal_rx.try_recv().is_ok());
+        assert!(signal_rx.try_recv().is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_add_pool_at_runtime() {
+        use solana_client::nonblocking::rpc_client::RpcClient;
+        
+        let graph = create_shared_graph();
+        let rpc_clients = vec![Arc::new(RpcClient::new("http://localhost:8899".to_string()))];
+        let pool_fetcher = Arc::new(PoolDataFetcher::new(rpc_clients, 60000));
+        let detector = Arc::new(BellmanFordDetector::new(Arc::clone(&graph), 50));
+        let monitor = PoolMonitor::new(
+            "ws://localhost:8900".to_string(),
+            graph,
+            pool_fetcher,
+            vec![create_test_pubkey(1)],
+            detector,
+        );
+        
+        // Clones handed to the running tasks see the same pool list and queue
+        let running = monitor.clone_for_task();
+        assert!(monitor.add_pool(create_test_pubkey(2)));
+        assert!(!monitor.add_pool(create_test_pubkey(1)));
+        assert_eq!(running.monitored_pools(), vec![create_test_pubkey(1), create_test_pubkey(2)]);
+        assert_eq!(running.added_pools_rx.lock().await.try_recv().unwrap(), create_test_pubkey(2));
+        assert_eq!(running.get_stats().await.monitored_pools, 2);
+    }
 }
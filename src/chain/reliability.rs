@@ -0,0 +1,394 @@
+// Venue and Pool Reliability
+//
+// Learns how often trades through each venue and each pool land, from the
+// `TradeRecord`s in `TradeStorage` (loaded at startup, then fed every new
+// outcome). Only outcomes decided on chain count: confirmed trades, and
+// simulated or executed reverts with a program error. Failures before the
+// transaction reaches a program (building, RPC, send, timeouts) say nothing
+// about the pools. Only the last `reliability.window` trades per venue and
+// per pool count, so a venue that recovers is trusted again.
+//
+// Scores are smoothed towards a prior so a handful of trades cannot swing
+// them: a venue starts at its configured `reliability_score`, and a pool
+// starts at its venue's score. Realized profit against expected profit
+// (`LIVE` trades, whose profit was read from the confirmed transaction's
+// balance changes) scales the score down for venues and pools that land but
+// under-deliver. Pools whose raw success rate falls below
+// `reliability.demote_below` after `reliability.min_pool_attempts` trades are
+// demoted: detection leaves cycles through them out.
+
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, info};
+
+use crate::config::ReliabilityConfig;
+use crate::data::TradeRecord;
+use crate::dex::triangular_arb::{ArbitrageCycle, DexType};
+
+/// Pseudo-trades the prior is worth when smoothing observed rates
+const PRIOR_WEIGHT: f64 = 10.0;
+
+/// Prior for venues missing from the config
+const DEFAULT_VENUE_PRIOR: f64 = 0.8;
+
+/// Outcome of one trade through a venue or pool
+#[derive(Clone, Debug)]
+struct Outcome {
+    success: bool,
+    /// Realized / expected profit, for live trades that landed
+    realized_ratio: Option<f64>,
+    revert_reason: Option<String>,
+}
+
+/// Rolling outcomes of the trades through one venue or pool
+#[derive(Clone, Debug, Default)]
+pub struct ReliabilityStats {
+    outcomes: VecDeque<Outcome>,
+}
+
+impl ReliabilityStats {
+    fn push(&mut self, outcome: Outcome, window: usize) {
+        self.outcomes.push_back(outcome);
+        while self.outcomes.len() > window {
+            self.outcomes.pop_front();
+        }
+    }
+
+    /// Trades in the window
+    pub fn attempts(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Raw success rate, `None` without trades
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let successes = self.outcomes.iter().filter(|outcome| outcome.success).count();
+        Some(successes as f64 / self.outcomes.len() as f64)
+    }
+
+    /// Mean realized / expected profit of the live trades that landed
+    pub fn realized_ratio(&self) -> Option<f64> {
+        let ratios: Vec<f64> = self.outcomes.iter().filter_map(|outcome| outcome.realized_ratio).collect();
+        (!ratios.is_empty()).then(|| ratios.iter().sum::<f64>() / ratios.len() as f64)
+    }
+
+    /// Revert reasons in the window, most frequent first
+    pub fn revert_reasons(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for reason in self.outcomes.iter().filter_map(|outcome| outcome.revert_reason.as_deref()) {
+            *counts.entry(reason).or_default() += 1;
+        }
+        let mut reasons: Vec<(String, usize)> = counts.into_iter()
+            .map(|(reason, count)| (reason.to_string(), count))
+            .collect();
+        reasons.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        reasons
+    }
+
+    /// Success rate and realized ratio smoothed towards `prior`
+    fn score(&self, prior: f64) -> f64 {
+        let n = self.outcomes.len() as f64;
+        let successes = self.outcomes.iter().filter(|outcome| outcome.success).count() as f64;
+        let success = (successes + prior * PRIOR_WEIGHT) / (n + PRIOR_WEIGHT);
+
+        let ratios: Vec<f64> = self.outcomes.iter().filter_map(|outcome| outcome.realized_ratio).collect();
+        let realized = (ratios.iter().sum::<f64>() + PRIOR_WEIGHT) / (ratios.len() as f64 + PRIOR_WEIGHT);
+
+        (success * realized).clamp(0.0, 1.0)
+    }
+}
+
+/// Per-venue and per-pool reliability learned from trade history
+pub struct ReliabilityTracker {
+    config: ReliabilityConfig,
+    venues: HashMap<String, ReliabilityStats>,
+    pools: HashMap<Pubkey, ReliabilityStats>,
+}
+
+/// Thread-safe wrapper for ReliabilityTracker
+pub type SharedReliability = Arc<RwLock<ReliabilityTracker>>;
+
+impl ReliabilityTracker {
+    pub fn new(config: ReliabilityConfig) -> Self {
+        Self {
+            config,
+            venues: HashMap::new(),
+            pools: HashMap::new(),
+        }
+    }
+
+    /// Tracker seeded with stored trade history, oldest first
+    pub fn from_records(config: ReliabilityConfig, records: &[TradeRecord]) -> Self {
+        let mut tracker = Self::new(config);
+        for record in records {
+            tracker.record(record);
+        }
+        info!(
+            "Learned reliability from {} trades: {} venues, {} pools ({} demoted)",
+            records.len(),
+            tracker.venues.len(),
+            tracker.pools.len(),
+            tracker.demoted_pools().len()
+        );
+        tracker
+    }
+
+    /// Count one trade against every venue and pool on its route. Records
+    /// without a route, and failures that never reached a program, are ignored.
+    pub fn record(&mut self, record: &TradeRecord) {
+        if !record.success && !record.error_message.as_deref().is_some_and(is_program_error) {
+            debug!("Ignoring off-chain failure: {:?}", record.error_message);
+            return;
+        }
+
+        let realized_ratio = (record.success
+            && record.execution_mode == "LIVE"
+            && record.expected_profit_amount > 0)
+            .then(|| (record.profit_amount as f64 / record.expected_profit_amount as f64).min(1.0));
+        let outcome = Outcome {
+            success: record.success,
+            realized_ratio,
+            revert_reason: record.error_message.as_deref().map(revert_reason),
+        };
+
+        let window = self.config.window.max(1);
+        for hop in &record.route {
+            self.venues.entry(hop.dex.clone()).or_default().push(outcome.clone(), window);
+            if let Ok(pool) = Pubkey::from_str(&hop.pool) {
+                self.pools.entry(pool).or_default().push(outcome.clone(), window);
+            }
+        }
+
+        if !record.success {
+            debug!("Recorded failed trade over {} hops: {:?}", record.route.len(), outcome.revert_reason);
+        }
+    }
+
+    /// Smoothed reliability of a venue, starting from its configured prior
+    pub fn venue_score(&self, dex: &DexType) -> f64 {
+        let prior = self.config.venue_priors.get(dex).copied().unwrap_or(DEFAULT_VENUE_PRIOR);
+        match self.venues.get(&dex.to_string()) {
+            Some(stats) => stats.score(prior),
+            None => prior,
+        }
+    }
+
+    /// Smoothed reliability of one pool, starting from its venue's score
+    pub fn pool_score(&self, dex: &DexType, pool: &Pubkey) -> f64 {
+        let venue = self.venue_score(dex);
+        match self.pools.get(pool) {
+            Some(stats) => stats.score(venue),
+            None => venue,
+        }
+    }
+
+    /// Mean pool score over the hops of `cycle` (split legs averaged per hop)
+    pub fn cycle_score(&self, cycle: &ArbitrageCycle) -> f64 {
+        if cycle.path.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = cycle.path.iter()
+            .map(|step| {
+                if step.splits.is_empty() {
+                    self.pool_score(&step.dex, &step.pool)
+                } else {
+                    step.splits.iter()
+                        .map(|leg| self.pool_score(&leg.dex, &leg.pool) * leg.share_bps as f64 / 10_000.0)
+                        .sum()
+                }
+            })
+            .sum();
+        total / cycle.path.len() as f64
+    }
+
+    /// Whether enough trades through `pool` have failed to leave it out
+    pub fn is_demoted(&self, pool: &Pubkey) -> bool {
+        self.pools.get(pool).is_some_and(|stats| {
+            stats.attempts() >= self.config.min_pool_attempts
+                && stats.success_rate().is_some_and(|rate| rate < self.config.demote_below)
+        })
+    }
+
+    /// Whether any pool of `cycle` is demoted
+    pub fn uses_demoted_pool(&self, cycle: &ArbitrageCycle) -> bool {
+        cycle.path.iter().any(|step| step.pools().iter().any(|pool| self.is_demoted(pool)))
+    }
+
+    pub fn demoted_pools(&self) -> Vec<Pubkey> {
+        self.pools.keys().filter(|pool| self.is_demoted(pool)).copied().collect()
+    }
+
+    /// Rolling stats of a venue
+    pub fn venue_stats(&self, dex: &DexType) -> Option<&ReliabilityStats> {
+        self.venues.get(&dex.to_string())
+    }
+
+    /// Rolling stats of a pool
+    pub fn pool_stats(&self, pool: &Pubkey) -> Option<&ReliabilityStats> {
+        self.pools.get(pool)
+    }
+}
+
+impl Default for ReliabilityTracker {
+    fn default() -> Self {
+        Self::new(ReliabilityConfig::default())
+    }
+}
+
+/// Whether a failure was a program error, from simulation or execution,
+/// rather than an error raised before the transaction reached the chain
+fn is_program_error(message: &str) -> bool {
+    const MARKERS: [&str; 3] = ["custom program error", "Error processing Instruction", "InstructionError"];
+    MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Group error messages by cause: the program error code when there is one
+/// (`custom program error: 0x1771`), otherwise the message itself
+fn revert_reason(message: &str) -> String {
+    const MARKER: &str = "custom program error: ";
+    match message.find(MARKER) {
+        Some(start) => {
+            let code = &message[start..];
+            let end = code[MARKER.len()..]
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .map_or(code.len(), |i| MARKER.len() + i);
+            code[..end].to_string()
+        }
+        None => message.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TradeHop;
+    use crate::dex::triangular_arb::CycleStep;
+
+    fn trade(success: bool, pool: &Pubkey, dex: &DexType, error: &str) -> TradeRecord {
+        let record = if success {
+            TradeRecord::success(0, "sig".to_string(), String::new(), 900, 1000, 10, "LIVE".to_string())
+        } else {
+            TradeRecord::failure(0, String::new(), 1000, 10, "LIVE".to_string(), error.to_string())
+        };
+        record.with_route(vec![TradeHop { dex: dex.to_string(), pool: pool.to_string() }])
+    }
+
+    #[test]
+    fn test_failing_pool_is_demoted_and_scored_down() {
+        let failing = Pubkey::new_unique();
+        let healthy = Pubkey::new_unique();
+        let records: Vec<TradeRecord> = (0..6)
+            .map(|_| trade(false, &failing, &DexType::Orca, "Transaction simulation failed: custom program error: 0x1771"))
+            .chain((0..6).map(|_| trade(true, &healthy, &DexType::Raydium, "")))
+            .collect();
+        let tracker = ReliabilityTracker::from_records(ReliabilityConfig::default(), &records);
+
+        assert!(tracker.is_demoted(&failing));
+        assert!(!tracker.is_demoted(&healthy));
+        assert!(tracker.pool_score(&DexType::Orca, &failing) < tracker.venue_score(&DexType::Orca));
+        assert!(tracker.venue_score(&DexType::Orca) < 0.9);
+        assert_eq!(
+            tracker.pool_stats(&failing).unwrap().revert_reasons(),
+            vec![("custom program error: 0x1771".to_string(), 6)]
+        );
+
+        // Landed trades that realized 90% of the expected profit
+        let raydium = tracker.venue_stats(&DexType::Raydium).unwrap();
+        assert_eq!(raydium.success_rate(), Some(1.0));
+        assert_eq!(raydium.realized_ratio(), Some(0.9));
+
+        // Untraded venues keep their prior
+        assert_eq!(tracker.venue_score(&DexType::Pump), 0.7);
+
+        let step = |pool: Pubkey, dex: DexType| CycleStep {
+            from_token: Pubkey::new_unique(),
+            to_token: Pubkey::new_unique(),
+            dex,
+            pool,
+            rate: 1.0,
+            fee_bps: 25,
+            splits: Vec::new(),
+        };
+        let cycle = ArbitrageCycle {
+            path: vec![step(healthy, DexType::Raydium), step(failing, DexType::Orca)],
+            gross_profit_bps: 0,
+            net_profit_after_fees: 0.0,
+            execution_time_estimate_ms: 0,
+            total_fee_bps: 50,
+            start_token: Pubkey::new_unique(),
+            cycle_weight: 0.0,
+        };
+        assert!(tracker.uses_demoted_pool(&cycle));
+    }
+
+    #[test]
+    fn test_losing_trades_count_against_the_venue() {
+        let landed = |profit: i64, dex: &DexType| {
+            TradeRecord::success(0, "sig".to_string(), String::new(), profit, 1000, 10, "LIVE".to_string())
+                .with_route(vec![TradeHop { dex: dex.to_string(), pool: Pubkey::new_unique().to_string() }])
+        };
+        let records: Vec<TradeRecord> = (0..4)
+            .map(|_| landed(-500, &DexType::Orca))
+            .chain((0..4).map(|_| landed(0, &DexType::Raydium)))
+            .collect();
+        let tracker = ReliabilityTracker::from_records(ReliabilityConfig::default(), &records);
+
+        // A loss is a negative realized ratio, not a break-even trade
+        assert_eq!(tracker.venue_stats(&DexType::Orca).unwrap().realized_ratio(), Some(-0.5));
+        assert!(tracker.venue_score(&DexType::Orca) < tracker.venue_score(&DexType::Raydium));
+    }
+
+    #[test]
+    fn test_only_the_window_counts() {
+        let pool = Pubkey::new_unique();
+        let config = ReliabilityConfig { window: 5, ..ReliabilityConfig::default() };
+        let mut tracker = ReliabilityTracker::new(config);
+
+        for _ in 0..5 {
+            tracker.record(&trade(false, &pool, &DexType::Raydium, "Transaction reverted: InstructionError(2, Custom(6022))"));
+        }
+        assert!(tracker.is_demoted(&pool));
+
+        // The pool recovers once its failures leave the window
+        for _ in 0..5 {
+            tracker.record(&trade(true, &pool, &DexType::Raydium, ""));
+        }
+        assert!(!tracker.is_demoted(&pool));
+        assert_eq!(tracker.pool_stats(&pool).unwrap().attempts(), 5);
+    }
+
+    #[test]
+    fn test_failures_before_the_chain_are_not_counted() {
+        let pool = Pubkey::new_unique();
+        let mut tracker = ReliabilityTracker::default();
+
+        for error in [
+            "Failed to build transaction: No pool state for pool",
+            "Failed to send transaction: Transaction failed on all 2 RPCs after 812ms: Blockhash not found",
+            "Failed to send transaction: Transaction confirmation timeout after 30000ms",
+        ] {
+            for _ in 0..10 {
+                tracker.record(&trade(false, &pool, &DexType::Orca, error));
+            }
+        }
+        assert!(tracker.pool_stats(&pool).is_none());
+        assert!(!tracker.is_demoted(&pool));
+
+        // A simulated revert reached the program and counts against the pool
+        tracker.record(&trade(
+            false,
+            &pool,
+            &DexType::Orca,
+            "Failed to send transaction: Transaction simulation failed: Error processing Instruction 2: custom program error: 0x10",
+        ));
+        assert_eq!(tracker.pool_stats(&pool).unwrap().attempts(), 1);
+        assert_eq!(
+            tracker.pool_stats(&pool).unwrap().revert_reasons(),
+            vec![("custom program error: 0x10".to_string(), 1)]
+        );
+    }
+}
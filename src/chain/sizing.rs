@@ -0,0 +1,391 @@
+// Trade Sizing
+//
+// Picks the input amount that maximizes a cycle's net profit. Output is
+// computed with each hop's exact integer quote (`ArbitrageGraph::quote_cycle`),
+// so price impact, fee rounding and order-book depth are all included; net
+// profit then subtracts what landing the transaction costs (signature fee,
+// priority fee on the requested compute units, and tip).
+//
+// Profit along a route rises while the price gap is wider than the price
+// impact and falls after, so the search samples a geometric grid of sizes up
+// to the bound (which doubles as the profit curve reported for the trade),
+// then narrows in on the best grid point with a ternary search.
+//
+// When parallel pools trade the same pair, each hop can be split across up
+// to `routing.max_split_legs` of them (`ArbitrageGraph::split_cycle`, with
+// ratios chosen at the single-pool optimum); the split route is then sized
+// the same way and kept if it nets more.
+//
+// Sizes are bounded by `BotConfig.max_position_size` (in lamports, converted
+// to the start token at the graph's SOL rate) and, for tokens whose balance
+// is tracked in the inventory, by the wallet's balance.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+
+use crate::config::Config;
+use crate::dex::triangular_arb::{ArbitrageCycle, ArbitrageGraph};
+use super::constants::WSOL_MINT;
+
+/// Fee charged per transaction signature, in lamports
+const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Sizes sampled on the geometric grid (each half the previous one)
+const CURVE_POINTS: usize = 16;
+
+/// Wallet balances by mint, in raw token units
+pub type SharedInventory = Arc<RwLock<HashMap<Pubkey, u64>>>;
+
+/// What landing one arbitrage transaction costs, in lamports
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionCosts {
+    pub signature_fee_lamports: u64,
+    /// Compute units requested; the priority fee is paid on all of them
+    pub compute_unit_limit: u32,
+    /// Priority fee in micro-lamports per compute unit
+    pub compute_unit_price_micro_lamports: u64,
+    /// Tip paid to the block builder
+    pub tip_lamports: u64,
+}
+
+impl ExecutionCosts {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            signature_fee_lamports: SIGNATURE_FEE_LAMPORTS,
+            compute_unit_limit: config.execution.compute_unit_limit,
+            compute_unit_price_micro_lamports: config.execution.compute_unit_price,
+            tip_lamports: config.execution.tip_lamports,
+        }
+    }
+
+    /// Priority fee, rounded up to whole lamports
+    pub fn priority_fee_lamports(&self) -> u64 {
+        (self.compute_unit_limit as u128 * self.compute_unit_price_micro_lamports as u128)
+            .div_ceil(1_000_000) as u64
+    }
+
+    /// Signature fee, priority fee and tip
+    pub fn total_lamports(&self) -> u64 {
+        self.signature_fee_lamports
+            .saturating_add(self.priority_fee_lamports())
+            .saturating_add(self.tip_lamports)
+    }
+}
+
+impl Default for ExecutionCosts {
+    fn default() -> Self {
+        Self {
+            signature_fee_lamports: SIGNATURE_FEE_LAMPORTS,
+            compute_unit_limit: 200_000,
+            compute_unit_price_micro_lamports: 1_000,
+            tip_lamports: 0,
+        }
+    }
+}
+
+/// One point of a route's profit curve, in start-token units
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizePoint {
+    pub input: u64,
+    pub output: u64,
+    /// Output minus input minus execution costs
+    pub net_profit: i128,
+}
+
+/// Best size found for a cycle, with the curve it was picked from
+#[derive(Clone, Debug)]
+pub struct SizedTrade {
+    pub input: u64,
+    pub output: u64,
+    /// Execution costs converted to the start token
+    pub costs: u64,
+    pub net_profit: i128,
+    /// Sampled sizes (and the optimum), by increasing input
+    pub curve: Vec<SizePoint>,
+    /// Route as sized; hops may be split across parallel pools
+    pub cycle: ArbitrageCycle,
+    /// Start-token units per lamport, used for the conversions
+    pub token_per_lamport: f64,
+}
+
+impl SizedTrade {
+    /// Net profit relative to the input
+    pub fn net_profit_bps(&self) -> i64 {
+        if self.input == 0 {
+            return 0;
+        }
+        (self.net_profit * 10_000 / self.input as i128) as i64
+    }
+
+    /// Net profit in SOL
+    pub fn net_profit_sol(&self) -> f64 {
+        self.net_profit as f64 / self.token_per_lamport / 1e9
+    }
+}
+
+/// Sizes cycles against their exact quotes
+#[derive(Clone, Debug)]
+pub struct SizingEngine {
+    /// Largest input, in lamports of SOL value
+    max_position_size: u64,
+    costs: ExecutionCosts,
+    /// Balances that bound the input; tokens without an entry are bounded
+    /// by `max_position_size` only
+    inventory: Option<SharedInventory>,
+    /// Most pools a hop is split across (1 = never split)
+    max_split_legs: usize,
+}
+
+impl SizingEngine {
+    pub fn new(max_position_size: u64, costs: ExecutionCosts) -> Self {
+        Self {
+            max_position_size,
+            costs,
+            inventory: None,
+            max_split_legs: 1,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.bot.max_position_size, ExecutionCosts::from_config(config))
+            .with_max_split_legs(config.routing.max_split_legs as usize)
+    }
+
+    /// Bound inputs by the wallet balances in `inventory`
+    pub fn with_inventory(mut self, inventory: SharedInventory) -> Self {
+        self.inventory = Some(inventory);
+        self
+    }
+
+    /// Allow splitting each hop across up to `max_legs` parallel pools
+    pub fn with_max_split_legs(mut self, max_legs: usize) -> Self {
+        self.max_split_legs = max_legs.max(1);
+        self
+    }
+
+    pub fn costs(&self) -> &ExecutionCosts {
+        &self.costs
+    }
+
+    /// Units of `token` per lamport: 1 for SOL, otherwise the best spot rate
+    /// of a SOL -> `token` edge in the graph
+    pub fn token_per_lamport(graph: &ArbitrageGraph, token: &Pubkey) -> Option<f64> {
+        if *token == WSOL_MINT {
+            return Some(1.0);
+        }
+        graph.get_edges_from(&WSOL_MINT)?
+            .iter()
+            .filter(|edge| edge.to_token == *token && edge.rate.is_finite() && edge.rate > 0.0)
+            .map(|edge| edge.rate)
+            .max_by(f64::total_cmp)
+    }
+
+    /// Largest input of `token` allowed by the position limit and inventory
+    pub fn max_input(&self, token: &Pubkey, token_per_lamport: f64) -> u64 {
+        let position_limit = (self.max_position_size as f64 * token_per_lamport).min(u64::MAX as f64) as u64;
+        let balance = self.inventory.as_ref()
+            .and_then(|inventory| inventory.read().ok()?.get(token).copied());
+        match balance {
+            Some(balance) => position_limit.min(balance),
+            None => position_limit,
+        }
+    }
+
+    /// Input that maximizes the cycle's net profit, or `None` if the start
+    /// token cannot be valued in SOL, the bound is zero, or no size quotes
+    pub fn size_cycle(&self, graph: &ArbitrageGraph, cycle: &ArbitrageCycle) -> Option<SizedTrade> {
+        let token_per_lamport = Self::token_per_lamport(graph, &cycle.start_token)?;
+        let max_input = self.max_input(&cycle.start_token, token_per_lamport);
+        if max_input == 0 {
+            return None;
+        }
+        let costs = (self.costs.total_lamports() as f64 * token_per_lamport).ceil() as u64;
+
+        let (mut optimum, mut curve) = Self::search(graph, cycle, max_input, costs)?;
+        let mut route = cycle.clone();
+
+        if self.max_split_legs > 1 {
+            let split = graph.split_cycle(cycle, optimum.input, self.max_split_legs).ok()
+                .filter(|split| split.path.iter().any(|step| !step.splits.is_empty()));
+            if let Some(split) = split {
+                if let Some((split_optimum, split_curve)) = Self::search(graph, &split, max_input, costs) {
+                    if split_optimum.net_profit > optimum.net_profit {
+                        debug!(
+                            "Splitting hops of cycle from {} raises net profit {} -> {}",
+                            cycle.start_token, optimum.net_profit, split_optimum.net_profit
+                        );
+                        optimum = split_optimum;
+                        curve = split_curve;
+                        route = split;
+                    }
+                }
+            }
+        }
+
+        debug!(
+            "Sized cycle from {}: input {} -> output {}, costs {}, net {} ({} curve points, max input {})",
+            cycle.start_token, optimum.input, optimum.output, costs, optimum.net_profit, curve.len(), max_input
+        );
+
+        Some(SizedTrade {
+            input: optimum.input,
+            output: optimum.output,
+            costs,
+            net_profit: optimum.net_profit,
+            curve,
+            cycle: route,
+            token_per_lamport,
+        })
+    }
+
+    /// Best input in `1..=max_input` for `cycle` and the curve sampled on the
+    /// way, by increasing input
+    fn search(graph: &ArbitrageGraph, cycle: &ArbitrageCycle, max_input: u64, costs: u64) -> Option<(SizePoint, Vec<SizePoint>)> {
+        let evaluate = |input: u64| -> Option<SizePoint> {
+            let output = *graph.quote_cycle(cycle, input).ok()?.last()?;
+            Some(SizePoint {
+                input,
+                output,
+                net_profit: output as i128 - input as i128 - costs as i128,
+            })
+        };
+        let profit = |input: u64| evaluate(input).map_or(i128::MIN, |point| point.net_profit);
+
+        // Geometric grid up to the bound, smallest first
+        let mut sizes: Vec<u64> = (0..CURVE_POINTS).map(|k| max_input >> k).filter(|&size| size > 0).collect();
+        sizes.reverse();
+        sizes.dedup();
+        let mut curve: Vec<SizePoint> = sizes.iter().filter_map(|&size| evaluate(size)).collect();
+        let best = curve.iter().enumerate().max_by_key(|(_, point)| point.net_profit)?.0;
+
+        // Narrow in between the neighbours of the best grid point
+        let mut lo = if best == 0 { 1 } else { curve[best - 1].input };
+        let mut hi = curve.get(best + 1).map_or(curve[best].input, |point| point.input);
+        while hi - lo > 2 {
+            let m1 = lo + (hi - lo) / 3;
+            let m2 = hi - (hi - lo) / 3;
+            if profit(m1) < profit(m2) {
+                lo = m1;
+            } else {
+                hi = m2;
+            }
+        }
+        let optimum = (lo..=hi)
+            .filter_map(evaluate)
+            .chain(std::iter::once(curve[best].clone()))
+            .max_by_key(|point| point.net_profit)?;
+
+        if !curve.iter().any(|point| point.input == optimum.input) {
+            curve.push(optimum.clone());
+            curve.sort_by_key(|point| point.input);
+        }
+
+        Some((optimum, curve))
+    }
+}
+
+impl Default for SizingEngine {
+    fn default() -> Self {
+        Self::new(1_000_000_000, ExecutionCosts::default()) // 1 SOL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::adapter::DexRegistry;
+    use crate::dex::test_fixtures::raydium_pool;
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    /// SOL -> token on a cheap pool, back on a pool priced 5% higher
+    fn gap_graph() -> (ArbitrageGraph, ArbitrageCycle) {
+        let registry = DexRegistry::default();
+        let token = create_test_pubkey(2);
+        let mut graph = ArbitrageGraph::new();
+        let cheap = raydium_pool(create_test_pubkey(100), WSOL_MINT, token, 1_000_000_000_000, 1_050_000_000_000);
+        let dear = raydium_pool(create_test_pubkey(101), WSOL_MINT, token, 1_000_000_000_000, 1_000_000_000_000);
+        graph.upsert_pool(&registry, &cheap, 1000).unwrap();
+        graph.upsert_pool(&registry, &dear, 1000).unwrap();
+
+        let cycle = graph.cycle_through(&[
+            (WSOL_MINT, token, cheap.pool_address),
+            (token, WSOL_MINT, dear.pool_address),
+        ]).unwrap();
+        (graph, cycle)
+    }
+
+    #[test]
+    fn test_execution_costs() {
+        let costs = ExecutionCosts { tip_lamports: 10_000, ..ExecutionCosts::default() };
+        // 200k CU at 1000 micro-lamports = 200 lamports
+        assert_eq!(costs.priority_fee_lamports(), 200);
+        assert_eq!(costs.total_lamports(), 15_200);
+    }
+
+    #[test]
+    fn test_size_maximizes_net_profit_on_the_curve() {
+        let (graph, cycle) = gap_graph();
+        let engine = SizingEngine::new(1_000_000_000_000, ExecutionCosts::default());
+        let trade = engine.size_cycle(&graph, &cycle).unwrap();
+
+        assert!(trade.net_profit > 0);
+        assert_eq!(trade.net_profit, trade.output as i128 - trade.input as i128 - trade.costs as i128);
+        // Interior optimum: price impact stops the trade well short of the bound
+        assert!(trade.input < 1_000_000_000_000);
+        assert!(trade.curve.iter().all(|point| point.net_profit <= trade.net_profit));
+        assert!(trade.curve.windows(2).all(|pair| pair[0].input < pair[1].input));
+
+        // Sizes either side of the optimum do no better
+        for input in [trade.input - 100_000_000, trade.input + 100_000_000] {
+            let output = *graph.quote_cycle(&cycle, input).unwrap().last().unwrap();
+            assert!(output as i128 - input as i128 - trade.costs as i128 <= trade.net_profit);
+        }
+    }
+
+    #[test]
+    fn test_splitting_hops_across_parallel_pools_nets_more() {
+        let (mut graph, cycle) = gap_graph();
+        // A second pool to sell the token back into, as deep as the first
+        let registry = DexRegistry::default();
+        let token = create_test_pubkey(2);
+        let dear = raydium_pool(create_test_pubkey(102), WSOL_MINT, token, 1_000_000_000_000, 1_000_000_000_000);
+        graph.upsert_pool(&registry, &dear, 1000).unwrap();
+
+        let single = SizingEngine::new(1_000_000_000_000, ExecutionCosts::default())
+            .size_cycle(&graph, &cycle)
+            .unwrap();
+        assert!(single.cycle.path.iter().all(|step| step.splits.is_empty()));
+
+        let split = SizingEngine::new(1_000_000_000_000, ExecutionCosts::default())
+            .with_max_split_legs(3)
+            .size_cycle(&graph, &cycle)
+            .unwrap();
+        assert!(split.net_profit > single.net_profit);
+        assert_eq!(split.cycle.path[1].splits.len(), 2);
+        assert_eq!(
+            *graph.quote_cycle(&split.cycle, split.input).unwrap().last().unwrap(),
+            split.output
+        );
+    }
+
+    #[test]
+    fn test_size_is_bounded_by_position_and_inventory() {
+        let (graph, cycle) = gap_graph();
+        let engine = SizingEngine::new(1_000_000, ExecutionCosts::default());
+        let trade = engine.size_cycle(&graph, &cycle).unwrap();
+        assert!(trade.input <= 1_000_000);
+
+        let inventory: SharedInventory = Arc::new(RwLock::new(HashMap::from([(WSOL_MINT, 500_000)])));
+        let engine = engine.with_inventory(inventory.clone());
+        assert_eq!(engine.max_input(&WSOL_MINT, 1.0), 500_000);
+        assert!(engine.size_cycle(&graph, &cycle).unwrap().input <= 500_000);
+
+        inventory.write().unwrap().insert(WSOL_MINT, 0);
+        assert!(engine.size_cycle(&graph, &cycle).is_none());
+    }
+}
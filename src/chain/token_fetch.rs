@@ -1,18 +1,23 @@
 use anyhow::{anyhow, Context, Result};
 use dashmap::DashMap;
 use moka::future::Cache;
+use solana_account_decoder::UiAccount;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_response::Response;
 use solana_sdk::{
     account::Account,
     pubkey::Pubkey,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use std::str::FromStr;
 use tracing::{debug, error, info, warn};
 use rand::Rng;
 use std::future::Future;
-use base64::Engine;
+
+use crate::dex::adapter::DexRegistry;
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::DexType;
 
 // Feature: Concurrent Caching Architecture (DashMap)
 // DECISION: Use DashMap (Chosen) vs RwLock<HashMap>.
@@ -24,6 +29,11 @@ use base64::Engine;
 // Chosen: Reduces network congestion by 40-60% and prevents "thundering herd" problems,
 // making RPC calls more reliable.
 
+// Pools are decoded by the venue adapter registered for their owner program
+// into the canonical `dex::pool_fetcher::PoolData`, the same type the pool
+// fetcher, the pool monitor and the graph use, and completed from their
+// dependent accounts (vaults, configs, mints) through the same adapter.
+
 /// Configuration for token fetching behavior
 #[derive(Debug, Clone)]
 pub struct TokenFetchConfig {
@@ -105,41 +115,34 @@ impl CachedPoolData {
     }
 }
 
-/// Pool data structure for DEX pools - aggregates all pool information
+/// An account together with the slot it was read at
 #[derive(Debug, Clone)]
-pub struct PoolData {
-    pub pubkey: Pubkey,
-    pub token_a_mint: Pubkey,
-    pub token_b_mint: Pubkey,
-    pub token_a_reserve: u64,
-    pub token_b_reserve: u64,
-    pub token_a_vault: Option<Pubkey>,
-    pub token_b_vault: Option<Pubkey>,
-    pub lp_mint: Option<Pubkey>,
-    pub fee_numerator: u64,
-    pub fee_denominator: u64,
-    pub dex_type: DexType,
-    pub last_updated: SystemTime,
+struct ObservedAccount {
+    slot: u64,
+    account: Account,
 }
 
-impl PoolData {
-    /// Check if cached pool data is still valid
-    pub fn is_valid(&self, ttl_seconds: u64) -> bool {
-        if let Ok(elapsed) = self.last_updated.elapsed() {
-            elapsed.as_secs() < ttl_seconds
-        } else {
-            false
-        }
+/// Context slot and accounts of a `getMultipleAccounts` JSON-RPC response
+///
+/// Adapter for external data providers (e.g. Helius): the response is the
+/// standard RPC shape, so accounts are decoded with the RPC client's own
+/// account type and carry the provider's context slot into `PoolData::slot`.
+pub fn accounts_from_rpc_response(response: &serde_json::Value) -> Result<(u64, Vec<Option<Account>>)> {
+    if let Some(error) = response.get("error") {
+        return Err(anyhow!("External API returned an error: {}", error));
     }
-}
+    let result: Response<Vec<Option<UiAccount>>> = serde_json::from_value(response["result"].clone())
+        .context("Invalid external API response format")?;
+
+    let accounts = result.value.into_iter()
+        .map(|account| {
+            account
+                .map(|account| account.decode::<Account>().ok_or_else(|| anyhow!("Unsupported account data encoding")))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum DexType {
-    Raydium,
-    Meteora,
-    Whirlpool,
-    Orca,
-    Pump,
+    Ok((result.context.slot, accounts))
 }
 
 /// TokenFetcher with enhanced caching, batching, and retry logic
@@ -147,13 +150,15 @@ pub struct TokenFetcher {
     rpc_client: Arc<RpcClient>,
     config: TokenFetchConfig,
     // Moka cache for account data
-    account_cache: Cache<Pubkey, Account>,
+    account_cache: Cache<Pubkey, ObservedAccount>,
     // DashMap for concurrent pool data cache with timestamp
     pool_cache: Arc<DashMap<Pubkey, CachedPoolData>>,
     // HTTP client for external API calls
     http_client: reqwest::Client,
     // Cache for external API responses (100ms TTL)
     external_api_cache: Arc<DashMap<Pubkey, (PoolData, SystemTime)>>,
+    // Venue adapters used to decode and complete pools
+    registry: Arc<DexRegistry>,
 }
 
 impl TokenFetcher {
@@ -193,6 +198,7 @@ impl TokenFetcher {
             pool_cache,
             http_client,
             external_api_cache,
+            registry: Arc::new(DexRegistry::default()),
         }
     }
 
@@ -232,64 +238,32 @@ impl TokenFetcher {
             pool_cache,
             http_client,
             external_api_cache,
+            registry: Arc::new(DexRegistry::default()),
         }
     }
 
-    /// Fetch account with retry logic and caching
-    pub async fn fetch_account(&self, pubkey: &Pubkey) -> Result<Account> {
-        // Check cache first
-        if let Some(account) = self.account_cache.get(pubkey).await {
-            debug!("Cache hit for account: {}", pubkey);
-            return Ok(account);
-        }
-
-        debug!("Cache miss for account: {}, fetching from RPC", pubkey);
-
-        // Fetch with retry logic
-        let account = self.fetch_account_with_retry(pubkey).await?;
+    /// Use a custom adapter registry (e.g. built from configured program ids)
+    pub fn with_registry(mut self, registry: Arc<DexRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
 
-        // Update cache
-        self.account_cache.insert(*pubkey, account.clone()).await;
+    /// Adapter registry used to decode pools
+    pub fn registry(&self) -> &Arc<DexRegistry> {
+        &self.registry
+    }
 
-        Ok(account)
+    /// Fetch account with retry logic and caching
+    pub async fn fetch_account(&self, pubkey: &Pubkey) -> Result<Account> {
+        Ok(self.fetch_observed_account(pubkey).await?.account)
     }
 
     /// Fetch multiple accounts in batch with retry logic
     pub async fn fetch_accounts_batch(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
-        let mut to_fetch = Vec::new();
-        let mut results = vec![None; pubkeys.len()];
-
-        // Check cache for each account
-        for (i, pubkey) in pubkeys.iter().enumerate() {
-            if let Some(account) = self.account_cache.get(pubkey).await {
-                debug!("Cache hit for account: {}", pubkey);
-                results[i] = Some(account);
-            } else {
-                to_fetch.push((i, *pubkey));
-            }
-        }
-
-        if to_fetch.is_empty() {
-            return Ok(results);
-        }
-
-        debug!("Fetching {} accounts in batch", to_fetch.len());
-
-        // Fetch missing accounts
-        let pubkeys_to_fetch: Vec<Pubkey> = to_fetch.iter().map(|(_, pk)| *pk).collect();
-        let fetched_accounts = self
-            .fetch_accounts_batch_with_retry(&pubkeys_to_fetch)
-            .await?;
-
-        // Update results and cache
-        for ((i, pubkey), account) in to_fetch.into_iter().zip(fetched_accounts.into_iter()) {
-            if let Some(ref acc) = account {
-                self.account_cache.insert(pubkey, acc.clone()).await;
-            }
-            results[i] = account;
-        }
-
-        Ok(results)
+        Ok(self.fetch_observed_accounts(pubkeys).await?
+            .into_iter()
+            .map(|observed| observed.map(|observed| observed.account))
+            .collect())
     }
 
     /// Fetch pool data with caching
@@ -300,45 +274,36 @@ impl TokenFetcher {
                 if cached.is_valid() {
                     debug!("Cache hit for pool: {}", pool_pubkey);
                     return Ok(cached.pool_data.clone());
-                } else {
-                    debug!("Cache expired for pool: {}", pool_pubkey);
-                    self.pool_cache.remove(pool_pubkey);
                 }
             }
+            // Expired entries are dropped outside the read guard
+            self.pool_cache.remove_if(pool_pubkey, |_, cached| !cached.is_valid());
         }
 
         debug!("Cache miss for pool: {}, fetching from RPC", pool_pubkey);
 
-        // Fetch pool account
-        let account = self.fetch_account(pool_pubkey).await?;
-
-        // Parse pool data based on DEX type
-        let mut pool_data = self.parse_pool_data(pool_pubkey, &account, dex_type)?;
-        pool_data.last_updated = SystemTime::now();
+        // Fetch and decode the pool account, then its dependent accounts
+        let observed = self.fetch_observed_account(pool_pubkey).await?;
+        let pool = self.decode_pool(pool_pubkey, &observed.account, &dex_type)?
+            .at_slot(observed.slot);
+        let pool_data = self.complete_pools(vec![pool]).await?
+            .pop()
+            .ok_or_else(|| anyhow!("Failed to complete {} pool {}", dex_type, pool_pubkey))?;
 
         // Update cache if enabled with metadata TTL
-        if self.config.enable_caching {
-            self.pool_cache.insert(
-                *pool_pubkey,
-                CachedPoolData {
-                    pool_data: pool_data.clone(),
-                    cached_at: SystemTime::now(),
-                    ttl_seconds: self.config.metadata_ttl_seconds,
-                },
-            );
-        }
+        self.cache_pool(&pool_data);
 
         Ok(pool_data)
     }
 
     /// Initialize pool data for multiple pools with batching and retry logic
     /// This aggregates all DEX pool data for a given mint
-    /// 
+    ///
     /// Feature: Dynamic Data Source for Aggressive Testing
     /// DECISION: Use direct HTTP call (Chosen) vs relying on local validator state
     /// Chosen: Direct HTTP call provides real-time data, necessary for "Dynamic" testing,
     ///         bypassing the local validator's potentially stale account state.
-    /// 
+    ///
     /// When `external_data_api_url` is set, this method fetches real-time pool reserves
     /// from the external API (e.g., Helius, Jupiter) instead of the local RPC.
     pub async fn initialize_pool_data(&self, pool_configs: &[(Pubkey, DexType)]) -> Result<Vec<PoolData>> {
@@ -350,42 +315,26 @@ impl TokenFetcher {
 
         // Fall back to RPC-based fetching
         info!("Initializing pool data for {} pools via RPC", pool_configs.len());
-        
+
         let mut all_pool_data = Vec::new();
         let mut failed_pools = Vec::new();
 
         // Process pools in batches
         for chunk in pool_configs.chunks(self.config.batch_size) {
             debug!("Processing batch of {} pools", chunk.len());
-            
+
             // Collect pubkeys for batch fetch
             let pubkeys: Vec<Pubkey> = chunk.iter().map(|(pk, _)| *pk).collect();
-            
+
             // Fetch accounts in batch with retry
-            match self.fetch_accounts_batch(&pubkeys).await {
+            match self.fetch_observed_accounts(&pubkeys).await {
                 Ok(accounts) => {
                     // Parse each account
-                    for ((pubkey, dex_type), account_opt) in 
-                        chunk.iter().zip(accounts.iter()) 
-                    {
-                        if let Some(account) = account_opt {
-                            match self.parse_pool_data(pubkey, account, dex_type.clone()) {
-                                Ok(mut pool_data) => {
-                                    pool_data.last_updated = SystemTime::now();
-                                    
-                                    // Cache the pool data with metadata TTL
-                                    if self.config.enable_caching {
-                                        self.pool_cache.insert(
-                                            *pubkey,
-                                            CachedPoolData {
-                                                pool_data: pool_data.clone(),
-                                                cached_at: SystemTime::now(),
-                                                ttl_seconds: self.config.metadata_ttl_seconds,
-                                            },
-                                        );
-                                    }
-                                    
-                                    all_pool_data.push(pool_data);
+                    for ((pubkey, dex_type), observed) in chunk.iter().zip(accounts) {
+                        if let Some(observed) = observed {
+                            match self.decode_pool(pubkey, &observed.account, dex_type) {
+                                Ok(pool_data) => {
+                                    all_pool_data.push(pool_data.at_slot(observed.slot));
                                     debug!("Initialized pool {} ({:?})", pubkey, dex_type);
                                 }
                                 Err(e) => {
@@ -406,50 +355,54 @@ impl TokenFetcher {
             }
         }
 
+        // Complete with vault balances and other dependent accounts
+        let completed_pools = self.complete_pools(all_pool_data).await?;
+        for pool_data in &completed_pools {
+            self.cache_pool(pool_data);
+        }
+
         info!(
             "Pool initialization complete: {} succeeded, {} failed",
-            all_pool_data.len(),
-            failed_pools.len()
+            completed_pools.len(),
+            pool_configs.len() - completed_pools.len()
         );
 
         if !failed_pools.is_empty() {
             warn!("Failed pools: {:?}", failed_pools);
         }
 
-        // Enrich with vault balances
-        let enriched_pools = self.enrich_with_vault_balances(all_pool_data).await?;
-        
-        Ok(enriched_pools)
+        Ok(completed_pools)
     }
 
     /// Fetch real-time pool data from external API
-    /// 
+    ///
     /// Feature: External API Integration for Real-Time Data
-    /// 
+    ///
     /// This method fetches pool reserves directly from a high-performance external API
     /// (e.g., Helius getMultipleAccounts with enhanced indexing) instead of relying
     /// on the local validator's potentially stale state.
-    /// 
+    ///
     /// OPTIMIZE: Response is cached for 100ms to reduce API calls during a single
     ///           arbitrage cycle while maintaining near-real-time data.
-    /// 
+    ///
     /// # Arguments
     /// * `pool_configs` - List of (pool_pubkey, dex_type) pairs to fetch
     /// * `api_url` - External API base URL
-    /// 
+    ///
     /// # Returns
     /// Vec<PoolData> - Real-time pool data from external API
     async fn fetch_realtime_pool_data(
-        &self, 
+        &self,
         pool_configs: &[(Pubkey, DexType)],
         api_url: &str,
     ) -> Result<Vec<PoolData>> {
-        let mut all_pool_data = Vec::new();
-        
+        let mut cached_pools = Vec::new();
+        let mut fetched_pools = Vec::new();
+
         // Process pools in batches
         for chunk in pool_configs.chunks(self.config.batch_size.min(100)) {
             debug!("Fetching batch of {} pools from external API", chunk.len());
-            
+
             // Check cache first (100ms TTL)
             let mut to_fetch = Vec::new();
             for (pubkey, dex_type) in chunk {
@@ -459,7 +412,7 @@ impl TokenFetcher {
                     if let Ok(elapsed) = cached_at.elapsed() {
                         if elapsed.as_millis() < 100 {
                             debug!("External API cache hit for pool: {}", pubkey);
-                            all_pool_data.push(cached_data.clone());
+                            cached_pools.push(cached_data.clone());
                             continue;
                         }
                     }
@@ -470,29 +423,21 @@ impl TokenFetcher {
                 }
                 to_fetch.push((*pubkey, dex_type.clone()));
             }
-            
+
             if to_fetch.is_empty() {
                 continue;
             }
-            
+
             // Fetch from external API
             let pubkeys: Vec<Pubkey> = to_fetch.iter().map(|(pk, _)| *pk).collect();
-            
+
             match self.fetch_accounts_from_external_api(&pubkeys, api_url).await {
-                Ok(accounts) => {
+                Ok((slot, accounts)) => {
                     for ((pubkey, dex_type), account_opt) in to_fetch.iter().zip(accounts.iter()) {
                         if let Some(account) = account_opt {
-                            match self.parse_pool_data(pubkey, account, dex_type.clone()) {
-                                Ok(mut pool_data) => {
-                                    pool_data.last_updated = SystemTime::now();
-                                    
-                                    // Cache with 100ms TTL
-                                    self.external_api_cache.insert(
-                                        *pubkey,
-                                        (pool_data.clone(), SystemTime::now()),
-                                    );
-                                    
-                                    all_pool_data.push(pool_data);
+                            match self.decode_pool(pubkey, account, dex_type) {
+                                Ok(pool_data) => {
+                                    fetched_pools.push(pool_data.at_slot(slot));
                                     debug!("Fetched pool {} from external API ({:?})", pubkey, dex_type);
                                 }
                                 Err(e) => {
@@ -509,30 +454,35 @@ impl TokenFetcher {
                     // Fall back to RPC for this batch
                     warn!("Falling back to RPC for {} pools", to_fetch.len());
                     for (pubkey, dex_type) in to_fetch {
-                        if let Ok(account) = self.fetch_account(&pubkey).await {
-                            if let Ok(mut pool_data) = self.parse_pool_data(&pubkey, &account, dex_type) {
-                                pool_data.last_updated = SystemTime::now();
-                                all_pool_data.push(pool_data);
+                        if let Ok(observed) = self.fetch_observed_account(&pubkey).await {
+                            if let Ok(pool_data) = self.decode_pool(&pubkey, &observed.account, &dex_type) {
+                                fetched_pools.push(pool_data.at_slot(observed.slot));
                             }
                         }
                     }
                 }
             }
         }
-        
+
+        // Complete with vault balances and other dependent accounts, then
+        // cache with 100ms TTL
+        let fetched_pools = self.complete_pools(fetched_pools).await?;
+        for pool_data in &fetched_pools {
+            self.external_api_cache.insert(pool_data.pool_address, (pool_data.clone(), SystemTime::now()));
+        }
+
         info!(
-            "Fetched {} pools from external API",
-            all_pool_data.len()
+            "Fetched {} pools from external API ({} cached)",
+            fetched_pools.len(),
+            cached_pools.len()
         );
-        
-        // Enrich with vault balances
-        let enriched_pools = self.enrich_with_vault_balances(all_pool_data).await?;
-        
-        Ok(enriched_pools)
+
+        cached_pools.extend(fetched_pools);
+        Ok(cached_pools)
     }
 
     /// Fetch multiple accounts from external API using getMultipleAccounts RPC call
-    /// 
+    ///
     /// This uses the standard Solana JSON-RPC getMultipleAccounts method but
     /// against a high-performance external endpoint (e.g., Helius) that may have
     /// better indexing or caching than a local fork.
@@ -540,10 +490,10 @@ impl TokenFetcher {
         &self,
         pubkeys: &[Pubkey],
         api_url: &str,
-    ) -> Result<Vec<Option<Account>>> {
+    ) -> Result<(u64, Vec<Option<Account>>)> {
         // Build JSON-RPC request
         let pubkey_strs: Vec<String> = pubkeys.iter().map(|pk| pk.to_string()).collect();
-        
+
         let request_body = serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -556,7 +506,7 @@ impl TokenFetcher {
                 }
             ]
         });
-        
+
         // Make HTTP request with retry
         let response = self.fetch_with_retry(
             "external_api_getMultipleAccounts",
@@ -567,68 +517,20 @@ impl TokenFetcher {
                     .send()
                     .await
                     .context("Failed to send request to external API")?;
-                    
+
                 let json: serde_json::Value = resp
                     .json()
                     .await
                     .context("Failed to parse external API response")?;
-                    
+
                 Ok(json)
             }
         ).await?;
-        
-        // Parse response
-        let accounts_json = response["result"]["value"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid external API response format"))?;
-        
-        println!("🔍 API returned {} account entries", accounts_json.len());
-        
-        let mut accounts = Vec::new();
-        for (i, account_json) in accounts_json.iter().enumerate() {
-            if account_json.is_null() {
-                println!("   Account {}: NULL", i);
-                accounts.push(None);
-            } else {
-                println!("   Account {}: EXISTS (data present)", i);
-                // Parse account data
-                let data_str = account_json["data"][0]
-                    .as_str()
-                    .ok_or_else(|| anyhow::anyhow!("Missing account data"))?;
-                
-                let data = base64::engine::general_purpose::STANDARD
-                    .decode(data_str)
-                    .context("Failed to decode base64 account data")?;
-                
-                let lamports = account_json["lamports"]
-                    .as_u64()
-                    .ok_or_else(|| anyhow::anyhow!("Missing lamports"))?;
-                
-                let owner_str = account_json["owner"]
-                    .as_str()
-                    .ok_or_else(|| anyhow::anyhow!("Missing owner"))?;
-                let owner = Pubkey::from_str(owner_str)
-                    .context("Invalid owner pubkey")?;
-                
-                let executable = account_json["executable"]
-                    .as_bool()
-                    .unwrap_or(false);
-                
-                let rent_epoch = account_json["rentEpoch"]
-                    .as_u64()
-                    .unwrap_or(0);
-                
-                accounts.push(Some(Account {
-                    lamports,
-                    data,
-                    owner,
-                    executable,
-                    rent_epoch,
-                }));
-            }
-        }
-        
-        Ok(accounts)
+
+        let (slot, accounts) = accounts_from_rpc_response(&response)?;
+        debug!("External API returned {} accounts at slot {}", accounts.len(), slot);
+
+        Ok((slot, accounts))
     }
 
     /// Invalidate cache for a specific account
@@ -663,21 +565,21 @@ impl TokenFetcher {
     // Private helper methods
 
     /// Feature: Exponential Backoff with Jitter
-    /// 
+    ///
     /// Generic retry wrapper with exponential backoff and jitter.
     /// This method provides:
     /// - Configurable retry attempts (default: 5)
     /// - Exponential delay growth (default: 2.0x factor)
     /// - Random jitter (default: ±25%) to prevent thundering herd
     /// - Maximum delay cap (default: 30s)
-    /// 
+    ///
     /// OPTIMIZE: The jitter calculation uses a random value within ±25% of the calculated delay,
     /// which reduces network congestion by 40-60% according to scientific research.
-    /// 
+    ///
     /// # Arguments
     /// * `operation_name` - Name for logging purposes
     /// * `operation` - Async function to retry
-    /// 
+    ///
     /// # Returns
     /// Result<T> - Success value or error after all retries exhausted
     async fn fetch_with_retry<T, F, Fut>(
@@ -691,10 +593,10 @@ impl TokenFetcher {
     {
         let mut attempt = 0;
         let mut delay_ms = self.config.initial_retry_delay_ms;
-        
+
         loop {
             attempt += 1;
-            
+
             match operation().await {
                 Ok(result) => {
                     if attempt > 1 {
@@ -714,10 +616,10 @@ impl TokenFetcher {
                         "{} failed on attempt {}/{}: {}",
                         operation_name, attempt, self.config.max_retries, e
                     );
-                    
+
                     // Calculate exponential backoff delay
                     let base_delay = delay_ms.min(self.config.max_retry_delay_ms);
-                    
+
                     // Add jitter: ±25% randomization
                     let jitter_range = (base_delay as f64 * self.config.jitter_percent) as u64;
                     let jitter = if jitter_range > 0 {
@@ -726,16 +628,16 @@ impl TokenFetcher {
                     } else {
                         0
                     };
-                    
+
                     let actual_delay = (base_delay as i64 + jitter).max(0) as u64;
-                    
+
                     debug!(
                         "Retrying {} in {}ms (base: {}ms, jitter: {:+}ms)",
                         operation_name, actual_delay, base_delay, jitter
                     );
-                    
+
                     tokio::time::sleep(Duration::from_millis(actual_delay)).await;
-                    
+
                     // Grow delay exponentially for next attempt
                     delay_ms = (delay_ms as f64 * self.config.retry_growth_factor) as u64;
                 }
@@ -743,417 +645,162 @@ impl TokenFetcher {
         }
     }
 
-    async fn fetch_account_with_retry(&self, pubkey: &Pubkey) -> Result<Account> {
+    /// Fetch one account with the slot it was read at, through the account cache
+    async fn fetch_observed_account(&self, pubkey: &Pubkey) -> Result<ObservedAccount> {
+        // Check cache first
+        if let Some(observed) = self.account_cache.get(pubkey).await {
+            debug!("Cache hit for account: {}", pubkey);
+            return Ok(observed);
+        }
+
+        debug!("Cache miss for account: {}, fetching from RPC", pubkey);
+
+        // Fetch with retry logic
+        let observed = self.fetch_account_with_retry(pubkey).await?;
+
+        // Update cache
+        self.account_cache.insert(*pubkey, observed.clone()).await;
+
+        Ok(observed)
+    }
+
+    /// Fetch accounts with the slot each was read at, through the account cache
+    async fn fetch_observed_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<ObservedAccount>>> {
+        let mut to_fetch = Vec::new();
+        let mut results = vec![None; pubkeys.len()];
+
+        // Check cache for each account
+        for (i, pubkey) in pubkeys.iter().enumerate() {
+            if let Some(observed) = self.account_cache.get(pubkey).await {
+                debug!("Cache hit for account: {}", pubkey);
+                results[i] = Some(observed);
+            } else {
+                to_fetch.push((i, *pubkey));
+            }
+        }
+
+        if to_fetch.is_empty() {
+            return Ok(results);
+        }
+
+        debug!("Fetching {} accounts in batch", to_fetch.len());
+
+        // Fetch missing accounts
+        let pubkeys_to_fetch: Vec<Pubkey> = to_fetch.iter().map(|(_, pk)| *pk).collect();
+        let (slot, fetched_accounts) = self
+            .fetch_accounts_batch_with_retry(&pubkeys_to_fetch)
+            .await?;
+
+        // Update results and cache
+        for ((i, pubkey), account) in to_fetch.into_iter().zip(fetched_accounts) {
+            if let Some(account) = account {
+                let observed = ObservedAccount { slot, account };
+                self.account_cache.insert(pubkey, observed.clone()).await;
+                results[i] = Some(observed);
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn fetch_account_with_retry(&self, pubkey: &Pubkey) -> Result<ObservedAccount> {
         let pubkey = *pubkey;
-        self.fetch_with_retry(
+        let response = self.fetch_with_retry(
             &format!("fetch_account({})", pubkey),
             || async move {
                 self.rpc_client
-                    .get_account(&pubkey)
+                    .get_account_with_commitment(&pubkey, self.rpc_client.commitment())
                     .await
                     .context(format!("Failed to fetch account {}", pubkey))
             },
         )
-        .await
+        .await?;
+
+        let account = response.value.ok_or_else(|| anyhow!("Account {} not found", pubkey))?;
+        Ok(ObservedAccount { slot: response.context.slot, account })
     }
 
-    async fn fetch_accounts_batch_with_retry(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+    async fn fetch_accounts_batch_with_retry(&self, pubkeys: &[Pubkey]) -> Result<(u64, Vec<Option<Account>>)> {
         let pubkeys_vec = pubkeys.to_vec();
-        self.fetch_with_retry(
+        let response = self.fetch_with_retry(
             &format!("fetch_accounts_batch({} accounts)", pubkeys.len()),
             || async {
                 self.rpc_client
-                    .get_multiple_accounts(&pubkeys_vec)
+                    .get_multiple_accounts_with_commitment(&pubkeys_vec, self.rpc_client.commitment())
                     .await
                     .context("Failed to fetch accounts batch")
             },
         )
-        .await
-    }
+        .await?;
 
-    fn parse_pool_data(&self, pool_pubkey: &Pubkey, account: &Account, dex_type: DexType) -> Result<PoolData> {
-        // This is a simplified parser - you'll need to implement proper parsing for each DEX type
-        // based on their account structures
-        
-        match dex_type {
-            DexType::Raydium => self.parse_raydium_pool(pool_pubkey, account),
-            DexType::Meteora => self.parse_meteora_pool(pool_pubkey, account),
-            DexType::Whirlpool => self.parse_whirlpool_pool(pool_pubkey, account),
-            DexType::Orca => self.parse_orca_pool(pool_pubkey, account),
-            DexType::Pump => self.parse_pump_pool(pool_pubkey, account),
-        }
+        Ok((response.context.slot, response.value))
     }
 
-    fn parse_raydium_pool(&self, pool_pubkey: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Raydium AMM V4 Pool Account Layout (752 bytes)
-        // Reference: https://github.com/raydium-io/raydium-amm
-        
-        let data = &account.data;
-        if data.len() < 752 {
-            return Err(anyhow!("Invalid Raydium pool account size: {} bytes", data.len()));
+    /// Decode a pool account with the adapter registered for its owner
+    /// program, checking it is the venue the caller expects
+    fn decode_pool(&self, pool_pubkey: &Pubkey, account: &Account, dex_type: &DexType) -> Result<PoolData> {
+        let pool = self.registry.decode_pool(pool_pubkey, account)?;
+        if pool.dex_type != *dex_type {
+            return Err(anyhow!(
+                "Pool {} is a {} pool, expected {}",
+                pool_pubkey, pool.dex_type, dex_type
+            ));
         }
-        
-        // Helper to read u64 in little-endian
-        let read_u64 = |offset: usize| -> u64 {
-            u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
-        };
-        
-        // Helper to read Pubkey (32 bytes)
-        let read_pubkey = |offset: usize| -> Pubkey {
-            Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
-        };
-        
-        // Raydium AMM V4 Layout Offsets (VERIFIED with actual mainnet data):
-        // 0-8: Status (u64)
-        // 8-16: Nonce (u64)
-        // ... (various pool parameters)
-        // 144-152: Trade fee numerator (u64)
-        // 152-160: Trade fee denominator (u64)
-        // ... (swap stats and other data)
-        // 336-368: Base Vault (Pubkey) ✅ VERIFIED
-        // 368-400: Quote Vault (Pubkey) ✅ VERIFIED
-        // 400-432: Base Mint (Pubkey) ✅ VERIFIED
-        // 432-464: Quote Mint (Pubkey) ✅ VERIFIED
-        // 464-496: LP Mint (Pubkey) ✅ VERIFIED
-        // 496-528: Open Orders (Pubkey)
-        // 528-560: Market ID (Pubkey)
-        // 560-592: Market Program ID (Pubkey)
-        // 592-624: Target Orders (Pubkey)
-        
-        let coin_vault = read_pubkey(336);   // baseVault ✅
-        let pc_vault = read_pubkey(368);     // quoteVault ✅
-        let coin_mint = read_pubkey(400);    // baseMint ✅
-        let pc_mint = read_pubkey(432);      // quoteMint ✅
-        let lp_mint = read_pubkey(464);      // lpMint ✅
-        
-        let fee_numerator = read_u64(144);
-        let fee_denominator = read_u64(152);
-        
-        // Note: Actual reserves need to be fetched from the vault accounts
-        // For now, we return the pool structure and the vaults
-        // The calling code should fetch vault balances separately
-        debug!("Parsed Raydium pool: coin={}, pc={}, fee={}/{}", 
-               coin_mint, pc_mint, fee_numerator, fee_denominator);
-        
-        Ok(PoolData {
-            pubkey: *pool_pubkey,
-            token_a_mint: coin_mint,
-            token_b_mint: pc_mint,
-            token_a_reserve: 0, // Need to fetch from coin_vault
-            token_b_reserve: 0, // Need to fetch from pc_vault
-            token_a_vault: Some(coin_vault),
-            token_b_vault: Some(pc_vault),
-            lp_mint: Some(lp_mint),
-            fee_numerator,
-            fee_denominator,
-            dex_type: DexType::Raydium,
-            last_updated: SystemTime::now(),
-        })
+        Ok(pool)
     }
 
-    fn parse_meteora_pool(&self, pool_pubkey: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Meteora DLMM (Dynamic Liquidity Market Maker) Pool Layout
-        // Reference: https://github.com/MeteoraAg/dlmm-sdk
-        
-        let data = &account.data;
-        if data.len() < 888 {
-            return Err(anyhow!("Invalid Meteora DLMM pool account size: {} bytes", data.len()));
+    /// Complete pools from their dependent accounts (vault balances, fee
+    /// configs, mints), read from the external API when one is configured
+    ///
+    /// Pools whose dependent accounts cannot be applied are dropped.
+    async fn complete_pools(&self, pools: Vec<PoolData>) -> Result<Vec<PoolData>> {
+        let mut addresses: Vec<Pubkey> = pools.iter()
+            .flat_map(|pool| self.registry.dependent_accounts(pool))
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+
+        if addresses.is_empty() {
+            return Ok(pools);
         }
-        
-        // Helper to read u64 in little-endian
-        let read_u64 = |offset: usize| -> u64 {
-            u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
-        };
-        
-        // Helper to read u16 in little-endian
-        let read_u16 = |offset: usize| -> u16 {
-            u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
-        };
-        
-        // Helper to read Pubkey (32 bytes)
-        let read_pubkey = |offset: usize| -> Pubkey {
-            Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
-        };
-        
-        // Meteora DLMM LbPair Account Layout:
-        // 0-8: Discriminator
-        // 8-16: Parameters (Pubkey reference or inline)
-        // 16-48: V parameters (Pubkey)
-        // 48-56: Bump seed (u8[8])
-        // 56-64: Bin step (u16)
-        // 64-96: Reserve X (Pubkey)
-        // 96-128: Reserve Y (Pubkey)
-        // 128-160: Token X Mint (Pubkey)
-        // 160-192: Token Y Mint (Pubkey)
-        // 192-224: Oracle (Pubkey)
-        // 224-256: Base factor (u16, aligned to 32)
-        // 256-260: Active bin ID (i32)
-        // 260-264: Status (u8)
-        // 264-280: Fees (structure with base fee, protocol fee, etc.)
-        // 280-312: Protocol fee X (u64)
-        // 312-344: Protocol fee Y (u64)
-        // ...
-        
-        let token_x_reserve = read_pubkey(64);
-        let token_y_reserve = read_pubkey(96);
-        let token_x_mint = read_pubkey(128);
-        let token_y_mint = read_pubkey(160);
-        
-        // Base fee is typically in basis points
-        // Meteora usually has dynamic fees, but we'll use a default
-        let bin_step = read_u16(56);
-        
-        // Fee calculation: base_fee_rate is typically derived from bin_step
-        // For DLMM pools, fee = base_fee_pct which varies by bin_step
-        // Common values: bin_step 1 = 0.01%, bin_step 10 = 0.10%, etc.
-        let fee_numerator = (bin_step as u64).max(1); // At least 1 basis point
-        let fee_denominator = 10000u64;
-        
-        debug!("Parsed Meteora DLMM: token_x={}, token_y={}, bin_step={}, fee={}/{}",
-               token_x_mint, token_y_mint, bin_step, fee_numerator, fee_denominator);
-        
-        Ok(PoolData {
-            pubkey: *pool_pubkey,
-            token_a_mint: token_x_mint,
-            token_b_mint: token_y_mint,
-            token_a_reserve: 0, // Need to fetch from token_x_reserve
-            token_b_reserve: 0, // Need to fetch from token_y_reserve
-            token_a_vault: Some(token_x_reserve),
-            token_b_vault: Some(token_y_reserve),
-            lp_mint: None, // DLMM uses position NFTs instead of fungible LP tokens
-            fee_numerator,
-            fee_denominator,
-            dex_type: DexType::Meteora,
-            last_updated: SystemTime::now(),
-        })
-    }
 
-    fn parse_whirlpool_pool(&self, pool_pubkey: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Orca Whirlpool Account Layout (653 bytes for Whirlpool state)
-        // Reference: https://github.com/orca-so/whirlpools
-        
-        let data = &account.data;
-        if data.len() < 653 {
-            return Err(anyhow!("Invalid Whirlpool account size: {} bytes", data.len()));
+        debug!("Fetching {} dependent accounts for {} pools", addresses.len(), pools.len());
+
+        // For mainnet pools read through an external API the dependent
+        // accounts live there too; for a local fork they exist locally
+        let mut accounts = HashMap::new();
+        for chunk in addresses.chunks(self.config.batch_size.clamp(1, 100)) {
+            let fetched = match self.config.external_data_api_url {
+                Some(ref api_url) => self.fetch_accounts_from_external_api(chunk, api_url).await?.1,
+                None => self.fetch_accounts_batch(chunk).await?,
+            };
+            accounts.extend(chunk.iter().zip(fetched)
+                .filter_map(|(address, account)| account.map(|account| (*address, account))));
         }
-        
-        // Helper to read u64 in little-endian
-        let read_u64 = |offset: usize| -> u64 {
-            u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
-        };
-        
-        // Helper to read u16 in little-endian
-        let read_u16 = |offset: usize| -> u16 {
-            u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
-        };
-        
-        // Helper to read Pubkey (32 bytes)
-        let read_pubkey = |offset: usize| -> Pubkey {
-            Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
-        };
-        
-        // Whirlpool Account Layout:
-        // 0-8: Discriminator
-        // 8-40: Whirlpools config (Pubkey)
-        // 40-72: Whirlpool bump (u8[1]) + padding
-        // 72-74: Tick spacing (u16)
-        // 74-76: Tick spacing seed (u16[2])
-        // 76-78: Fee rate (u16) - in hundredths of basis point
-        // 78-80: Protocol fee rate (u16)
-        // 80-82: Liquidity (u128)
-        // 82-114: sqrt_price (u128)
-        // 114-118: Tick current index (i32)
-        // 118-120: Protocol fee owed A (u64)
-        // 120-128: Protocol fee owed B (u64)
-        // 128-160: Token mint A (Pubkey)
-        // 160-192: Token vault A (Pubkey)
-        // 192-200: Fee growth global A (u128)
-        // 200-232: Token mint B (Pubkey)
-        // 232-264: Token vault B (Pubkey)
-        // 264-272: Fee growth global B (u128)
-        // 272-280: Reward last updated timestamp (u64)
-        // 280+: Reward infos (3 x 128 bytes)
-        
-        let token_mint_a = read_pubkey(128);
-        let token_vault_a = read_pubkey(160);
-        let token_mint_b = read_pubkey(200);
-        let token_vault_b = read_pubkey(232);
-        
-        // Fee rate is in hundredths of a basis point
-        // e.g., 30 = 0.30% = 30 basis points = 30/10000
-        let fee_rate = read_u16(76);
-        let fee_numerator = fee_rate as u64;
-        let fee_denominator = 10000u64;
-        
-        debug!("Parsed Whirlpool: token_a={}, token_b={}, fee={}/{}",
-               token_mint_a, token_mint_b, fee_numerator, fee_denominator);
-        
-        Ok(PoolData {
-            pubkey: *pool_pubkey,
-            token_a_mint: token_mint_a,
-            token_b_mint: token_mint_b,
-            token_a_reserve: 0, // Need to fetch from token_vault_a
-            token_b_reserve: 0, // Need to fetch from token_vault_b
-            token_a_vault: Some(token_vault_a),
-            token_b_vault: Some(token_vault_b),
-            lp_mint: None, // Whirlpools don't use traditional LP tokens
-            fee_numerator,
-            fee_denominator,
-            dex_type: DexType::Whirlpool,
-            last_updated: SystemTime::now(),
-        })
-    }
 
-    /// Enrich pool data with actual vault balances
-    /// 
-    /// After parsing pool metadata (mints, vaults, fees), this method fetches
-    /// the actual token balances from the vault accounts to get real-time reserves.
-    /// 
-    /// This is necessary because pool accounts store vault addresses, not balances.
-    async fn enrich_with_vault_balances(&self, mut pools: Vec<PoolData>) -> Result<Vec<PoolData>> {
-        println!("🔧 Enriching {} pools with vault balances", pools.len());
-        
-        // Collect all vault addresses that need to be fetched
-        let mut vault_addresses = Vec::new();
-        for pool in &pools {
-            if let Some(vault_a) = pool.token_a_vault {
-                vault_addresses.push(vault_a);
-                println!("  Adding vault_a: {}", vault_a);
-            }
-            if let Some(vault_b) = pool.token_b_vault {
-                vault_addresses.push(vault_b);
-                println!("  Adding vault_b: {}", vault_b);
-            }
-        }
-        
-        if vault_addresses.is_empty() {
-            println!("⚠️  No vault addresses to fetch");
-            return Ok(pools);
-        }
-        
-                
-        println!("📡 Fetching {} vault accounts...", vault_addresses.len());
-        
-        // Use external API if configured, otherwise use local RPC
-        // For mainnet pools: vaults exist on mainnet, use external API
-        // For local fork: vaults exist locally, use local RPC
-        let vault_accounts = if let Some(ref api_url) = self.config.external_data_api_url {
-            println!("   Using external API for vaults: {}", api_url.split('?').next().unwrap());
-            match self.fetch_accounts_from_external_api(&vault_addresses, api_url).await {
-                Ok(accounts) => {
-                    println!("   ✅ Fetched {} vault accounts from external API", accounts.len());
-                    accounts
-                }
-                Err(e) => {
-                    println!("   ❌ Failed to fetch vaults from external API: {}", e);
-                    return Err(e);
-                }
-            }
-        } else {
-            println!("   Using local RPC for vaults");
-            match self.fetch_accounts_batch(&vault_addresses).await {
-                Ok(accounts) => {
-                    println!("   ✅ Fetched {} vault accounts from local RPC", accounts.len());
-                    accounts
-                }
+        Ok(pools.into_iter()
+            .filter_map(|mut pool| match self.registry.apply_dependent_accounts(&mut pool, &accounts) {
+                Ok(()) => Some(pool),
                 Err(e) => {
-                    println!("   ❌ Failed to fetch vaults from local RPC: {}", e);
-                    return Err(e);
-                }
-            }
-        };
-        
-        println!("🔍 API returned {} account entries", vault_accounts.len());
-        
-        println!("📦 Processing {} vault responses...", vault_accounts.len());
-        
-        // Create a map of vault_address -> balance
-        let mut vault_balances = std::collections::HashMap::new();
-        for (vault_addr, account_opt) in vault_addresses.iter().zip(vault_accounts.iter()) {
-            println!("   Checking vault: {}", vault_addr);
-            if let Some(account) = account_opt {
-                println!("     Account exists, data length: {}", account.data.len());
-                // Parse SPL Token Account to get amount
-                // SPL Token Account layout: 165 bytes
-                // Offset 64-72: amount (u64)
-                if account.data.len() >= 72 {
-                    let amount = u64::from_le_bytes(
-                        account.data[64..72].try_into().unwrap()
-                    );
-                    println!("     ✅ Parsed amount: {}", amount);
-                    vault_balances.insert(*vault_addr, amount);
-                    debug!("Vault {} balance: {}", vault_addr, amount);
-                } else {
-                    println!("     ❌ Data too short: {}", account.data.len());
-                    warn!("Vault {} has invalid data length: {}", vault_addr, account.data.len());
-                }
-            } else {
-                println!("     ❌ Account is None");
-                warn!("Vault {} not found", vault_addr);
-            }
-        }
-        
-        // Update pool data with vault balances
-        for pool in &mut pools {
-            if let Some(vault_a) = pool.token_a_vault {
-                if let Some(&balance) = vault_balances.get(&vault_a) {
-                    pool.token_a_reserve = balance;
-                } else {
-                    warn!("No balance found for vault_a {} in pool {}", vault_a, pool.pubkey);
-                }
-            }
-            if let Some(vault_b) = pool.token_b_vault {
-                if let Some(&balance) = vault_balances.get(&vault_b) {
-                    pool.token_b_reserve = balance;
-                } else {
-                    warn!("No balance found for vault_b {} in pool {}", vault_b, pool.pubkey);
+                    warn!("Failed to complete pool {}: {}", pool.pool_address, e);
+                    None
                 }
-            }
-        }
-        
-        info!("Enriched {} pools with vault balances", pools.len());
-        
-        Ok(pools)
+            })
+            .collect())
     }
 
-    fn parse_orca_pool(&self, pool_pubkey: &Pubkey, _account: &Account) -> Result<PoolData> {
-        // Placeholder - implement actual Orca pool parsing
-        warn!("Orca pool parsing not fully implemented");
-        
-        Ok(PoolData {
-            pubkey: *pool_pubkey,
-            token_a_mint: Pubkey::default(),
-            token_b_mint: Pubkey::default(),
-            token_a_reserve: 0,
-            token_b_reserve: 0,
-            token_a_vault: None,
-            token_b_vault: None,
-            lp_mint: None,
-            fee_numerator: 30,
-            fee_denominator: 10000,
-            dex_type: DexType::Orca,
-            last_updated: SystemTime::now(),
-        })
-    }
-
-    fn parse_pump_pool(&self, pool_pubkey: &Pubkey, _account: &Account) -> Result<PoolData> {
-        // Placeholder - implement actual Pump pool parsing
-        warn!("Pump pool parsing not fully implemented");
-        
-        Ok(PoolData {
-            pubkey: *pool_pubkey,
-            token_a_mint: Pubkey::default(),
-            token_b_mint: Pubkey::default(),
-            token_a_reserve: 0,
-            token_b_reserve: 0,
-            token_a_vault: None,
-            token_b_vault: None,
-            lp_mint: None,
-            fee_numerator: 100,
-            fee_denominator: 10000,
-            dex_type: DexType::Pump,
-            last_updated: SystemTime::now(),
-        })
+    /// Cache pool data with the metadata TTL, if caching is enabled
+    fn cache_pool(&self, pool_data: &PoolData) {
+        if self.config.enable_caching {
+            self.pool_cache.insert(
+                pool_data.pool_address,
+                CachedPoolData {
+                    pool_data: pool_data.clone(),
+                    cached_at: SystemTime::now(),
+                    ttl_seconds: self.config.metadata_ttl_seconds,
+                },
+            );
+        }
     }
 }
 
@@ -1179,7 +826,44 @@ mod tests {
             10000,
             3,
         );
-        
+
         assert_eq!(fetcher.config.max_retries, 3);
     }
+
+    #[test]
+    fn test_accounts_from_rpc_response() {
+        use base64::Engine;
+
+        let owner = Pubkey::new_unique();
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "apiVersion": "1.18.22", "slot": 289_000_123u64 },
+                "value": [
+                    {
+                        "data": [base64::engine::general_purpose::STANDARD.encode([1u8, 2, 3]), "base64"],
+                        "executable": false,
+                        "lamports": 2_039_280u64,
+                        "owner": owner.to_string(),
+                        "rentEpoch": 18_446_744_073_709_551_615u64,
+                        "space": 3
+                    },
+                    null
+                ]
+            }
+        });
+
+        let (slot, accounts) = accounts_from_rpc_response(&response).unwrap();
+        assert_eq!(slot, 289_000_123);
+        assert_eq!(accounts.len(), 2);
+        let account = accounts[0].as_ref().unwrap();
+        assert_eq!(account.data, vec![1, 2, 3]);
+        assert_eq!(account.owner, owner);
+        assert_eq!(account.lamports, 2_039_280);
+        assert!(accounts[1].is_none());
+
+        let error = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32005, "message": "Node is behind" } });
+        assert!(accounts_from_rpc_response(&error).is_err());
+    }
 }
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::Utc;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
@@ -7,8 +8,11 @@ use std::time::{Duration, SystemTime};
 use tokio::time;
 use tracing::{debug, error, info};
 
-use super::token_fetch::{DexType, PoolData, TokenFetcher};
+use super::detector::{ArbitrageOpportunity, RiskLevel, ScoreBreakdown};
+use super::token_fetch::TokenFetcher;
 use super::constants::USDC_MINT;
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::{ArbitrageCycle, CycleStep, DexType, ExchangeEdge};
 
 // Feature: Base Currency Definition for Profit Calculation
 // 
@@ -65,40 +69,10 @@ pub struct PriceInfo {
     pub timestamp: std::time::SystemTime,
 }
 
-/// Arbitrage opportunity with detailed profitability analysis
-#[derive(Debug, Clone)]
-pub struct ArbitrageOpportunity {
-    pub buy_dex: DexType,
-    pub buy_pool: Pubkey,
-    pub buy_price: f64,
-    pub sell_dex: DexType,
-    pub sell_pool: Pubkey,
-    pub sell_price: f64,
-    pub gross_profit_bps: u64, // Gross profit in basis points
-    pub net_profit_bps: i64,   // Net profit after fees/slippage
-    pub estimated_slippage_bps: u64,
-    pub total_fees_bps: u64,
-    pub token_a_mint: Pubkey,
-    pub token_b_mint: Pubkey,
-    pub recommended_amount: u64,
-    pub execution_risk: RiskLevel,
-    /// The token in which profit is denominated (usually the quote token)
-    pub profit_token: Pubkey,
-    /// Profit normalized to BASE_CURRENCY_MINT (USDC) for consistent comparison
-    pub profit_in_base_currency_bps: Option<i64>,
-}
-
-/// Risk level for arbitrage execution
-#[derive(Debug, Clone, PartialEq)]
-pub enum RiskLevel {
-    Low,    // High liquidity, low slippage
-    Medium, // Moderate liquidity
-    High,   // Low liquidity, high slippage
-}
-
 /// Market data fetcher for price monitoring and arbitrage detection
 pub struct MarketDataFetcher {
     token_fetcher: Arc<TokenFetcher>,
+    #[allow(dead_code)]
     rpc_client: Arc<RpcClient>,
     min_profit_bps: u64,
     max_slippage_bps: u64,
@@ -134,10 +108,10 @@ impl MarketDataFetcher {
         let price = self.calculate_price(&pool_data)?;
 
         Ok(PriceInfo {
-            token_a_mint: pool_data.token_a_mint,
-            token_b_mint: pool_data.token_b_mint,
+            token_a_mint: pool_data.token_a,
+            token_b_mint: pool_data.token_b,
             price,
-            liquidity: pool_data.token_a_reserve.min(pool_data.token_b_reserve),
+            liquidity: pool_data.reserve_a.min(pool_data.reserve_b),
             dex_type: pool_data.dex_type,
             pool_address: *pool_pubkey,
             timestamp: std::time::SystemTime::now(),
@@ -171,14 +145,12 @@ impl MarketDataFetcher {
         // Group prices by token pair
         let mut prices_by_pair: HashMap<(Pubkey, Pubkey), Vec<PriceInfo>> = HashMap::new();
 
-        for result in price_results {
-            if let Ok(price_info) = result {
-                let pair_key = normalize_pair(price_info.token_a_mint, price_info.token_b_mint);
-                prices_by_pair
-                    .entry(pair_key)
-                    .or_insert_with(Vec::new)
-                    .push(price_info);
-            }
+        for price_info in price_results.into_iter().flatten() {
+            let pair_key = normalize_pair(price_info.token_a_mint, price_info.token_b_mint);
+            prices_by_pair
+                .entry(pair_key)
+                .or_default()
+                .push(price_info);
         }
 
         // Parallelize arbitrage calculation across all pool pairs
@@ -220,7 +192,7 @@ impl MarketDataFetcher {
         }
 
         // Sort by net profit (descending)
-        all_opportunities.sort_by(|a, b| b.net_profit_bps.cmp(&a.net_profit_bps));
+        all_opportunities.sort_by_key(|opportunity| std::cmp::Reverse(opportunity.expected_profit_bps));
 
         info!("Found {} total arbitrage opportunities", all_opportunities.len());
 
@@ -295,10 +267,8 @@ impl MarketDataFetcher {
                 }
 
                 // Calculate total fees (DEX fees + transaction fees)
-                let buy_fee_bps = (buy_pool_data.fee_numerator * 10000)
-                    / buy_pool_data.fee_denominator;
-                let sell_fee_bps = (sell_pool_data.fee_numerator * 10000)
-                    / sell_pool_data.fee_denominator;
+                let buy_fee_bps = buy_pool_data.fee_bps as u64;
+                let sell_fee_bps = sell_pool_data.fee_bps as u64;
                 let tx_fee_bps = 10; // Approximate 0.1% for transaction costs
                 let total_fees_bps = buy_fee_bps + sell_fee_bps + tx_fee_bps;
 
@@ -323,36 +293,43 @@ impl MarketDataFetcher {
                 // For pairs like TOKEN/SOL, profit is in SOL
                 let profit_token = Self::determine_profit_token(token_a, token_b);
 
-                let opportunity = ArbitrageOpportunity {
-                    buy_dex: buy_info.dex_type.clone(),
-                    buy_pool: buy_info.pool_address,
-                    buy_price: buy_info.price,
-                    sell_dex: sell_info.dex_type.clone(),
-                    sell_pool: sell_info.pool_address,
-                    sell_price: sell_info.price,
-                    gross_profit_bps,
-                    net_profit_bps,
-                    estimated_slippage_bps: total_slippage_bps,
-                    total_fees_bps,
-                    token_a_mint: token_a,
-                    token_b_mint: token_b,
-                    recommended_amount,
-                    execution_risk,
+                // Two-hop cycle through the buy and sell pools, starting
+                // and ending in the profit token
+                let cycle = Self::pair_cycle(
+                    buy_info,
+                    sell_info,
                     profit_token,
-                    profit_in_base_currency_bps: None, // Will be populated by normalization
+                    buy_fee_bps as u16,
+                    sell_fee_bps as u16,
+                    gross_profit_bps as i64,
+                    total_fees_bps as u16,
+                );
+                let expected_profit = (recommended_amount as i128 * net_profit_bps as i128 / 10_000) as i64;
+                let expected_output_amount = recommended_amount.saturating_add_signed(expected_profit);
+
+                let opportunity = ArbitrageOpportunity {
+                    cycle,
+                    optimal_input_amount: recommended_amount,
+                    expected_output_amount,
+                    expected_profit_sol: expected_profit as f64 / 1e9,
+                    expected_profit_bps: net_profit_bps,
+                    detected_at: Utc::now().timestamp(),
+                    priority_score: 0.0,
+                    score: ScoreBreakdown::default(),
+                    risk_level: execution_risk,
                 };
 
                 info!(
                     "Found arbitrage: Buy {:?} @ {:.6}, Sell {:?} @ {:.6} | Gross: {} bps, Net: {} bps, Fees: {} bps, Slippage: {} bps, Risk: {:?}",
-                    opportunity.buy_dex,
-                    opportunity.buy_price,
-                    opportunity.sell_dex,
-                    opportunity.sell_price,
-                    opportunity.gross_profit_bps,
-                    opportunity.net_profit_bps,
-                    opportunity.total_fees_bps,
-                    opportunity.estimated_slippage_bps,
-                    opportunity.execution_risk
+                    buy_info.dex_type,
+                    buy_info.price,
+                    sell_info.dex_type,
+                    sell_info.price,
+                    opportunity.gross_profit_bps(),
+                    opportunity.expected_profit_bps,
+                    opportunity.total_fee_bps(),
+                    opportunity.estimated_slippage_bps(),
+                    opportunity.risk_level
                 );
 
                 opportunities.push(opportunity);
@@ -362,6 +339,63 @@ impl MarketDataFetcher {
         Ok(opportunities)
     }
 
+    /// Cycle that buys token B on the cheaper pool and sells it on the dearer
+    /// one, oriented to start and end in `profit_token`
+    ///
+    /// Prices are token A per token B, so the buy leg pays A for B at
+    /// `1 / buy_price` and the sell leg receives `sell_price` A per B.
+    fn pair_cycle(
+        buy_info: &PriceInfo,
+        sell_info: &PriceInfo,
+        profit_token: Pubkey,
+        buy_fee_bps: u16,
+        sell_fee_bps: u16,
+        gross_profit_bps: i64,
+        total_fee_bps: u16,
+    ) -> ArbitrageCycle {
+        let (token_a, token_b) = (buy_info.token_a_mint, buy_info.token_b_mint);
+        let buy_leg = CycleStep {
+            from_token: token_a,
+            to_token: token_b,
+            dex: buy_info.dex_type.clone(),
+            pool: buy_info.pool_address,
+            rate: 1.0 / buy_info.price,
+            fee_bps: buy_fee_bps,
+            splits: Vec::new(),
+        };
+        let sell_leg = CycleStep {
+            from_token: token_b,
+            to_token: token_a,
+            dex: sell_info.dex_type.clone(),
+            pool: sell_info.pool_address,
+            rate: sell_info.price,
+            fee_bps: sell_fee_bps,
+            splits: Vec::new(),
+        };
+
+        // Starting from token B the same legs run the other way round: sell
+        // B where it is dear, then buy it back where it is cheap
+        let (start_token, path) = if profit_token == token_b {
+            (token_b, vec![sell_leg, buy_leg])
+        } else {
+            (token_a, vec![buy_leg, sell_leg])
+        };
+
+        let cycle_weight = path.iter()
+            .map(|step| ExchangeEdge::calculate_weight(step.rate, step.fee_bps))
+            .sum();
+
+        ArbitrageCycle {
+            execution_time_estimate_ms: (path.len() as u64 * 50) + 400,
+            path,
+            gross_profit_bps,
+            net_profit_after_fees: (gross_profit_bps - total_fee_bps as i64) as f64 / 10_000.0,
+            total_fee_bps,
+            start_token,
+            cycle_weight,
+        }
+    }
+
     /// Assess execution risk based on liquidity and slippage
     fn assess_risk(buy_liquidity: u64, sell_liquidity: u64, slippage_bps: u64) -> RiskLevel {
         let min_liquidity = buy_liquidity.min(sell_liquidity);
@@ -377,11 +411,11 @@ impl MarketDataFetcher {
 
     /// Calculate price from pool data
     fn calculate_price(&self, pool_data: &PoolData) -> Result<f64> {
-        if pool_data.token_b_reserve == 0 {
+        if pool_data.reserve_b == 0 {
             return Err(anyhow::anyhow!("Zero reserve in pool"));
         }
 
-        let price = pool_data.token_a_reserve as f64 / pool_data.token_b_reserve as f64;
+        let price = pool_data.reserve_a as f64 / pool_data.reserve_b as f64;
         Ok(price)
     }
 
@@ -400,16 +434,16 @@ impl MarketDataFetcher {
         // Simplified constant product AMM slippage calculation (x * y = k)
         // Real implementation should handle different AMM types (stable swap, concentrated liquidity, etc.)
         
-        if pool_data.token_a_reserve == 0 || trade_amount == 0 {
+        if pool_data.reserve_a == 0 || trade_amount == 0 {
             return 0.0;
         }
 
-        let k = pool_data.token_a_reserve as f64 * pool_data.token_b_reserve as f64;
-        let new_reserve_a = pool_data.token_a_reserve as f64 + trade_amount as f64;
+        let k = pool_data.reserve_a as f64 * pool_data.reserve_b as f64;
+        let new_reserve_a = pool_data.reserve_a as f64 + trade_amount as f64;
         let new_reserve_b = k / new_reserve_a;
-        let amount_out = pool_data.token_b_reserve as f64 - new_reserve_b;
+        let amount_out = pool_data.reserve_b as f64 - new_reserve_b;
 
-        let expected_price = pool_data.token_a_reserve as f64 / pool_data.token_b_reserve as f64;
+        let expected_price = pool_data.reserve_a as f64 / pool_data.reserve_b as f64;
         let actual_price = trade_amount as f64 / amount_out;
 
         ((actual_price - expected_price) / expected_price).abs()
@@ -539,28 +573,26 @@ impl PriceMonitor {
             let mut significant_change = false;
             let mut last_prices = self.last_prices.write().await;
 
-            for result in &price_results {
-                if let Ok(price_info) = result {
-                    let pool_key = price_info.pool_address;
+            for price_info in price_results.iter().flatten() {
+                let pool_key = price_info.pool_address;
+                
+                if let Some(&last_price) = last_prices.get(&pool_key) {
+                    let change_bps = ((price_info.price - last_price).abs() / last_price
+                        * 10000.0) as u64;
                     
-                    if let Some(&last_price) = last_prices.get(&pool_key) {
-                        let change_bps = ((price_info.price - last_price).abs() / last_price
-                            * 10000.0) as u64;
-                        
-                        if change_bps >= self.price_threshold_bps {
-                            debug!(
-                                "Significant price change detected on pool {}: {} bps",
-                                pool_key, change_bps
-                            );
-                            significant_change = true;
-                        }
-                    } else {
-                        // First time seeing this pool
+                    if change_bps >= self.price_threshold_bps {
+                        debug!(
+                            "Significant price change detected on pool {}: {} bps",
+                            pool_key, change_bps
+                        );
                         significant_change = true;
                     }
-                    
-                    last_prices.insert(pool_key, price_info.price);
+                } else {
+                    // First time seeing this pool
+                    significant_change = true;
                 }
+                
+                last_prices.insert(pool_key, price_info.price);
             }
 
             drop(last_prices); // Release write lock
@@ -586,16 +618,11 @@ impl PriceMonitor {
                         // Log top 5 opportunities
                         for opp in opportunities.iter().take(5) {
                             info!(
-                                "Opportunity: {} -> {} | Buy: {:?} @ {:.6} | Sell: {:?} @ {:.6} | Gross: {} bps | Net: {} bps | Risk: {:?}",
-                                opp.token_a_mint,
-                                opp.token_b_mint,
-                                opp.buy_dex,
-                                opp.buy_price,
-                                opp.sell_dex,
-                                opp.sell_price,
-                                opp.gross_profit_bps,
-                                opp.net_profit_bps,
-                                opp.execution_risk
+                                "Opportunity: {} | Gross: {} bps | Net: {} bps | Risk: {:?}",
+                                opp.route(),
+                                opp.gross_profit_bps(),
+                                opp.expected_profit_bps,
+                                opp.risk_level
                             );
                             
                             // TODO: Execute arbitrage transaction
@@ -632,32 +659,47 @@ impl PriceMonitor {
 mod tests {
     use super::*;
 
-    /// Helper function to create a mock ArbitrageOpportunity for testing
+    /// Helper function to create a mock ArbitrageOpportunity for testing:
+    /// a two-pool cycle from USDC of 1 SOL equivalent
     fn create_mock_opportunity(
         buy_price: f64,
         sell_price: f64,
-        gross_profit_bps: u64,
+        gross_profit_bps: i64,
         net_profit_bps: i64,
-        estimated_slippage_bps: u64,
-        total_fees_bps: u64,
+        total_fees_bps: u16,
     ) -> ArbitrageOpportunity {
-        ArbitrageOpportunity {
-            buy_dex: DexType::Raydium,
-            buy_pool: Pubkey::new_unique(),
-            buy_price,
-            sell_dex: DexType::Meteora,
-            sell_pool: Pubkey::new_unique(),
-            sell_price,
+        let token = Pubkey::new_unique();
+        let price_info = |price: f64, dex_type: DexType| PriceInfo {
+            token_a_mint: BASE_CURRENCY_MINT,
+            token_b_mint: token,
+            price,
+            liquidity: 100_000_000_000,
+            dex_type,
+            pool_address: Pubkey::new_unique(),
+            timestamp: SystemTime::now(),
+        };
+        let cycle = MarketDataFetcher::pair_cycle(
+            &price_info(buy_price, DexType::Raydium),
+            &price_info(sell_price, DexType::MeteoraDlmm),
+            BASE_CURRENCY_MINT, // Default to USDC for tests
+            total_fees_bps / 2,
+            total_fees_bps - total_fees_bps / 2,
             gross_profit_bps,
-            net_profit_bps,
-            estimated_slippage_bps,
             total_fees_bps,
-            token_a_mint: Pubkey::new_unique(),
-            token_b_mint: Pubkey::new_unique(),
-            recommended_amount: 1_000_000_000, // 1 SOL
-            execution_risk: RiskLevel::Low,
-            profit_token: BASE_CURRENCY_MINT, // Default to USDC for tests
-            profit_in_base_currency_bps: Some(net_profit_bps), // Already in USDC
+        );
+        let input = 1_000_000_000u64; // 1 SOL
+        let profit = input as i64 * net_profit_bps / 10_000;
+
+        ArbitrageOpportunity {
+            cycle,
+            optimal_input_amount: input,
+            expected_output_amount: input.saturating_add_signed(profit),
+            expected_profit_sol: profit as f64 / 1e9,
+            expected_profit_bps: net_profit_bps,
+            detected_at: 0,
+            priority_score: 0.0,
+            score: ScoreBreakdown::default(),
+            risk_level: RiskLevel::Low,
         }
     }
 
@@ -672,14 +714,13 @@ mod tests {
             1.02,   // sell_price
             200,    // gross_profit_bps (2%)
             150,    // net_profit_bps (1.5% after fees)
-            30,     // estimated_slippage_bps (0.3%)
             20,     // total_fees_bps (0.2%)
         );
 
         // With min_profit_bps = 50 (0.5%), this should be profitable
         let min_profit_bps = 50;
         assert!(
-            profitable_opp.net_profit_bps as u64 > min_profit_bps,
+            profitable_opp.expected_profit_bps as u64 > min_profit_bps,
             "Opportunity with 150 bps profit should exceed 50 bps threshold"
         );
 
@@ -690,12 +731,11 @@ mod tests {
             1.003, // sell_price
             30,    // gross_profit_bps (0.3%)
             10,    // net_profit_bps (0.1% after fees)
-            15,    // estimated_slippage_bps (0.15%)
             5,     // total_fees_bps (0.05%)
         );
 
         assert!(
-            (unprofitable_opp.net_profit_bps as i64) < (min_profit_bps as i64),
+            unprofitable_opp.expected_profit_bps < (min_profit_bps as i64),
             "Opportunity with 10 bps profit should be below 50 bps threshold"
         );
 
@@ -705,12 +745,11 @@ mod tests {
             1.005, // sell_price
             50,    // gross_profit_bps (0.5%)
             50,    // net_profit_bps (0.5%)
-            0,     // estimated_slippage_bps (0%)
             0,     // total_fees_bps (0%)
         );
 
         assert_eq!(
-            edge_case_opp.net_profit_bps as u64, min_profit_bps,
+            edge_case_opp.expected_profit_bps as u64, min_profit_bps,
             "Edge case: 50 bps profit equals 50 bps threshold"
         );
     }
@@ -726,19 +765,18 @@ mod tests {
             1.002, // sell_price
             20,    // gross_profit_bps (0.2%)
             -30,   // net_profit_bps (-0.3% after fees)
-            40,    // estimated_slippage_bps (0.4%)
             10,    // total_fees_bps (0.1%)
         );
 
         assert!(
-            losing_opp.net_profit_bps < 0,
+            losing_opp.expected_profit_bps < 0,
             "Net profit should be negative when fees exceed gross profit"
         );
 
         // Verify the math: gross_profit - slippage - fees = net_profit
-        let calculated_net = losing_opp.gross_profit_bps as i64
-            - losing_opp.estimated_slippage_bps as i64
-            - losing_opp.total_fees_bps as i64;
+        let calculated_net = losing_opp.gross_profit_bps()
+            - losing_opp.estimated_slippage_bps() as i64
+            - losing_opp.total_fee_bps() as i64;
         
         assert_eq!(
             calculated_net, -30,
@@ -756,19 +794,18 @@ mod tests {
             1.05, // sell_price (5% profit looks good)
             500,  // gross_profit_bps (5%)
             450,  // net_profit_bps (4.5%)
-            30,   // estimated_slippage_bps (0.3%)
             20,   // total_fees_bps (0.2%)
         );
-        zero_liquidity_opp.recommended_amount = 0;
+        zero_liquidity_opp.optimal_input_amount = 0;
 
         assert_eq!(
-            zero_liquidity_opp.recommended_amount, 0,
+            zero_liquidity_opp.optimal_input_amount, 0,
             "Zero liquidity pool should have zero recommended amount"
         );
 
         // Even with high profit, zero liquidity means no execution
         assert!(
-            zero_liquidity_opp.net_profit_bps > 400,
+            zero_liquidity_opp.expected_profit_bps > 400,
             "High profit opportunity exists but cannot be executed"
         );
     }
@@ -783,7 +820,6 @@ mod tests {
             1.10,  // sell_price (10% gross)
             1000,  // gross_profit_bps (10%)
             950,   // net_profit_bps (9.5%)
-            30,    // estimated_slippage_bps (0.3%)
             20,    // total_fees_bps (0.2%)
         );
 
@@ -793,19 +829,18 @@ mod tests {
             1.10,  // sell_price (10% gross)
             1000,  // gross_profit_bps (10%)
             200,   // net_profit_bps (2%)
-            750,   // estimated_slippage_bps (7.5%)
             50,    // total_fees_bps (0.5%)
         );
 
         assert!(
-            low_slippage_opp.net_profit_bps > high_slippage_opp.net_profit_bps,
+            low_slippage_opp.expected_profit_bps > high_slippage_opp.expected_profit_bps,
             "High slippage should significantly reduce net profit"
         );
 
         // Verify slippage impact: same gross profit, different net profit
-        assert_eq!(low_slippage_opp.gross_profit_bps, high_slippage_opp.gross_profit_bps);
+        assert_eq!(low_slippage_opp.gross_profit_bps(), high_slippage_opp.gross_profit_bps());
         
-        let slippage_impact = low_slippage_opp.net_profit_bps - high_slippage_opp.net_profit_bps;
+        let slippage_impact = low_slippage_opp.expected_profit_bps - high_slippage_opp.expected_profit_bps;
         assert!(
             slippage_impact > 700,
             "Slippage impact should be substantial: {} bps", slippage_impact
@@ -822,7 +857,6 @@ mod tests {
             1.05, // sell_price
             500,  // gross_profit_bps (5%)
             440,  // net_profit_bps (4.4%)
-            30,   // estimated_slippage_bps (0.3%)
             30,   // total_fees_bps (0.3%)
         );
 
@@ -832,11 +866,10 @@ mod tests {
             1.05, // sell_price
             500,  // gross_profit_bps (5%)
             370,  // net_profit_bps (3.7%)
-            30,   // estimated_slippage_bps (0.3%)
             100,  // total_fees_bps (1%)
         );
 
-        let fee_impact = standard_fees_opp.net_profit_bps - high_fees_opp.net_profit_bps;
+        let fee_impact = standard_fees_opp.expected_profit_bps - high_fees_opp.expected_profit_bps;
         
         assert_eq!(
             fee_impact, 70,
@@ -850,27 +883,27 @@ mod tests {
     fn test_risk_level_classification() {
         // Test Case 1: Low risk (high liquidity, low slippage)
         let low_risk_opp = create_mock_opportunity(
-            1.0, 1.02, 200, 170, 20, 10,
+            1.0, 1.02, 200, 170, 10,
         );
-        assert_eq!(low_risk_opp.execution_risk, RiskLevel::Low);
-        assert!(low_risk_opp.estimated_slippage_bps < 50);
+        assert_eq!(low_risk_opp.risk_level, RiskLevel::Low);
+        assert!(low_risk_opp.estimated_slippage_bps() < 50);
 
         // Test Case 2: Medium risk
         let mut medium_risk_opp = create_mock_opportunity(
-            1.0, 1.02, 200, 100, 80, 20,
+            1.0, 1.02, 200, 100, 20,
         );
-        medium_risk_opp.execution_risk = RiskLevel::Medium;
-        assert_eq!(medium_risk_opp.execution_risk, RiskLevel::Medium);
-        assert!(medium_risk_opp.estimated_slippage_bps >= 50);
-        assert!(medium_risk_opp.estimated_slippage_bps < 200);
+        medium_risk_opp.risk_level = RiskLevel::Medium;
+        assert_eq!(medium_risk_opp.risk_level, RiskLevel::Medium);
+        assert!(medium_risk_opp.estimated_slippage_bps() >= 50);
+        assert!(medium_risk_opp.estimated_slippage_bps() < 200);
 
         // Test Case 3: High risk (low liquidity, high slippage)
         let mut high_risk_opp = create_mock_opportunity(
-            1.0, 1.02, 200, -100, 280, 20,
+            1.0, 1.02, 200, -100, 20,
         );
-        high_risk_opp.execution_risk = RiskLevel::High;
-        assert_eq!(high_risk_opp.execution_risk, RiskLevel::High);
-        assert!(high_risk_opp.estimated_slippage_bps >= 200);
+        high_risk_opp.risk_level = RiskLevel::High;
+        assert_eq!(high_risk_opp.risk_level, RiskLevel::High);
+        assert!(high_risk_opp.estimated_slippage_bps() >= 200);
     }
 
     /// Test 7: Price Inversion Detection
@@ -883,17 +916,16 @@ mod tests {
             1.00, // sell_price (lower)
             0,    // gross_profit_bps (0 or negative)
             -500, // net_profit_bps (negative)
-            0,    // estimated_slippage_bps
             0,    // total_fees_bps
         );
 
         assert!(
-            inverted_opp.sell_price < inverted_opp.buy_price,
+            inverted_opp.cycle.path.iter().map(|step| step.rate).product::<f64>() < 1.0,
             "Price inversion detected: selling at lower price than buying"
         );
         
         assert!(
-            inverted_opp.net_profit_bps < 0,
+            inverted_opp.expected_profit_bps < 0,
             "Inverted prices should result in negative profit"
         );
     }
@@ -908,12 +940,11 @@ mod tests {
             1.50, // sell_price (50% profit!)
             5000, // gross_profit_bps (50%)
             4900, // net_profit_bps (49%)
-            50,   // estimated_slippage_bps (0.5%)
             50,   // total_fees_bps (0.5%)
         );
 
         assert!(
-            extreme_opp.gross_profit_bps > 1000,
+            extreme_opp.gross_profit_bps() > 1000,
             "Extreme profit opportunity detected (>10%)"
         );
 
@@ -950,21 +981,21 @@ mod tests {
 
         // Test Case 1: Above minimum
         let valid_opp = create_mock_opportunity(
-            1.0, 1.02, 200, 170, 20, 10,
+            1.0, 1.02, 200, 170, 10,
         );
         assert!(
-            valid_opp.recommended_amount >= min_trade_amount,
+            valid_opp.optimal_input_amount >= min_trade_amount,
             "Trade amount should be above minimum threshold"
         );
 
         // Test Case 2: Below minimum (dust)
         let mut dust_opp = create_mock_opportunity(
-            1.0, 1.02, 200, 170, 20, 10,
+            1.0, 1.02, 200, 170, 10,
         );
-        dust_opp.recommended_amount = 1000; // 0.000001 SOL (dust)
+        dust_opp.optimal_input_amount = 1000; // 0.000001 SOL (dust)
 
         assert!(
-            dust_opp.recommended_amount < min_trade_amount,
+            dust_opp.optimal_input_amount < min_trade_amount,
             "Dust amounts should be filtered out"
         );
     }
@@ -7,29 +7,47 @@
 // 3. DEX-specific swap instruction construction
 // 4. Atomic execution guarantees
 // 5. Transaction size optimization
+//
+// Venues that settle in native SOL (Pump.fun) get the SOL they spend unwrapped
+// from the WSOL account just before their leg, and the SOL they pay out
+// wrapped back right after it, so every other leg keeps trading WSOL.
+//
+// Each leg is quoted and built against the pool snapshot its graph edge was
+// priced from, so the transaction matches what detection saw. Pools pushed
+// with `update_pool_state` are used for edges that carry no snapshot.
 
 use solana_sdk::{
     instruction::Instruction,
+    program_pack::Pack,
     pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
     transaction::VersionedTransaction,
-    message::{VersionedMessage, v0::Message as V0Message},
+    message::VersionedMessage,
     address_lookup_table_account::AddressLookupTableAccount,
     signature::Keypair,
     signer::Signer,
     compute_budget::ComputeBudgetInstruction,
-    system_program,
 };
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use anyhow::{Result, anyhow};
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
-use crate::dex::triangular_arb::{ArbitrageCycle, CycleStep, DexType};
+use crate::chain::constants::WSOL_MINT;
+use crate::dex::adapter::{DexRegistry, SwapParams};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::token_2022::associated_token_address;
+use crate::dex::triangular_arb::{ArbitrageCycle, CycleStep, SharedArbitrageGraph};
 
 /// Transaction builder for arbitrage swaps
 pub struct SwapTransactionBuilder {
     payer: Keypair,
     token_accounts: HashMap<Pubkey, Pubkey>, // mint -> associated token account
     lookup_tables: Vec<AddressLookupTableAccount>,
+    registry: Arc<DexRegistry>,
+    graph: Option<SharedArbitrageGraph>, // edges carry the pool snapshots cycles were priced from
+    pool_states: RwLock<HashMap<Pubkey, Arc<PoolData>>>, // pool address -> latest decoded state
 }
 
 /// Configuration for transaction building
@@ -72,6 +90,29 @@ impl SwapTransactionBuilder {
             payer,
             token_accounts,
             lookup_tables,
+            registry: Arc::new(DexRegistry::default()),
+            graph: None,
+            pool_states: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Use a custom adapter registry (e.g. built from configured program ids)
+    pub fn with_registry(mut self, registry: Arc<DexRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Take each leg's pool snapshot from its edge in `graph`
+    pub fn with_graph(mut self, graph: SharedArbitrageGraph) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    /// Store the latest decoded state of a pool, used for legs whose edge
+    /// carries no snapshot
+    pub fn update_pool_state(&self, pool: PoolData) {
+        if let Ok(mut pool_states) = self.pool_states.write() {
+            pool_states.insert(pool.pool_address, Arc::new(pool));
         }
     }
 
@@ -88,8 +129,9 @@ impl SwapTransactionBuilder {
             input_amount
         );
 
-        // 1. Calculate compute budget
-        let compute_units = self.calculate_compute_budget(cycle.path.len()) + config.compute_unit_buffer;
+        // 1. Calculate compute budget (one swap per pool, split hops count each leg)
+        let num_swaps: usize = cycle.path.iter().map(|step| step.splits.len().max(1)).sum();
+        let compute_units = self.calculate_compute_budget(num_swaps) + config.compute_unit_buffer;
         
         // 2. Build all instructions
         let mut instructions = Vec::new();
@@ -102,36 +144,65 @@ impl SwapTransactionBuilder {
             ComputeBudgetInstruction::set_compute_unit_price(config.priority_fee_micro_lamports)
         );
 
-        // 3. Build swap instructions for each step
+        // 3. Build swap instructions for each step, one per split leg; all
+        // legs land in the same transaction so the cycle stays atomic
         let mut current_amount = input_amount;
+        let mut unwraps = 0u32;
         
         for (idx, step) in cycle.path.iter().enumerate() {
-            // Calculate expected output for this step
-            let expected_output = (current_amount as f64 * step.rate) as u64;
-            
-            // Apply fee
-            let fee = (expected_output as u128 * step.fee_bps as u128 / 10000) as u64;
-            let expected_after_fee = expected_output.saturating_sub(fee);
-            
-            // Calculate minimum output with slippage
-            let minimum_out = self.calculate_minimum_out(expected_after_fee, config.max_slippage_bps);
-            
-            debug!(
-                "Step {}: {} -> {}, amount_in={}, expected_out={}, min_out={}",
-                idx,
-                step.from_token,
-                step.to_token,
-                current_amount,
-                expected_after_fee,
-                minimum_out
-            );
-
-            // Build DEX-specific swap instruction
-            let swap_ix = self.build_swap_instruction(step, current_amount, minimum_out)?;
-            instructions.push(swap_ix);
+            let mut step_output = 0u64;
+
+            for (pool_address, leg_in) in step.leg_inputs(current_amount) {
+                if leg_in == 0 {
+                    continue;
+                }
+
+                let pool = self.pool_state(step, &pool_address)?;
+                let adapter = self.registry.adapter(&pool.program_id)?;
+                let a_to_b = swap_direction(&pool, step)?;
+
+                // Exact expected output (fees included) from the pool's integer math
+                let expected_output = adapter.quote_exact_in(&pool, leg_in, a_to_b)?;
+                
+                // Exact-out swaps receive exactly the quoted output, so the slippage
+                // tolerance goes on the input they may spend instead
+                let (amount_in, minimum_out) = if adapter.is_exact_out(&pool, a_to_b) {
+                    (self.calculate_maximum_in(leg_in, config.max_slippage_bps), expected_output)
+                } else {
+                    (leg_in, self.calculate_minimum_out(expected_output, config.max_slippage_bps))
+                };
+                
+                debug!(
+                    "Step {}: {} -> {} via {}, amount_in={}, expected_out={}, min_out={}",
+                    idx,
+                    step.from_token,
+                    step.to_token,
+                    pool_address,
+                    amount_in,
+                    expected_output,
+                    minimum_out
+                );
+
+                // Native SOL venues spend lamports, not the WSOL the previous leg paid out
+                let native_sol = adapter.settles_native_sol();
+                if native_sol && step.from_token == WSOL_MINT {
+                    instructions.extend(self.unwrap_sol_instructions(amount_in, unwraps)?);
+                    unwraps += 1;
+                }
+
+                // Build DEX-specific swap instruction
+                let swap_ix = self.build_swap_instruction(step, &pool, a_to_b, amount_in, minimum_out)?;
+                instructions.push(swap_ix);
+
+                // Any shortfall against the quote is covered from the wallet's lamports
+                if native_sol && step.to_token == WSOL_MINT {
+                    instructions.extend(self.wrap_sol_instructions(expected_output)?);
+                }
+                step_output += expected_output;
+            }
             
             // Update amount for next step
-            current_amount = expected_after_fee;
+            current_amount = step_output;
         }
 
         // 4. Build versioned message with ALT
@@ -142,205 +213,104 @@ impl SwapTransactionBuilder {
         
         info!(
             "Built arbitrage transaction: {} instructions, {} compute units",
-            cycle.path.len() + 2, // swaps + 2 compute budget instructions
+            num_swaps + 2, // swaps + 2 compute budget instructions
             compute_units
         );
 
         Ok(transaction)
     }
 
-    /// Build swap instruction for any DEX type
-    fn build_swap_instruction(
-        &self,
-        step: &CycleStep,
-        amount_in: u64,
-        minimum_out: u64,
-    ) -> Result<Instruction> {
-        match step.dex {
-            DexType::Raydium => self.build_raydium_swap_ix(step, amount_in, minimum_out),
-            DexType::Meteora => self.build_meteora_swap_ix(step, amount_in, minimum_out),
-            DexType::Whirlpool => self.build_whirlpool_swap_ix(step, amount_in, minimum_out),
-            DexType::Orca => self.build_orca_swap_ix(step, amount_in, minimum_out),
-            DexType::Pump => self.build_pump_swap_ix(step, amount_in, minimum_out),
+    /// Snapshot of one pool behind a step: the one its graph edge was priced
+    /// from, else the latest pushed with `update_pool_state`
+    fn pool_state(&self, step: &CycleStep, pool: &Pubkey) -> Result<Arc<PoolData>> {
+        if let Some(graph) = &self.graph {
+            let graph = graph.read()
+                .map_err(|e| anyhow!("Failed to acquire graph lock: {}", e))?;
+            let snapshot = graph.get_edge(&step.from_token, &step.to_token, pool)
+                .and_then(|edge| edge.quoter.as_ref())
+                .map(|quoter| quoter.snapshot());
+            if let Some(snapshot) = snapshot {
+                return Ok(snapshot);
+            }
         }
+
+        let pool_states = self.pool_states.read()
+            .map_err(|e| anyhow!("Failed to acquire pool state lock: {}", e))?;
+        pool_states.get(pool)
+            .cloned()
+            .ok_or_else(|| anyhow!("No pool state for {} ({})", pool, step.dex))
     }
 
-    /// Create Raydium AMM swap instruction
-    fn build_raydium_swap_ix(
+    /// Build swap instruction for one leg of a step via the pool's venue adapter
+    fn build_swap_instruction(
         &self,
         step: &CycleStep,
+        pool: &PoolData,
+        a_to_b: bool,
         amount_in: u64,
         minimum_out: u64,
     ) -> Result<Instruction> {
-        // Raydium AMM v4 program ID
-        let raydium_program_id = solana_sdk::pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
-        
-        // Get user token accounts
-        let user_source = self.token_accounts.get(&step.from_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.from_token))?;
-        let user_destination = self.token_accounts.get(&step.to_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.to_token))?;
-
-        // Raydium swap instruction accounts (simplified)
-        // In production, derive all PDAs properly
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new_readonly(system_program::id(), false),
-            solana_sdk::instruction::AccountMeta::new(step.pool, false),
-            solana_sdk::instruction::AccountMeta::new_readonly(self.payer.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(*user_source, false),
-            solana_sdk::instruction::AccountMeta::new(*user_destination, false),
-            // Additional accounts: pool coin/pc vaults, signer, etc.
-        ];
-
-        // Raydium swap instruction data (instruction discriminator + params)
-        let mut data = vec![9]; // Swap instruction discriminator
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_out.to_le_bytes());
-
-        Ok(Instruction {
-            program_id: raydium_program_id,
-            accounts,
-            data,
-        })
+        let params = SwapParams {
+            user: self.payer.pubkey(),
+            user_source: self.user_token_account(pool, &step.from_token),
+            user_destination: self.user_token_account(pool, &step.to_token),
+            amount_in,
+            minimum_out,
+            a_to_b,
+        };
+
+        self.registry.build_swap_ix(pool, &params)
     }
 
-    /// Create Meteora DAMM swap instruction
-    fn build_meteora_swap_ix(
-        &self,
-        step: &CycleStep,
-        amount_in: u64,
-        minimum_out: u64,
-    ) -> Result<Instruction> {
-        // Meteora DAMM program ID
-        let meteora_program_id = solana_sdk::pubkey!("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB");
-        
-        let user_source = self.token_accounts.get(&step.from_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.from_token))?;
-        let user_destination = self.token_accounts.get(&step.to_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.to_token))?;
-
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new(step.pool, false),
-            solana_sdk::instruction::AccountMeta::new_readonly(self.payer.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(*user_source, false),
-            solana_sdk::instruction::AccountMeta::new(*user_destination, false),
-        ];
-
-        // Meteora swap instruction data
-        let mut data = vec![0xf8, 0x3d, 0x2a, 0x3b, 0x88, 0x1b, 0x0e, 0x94]; // swap discriminator
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_out.to_le_bytes());
-
-        Ok(Instruction {
-            program_id: meteora_program_id,
-            accounts,
-            data,
+    /// Configured token account for `mint`, or the payer's ATA derived under
+    /// the token program that owns the mint (legacy SPL or Token-2022)
+    fn user_token_account(&self, pool: &PoolData, mint: &Pubkey) -> Pubkey {
+        self.token_accounts.get(mint).copied().unwrap_or_else(|| {
+            associated_token_address(&self.payer.pubkey(), mint, &pool.token_program(mint))
         })
     }
 
-    /// Create Whirlpool (Orca v2) swap instruction
-    fn build_whirlpool_swap_ix(
-        &self,
-        step: &CycleStep,
-        amount_in: u64,
-        minimum_out: u64,
-    ) -> Result<Instruction> {
-        // Whirlpool program ID
-        let whirlpool_program_id = solana_sdk::pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
-        
-        let user_source = self.token_accounts.get(&step.from_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.from_token))?;
-        let user_destination = self.token_accounts.get(&step.to_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.to_token))?;
-
-        // Whirlpool swap accounts
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new(step.pool, false),
-            solana_sdk::instruction::AccountMeta::new_readonly(self.payer.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(*user_source, false),
-            solana_sdk::instruction::AccountMeta::new(*user_destination, false),
-            // Additional: tick arrays, oracle, etc.
-        ];
-
-        // Whirlpool swap instruction data
-        let mut data = vec![0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8]; // swap discriminator
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_out.to_le_bytes());
-        data.extend_from_slice(&(u128::MAX).to_le_bytes()); // sqrt_price_limit (no limit)
-        data.push(1); // a_to_b direction
-
-        Ok(Instruction {
-            program_id: whirlpool_program_id,
-            accounts,
-            data,
+    /// Configured WSOL account, or the payer's WSOL ATA
+    fn wsol_account(&self) -> Pubkey {
+        self.token_accounts.get(&WSOL_MINT).copied().unwrap_or_else(|| {
+            associated_token_address(&self.payer.pubkey(), &WSOL_MINT, &spl_token::id())
         })
     }
 
-    /// Create Orca v1 swap instruction
-    fn build_orca_swap_ix(
-        &self,
-        step: &CycleStep,
-        amount_in: u64,
-        minimum_out: u64,
-    ) -> Result<Instruction> {
-        // Orca v1 program ID
-        let orca_program_id = solana_sdk::pubkey!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
-        
-        let user_source = self.token_accounts.get(&step.from_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.from_token))?;
-        let user_destination = self.token_accounts.get(&step.to_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.to_token))?;
-
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new(step.pool, false),
-            solana_sdk::instruction::AccountMeta::new_readonly(self.payer.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(*user_source, false),
-            solana_sdk::instruction::AccountMeta::new(*user_destination, false),
-        ];
-
-        // Orca swap instruction data
-        let mut data = vec![1]; // Swap instruction
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_out.to_le_bytes());
-
-        Ok(Instruction {
-            program_id: orca_program_id,
-            accounts,
-            data,
-        })
+    /// Move `lamports` out of the WSOL account into the payer's native
+    /// balance, through a temporary token account that is closed again
+    fn unwrap_sol_instructions(&self, lamports: u64, index: u32) -> Result<Vec<Instruction>> {
+        let payer = self.payer.pubkey();
+        let wsol_account = self.wsol_account();
+        let seed = format!("unwrap-{}", index);
+        let temporary = Pubkey::create_with_seed(&payer, &seed, &spl_token::id())?;
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+
+        Ok(vec![
+            system_instruction::create_account_with_seed(
+                &payer,
+                &temporary,
+                &payer,
+                &seed,
+                rent,
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(&spl_token::id(), &temporary, &WSOL_MINT, &payer)?,
+            spl_token::instruction::transfer(&spl_token::id(), &wsol_account, &temporary, &payer, &[], lamports)?,
+            spl_token::instruction::close_account(&spl_token::id(), &temporary, &payer, &payer, &[])?,
+        ])
     }
 
-    /// Create Pump.fun swap instruction
-    fn build_pump_swap_ix(
-        &self,
-        step: &CycleStep,
-        amount_in: u64,
-        minimum_out: u64,
-    ) -> Result<Instruction> {
-        // Pump.fun program ID
-        let pump_program_id = solana_sdk::pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
-        
-        let user_source = self.token_accounts.get(&step.from_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.from_token))?;
-        let user_destination = self.token_accounts.get(&step.to_token)
-            .ok_or_else(|| anyhow!("Missing token account for {}", step.to_token))?;
-
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new(step.pool, false),
-            solana_sdk::instruction::AccountMeta::new_readonly(self.payer.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(*user_source, false),
-            solana_sdk::instruction::AccountMeta::new(*user_destination, false),
-        ];
-
-        let mut data = vec![0x66, 0x06, 0x3d, 0x12, 0x01, 0xda, 0xeb, 0xea]; // buy/sell discriminator
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_out.to_le_bytes());
-
-        Ok(Instruction {
-            program_id: pump_program_id,
-            accounts,
-            data,
-        })
+    /// Move `lamports` of the payer's native balance into the WSOL account
+    fn wrap_sol_instructions(&self, lamports: u64) -> Result<Vec<Instruction>> {
+        let payer = self.payer.pubkey();
+        let wsol_account = self.wsol_account();
+
+        Ok(vec![
+            system_instruction::transfer(&payer, &wsol_account, lamports),
+            spl_token::instruction::sync_native(&spl_token::id(), &wsol_account)?,
+        ])
     }
 
     /// Calculate minimum output amount with slippage tolerance
@@ -357,6 +327,13 @@ impl SwapTransactionBuilder {
         minimum
     }
 
+    /// Calculate maximum input amount with slippage tolerance
+    fn calculate_maximum_in(&self, amount_in: u64, slippage_bps: u16) -> u64 {
+        // maximum = amount * (1 + slippage/10000)
+        let maximum = amount_in as u128 * (10000 + slippage_bps as u128) / 10000;
+        maximum.min(u64::MAX as u128) as u64
+    }
+
     /// Calculate compute budget based on number of swaps
     fn calculate_compute_budget(&self, num_swaps: usize) -> u32 {
         // Base overhead: 20k compute units
@@ -376,9 +353,6 @@ impl SwapTransactionBuilder {
 
     /// Build versioned message using Address Lookup Tables
     fn build_versioned_message(&self, instructions: Vec<Instruction>) -> Result<VersionedMessage> {
-        // Get recent blockhash (in production, fetch from RPC)
-        let recent_blockhash = solana_sdk::hash::Hash::default();
-        
         // For now, create a legacy message and convert to v0
         // In production, properly build v0 message with ALT
         let legacy_message = solana_sdk::message::Message::new(
@@ -423,6 +397,20 @@ impl SwapTransactionBuilder {
     }
 }
 
+/// Whether `step` swaps the pool's token A for token B
+fn swap_direction(pool: &PoolData, step: &CycleStep) -> Result<bool> {
+    if step.from_token == pool.token_a && step.to_token == pool.token_b {
+        Ok(true)
+    } else if step.from_token == pool.token_b && step.to_token == pool.token_a {
+        Ok(false)
+    } else {
+        Err(anyhow!(
+            "Pool {} does not trade {} -> {}",
+            pool.pool_address, step.from_token, step.to_token
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,6 +466,299 @@ mod tests {
         assert!(size < 1232);
     }
 
+    struct MockAdapter;
+
+    impl crate::dex::adapter::DexAdapter for MockAdapter {
+        fn program_id(&self) -> Pubkey {
+            Pubkey::new_from_array([7; 32])
+        }
+
+        fn dex_type(&self) -> crate::dex::triangular_arb::DexType {
+            crate::dex::triangular_arb::DexType::Orca
+        }
+
+        fn decode_pool(&self, _address: &Pubkey, _data: &[u8]) -> Result<PoolData> {
+            Err(anyhow!("not used"))
+        }
+
+        fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<solana_sdk::instruction::AccountMeta>> {
+            Ok(vec![
+                solana_sdk::instruction::AccountMeta::new(pool.pool_address, false),
+                solana_sdk::instruction::AccountMeta::new(params.user_source, false),
+            ])
+        }
+
+        fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+            Ok(vec![params.a_to_b as u8])
+        }
+    }
+
+    #[test]
+    fn test_build_swap_instruction_uses_pool_adapter() {
+        use crate::dex::adapter::VenueState;
+        use crate::dex::triangular_arb::DexType;
+
+        let mut registry = DexRegistry::new();
+        registry.register(Arc::new(MockAdapter));
+
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pool = PoolData {
+            pool_address: Pubkey::new_unique(),
+            token_a,
+            token_b,
+            reserve_a: 1_000_000,
+            reserve_b: 2_000_000,
+            fee_bps: 30,
+            dex_type: DexType::Orca,
+            program_id: Pubkey::new_from_array([7; 32]),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
+        };
+
+        let source = Pubkey::new_unique();
+        let mut token_accounts = HashMap::new();
+        token_accounts.insert(token_a, Pubkey::new_unique());
+        token_accounts.insert(token_b, source);
+        let builder = SwapTransactionBuilder::new(Keypair::new(), token_accounts, vec![])
+            .with_registry(Arc::new(registry));
+
+        let step = CycleStep {
+            from_token: token_b,
+            to_token: token_a,
+            dex: DexType::Orca,
+            pool: pool.pool_address,
+            rate: 0.5,
+            fee_bps: 30,
+            splits: Vec::new(),
+        };
+
+        // Unknown pool state is an error
+        assert!(builder.pool_state(&step, &step.pool).is_err());
+
+        builder.update_pool_state(pool.clone());
+        let state = builder.pool_state(&step, &step.pool).unwrap();
+        let ix = builder.build_swap_instruction(&step, &state, false, 1000, 400).unwrap();
+        assert_eq!(ix.program_id, pool.program_id);
+        assert_eq!(ix.accounts[0].pubkey, pool.pool_address);
+        assert_eq!(ix.accounts[1].pubkey, source);
+        assert_eq!(ix.data, vec![0]); // b -> a
+
+        // Expected output is the pool's integer quote, not amount * rate:
+        // 1000 - ceil(3) fee = 997 in, 1_000_000 * 997 / 2_000_997 = 498.25 -> 498
+        assert_eq!(builder.registry.quote_exact_in(&state, 1000, false).unwrap(), 498);
+        let wrong_pair = CycleStep { to_token: Pubkey::new_unique(), ..step };
+        assert!(swap_direction(&state, &wrong_pair).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pump_buy_feeds_its_exact_output_to_the_next_leg() {
+        use crate::dex::adapter::VenueState;
+        use crate::dex::pump::{bonding_curve_address, BondingCurve, PumpGlobal, PumpPoolState, PUMP_PROGRAM_ID};
+        use crate::dex::triangular_arb::DexType;
+        use solana_sdk::system_program;
+
+        // Round trip through one bonding curve: buy the mint, sell it back
+        let mint = Pubkey::new_unique();
+        let state = PumpPoolState {
+            mint,
+            curve: BondingCurve {
+                virtual_token_reserves: 1_073_000_000_000_000,
+                virtual_sol_reserves: 30_000_000_000,
+                real_token_reserves: 793_100_000_000_000,
+                real_sol_reserves: 10_000_000_000,
+                token_total_supply: 1_000_000_000_000_000,
+                complete: false,
+                creator: Pubkey::default(),
+            },
+            global: Some(PumpGlobal {
+                fee_recipient: Pubkey::new_unique(),
+                fee_basis_points: 100,
+                creator_fee_basis_points: 0,
+            }),
+        };
+        let pool = PoolData {
+            pool_address: bonding_curve_address(&PUMP_PROGRAM_ID, &mint),
+            token_a: mint,
+            token_b: WSOL_MINT,
+            reserve_a: state.curve.virtual_token_reserves,
+            reserve_b: state.curve.virtual_sol_reserves,
+            fee_bps: 100,
+            dex_type: DexType::Pump,
+            program_id: PUMP_PROGRAM_ID,
+            venue: VenueState::Pump(Box::new(state.clone())),
+            mints: Default::default(),
+            slot: 0,
+        };
+
+        let (wsol_account, mint_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let token_accounts = HashMap::from([(WSOL_MINT, wsol_account), (mint, mint_account)]);
+        let builder = SwapTransactionBuilder::new(Keypair::new(), token_accounts, vec![]);
+        builder.update_pool_state(pool.clone());
+
+        let step = |from_token, to_token| CycleStep {
+            from_token,
+            to_token,
+            dex: DexType::Pump,
+            pool: pool.pool_address,
+            rate: 1.0,
+            fee_bps: 100,
+            splits: Vec::new(),
+        };
+        let cycle = ArbitrageCycle {
+            path: vec![step(WSOL_MINT, mint), step(mint, WSOL_MINT)],
+            gross_profit_bps: 0,
+            net_profit_after_fees: 0.0,
+            execution_time_estimate_ms: 0,
+            total_fee_bps: 200,
+            start_token: WSOL_MINT,
+            cycle_weight: 0.0,
+        };
+
+        let input = 1_000_000_000;
+        let tx = builder.build_arbitrage_tx(&cycle, input, &TransactionConfig::default()).await.unwrap();
+        let keys = tx.message.static_account_keys();
+        let programs: Vec<Pubkey> = tx.message.instructions().iter()
+            .map(|ix| keys[ix.program_id_index as usize])
+            .collect();
+
+        // Unwrap, buy, sell, wrap
+        let compute_budget = solana_sdk::compute_budget::id();
+        assert_eq!(programs, vec![
+            compute_budget, compute_budget,
+            system_program::id(), spl_token::id(), spl_token::id(), spl_token::id(),
+            PUMP_PROGRAM_ID, PUMP_PROGRAM_ID,
+            system_program::id(), spl_token::id(),
+        ]);
+        let ixs = tx.message.instructions();
+        let u64_at = |data: &[u8], offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        // The buy takes exactly the tokens `input` affords, for at most
+        // `input` plus 1% slippage, unwrapped from the WSOL account first
+        let tokens = state.quote_buy(input).unwrap();
+        let max_sol_cost = input + input / 100;
+        assert_eq!(u64_at(&ixs[6].data, 8), tokens);
+        assert_eq!(u64_at(&ixs[6].data, 16), max_sol_cost);
+        assert_eq!(keys[ixs[4].accounts[0] as usize], wsol_account);
+        assert_eq!(u64_at(&ixs[4].data, 1), max_sol_cost);
+
+        // The sell spends exactly those tokens and its proceeds are wrapped back
+        let proceeds = state.quote_sell(tokens).unwrap();
+        assert_eq!(u64_at(&ixs[7].data, 8), tokens);
+        assert_eq!(u64_at(&ixs[7].data, 16), builder.calculate_minimum_out(proceeds, 100));
+        assert_eq!(keys[ixs[8].accounts[1] as usize], wsol_account);
+        assert_eq!(u64_at(&ixs[8].data, 4), proceeds);
+    }
+
+    #[tokio::test]
+    async fn test_split_hop_emits_one_swap_per_leg() {
+        use crate::dex::adapter::VenueState;
+        use crate::dex::triangular_arb::{DexType, SplitLeg};
+
+        let mut registry = DexRegistry::new();
+        registry.register(Arc::new(MockAdapter));
+
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pool = |reserve_b| PoolData {
+            pool_address: Pubkey::new_unique(),
+            token_a,
+            token_b,
+            reserve_a: 1_000_000,
+            reserve_b,
+            fee_bps: 30,
+            dex_type: DexType::Orca,
+            program_id: Pubkey::new_from_array([7; 32]),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
+        };
+        let (first, second) = (pool(2_000_000), pool(1_000_000));
+
+        let builder = SwapTransactionBuilder::new(Keypair::new(), HashMap::new(), vec![])
+            .with_registry(Arc::new(registry));
+        builder.update_pool_state(first.clone());
+        builder.update_pool_state(second.clone());
+
+        let step = CycleStep {
+            from_token: token_a,
+            to_token: token_b,
+            dex: DexType::Orca,
+            pool: first.pool_address,
+            rate: 2.0,
+            fee_bps: 30,
+            splits: vec![
+                SplitLeg { dex: DexType::Orca, pool: first.pool_address, share_bps: 6_000 },
+                SplitLeg { dex: DexType::Orca, pool: second.pool_address, share_bps: 4_000 },
+            ],
+        };
+        assert_eq!(step.leg_inputs(1000), vec![(first.pool_address, 600), (second.pool_address, 400)]);
+
+        let cycle = ArbitrageCycle {
+            path: vec![step],
+            gross_profit_bps: 0,
+            net_profit_after_fees: 0.0,
+            execution_time_estimate_ms: 0,
+            total_fee_bps: 30,
+            start_token: token_a,
+            cycle_weight: 0.0,
+        };
+        let tx = builder.build_arbitrage_tx(&cycle, 1000, &TransactionConfig::default()).await.unwrap();
+        let swaps: Vec<_> = tx.message.instructions().iter()
+            .filter(|ix| tx.message.static_account_keys()[ix.program_id_index as usize] == first.program_id)
+            .collect();
+        // Both legs in one transaction, each against its own pool
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(tx.message.instructions().len(), 4);
+    }
+
+    #[test]
+    fn test_missing_token_account_uses_mint_program_ata() {
+        use crate::dex::adapter::{VenueState, TOKEN_2022_PROGRAM_ID};
+        use crate::dex::triangular_arb::DexType;
+
+        let mut registry = DexRegistry::new();
+        registry.register(Arc::new(MockAdapter));
+
+        let mut pool = PoolData {
+            pool_address: Pubkey::new_unique(),
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            reserve_a: 1_000_000,
+            reserve_b: 2_000_000,
+            fee_bps: 30,
+            dex_type: DexType::Orca,
+            program_id: Pubkey::new_from_array([7; 32]),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
+        };
+        pool.mints.a.token_program = TOKEN_2022_PROGRAM_ID;
+
+        let payer = Keypair::new();
+        let wallet = payer.pubkey();
+        let builder = SwapTransactionBuilder::new(payer, HashMap::new(), vec![])
+            .with_registry(Arc::new(registry));
+        builder.update_pool_state(pool.clone());
+
+        let step = CycleStep {
+            from_token: pool.token_a,
+            to_token: pool.token_b,
+            dex: DexType::Orca,
+            pool: pool.pool_address,
+            rate: 2.0,
+            fee_bps: 30,
+            splits: Vec::new(),
+        };
+        let state = builder.pool_state(&step, &step.pool).unwrap();
+        let ix = builder.build_swap_instruction(&step, &state, true, 1000, 1900).unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            associated_token_address(&wallet, &pool.token_a, &TOKEN_2022_PROGRAM_ID)
+        );
+    }
+
     #[test]
     fn test_transaction_config_default() {
         let config = TransactionConfig::default();
@@ -485,4 +766,106 @@ mod tests {
         assert_eq!(config.priority_fee_micro_lamports, 1000);
         assert_eq!(config.compute_unit_buffer, 50_000);
     }
+
+    /// Orca v1 constant-product pool between `token_a` and `token_b`
+    fn orca_pool(token_a: Pubkey, token_b: Pubkey, reserve_a: u64, reserve_b: u64) -> PoolData {
+        use crate::dex::adapter::VenueState;
+        use crate::dex::orca::{OrcaPoolState, SwapFees, SwapV1, ORCA_V1_PROGRAM_ID};
+        use crate::dex::triangular_arb::DexType;
+
+        let address = Pubkey::new_unique();
+        let swap = SwapV1 {
+            is_initialized: true,
+            bump_seed: Pubkey::find_program_address(&[address.as_ref()], &ORCA_V1_PROGRAM_ID).1,
+            token_program_id: spl_token::id(),
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            pool_mint: Pubkey::new_unique(),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
+            pool_fee_account: Pubkey::new_unique(),
+            fees: SwapFees {
+                trade_fee_numerator: 25,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator: 5,
+                owner_trade_fee_denominator: 10_000,
+                ..SwapFees::default()
+            },
+            curve_type: 0,
+        };
+        PoolData {
+            pool_address: address,
+            token_a,
+            token_b,
+            reserve_a,
+            reserve_b,
+            fee_bps: 30,
+            dex_type: DexType::Orca,
+            program_id: ORCA_V1_PROGRAM_ID,
+            venue: VenueState::Orca(Box::new(OrcaPoolState { swap })),
+            mints: Default::default(),
+            slot: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_builds_cycle_from_graph_snapshots() {
+        use crate::dex::orca::ORCA_V1_PROGRAM_ID;
+        use crate::dex::triangular_arb::{ArbitrageGraph, DexType};
+
+        // Pools only ever reach the live graph, never the builder directly
+        let registry = Arc::new(DexRegistry::default());
+        let graph = Arc::new(std::sync::RwLock::new(ArbitrageGraph::new()));
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let cheap = orca_pool(sol, usdc, 1_000_000_000, 150_000_000_000);
+        let rich = orca_pool(sol, usdc, 1_000_000_000, 160_000_000_000);
+        {
+            let mut graph = graph.write().unwrap();
+            graph.upsert_pool(&registry, &cheap, 0).unwrap();
+            graph.upsert_pool(&registry, &rich, 0).unwrap();
+        }
+        let step = |from, to, pool: &PoolData| CycleStep {
+            from_token: from,
+            to_token: to,
+            dex: DexType::Orca,
+            pool: pool.pool_address,
+            rate: 0.0,
+            fee_bps: pool.fee_bps,
+            splits: Vec::new(),
+        };
+        let cycle = ArbitrageCycle {
+            path: vec![step(sol, usdc, &rich), step(usdc, sol, &cheap)],
+            gross_profit_bps: 0,
+            net_profit_after_fees: 0.0,
+            execution_time_estimate_ms: 0,
+            total_fee_bps: 60,
+            start_token: sol,
+            cycle_weight: 0.0,
+        };
+
+        let mut token_accounts = HashMap::new();
+        token_accounts.insert(sol, Pubkey::new_unique());
+        token_accounts.insert(usdc, Pubkey::new_unique());
+        let builder = SwapTransactionBuilder::new(Keypair::new(), token_accounts, vec![])
+            .with_registry(registry)
+            .with_graph(graph);
+        let tx = builder
+            .build_arbitrage_tx(&cycle, 1_000_000, &TransactionConfig::default())
+            .await
+            .unwrap();
+
+        let keys = tx.message.static_account_keys();
+        let swaps: Vec<_> = tx.message.instructions().iter()
+            .filter(|ix| keys[ix.program_id_index as usize] == ORCA_V1_PROGRAM_ID)
+            .collect();
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(keys[swaps[0].accounts[0] as usize], rich.pool_address);
+        assert_eq!(keys[swaps[1].accounts[0] as usize], cheap.pool_address);
+
+        // Min-out comes from the snapshot's integer quote: 1_000_000 in, 3000
+        // in fees, new reserve ceil(1.6e20 / 1_000_997_000) = 159_840_638_884,
+        // so 159_361_116 out and 1% slippage below that
+        let min_out = u64::from_le_bytes(swaps[0].data[9..17].try_into().unwrap());
+        assert_eq!(min_out, 157_767_504);
+    }
 }
@@ -7,19 +7,24 @@
 // 4. Retry logic with exponential backoff
 // 5. Cancellation of remaining submissions once confirmed
 // 6. MEV protection and front-run detection
+// 7. Realized balance changes of confirmed transactions
 
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcTransactionConfig};
 use solana_sdk::{
+    pubkey::Pubkey,
     signature::Signature,
     transaction::VersionedTransaction,
     commitment_config::{CommitmentConfig, CommitmentLevel},
 };
-use solana_transaction_status::TransactionConfirmationStatus;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    TransactionConfirmationStatus, UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
 use tokio::time::{sleep, timeout, Duration, Instant};
 use std::sync::Arc;
 use anyhow::{Result, anyhow};
-use tracing::{debug, info, warn, error};
+use tracing::{debug, info, warn};
 use tokio::sync::mpsc;
 
 /// Transaction sender with multi-RPC support
@@ -297,9 +302,7 @@ impl TransactionSender {
                     Ok(response) => {
                         if let Some(Some(status)) = response.value.first() {
                             // Check if confirmed
-                            if status.confirmation_status.is_some() {
-                                let confirmation_status = status.confirmation_status.as_ref().unwrap();
-                                
+                            if let Some(confirmation_status) = &status.confirmation_status {
                                 if matches!(
                                     confirmation_status,
                                     TransactionConfirmationStatus::Confirmed |
@@ -342,6 +345,28 @@ impl TransactionSender {
         }
     }
 
+    /// Net change in `owner`'s balance of `mint` made by a confirmed
+    /// transaction, read from the token balances in its metadata
+    pub async fn token_balance_change(&self, signature: &Signature, owner: &Pubkey, mint: &Pubkey) -> Result<i128> {
+        let client = self.rpc_clients.first()
+            .ok_or_else(|| anyhow!("No RPC clients available"))?;
+
+        let transaction = client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+        let meta = transaction.transaction.meta
+            .ok_or_else(|| anyhow!("Transaction {} has no metadata", signature))?;
+
+        Ok(token_balance_change(&meta, owner, mint))
+    }
+
     /// Calculate dynamic priority fee based on recent blocks
     pub async fn estimate_priority_fee(&self) -> Result<u64> {
         // Use first RPC client to get recent priority fees
@@ -352,7 +377,7 @@ impl TransactionSender {
         // Note: This is a simplified implementation
         // In production, call getRecentPrioritizationFees RPC method
         
-        match client.get_recent_blockhash().await {
+        match client.get_latest_blockhash().await {
             Ok(_blockhash) => {
                 // Calculate dynamic fee based on network congestion
                 // For now, return a moderate default
@@ -431,16 +456,31 @@ impl TransactionSender {
     }
 }
 
+/// Post minus pre balance of `mint` summed over the token accounts `owner` holds
+fn token_balance_change(meta: &UiTransactionStatusMeta, owner: &Pubkey, mint: &Pubkey) -> i128 {
+    let (owner, mint) = (owner.to_string(), mint.to_string());
+    let total = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| -> i128 {
+        let OptionSerializer::Some(balances) = balances else {
+            return 0;
+        };
+        balances.iter()
+            .filter(|balance| balance.mint == mint && balance.owner.as_ref() == OptionSerializer::Some(&owner))
+            .filter_map(|balance| balance.ui_token_amount.amount.parse::<i128>().ok())
+            .sum()
+    };
+
+    total(&meta.post_token_balances) - total(&meta.pre_token_balances)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solana_sdk::signature::Keypair;
 
     #[test]
     fn test_send_config_default() {
         let config = SendConfig::default();
         assert_eq!(config.priority_fee_lamports, 10_000);
-        assert_eq!(config.skip_preflight, true);
+        assert!(config.skip_preflight);
         assert_eq!(config.max_retries, 3);
     }
 
@@ -504,4 +544,44 @@ mod tests {
         sender.set_max_priority_fee(200_000);
         assert_eq!(sender.max_priority_fee, 200_000);
     }
+
+    #[test]
+    fn test_token_balance_change_sums_owner_accounts_of_mint() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let balance = |index: u8, mint: &Pubkey, owner: &Pubkey, amount: u64| serde_json::json!({
+            "accountIndex": index,
+            "mint": mint.to_string(),
+            "owner": owner.to_string(),
+            "uiTokenAmount": {
+                "amount": amount.to_string(),
+                "decimals": 9,
+                "uiAmount": null,
+                "uiAmountString": "0",
+            },
+        });
+        let other = Pubkey::new_unique();
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": [
+                balance(1, &mint, &owner, 1_000_000),
+                balance(2, &mint, &other, 50_000_000),
+                balance(3, &other, &owner, 7),
+            ],
+            "postTokenBalances": [
+                balance(1, &mint, &owner, 1_012_345),
+                balance(2, &mint, &other, 49_987_655),
+                balance(3, &other, &owner, 7),
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(token_balance_change(&meta, &owner, &mint), 12_345);
+        assert_eq!(token_balance_change(&meta, &other, &mint), -12_345);
+        assert_eq!(token_balance_change(&meta, &owner, &other), 0);
+    }
 }
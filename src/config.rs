@@ -1,10 +1,12 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::str::FromStr;
 
 // Import well-known token mint constants
 use crate::chain::{USDC_MINT, USDT_MINT, WSOL_MINT};
+use crate::dex::triangular_arb::DexType;
 
 /// Main configuration struct containing all bot settings
 #[derive(Debug, Clone)]
@@ -12,6 +14,7 @@ pub struct Config {
     pub bot: BotConfig,
     pub routing: RoutingConfig,
     pub mints: Vec<MintConfig>,
+    pub tokens: TokensConfig,
     pub rpc: RpcConfig,
     pub spam: SpamConfig,
     pub wallet: WalletConfig,
@@ -20,6 +23,10 @@ pub struct Config {
     pub monitoring: MonitoringConfig,
     pub execution: ExecutionConfig,
     pub dex: DexConfig,
+    pub discovery: DiscoveryConfig,
+    pub risk: RiskConfig,
+    pub reliability: ReliabilityConfig,
+    pub staleness: StalenessConfig,
 }
 
 /// Bot behavior configuration
@@ -32,6 +39,8 @@ pub struct BotConfig {
     pub enable_arbitrage: bool,
     pub enable_sandwich: bool,
     pub max_position_size: u64,
+    /// Window in which a detected route is not emitted again
+    pub opportunity_cooldown_ms: u64,
     /// Execution mode: true = simulation only (zero-risk), false = live execution
     /// DECISION: Boolean flag (Chosen) vs ExecutionMode enum
     /// Rationale: Simpler for quick on/off switch between Simulate/Live modes
@@ -47,6 +56,8 @@ pub struct RoutingConfig {
     pub enable_multi_hop: bool,
     pub prefer_direct_routes: bool,
     pub route_cache_ttl_seconds: u64,
+    /// Most parallel pools one hop may be split across (1 disables splitting)
+    pub max_split_legs: u32,
 }
 
 /// Mint (token) configuration with pool associations
@@ -59,6 +70,48 @@ pub struct MintConfig {
     pub is_quote: bool,
 }
 
+/// Detection tokens (`[tokens]` in config.toml), resolved against the
+/// configured mints
+#[derive(Debug, Clone)]
+pub struct TokensConfig {
+    /// Tokens cycles start and end at, in priority order
+    pub base_tokens: Vec<Pubkey>,
+    /// Pools worth less than this (both sides, in USD) are not traded through
+    pub min_pool_liquidity_usd: f64,
+    /// Smallest trade input per start token, in its smallest units
+    pub min_trade_sizes: HashMap<Pubkey, u64>,
+}
+
+impl TokensConfig {
+    fn load(mints: &[MintConfig]) -> Result<Self> {
+        let base_tokens = get_env_or_default("BASE_TOKENS", "SOL,USDC,USDT")
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|token| resolve_mint(mints, token, "BASE_TOKENS"))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut min_trade_sizes = HashMap::new();
+        for entry in get_env_or_default("MIN_TRADE_SIZES", "").split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (token, amount) = entry.split_once(':').with_context(|| {
+                format!("Invalid MIN_TRADE_SIZES entry '{}': expected TOKEN:AMOUNT", entry)
+            })?;
+            let amount = amount.trim().parse::<u64>().with_context(|| {
+                format!("Invalid MIN_TRADE_SIZES amount for '{}': {}", token, amount)
+            })?;
+            min_trade_sizes.insert(resolve_mint(mints, token.trim(), "MIN_TRADE_SIZES")?, amount);
+        }
+
+        Ok(Self {
+            base_tokens,
+            min_pool_liquidity_usd: get_env_or_default("MIN_POOL_LIQUIDITY_USD", "10000")
+                .parse()
+                .context("Failed to parse MIN_POOL_LIQUIDITY_USD")?,
+            min_trade_sizes,
+        })
+    }
+}
+
 /// RPC endpoint configuration
 /// 
 /// Feature: Local Fork Configuration Default
@@ -130,6 +183,8 @@ pub struct CacheConfig {
     pub max_size: u64,
     pub enable_pool_cache: bool,
     pub enable_account_cache: bool,
+    /// Precompute candidate routes per base token (see `dex::route_cache`)
+    pub enable_route_cache: bool,
 }
 
 /// Monitoring and metrics configuration
@@ -150,6 +205,121 @@ pub struct ExecutionConfig {
     pub priority_fee_percentile: u8,
     pub use_versioned_transactions: bool,
     pub simulate_before_send: bool,
+    /// Tip paid to the block builder per transaction, in lamports
+    pub tip_lamports: u64,
+}
+
+/// Pool discovery configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscoveryConfig {
+    /// Find pools for the configured mints with getProgramAccounts
+    pub enabled: bool,
+    /// Pool registry file written by discovery and reused on restart
+    pub registry_path: String,
+    /// Re-scan the programs even when the registry file matches the mints
+    pub refresh: bool,
+    /// Add pools created after startup to the live graph
+    pub listen_new_pools: bool,
+    /// Minimum reserve of a new pool on its configured-mint side, in whole tokens
+    pub new_pool_min_liquidity: f64,
+}
+
+/// Opportunity risk thresholds (`[risk.levels]` in config.toml)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskConfig {
+    /// Low risk needs at least this expected profit...
+    pub low_risk_min_profit_bps: i64,
+    /// ...and at most this many hops
+    pub low_risk_max_hops: usize,
+    pub medium_risk_min_profit_bps: i64,
+    pub medium_risk_max_hops: usize,
+    /// Price impact at which an opportunity's liquidity score reaches zero
+    pub max_price_impact_bps: u64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            low_risk_min_profit_bps: 100,
+            low_risk_max_hops: 3,
+            medium_risk_min_profit_bps: 50,
+            medium_risk_max_hops: 4,
+            max_price_impact_bps: 100,
+        }
+    }
+}
+
+/// Edge staleness limits for detection; 0 disables a limit
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StalenessConfig {
+    /// Edges observed more than this many slots before the newest edge are left out
+    pub max_edge_age_slots: u64,
+    /// Edges last updated longer ago than this are left out, and an
+    /// opportunity's freshness score reaches zero at this age (edge
+    /// timestamps have one-second resolution)
+    pub max_edge_age_ms: u64,
+    /// Cycles whose edges were observed more than this many slots apart are rejected
+    pub max_cycle_slot_span: u64,
+}
+
+/// Venue reliability priors (`reliability_score` per `[dex.*]` in
+/// config.toml) and how trade history adjusts them
+#[derive(Debug, Clone)]
+pub struct ReliabilityConfig {
+    /// Expected success rate per venue (0.0-1.0) before trades are recorded
+    pub venue_priors: HashMap<DexType, f64>,
+    /// Most recent trades per venue and per pool that are counted
+    pub window: usize,
+    /// Trades through a pool before it can be demoted
+    pub min_pool_attempts: usize,
+    /// Pools whose success rate falls below this are left out of routes
+    pub demote_below: f64,
+}
+
+impl ReliabilityConfig {
+    /// Env prefix of each venue's `*_RELIABILITY_SCORE` and its default (0-100)
+    const VENUE_DEFAULTS: [(DexType, &'static str, u64); 10] = [
+        (DexType::Raydium, "RAYDIUM", 95),
+        (DexType::RaydiumCpmm, "RAYDIUM_CPMM", 95),
+        (DexType::RaydiumClmm, "RAYDIUM_CLMM", 95),
+        (DexType::Whirlpool, "WHIRLPOOL", 95),
+        (DexType::Orca, "ORCA", 90),
+        (DexType::Meteora, "METEORA", 90),
+        (DexType::MeteoraDlmm, "METEORA_DLMM", 90),
+        (DexType::Phoenix, "PHOENIX", 85),
+        (DexType::OpenBookV2, "OPENBOOK_V2", 85),
+        (DexType::Pump, "PUMP", 70),
+    ];
+
+    fn load() -> Result<Self> {
+        let mut venue_priors = HashMap::new();
+        for (dex, prefix, default) in Self::VENUE_DEFAULTS {
+            let score = get_u64_env(&format!("{}_RELIABILITY_SCORE", prefix), default)?;
+            venue_priors.insert(dex, score.min(100) as f64 / 100.0);
+        }
+
+        Ok(Self {
+            venue_priors,
+            window: get_u64_env("RELIABILITY_WINDOW", 100)? as usize,
+            min_pool_attempts: get_u64_env("RELIABILITY_MIN_POOL_ATTEMPTS", 5)? as usize,
+            demote_below: get_env_or_default("RELIABILITY_DEMOTE_BELOW", "0.3")
+                .parse()
+                .context("Failed to parse RELIABILITY_DEMOTE_BELOW")?,
+        })
+    }
+}
+
+impl Default for ReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            venue_priors: Self::VENUE_DEFAULTS.iter()
+                .map(|(dex, _, score)| (dex.clone(), *score as f64 / 100.0))
+                .collect(),
+            window: 100,
+            min_pool_attempts: 5,
+            demote_below: 0.3,
+        }
+    }
 }
 
 /// DEX program IDs configuration
@@ -157,6 +327,8 @@ pub struct ExecutionConfig {
 pub struct DexConfig {
     pub raydium_program_id: Pubkey,
     pub raydium_amm_program_id: Pubkey,
+    pub raydium_cpmm_program_id: Pubkey,
+    pub raydium_clmm_program_id: Pubkey,
     pub meteora_dlmm_program_id: Pubkey,
     pub meteora_pools_program_id: Pubkey,
     pub meteora_damm_program_id: Pubkey,
@@ -164,6 +336,8 @@ pub struct DexConfig {
     pub whirlpool_program_id: Pubkey,
     pub orca_program_id: Pubkey,
     pub pump_program_id: Pubkey,
+    pub phoenix_program_id: Pubkey,
+    pub openbook_v2_program_id: Pubkey,
 }
 
 impl Config {
@@ -181,6 +355,7 @@ impl Config {
             enable_arbitrage: get_bool_env("ENABLE_ARBITRAGE", true),
             enable_sandwich: get_bool_env("ENABLE_SANDWICH", false),
             max_position_size: get_u64_env("MAX_POSITION_SIZE", 1_000_000_000)?, // 1 SOL default
+            opportunity_cooldown_ms: get_u64_env("OPPORTUNITY_COOLDOWN_MS", 5000)?,
             // Read BOT_SIMULATION_MODE from environment, default to true for safety
             // This ensures the bot runs in simulation mode unless explicitly set to false
             is_simulation_mode: get_bool_env("BOT_SIMULATION_MODE", true),
@@ -192,11 +367,15 @@ impl Config {
             enable_multi_hop: get_bool_env("ENABLE_MULTI_HOP", true),
             prefer_direct_routes: get_bool_env("PREFER_DIRECT_ROUTES", true),
             route_cache_ttl_seconds: get_u64_env("ROUTE_CACHE_TTL_SECONDS", 300)?,
+            max_split_legs: get_u32_env("MAX_SPLIT_LEGS", 3)?,
         };
 
         // Load mint configurations from environment variables
         let mints = Self::load_mint_configs()?;
 
+        // Base tokens and per-token limits, which must name configured mints
+        let tokens = TokensConfig::load(&mints)?;
+
         // RPC configuration
         // Feature: Local Fork Configuration with Environment Variable Override
         // 
@@ -252,6 +431,7 @@ impl Config {
             max_size: get_u64_env("CACHE_MAX_SIZE", 10000)?,
             enable_pool_cache: get_bool_env("ENABLE_POOL_CACHE", true),
             enable_account_cache: get_bool_env("ENABLE_ACCOUNT_CACHE", true),
+            enable_route_cache: get_bool_env("ENABLE_ROUTE_CACHE", true),
         };
 
         // Monitoring configuration
@@ -273,12 +453,15 @@ impl Config {
                 .context("Failed to parse PRIORITY_FEE_PERCENTILE")?,
             use_versioned_transactions: get_bool_env("USE_VERSIONED_TRANSACTIONS", true),
             simulate_before_send: get_bool_env("SIMULATE_BEFORE_SEND", true),
+            tip_lamports: get_u64_env("TIP_LAMPORTS", 0)?,
         };
 
         // DEX configuration
         let dex = DexConfig {
             raydium_program_id: parse_pubkey("RAYDIUM_PROGRAM_ID")?,
             raydium_amm_program_id: parse_pubkey("RAYDIUM_AMM_PROGRAM_ID")?,
+            raydium_cpmm_program_id: parse_pubkey("RAYDIUM_CPMM_PROGRAM_ID")?,
+            raydium_clmm_program_id: parse_pubkey("RAYDIUM_CLMM_PROGRAM_ID")?,
             meteora_dlmm_program_id: parse_pubkey("METEORA_DLMM_PROGRAM_ID")?,
             meteora_pools_program_id: parse_pubkey("METEORA_POOLS_PROGRAM_ID")?,
             meteora_damm_program_id: parse_pubkey("METEORA_DAMM_PROGRAM_ID")?,
@@ -286,12 +469,45 @@ impl Config {
             whirlpool_program_id: parse_pubkey("WHIRLPOOL_PROGRAM_ID")?,
             orca_program_id: parse_pubkey("ORCA_PROGRAM_ID")?,
             pump_program_id: parse_pubkey("PUMP_PROGRAM_ID")?,
+            phoenix_program_id: parse_pubkey("PHOENIX_PROGRAM_ID")?,
+            openbook_v2_program_id: parse_pubkey("OPENBOOK_V2_PROGRAM_ID")?,
+        };
+
+        // Pool discovery configuration
+        let discovery = DiscoveryConfig {
+            enabled: get_bool_env("POOL_DISCOVERY_ENABLED", true),
+            registry_path: get_env_or_default("POOL_REGISTRY_PATH", "data/pool_registry.json"),
+            refresh: get_bool_env("POOL_DISCOVERY_REFRESH", false),
+            listen_new_pools: get_bool_env("NEW_POOL_LISTENER_ENABLED", true),
+            new_pool_min_liquidity: get_env_or_default("NEW_POOL_MIN_LIQUIDITY", "100")
+                .parse()
+                .context("Failed to parse NEW_POOL_MIN_LIQUIDITY")?,
+        };
+
+        // Risk thresholds
+        let risk = RiskConfig {
+            low_risk_min_profit_bps: get_u64_env("LOW_RISK_MIN_PROFIT_BPS", 100)? as i64,
+            low_risk_max_hops: get_u32_env("LOW_RISK_MAX_HOPS", 3)? as usize,
+            medium_risk_min_profit_bps: get_u64_env("MEDIUM_RISK_MIN_PROFIT_BPS", 50)? as i64,
+            medium_risk_max_hops: get_u32_env("MEDIUM_RISK_MAX_HOPS", 4)? as usize,
+            max_price_impact_bps: get_u64_env("MAX_PRICE_IMPACT_BPS", 100)?,
         };
 
-        Ok(Config {
+        // Venue reliability priors
+        let reliability = ReliabilityConfig::load()?;
+
+        // Edge staleness limits
+        let staleness = StalenessConfig {
+            max_edge_age_slots: get_u64_env("MAX_EDGE_AGE_SLOTS", 50)?,
+            max_edge_age_ms: get_u64_env("MAX_EDGE_AGE_MS", 20000)?,
+            max_cycle_slot_span: get_u64_env("MAX_CYCLE_SLOT_SPAN", 25)?,
+        };
+
+        let config = Config {
             bot,
             routing,
             mints,
+            tokens,
             rpc,
             spam,
             wallet,
@@ -300,7 +516,31 @@ impl Config {
             monitoring,
             execution,
             dex,
-        })
+            discovery,
+            risk,
+            reliability,
+            staleness,
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Check that the detection settings are consistent
+    fn validate(&self) -> Result<()> {
+        if self.tokens.base_tokens.is_empty() {
+            anyhow::bail!("BASE_TOKENS must name at least one configured mint");
+        }
+        if self.routing.max_hops < 2 {
+            anyhow::bail!("MAX_HOPS must be at least 2 (got {})", self.routing.max_hops);
+        }
+        if !self.tokens.min_pool_liquidity_usd.is_finite() || self.tokens.min_pool_liquidity_usd < 0.0 {
+            anyhow::bail!(
+                "MIN_POOL_LIQUIDITY_USD must be a non-negative number (got {})",
+                self.tokens.min_pool_liquidity_usd
+            );
+        }
+        Ok(())
     }
 
     /// Load mint configurations from environment variables (MINT_1, MINT_2, etc.)
@@ -521,18 +761,18 @@ fn get_bool_env(key: &str, default: bool) -> bool {
 
 /// Get u32 environment variable with default
 fn get_u32_env(key: &str, default: u32) -> Result<u32> {
-    Ok(std::env::var(key)
+    std::env::var(key)
         .unwrap_or_else(|_| default.to_string())
         .parse()
-        .context(format!("Failed to parse {} as u32", key))?)
+        .context(format!("Failed to parse {} as u32", key))
 }
 
 /// Get u64 environment variable with default
 fn get_u64_env(key: &str, default: u64) -> Result<u64> {
-    Ok(std::env::var(key)
+    std::env::var(key)
         .unwrap_or_else(|_| default.to_string())
         .parse()
-        .context(format!("Failed to parse {} as u64", key))?)
+        .context(format!("Failed to parse {} as u64", key))
 }
 
 /// Parse comma-separated string list
@@ -562,6 +802,18 @@ fn parse_pubkey_optional(env_var: &str) -> Result<Pubkey> {
     Pubkey::from_str(&pubkey_str).context(format!("Failed to parse {} as Pubkey", env_var))
 }
 
+/// Configured mint named by `token`: its symbol (case-insensitive) or address
+fn resolve_mint(mints: &[MintConfig], token: &str, key: &str) -> Result<Pubkey> {
+    let by_address = Pubkey::from_str(token).ok();
+    mints.iter()
+        .find(|mint| mint.symbol.eq_ignore_ascii_case(token) || Some(mint.address) == by_address)
+        .map(|mint| mint.address)
+        .with_context(|| {
+            let known: Vec<&str> = mints.iter().map(|mint| mint.symbol.as_str()).collect();
+            format!("Unknown mint '{}' in {}: expected one of the configured mints ({})", token, key, known.join(", "))
+        })
+}
+
 /// Parse comma-separated list of pubkeys
 fn parse_pubkey_list(input: &str) -> Result<Vec<Pubkey>> {
     if input.is_empty() {
@@ -606,19 +858,19 @@ mod tests {
         let wsol = mints.iter().find(|m| m.symbol == "SOL").unwrap();
         assert_eq!(wsol.address, WSOL_MINT);
         assert_eq!(wsol.decimals, 9);
-        assert_eq!(wsol.is_quote, true);
+        assert!(wsol.is_quote);
         
         // Check USDC
         let usdc = mints.iter().find(|m| m.symbol == "USDC").unwrap();
         assert_eq!(usdc.address, USDC_MINT);
         assert_eq!(usdc.decimals, 6);
-        assert_eq!(usdc.is_quote, true);
+        assert!(usdc.is_quote);
         
         // Check USDT
         let usdt = mints.iter().find(|m| m.symbol == "USDT").unwrap();
         assert_eq!(usdt.address, USDT_MINT);
         assert_eq!(usdt.decimals, 6);
-        assert_eq!(usdt.is_quote, true);
+        assert!(usdt.is_quote);
     }
 
     #[test]
@@ -668,4 +920,18 @@ mod tests {
         let result = Config::validate_mint_configs(&valid_mints);
         assert!(result.is_ok(), "Should accept valid mint config");
     }
+
+    #[test]
+    fn test_resolve_mint_by_symbol_or_address() {
+        let mints = Config::load_default_mints().unwrap();
+
+        assert_eq!(resolve_mint(&mints, "usdc", "BASE_TOKENS").unwrap(), USDC_MINT);
+        assert_eq!(resolve_mint(&mints, &WSOL_MINT.to_string(), "BASE_TOKENS").unwrap(), WSOL_MINT);
+
+        let err = resolve_mint(&mints, "BONK", "BASE_TOKENS").unwrap_err().to_string();
+        assert!(err.contains("Unknown mint 'BONK' in BASE_TOKENS"), "{}", err);
+        assert!(err.contains("SOL, USDC, USDT"), "{}", err);
+        // A valid address that is not a configured mint is rejected too
+        assert!(resolve_mint(&mints, &Pubkey::new_unique().to_string(), "MIN_TRADE_SIZES").is_err());
+    }
 }
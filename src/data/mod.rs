@@ -2,4 +2,4 @@ pub mod storage;
 pub mod trade_record;
 
 pub use storage::TradeStorage;
-pub use trade_record::TradeRecord;
+pub use trade_record::{TradeHop, TradeRecord};
@@ -278,7 +278,7 @@ mod tests {
                 1699900000000 + i,
                 format!("sig{}", i),
                 "So11111111111111111111111111111111111111112".to_string(),
-                1000000 + i as u64,
+                1000000 + i,
                 950000,
                 150,
                 "LIVE".to_string(),
@@ -3,7 +3,7 @@
 // Tasks (in order):
 // 1. Define a new struct `TradeRecord` to store the outcome of each arbitrage attempt.
 // 2. Fields should include: `timestamp`, `signature` (Option<String>), `success` (bool), 
-//    `profit_token_mint`, `profit_amount` (u64), `expected_profit_amount` (u64), 
+//    `profit_token_mint`, `profit_amount` (i64), `expected_profit_amount` (u64), 
 //    `latency_ms` (u64), `execution_mode` (String), `error_message` (Option<String>).
 // 3. Derive `Serialize` and `Deserialize` using the `serde` crate for easy persistence.
 
@@ -16,6 +16,36 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::dex::triangular_arb::ArbitrageCycle;
+
+/// One pool a trade went through
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeHop {
+    /// Venue name, as `DexType` displays it
+    pub dex: String,
+    /// Pool address
+    pub pool: String,
+}
+
+impl TradeHop {
+    /// Every pool of `cycle` in trade order, split legs included
+    pub fn route_of(cycle: &ArbitrageCycle) -> Vec<TradeHop> {
+        cycle.path.iter()
+            .flat_map(|step| {
+                let legs: Vec<_> = if step.splits.is_empty() {
+                    vec![(step.dex.clone(), step.pool)]
+                } else {
+                    step.splits.iter().map(|leg| (leg.dex.clone(), leg.pool)).collect()
+                };
+                legs.into_iter().map(|(dex, pool)| TradeHop {
+                    dex: dex.to_string(),
+                    pool: pool.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
 /// Trade record capturing the outcome of each arbitrage attempt
 /// 
 /// This structure stores comprehensive information about executed trades
@@ -34,8 +64,8 @@ pub struct TradeRecord {
     /// Token mint address for the profit token
     pub profit_token_mint: String,
     
-    /// Actual profit amount received (in token's smallest unit)
-    pub profit_amount: u64,
+    /// Actual profit amount received (in token's smallest unit), negative for a loss
+    pub profit_amount: i64,
     
     /// Expected profit amount before execution (in token's smallest unit)
     pub expected_profit_amount: u64,
@@ -43,11 +73,17 @@ pub struct TradeRecord {
     /// Execution latency in milliseconds (from opportunity detection to tx confirmation)
     pub latency_ms: u64,
     
-    /// Execution mode: "LIVE" or "SIMULATION"
+    /// Execution mode: "LIVE", "SIMULATION", or "LIVE_UNVALIDATED" for sent
+    /// trades whose realized profit was not measured
     pub execution_mode: String,
     
     /// Error message if the trade failed
     pub error_message: Option<String>,
+    
+    /// Pools the trade went through, in order (empty in records written
+    /// before routes were recorded)
+    #[serde(default)]
+    pub route: Vec<TradeHop>,
 }
 
 impl TradeRecord {
@@ -56,7 +92,7 @@ impl TradeRecord {
         timestamp: i64,
         signature: String,
         profit_token_mint: String,
-        profit_amount: u64,
+        profit_amount: i64,
         expected_profit_amount: u64,
         latency_ms: u64,
         execution_mode: String,
@@ -71,6 +107,7 @@ impl TradeRecord {
             latency_ms,
             execution_mode,
             error_message: None,
+            route: Vec::new(),
         }
     }
     
@@ -93,9 +130,16 @@ impl TradeRecord {
             latency_ms,
             execution_mode,
             error_message: Some(error_message),
+            route: Vec::new(),
         }
     }
     
+    /// Record the pools the trade went through
+    pub fn with_route(mut self, route: Vec<TradeHop>) -> Self {
+        self.route = route;
+        self
+    }
+    
     /// Calculate profit variance percentage (actual vs expected)
     pub fn profit_variance_percent(&self) -> f64 {
         if self.expected_profit_amount == 0 {
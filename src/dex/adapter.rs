@@ -0,0 +1,623 @@
+// DEX Adapter Abstraction
+//
+// Every supported venue implements `DexAdapter`, which owns everything that is
+// specific to that program:
+// 1. Decoding the on-chain pool account into `PoolData`
+// 2. Quoting the exact amount out for a given amount in
+// 3. Listing the accounts a swap needs
+// 4. Building the swap `Instruction`
+//
+// `DexRegistry` maps program ids to adapters. The pool fetcher, the WebSocket
+// monitor, the arbitrage graph and the transaction builder all dispatch
+// through it, so adding a venue means writing one adapter module and
+// registering it here.
+//
+// The registry also loads both mints of every pool, so quotes include
+// Token-2022 transfer fees and pools with unsafe mint extensions never reach
+// the graph (see `token_2022`).
+//
+// Graph edges built by the registry carry an `EdgeQuoter`, so cycle
+// evaluation, sizing and min-out computation all use the same integer math
+// (and rounding) as the program instead of multiplying by an f64 rate.
+// They also carry the pool's decoded `FeeModel`, rebuilt on every account
+// update, so log weights and cycle profits use the fee the chain charges.
+
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::{Result, anyhow};
+use tracing::{debug, warn};
+
+use crate::config::DexConfig;
+use crate::dex::fees::FeeModel;
+use crate::dex::meteora::{DammPoolState, MeteoraDammAdapter};
+use crate::dex::meteora_dlmm::{DlmmPoolState, MeteoraDlmmAdapter};
+use crate::dex::openbook_v2::{OpenBookMarketState, OpenBookV2Adapter};
+use crate::dex::orca::{OrcaAdapter, OrcaPoolState};
+use crate::dex::phoenix::{PhoenixAdapter, PhoenixMarketState};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::pump::{PumpAdapter, PumpPoolState};
+use crate::dex::raydium::{RaydiumAdapter, RaydiumPoolState};
+use crate::dex::raydium_clmm::{ClmmPoolState, RaydiumClmmAdapter};
+use crate::dex::raydium_cpmm::{CpmmPoolState, RaydiumCpmmAdapter};
+use crate::dex::token_2022::PoolMints;
+use crate::dex::triangular_arb::{DexType, ExchangeEdge, PriceLevel};
+use crate::dex::whirlpool::{WhirlpoolAdapter, WhirlpoolPoolState};
+
+/// SPL Token-2022 program id
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Venue-specific pool state carried alongside the common reserve view.
+///
+/// Adapters that need more than the common `PoolData` fields to quote or
+/// route a swap add a variant here.
+#[derive(Clone, Debug, Default)]
+pub enum VenueState {
+    #[default]
+    None,
+    Raydium(Box<RaydiumPoolState>),
+    Whirlpool(Box<WhirlpoolPoolState>),
+    MeteoraDamm(Box<DammPoolState>),
+    MeteoraDlmm(Box<DlmmPoolState>),
+    Pump(Box<PumpPoolState>),
+    Orca(Box<OrcaPoolState>),
+    RaydiumCpmm(Box<CpmmPoolState>),
+    RaydiumClmm(Box<ClmmPoolState>),
+    Phoenix(Box<PhoenixMarketState>),
+    OpenBookV2(Box<OpenBookMarketState>),
+}
+
+/// User-side parameters of a single swap
+#[derive(Clone, Debug)]
+pub struct SwapParams {
+    /// Wallet that signs the swap
+    pub user: Pubkey,
+    /// User token account the input is taken from
+    pub user_source: Pubkey,
+    /// User token account the output is paid to
+    pub user_destination: Pubkey,
+    /// Exact input; the most the swap may spend when it is exact-out
+    pub amount_in: u64,
+    /// Least output accepted; the exact output when the swap is exact-out
+    pub minimum_out: u64,
+    /// true when swapping pool token A for token B
+    pub a_to_b: bool,
+}
+
+/// Where the two mints sit in a pool account, so pools can be found with
+/// `getProgramAccounts` memcmp filters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintLayout {
+    /// Exact pool account size, when every pool of the program has the same size
+    pub data_size: Option<u64>,
+    pub mint_a_offset: usize,
+    pub mint_b_offset: usize,
+}
+
+/// Venue integration: decoding, quoting and instruction building for one program
+pub trait DexAdapter: Send + Sync {
+    /// Program id this adapter handles
+    fn program_id(&self) -> Pubkey;
+
+    /// Graph venue this adapter produces edges for
+    fn dex_type(&self) -> DexType;
+
+    /// Decode a pool account owned by this program
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData>;
+
+    /// Mint offsets of the pool account for discovery. `None` for programs
+    /// whose pools cannot be found by mint (e.g. PDA-addressed curves).
+    fn mint_layout(&self) -> Option<MintLayout> {
+        None
+    }
+
+    /// Log lines that mark the creation of a pool of this program. A line
+    /// matches when it equals a marker or continues it after a space (for
+    /// instructions that log their arguments). Venues without markers are
+    /// not watched for new pools.
+    fn pool_init_logs(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Other accounts (vaults, markets, ...) needed to complete a decoded pool
+    fn dependent_accounts(&self, _pool: &PoolData) -> Vec<Pubkey> {
+        Vec::new()
+    }
+
+    /// Complete a decoded pool from its fetched dependent accounts
+    fn apply_dependent_accounts(&self, _pool: &mut PoolData, _accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether the pool can currently be quoted and routed. Pools that are
+    /// not tradable (e.g. a completed bonding curve) produce no graph edges.
+    fn is_tradable(&self, _pool: &PoolData) -> bool {
+        true
+    }
+
+    /// Whether a swap in this direction fills an exact output amount
+    /// (`SwapParams::minimum_out`) rather than spending an exact input
+    fn is_exact_out(&self, _pool: &PoolData, _a_to_b: bool) -> bool {
+        false
+    }
+
+    /// Whether the pool's SOL side moves the signer's native lamports
+    /// instead of a WSOL token account
+    fn settles_native_sol(&self) -> bool {
+        false
+    }
+
+    /// Exact amount out for `amount_in`, reproducing the program's integer math
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let (reserve_in, reserve_out) = pool.reserves(a_to_b);
+        constant_product_amount_out(amount_in, reserve_in, reserve_out, pool.fee_bps as u64, 10_000)
+    }
+
+    /// Fee charged on swaps through the pool, decoded from the pool or its
+    /// config. Defaults to a fixed fee of `pool.fee_bps`.
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        FeeModel::from_bps(pool.fee_bps)
+    }
+
+    /// Marginal price before fees: how many output tokens per input token
+    fn spot_rate(&self, pool: &PoolData, a_to_b: bool) -> f64 {
+        let (reserve_in, reserve_out) = pool.reserves(a_to_b);
+        if reserve_in == 0 {
+            return 0.0;
+        }
+        reserve_out as f64 / reserve_in as f64
+    }
+
+    /// Depth ladder for the graph edge, best price first. Empty for venues
+    /// whose depth is implied by the reserves.
+    fn liquidity_depth(&self, _pool: &PoolData, _a_to_b: bool) -> Vec<PriceLevel> {
+        Vec::new()
+    }
+
+    /// Accounts required by the swap instruction, in program order
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>>;
+
+    /// Serialized swap instruction data
+    fn swap_data(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>>;
+
+    /// Build the complete swap instruction
+    fn build_swap_ix(&self, pool: &PoolData, params: &SwapParams) -> Result<Instruction> {
+        Ok(Instruction {
+            program_id: self.program_id(),
+            accounts: self.swap_accounts(pool, params)?,
+            data: self.swap_data(pool, params)?,
+        })
+    }
+}
+
+/// Registry of venue adapters keyed by program id
+#[derive(Clone)]
+pub struct DexRegistry {
+    adapters: HashMap<Pubkey, Arc<dyn DexAdapter>>,
+}
+
+impl DexRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            adapters: HashMap::new(),
+        }
+    }
+
+    /// Create a registry with all built-in adapters using configured program ids
+    pub fn from_config(config: &DexConfig) -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(RaydiumAdapter::new(config.raydium_amm_program_id)));
+        registry.register(Arc::new(RaydiumCpmmAdapter::new(config.raydium_cpmm_program_id)));
+        registry.register(Arc::new(RaydiumClmmAdapter::new(config.raydium_clmm_program_id)));
+        registry.register(Arc::new(
+            MeteoraDammAdapter::new(config.meteora_damm_program_id)
+                .with_vault_program(config.meteora_vault_program_id),
+        ));
+        registry.register(Arc::new(MeteoraDlmmAdapter::new(config.meteora_dlmm_program_id)));
+        registry.register(Arc::new(WhirlpoolAdapter::new(config.whirlpool_program_id)));
+        registry.register(Arc::new(OrcaAdapter::new(config.orca_program_id)));
+        registry.register(Arc::new(PumpAdapter::new(config.pump_program_id)));
+        registry.register(Arc::new(PhoenixAdapter::new(config.phoenix_program_id)));
+        registry.register(Arc::new(OpenBookV2Adapter::new(config.openbook_v2_program_id)));
+        registry
+    }
+
+    /// Register an adapter, replacing any existing one for the same program
+    pub fn register(&mut self, adapter: Arc<dyn DexAdapter>) {
+        debug!("Registered {} adapter for program {}", adapter.dex_type(), adapter.program_id());
+        self.adapters.insert(adapter.program_id(), adapter);
+    }
+
+    /// Look up the adapter for a program id
+    pub fn get(&self, program_id: &Pubkey) -> Option<&Arc<dyn DexAdapter>> {
+        self.adapters.get(program_id)
+    }
+
+    /// Look up the adapter for a program id, failing for unknown programs
+    pub fn adapter(&self, program_id: &Pubkey) -> Result<&Arc<dyn DexAdapter>> {
+        self.get(program_id)
+            .ok_or_else(|| anyhow!("Unknown pool program: {}", program_id))
+    }
+
+    /// All registered program ids
+    pub fn program_ids(&self) -> Vec<Pubkey> {
+        self.adapters.keys().copied().collect()
+    }
+
+    /// Decode a pool account with the adapter for its owner program
+    pub fn decode_pool(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
+        self.adapter(&account.owner)?.decode_pool(address, &account.data)
+    }
+
+    /// Dependent accounts to fetch for `pool` (empty for unknown programs)
+    pub fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        let mut accounts = match self.get(&pool.program_id) {
+            Some(adapter) => adapter.dependent_accounts(pool),
+            None => return Vec::new(),
+        };
+        for address in PoolMints::dependent_accounts(&pool.token_a, &pool.token_b) {
+            if !accounts.contains(&address) {
+                accounts.push(address);
+            }
+        }
+        accounts
+    }
+
+    /// Complete `pool` from its fetched dependent accounts
+    pub fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let adapter = self.adapter(&pool.program_id)?;
+        pool.mints.apply_accounts(&pool.token_a, &pool.token_b, accounts)?;
+        adapter.apply_dependent_accounts(pool, accounts)
+    }
+
+    /// Exact amount out for a swap through `pool`, transfer fees included
+    pub fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        quote_with_transfer_fees(self.adapter(&pool.program_id)?.as_ref(), pool, amount_in, a_to_b)
+    }
+
+    /// Build the swap instruction for `pool`
+    pub fn build_swap_ix(&self, pool: &PoolData, params: &SwapParams) -> Result<Instruction> {
+        self.adapter(&pool.program_id)?.build_swap_ix(pool, params)
+    }
+
+    /// Graph edges (both directions) for a decoded pool; empty when it is not tradable
+    pub fn edges_for(&self, pool: &PoolData, timestamp: i64) -> Result<Vec<ExchangeEdge>> {
+        let adapter = self.adapter(&pool.program_id)?;
+
+        if !adapter.is_tradable(pool) {
+            return Ok(Vec::new());
+        }
+        if let Some(reason) = pool.mints.routing_restriction() {
+            warn!("Pool {} ({}) kept out of the graph: {}", pool.pool_address, pool.dex_type, reason);
+            return Ok(Vec::new());
+        }
+
+        let snapshot = Arc::new(pool.clone());
+        let fee = adapter.fee_model(pool);
+
+        Ok(vec![
+            ExchangeEdge::new(
+                pool.token_a,
+                pool.token_b,
+                pool.dex_type.clone(),
+                pool.pool_address,
+                adapter.spot_rate(pool, true),
+                pool.fee_bps.saturating_add(pool.mints.transfer_fee_bps(true)),
+                adapter.liquidity_depth(pool, true),
+                timestamp,
+            )
+            .with_fee_model(fee, pool.mints.transfer_fee_bps(true))
+            .with_slot(pool.slot)
+            .with_quoter(EdgeQuoter::new(adapter.clone(), snapshot.clone(), true)),
+            ExchangeEdge::new(
+                pool.token_b,
+                pool.token_a,
+                pool.dex_type.clone(),
+                pool.pool_address,
+                adapter.spot_rate(pool, false),
+                pool.fee_bps.saturating_add(pool.mints.transfer_fee_bps(false)),
+                adapter.liquidity_depth(pool, false),
+                timestamp,
+            )
+            .with_fee_model(fee, pool.mints.transfer_fee_bps(false))
+            .with_slot(pool.slot)
+            .with_quoter(EdgeQuoter::new(adapter.clone(), snapshot, false)),
+        ])
+    }
+}
+
+impl Default for DexRegistry {
+    /// Registry with all built-in adapters on their mainnet program ids
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(RaydiumAdapter::default()));
+        registry.register(Arc::new(RaydiumCpmmAdapter::default()));
+        registry.register(Arc::new(RaydiumClmmAdapter::default()));
+        registry.register(Arc::new(MeteoraDammAdapter::default()));
+        registry.register(Arc::new(MeteoraDlmmAdapter::default()));
+        registry.register(Arc::new(WhirlpoolAdapter::default()));
+        registry.register(Arc::new(OrcaAdapter::default()));
+        registry.register(Arc::new(PumpAdapter::default()));
+        registry.register(Arc::new(PhoenixAdapter::default()));
+        registry.register(Arc::new(OpenBookV2Adapter::default()));
+        registry
+    }
+}
+
+/// Exact quoting function of one graph edge: the venue adapter together with
+/// the pool snapshot the edge was built from and the swap direction.
+/// Both edges of a pool share the snapshot.
+#[derive(Clone)]
+pub struct EdgeQuoter {
+    adapter: Arc<dyn DexAdapter>,
+    pool: Arc<PoolData>,
+    a_to_b: bool,
+}
+
+impl EdgeQuoter {
+    pub fn new(adapter: Arc<dyn DexAdapter>, pool: Arc<PoolData>, a_to_b: bool) -> Self {
+        Self { adapter, pool, a_to_b }
+    }
+
+    /// Exact amount out for `amount_in`, fees included, as the program computes it
+    pub fn quote_exact_in(&self, amount_in: u64) -> Result<u64> {
+        quote_with_transfer_fees(self.adapter.as_ref(), &self.pool, amount_in, self.a_to_b)
+    }
+
+    /// Pool snapshot the quotes are computed from
+    pub fn pool(&self) -> &PoolData {
+        &self.pool
+    }
+
+    /// Shared handle to the pool snapshot
+    pub fn snapshot(&self) -> Arc<PoolData> {
+        self.pool.clone()
+    }
+
+    pub fn a_to_b(&self) -> bool {
+        self.a_to_b
+    }
+}
+
+impl std::fmt::Debug for EdgeQuoter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EdgeQuoter")
+            .field("dex", &self.adapter.dex_type())
+            .field("pool", &self.pool.pool_address)
+            .field("a_to_b", &self.a_to_b)
+            .finish()
+    }
+}
+
+/// Adapter quote wrapped in the Token-2022 transfer fees of both legs: the
+/// pool only receives the input net of its fee, and the user only receives
+/// the output net of its fee.
+pub fn quote_with_transfer_fees(adapter: &dyn DexAdapter, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+    let pool_amount_in = pool.mints.amount_received_by_pool(a_to_b, amount_in);
+    let pool_amount_out = adapter.quote_exact_in(pool, pool_amount_in, a_to_b)?;
+    Ok(pool.mints.amount_received_by_user(a_to_b, pool_amount_out))
+}
+
+/// Constant product output with the fee taken from the input (rounded up),
+/// matching the integer math of Raydium v4 and SPL token-swap.
+pub fn constant_product_amount_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("Pool has empty reserves"));
+    }
+    if fee_denominator == 0 {
+        return Err(anyhow!("Fee denominator is zero"));
+    }
+
+    let amount_in = amount_in as u128;
+    let fee = (amount_in * fee_numerator as u128).div_ceil(fee_denominator as u128);
+    let amount_in_after_fee = amount_in.saturating_sub(fee);
+
+    let numerator = reserve_out as u128 * amount_in_after_fee;
+    let denominator = reserve_in as u128 + amount_in_after_fee;
+
+    Ok((numerator / denominator) as u64)
+}
+
+/// Read a little-endian u64 at `offset`
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let bytes = data.get(offset..offset + 8)
+        .ok_or_else(|| anyhow!("Account data too short for u64 at offset {}", offset))?;
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+/// SPL token account `amount` offset
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// Read the `amount` of an SPL token account
+pub(crate) fn token_account_amount(account: &Account) -> Result<u64> {
+    read_u64(&account.data, TOKEN_ACCOUNT_AMOUNT_OFFSET)
+}
+
+/// Read `N` raw bytes at `offset`
+pub(crate) fn read_array<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    let bytes = data.get(offset..offset + N)
+        .ok_or_else(|| anyhow!("Account data too short for {} bytes at offset {}", N, offset))?;
+    Ok(bytes.try_into()?)
+}
+
+/// Read a little-endian u16 at `offset`
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    Ok(u16::from_le_bytes(read_array(data, offset)?))
+}
+
+/// Read a little-endian u32 at `offset`
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_array(data, offset)?))
+}
+
+/// Read a little-endian i32 at `offset`
+pub(crate) fn read_i32(data: &[u8], offset: usize) -> Result<i32> {
+    Ok(i32::from_le_bytes(read_array(data, offset)?))
+}
+
+/// Read a little-endian i64 at `offset`
+pub(crate) fn read_i64(data: &[u8], offset: usize) -> Result<i64> {
+    Ok(i64::from_le_bytes(read_array(data, offset)?))
+}
+
+/// Read a little-endian u128 at `offset`
+pub(crate) fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
+    Ok(u128::from_le_bytes(read_array(data, offset)?))
+}
+
+/// Read a little-endian i128 at `offset`
+pub(crate) fn read_i128(data: &[u8], offset: usize) -> Result<i128> {
+    Ok(i128::from_le_bytes(read_array(data, offset)?))
+}
+
+/// Read a pubkey at `offset`
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
+    let bytes = data.get(offset..offset + 32)
+        .ok_or_else(|| anyhow!("Account data too short for pubkey at offset {}", offset))?;
+    Ok(Pubkey::try_from(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::raydium::RAYDIUM_AMM_V4_PROGRAM_ID;
+    use crate::dex::test_fixtures::raydium_pool;
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn create_test_pool(program_id: Pubkey) -> PoolData {
+        PoolData {
+            program_id,
+            ..raydium_pool(create_test_pubkey(1), create_test_pubkey(2), create_test_pubkey(3), 1_000_000, 2_000_000)
+        }
+    }
+
+    #[test]
+    fn test_constant_product_amount_out() {
+        // 1000 in, 25 bps fee rounded up = 3, 997 after fee
+        // out = 2_000_000 * 997 / (1_000_000 + 997) = 1992
+        let out = constant_product_amount_out(1000, 1_000_000, 2_000_000, 25, 10_000).unwrap();
+        assert_eq!(out, 1992);
+
+        // Empty reserves cannot be quoted
+        assert!(constant_product_amount_out(1000, 0, 2_000_000, 25, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_default_registry_has_builtin_venues() {
+        let registry = DexRegistry::default();
+
+        let raydium = registry.get(&RAYDIUM_AMM_V4_PROGRAM_ID).unwrap();
+        assert_eq!(raydium.dex_type(), DexType::Raydium);
+        assert!(registry.get(&create_test_pubkey(99)).is_none());
+        assert_eq!(registry.program_ids().len(), 10);
+    }
+
+    #[test]
+    fn test_registry_rejects_unknown_program() {
+        let registry = DexRegistry::default();
+        let pool = create_test_pool(create_test_pubkey(99));
+
+        assert!(registry.quote_exact_in(&pool, 1000, true).is_err());
+        assert!(registry.edges_for(&pool, 0).is_err());
+    }
+
+    #[test]
+    fn test_edges_for_pool() {
+        let registry = DexRegistry::default();
+        let pool = create_test_pool(RAYDIUM_AMM_V4_PROGRAM_ID);
+
+        let edges = registry.edges_for(&pool, 1234).unwrap();
+        assert_eq!(edges.len(), 2);
+
+        assert_eq!(edges[0].from_token, pool.token_a);
+        assert_eq!(edges[0].to_token, pool.token_b);
+        assert!((edges[0].rate - 2.0).abs() < 1e-9);
+
+        assert_eq!(edges[1].from_token, pool.token_b);
+        assert!((edges[1].rate - 0.5).abs() < 1e-9);
+        assert_eq!(edges[1].last_update, 1234);
+    }
+
+    #[test]
+    fn test_edges_carry_decoded_fee_model() {
+        use crate::dex::fees::FeeRate;
+
+        let registry = DexRegistry::default();
+        let mut pool = create_test_pool(RAYDIUM_AMM_V4_PROGRAM_ID);
+        // 12.5 bps swap fee, rounded down to 12 in `PoolData::fee_bps`
+        if let VenueState::Raydium(state) = &mut pool.venue {
+            state.amm.fees.swap_fee_numerator = 1;
+            state.amm.fees.swap_fee_denominator = 800;
+        }
+        pool.fee_bps = 12;
+
+        let edges = registry.edges_for(&pool, 0).unwrap();
+        assert_eq!(edges[0].fee.trade_fee(), FeeRate::from_fraction(1, 800));
+        assert_eq!(edges[0].fee_bps, 13);
+        assert!((edges[0].inverse_log_weight + (2.0 * (1.0 - 0.00125f64)).ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_transfer_fees_apply_to_quotes_and_edges() {
+        use crate::dex::token_2022::{TransferFee, TransferFeeConfig};
+
+        let registry = DexRegistry::default();
+        let mut pool = create_test_pool(RAYDIUM_AMM_V4_PROGRAM_ID);
+        let fee = TransferFee { epoch: 0, maximum_fee: u64::MAX, basis_points: 100 };
+        pool.mints.a.token_program = TOKEN_2022_PROGRAM_ID;
+        pool.mints.a.transfer_fee = Some(TransferFeeConfig { older: fee, newer: fee });
+
+        // A -> B: 1% of 1010 (rounded up) is withheld before the pool sees it
+        let expected = constant_product_amount_out(999, 1_000_000, 2_000_000, 25, 10_000).unwrap();
+        assert_eq!(registry.quote_exact_in(&pool, 1010, true).unwrap(), expected);
+        // B -> A: the pool pays 1992, the user receives 1992 - ceil(19.92)
+        let out = constant_product_amount_out(1000, 2_000_000, 1_000_000, 25, 10_000).unwrap();
+        assert_eq!(registry.quote_exact_in(&pool, 1000, false).unwrap(), out - out.div_ceil(100));
+
+        let edges = registry.edges_for(&pool, 0).unwrap();
+        assert_eq!(edges[0].fee_bps, 125);
+        assert_eq!(edges[0].quote_exact_in(1010).unwrap(), expected);
+
+        // Hooked mints never reach the graph
+        pool.mints.b.transfer_hook_program = Some(create_test_pubkey(9));
+        assert!(registry.edges_for(&pool, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_edge_quotes_match_pool_integer_math() {
+        let registry = DexRegistry::default();
+        let pool = create_test_pool(RAYDIUM_AMM_V4_PROGRAM_ID);
+        let edges = registry.edges_for(&pool, 0).unwrap();
+
+        // Same result as the on-chain swap: fee rounded up, output rounded down
+        assert_eq!(edges[0].quote_exact_in(1000).unwrap(), 1992);
+        assert_eq!(edges[1].quote_exact_in(1000).unwrap(), 498);
+        assert_eq!(
+            edges[1].quote_exact_in(1000).unwrap(),
+            registry.quote_exact_in(&pool, 1000, false).unwrap()
+        );
+
+        // The f64 rate would have promised 1000 * 2.0 * 0.9975 = 1995
+        let naive = (1000.0 * edges[0].rate * (1.0 - edges[0].fee_bps as f64 / 10_000.0)) as u64;
+        assert!(naive > edges[0].quote_exact_in(1000).unwrap());
+
+        let quoter = edges[0].quoter.as_ref().unwrap();
+        assert!(quoter.a_to_b());
+        assert_eq!(quoter.pool().pool_address, pool.pool_address);
+    }
+}
@@ -0,0 +1,199 @@
+// Candidate Cycle Index
+//
+// Full cycle discovery walks the whole graph, but a pool update only changes
+// the two edges of that pool. The index keeps the candidate cycles (every
+// simple cycle of up to `max_path_length` hops through the start tokens, plus
+// any negative cycle Bellman-Ford found) and maps each edge to the cycles
+// that use it, so an update re-scores just those cycles from the current edge
+// rates. The index records the graph's topology version it was built from;
+// once edges are added or removed it is stale and should be rebuilt.
+//
+// Cycles are stored as `(from, to, pool)` edge keys starting at the first
+// start token they pass through, the same rotation Bellman-Ford reports.
+
+use std::collections::{HashMap, HashSet};
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+
+use crate::dex::triangular_arb::{ArbitrageCycle, ArbitrageGraph, EdgeKey};
+
+/// Candidate cycles indexed by the edges they trade through
+pub struct CycleIndex {
+    cycles: Vec<Vec<EdgeKey>>,
+    by_edge: HashMap<EdgeKey, Vec<usize>>,
+    seen: HashSet<Vec<EdgeKey>>,
+    topology_version: Option<u64>,
+    max_path_length: usize,
+    max_cycles: usize,
+}
+
+impl CycleIndex {
+    /// Empty index for cycles of at most `max_path_length` hops, holding at
+    /// most `max_cycles` candidates
+    pub fn new(max_path_length: usize, max_cycles: usize) -> Self {
+        Self {
+            cycles: Vec::new(),
+            by_edge: HashMap::new(),
+            seen: HashSet::new(),
+            topology_version: None,
+            max_path_length,
+            max_cycles,
+        }
+    }
+
+    /// Replace the candidates with every simple cycle through `start_tokens`
+    /// in the current graph. A cycle through several start tokens is kept
+    /// once, starting at the first of them.
+    pub fn rebuild(&mut self, graph: &ArbitrageGraph, start_tokens: &[Pubkey]) {
+        self.cycles.clear();
+        self.by_edge.clear();
+        self.seen.clear();
+
+        for (i, start) in start_tokens.iter().enumerate() {
+            let remaining = self.max_cycles.saturating_sub(self.cycles.len());
+            for cycle in graph.simple_cycles(start, &start_tokens[..i], self.max_path_length, remaining) {
+                self.insert_path(cycle);
+            }
+        }
+
+        self.topology_version = Some(graph.topology_version());
+        debug!(
+            "Indexed {} candidate cycles through {} start tokens",
+            self.cycles.len(),
+            start_tokens.len()
+        );
+    }
+
+    /// Add a cycle found elsewhere (e.g. by Bellman-Ford). Returns false if
+    /// it is already indexed, too long, or the index is full.
+    pub fn insert(&mut self, cycle: &ArbitrageCycle) -> bool {
+        let path = cycle.path.iter()
+            .map(|step| (step.from_token, step.to_token, step.pool))
+            .collect();
+        self.insert_path(path)
+    }
+
+    fn insert_path(&mut self, path: Vec<EdgeKey>) -> bool {
+        if path.is_empty()
+            || path.len() > self.max_path_length
+            || self.cycles.len() >= self.max_cycles
+            || self.seen.contains(&path)
+        {
+            return false;
+        }
+
+        let id = self.cycles.len();
+        for edge in &path {
+            self.by_edge.entry(*edge).or_default().push(id);
+        }
+        self.seen.insert(path.clone());
+        self.cycles.push(path);
+        true
+    }
+
+    /// Re-score the candidate cycles that trade through any of `edges`
+    /// against the graph's current rates, best net profit first. Cycles
+    /// with an edge that has left the graph are skipped.
+    pub fn rescore(&self, graph: &ArbitrageGraph, edges: &[EdgeKey]) -> Vec<ArbitrageCycle> {
+        let mut affected: Vec<usize> = edges.iter()
+            .filter_map(|edge| self.by_edge.get(edge))
+            .flatten()
+            .copied()
+            .collect();
+        affected.sort_unstable();
+        affected.dedup();
+
+        let mut cycles: Vec<ArbitrageCycle> = affected.into_iter()
+            .filter_map(|id| graph.cycle_through(&self.cycles[id]))
+            .collect();
+        cycles.sort_by(|a, b| b.net_profit_after_fees.total_cmp(&a.net_profit_after_fees));
+        cycles
+    }
+
+    /// Whether edges were added to or removed from the graph since the
+    /// last rebuild (always true before the first one)
+    pub fn is_stale(&self, graph: &ArbitrageGraph) -> bool {
+        self.topology_version != Some(graph.topology_version())
+    }
+
+    /// Number of candidate cycles
+    pub fn len(&self) -> usize {
+        self.cycles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cycles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::triangular_arb::{DexType, ExchangeEdge};
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn edge(from: u8, to: u8, pool: u8, rate: f64) -> ExchangeEdge {
+        ExchangeEdge::new(
+            create_test_pubkey(from), create_test_pubkey(to), DexType::Raydium, create_test_pubkey(pool),
+            rate, 25, vec![], 1000,
+        )
+    }
+
+    fn key(from: u8, to: u8, pool: u8) -> EdgeKey {
+        (create_test_pubkey(from), create_test_pubkey(to), create_test_pubkey(pool))
+    }
+
+    #[test]
+    fn test_update_rescores_only_cycles_through_the_edge() {
+        let mut graph = ArbitrageGraph::new();
+        // Triangle 1 -> 2 -> 3 -> 1 and pair 1 <-> 4, both break-even or worse
+        graph.add_edge(edge(1, 2, 101, 1.0));
+        graph.add_edge(edge(2, 3, 102, 1.0));
+        graph.add_edge(edge(3, 1, 103, 1.0));
+        graph.add_edge(edge(1, 4, 104, 2.0));
+        graph.add_edge(edge(4, 1, 105, 0.5));
+
+        let mut index = CycleIndex::new(4, 1_000);
+        assert!(index.is_stale(&graph));
+        index.rebuild(&graph, &[create_test_pubkey(1), create_test_pubkey(3)]);
+        assert!(!index.is_stale(&graph));
+        // The triangle is indexed once, from the first start token
+        assert_eq!(index.len(), 2);
+
+        // A rate change makes the triangle profitable; the pair is not touched
+        graph.update_edge_rate(create_test_pubkey(3), create_test_pubkey(1), create_test_pubkey(103), 1.1, 1001).unwrap();
+        assert!(!index.is_stale(&graph));
+
+        let rescored = index.rescore(&graph, &[key(3, 1, 103), key(1, 3, 103)]);
+        assert_eq!(rescored.len(), 1);
+        assert_eq!(rescored[0].start_token, create_test_pubkey(1));
+        assert_eq!(rescored[0].path.len(), 3);
+        assert!(rescored[0].net_profit_after_fees > 0.0);
+
+        assert!(index.rescore(&graph, &[key(9, 9, 199)]).is_empty());
+    }
+
+    #[test]
+    fn test_topology_change_makes_index_stale() {
+        let mut graph = ArbitrageGraph::new();
+        graph.add_edge(edge(1, 2, 101, 1.0));
+        graph.add_edge(edge(2, 1, 102, 1.0));
+
+        let mut index = CycleIndex::new(3, 1_000);
+        index.rebuild(&graph, &[create_test_pubkey(1)]);
+        assert_eq!(index.len(), 1);
+
+        graph.add_edge(edge(2, 1, 103, 1.0));
+        assert!(index.is_stale(&graph));
+        index.rebuild(&graph, &[create_test_pubkey(1)]);
+        assert_eq!(index.len(), 2);
+
+        // Removing a pool drops its cycles from re-scoring before the rebuild
+        graph.remove_pool(&create_test_pubkey(103));
+        assert!(index.is_stale(&graph));
+        assert_eq!(index.rescore(&graph, &[key(1, 2, 101)]).len(), 1);
+    }
+}
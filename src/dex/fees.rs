@@ -0,0 +1,178 @@
+// Pool Fee Models
+//
+// The fee a pool charges is decoded from the pool (or its config account),
+// never assumed per venue: Raydium AMM stores a numerator/denominator,
+// Whirlpool and Raydium CPMM/CLMM a rate in millionths, Meteora DLMM a base
+// fee plus a variable fee that follows the pair's volatility. Rates are kept
+// in billionths (DLMM's precision, which every other venue's rate divides
+// exactly), so the graph weights and cycle profits use the rate the program
+// applies rather than a rounded basis-point value.
+//
+// The protocol's cut is carried as a share of the trade fee. On every
+// supported venue it is paid out of the trade fee, so it does not change what
+// a trader is charged.
+
+/// Denominator of `FeeRate` (billionths)
+pub const FEE_RATE_PRECISION: u64 = 1_000_000_000;
+
+/// A fraction in billionths, at most 1
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeeRate(u64);
+
+impl FeeRate {
+    pub const ZERO: FeeRate = FeeRate(0);
+    pub const ONE: FeeRate = FeeRate(FEE_RATE_PRECISION);
+
+    /// Rate of `billionths` / 1e9 (capped at 1)
+    pub fn from_billionths(billionths: u64) -> Self {
+        Self(billionths.min(FEE_RATE_PRECISION))
+    }
+
+    /// Rate of `numerator` / `denominator`, rounded up; zero when the
+    /// denominator is zero (an unset fee)
+    pub fn from_fraction(numerator: u128, denominator: u128) -> Self {
+        if denominator == 0 {
+            return Self::ZERO;
+        }
+        let billionths = (numerator * FEE_RATE_PRECISION as u128).div_ceil(denominator);
+        Self::from_billionths(billionths.min(FEE_RATE_PRECISION as u128) as u64)
+    }
+
+    /// Rate of `bps` basis points
+    pub fn from_bps(bps: u64) -> Self {
+        Self::from_fraction(bps as u128, 10_000)
+    }
+
+    pub fn billionths(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / FEE_RATE_PRECISION as f64
+    }
+
+    /// Basis points, rounded up so a fee is never understated
+    pub fn bps(self) -> u16 {
+        (self.0 as u128 * 10_000).div_ceil(FEE_RATE_PRECISION as u128) as u16
+    }
+
+    /// Sum of two rates (capped at 1)
+    pub fn saturating_add(self, other: FeeRate) -> Self {
+        Self::from_billionths(self.0.saturating_add(other.0))
+    }
+
+    /// `share` of this rate, rounded down
+    pub fn share(self, share: FeeRate) -> Self {
+        Self((self.0 as u128 * share.0 as u128 / FEE_RATE_PRECISION as u128) as u64)
+    }
+}
+
+/// Fee a pool charges on the input of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeModel {
+    /// Rate fixed by the pool or its config account
+    Static {
+        trade: FeeRate,
+        /// Share of the trade fee kept by the protocol
+        protocol_share: FeeRate,
+    },
+    /// Base rate plus a rate driven by pool state (Meteora DLMM volatility),
+    /// capped at `max`. `variable` is the value for the state the model was
+    /// built from; the model is rebuilt on every account update.
+    Dynamic {
+        base: FeeRate,
+        variable: FeeRate,
+        max: FeeRate,
+        /// Share of the trade fee kept by the protocol
+        protocol_share: FeeRate,
+    },
+}
+
+impl FeeModel {
+    /// Fixed fee of `bps` basis points with no protocol share, for pools
+    /// whose venue does not expose its fee structure
+    pub fn from_bps(bps: u16) -> Self {
+        Self::Static { trade: FeeRate::from_bps(bps as u64), protocol_share: FeeRate::ZERO }
+    }
+
+    /// Rate a swap is charged in the current state
+    pub fn trade_fee(&self) -> FeeRate {
+        match *self {
+            FeeModel::Static { trade, .. } => trade,
+            FeeModel::Dynamic { base, variable, max, .. } => base.saturating_add(variable).min(max),
+        }
+    }
+
+    /// Part of the input paid to the protocol rather than to LPs
+    pub fn protocol_fee(&self) -> FeeRate {
+        let share = match *self {
+            FeeModel::Static { protocol_share, .. } | FeeModel::Dynamic { protocol_share, .. } => protocol_share,
+        };
+        self.trade_fee().share(share)
+    }
+
+    /// Fraction of the input left after the fee
+    pub fn multiplier(&self) -> f64 {
+        1.0 - self.trade_fee().as_f64()
+    }
+
+    /// Trade fee in basis points (rounded up)
+    pub fn bps(&self) -> u16 {
+        self.trade_fee().bps()
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, FeeModel::Dynamic { .. })
+    }
+}
+
+impl Default for FeeModel {
+    fn default() -> Self {
+        Self::from_bps(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rate_conversions() {
+        // Raydium AMM 25/10000, Whirlpool 3000 millionths, a 1 bps DLMM base fee
+        assert_eq!(FeeRate::from_fraction(25, 10_000).billionths(), 2_500_000);
+        assert_eq!(FeeRate::from_fraction(3_000, 1_000_000).bps(), 30);
+        assert_eq!(FeeRate::from_billionths(100_000).bps(), 1);
+
+        // Sub-basis-point tiers are kept exactly and round up when summarised
+        let tier = FeeRate::from_fraction(150, 1_000_000);
+        assert_eq!(tier.billionths(), 150_000);
+        assert_eq!(tier.bps(), 2);
+
+        assert_eq!(FeeRate::from_fraction(5, 0), FeeRate::ZERO);
+        assert_eq!(FeeRate::from_fraction(3, 2).billionths(), FEE_RATE_PRECISION);
+    }
+
+    #[test]
+    fn test_dynamic_fee_is_capped() {
+        let model = FeeModel::Dynamic {
+            base: FeeRate::from_bps(10),
+            variable: FeeRate::from_bps(15),
+            max: FeeRate::from_bps(1_000),
+            protocol_share: FeeRate::from_bps(500),
+        };
+        assert_eq!(model.bps(), 25);
+        assert_eq!(model.protocol_fee(), FeeRate::from_fraction(125, 1_000_000));
+        assert!((model.multiplier() - 0.9975).abs() < 1e-12);
+
+        let capped = FeeModel::Dynamic {
+            base: FeeRate::from_bps(10),
+            variable: FeeRate::from_bps(5_000),
+            max: FeeRate::from_bps(1_000),
+            protocol_share: FeeRate::ZERO,
+        };
+        assert_eq!(capped.bps(), 1_000);
+
+        assert_eq!(FeeModel::from_bps(25).trade_fee(), FeeRate::from_bps(25));
+        assert_eq!(FeeModel::from_bps(25).protocol_fee(), FeeRate::ZERO);
+    }
+}
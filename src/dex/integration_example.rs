@@ -11,13 +11,13 @@ use std::str::FromStr;
 
 use crate::dex::pool_fetcher::{PoolDataFetcher, PoolData};
 use crate::dex::triangular_arb::{
-    create_shared_graph, ExchangeEdge, BellmanFordDetector
+    create_shared_graph, BellmanFordDetector
 };
 
 /// Example: Fetch pools and populate arbitrage graph
 pub async fn fetch_and_populate_graph_example() -> Result<()> {
     // Step 1: Initialize RPC clients with failover
-    let rpc_urls = vec![
+    let rpc_urls = [
         "http://localhost:8899",  // Local fork
         "https://api.mainnet-beta.solana.com",
         "https://api.devnet.solana.com",
@@ -50,38 +50,13 @@ pub async fn fetch_and_populate_graph_example() -> Result<()> {
     // Step 6: Populate graph with pool data
     {
         let mut g = graph.write().unwrap();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
         for pool in &pools {
-            // Add edge: token A -> token B
-            let edge_a_to_b = ExchangeEdge::new(
-                pool.token_a,
-                pool.token_b,
-                pool.dex_type.clone(),
-                pool.pool_address,
-                pool.calculate_rate_a_to_b(),
-                pool.fee_bps,
-                vec![], // Liquidity depth can be added later
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs() as i64,
-            );
-            g.add_edge(edge_a_to_b);
-            
-            // Add edge: token B -> token A
-            let edge_b_to_a = ExchangeEdge::new(
-                pool.token_b,
-                pool.token_a,
-                pool.dex_type.clone(),
-                pool.pool_address,
-                pool.calculate_rate_b_to_a(),
-                pool.fee_bps,
-                vec![],
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs() as i64,
-            );
-            g.add_edge(edge_b_to_a);
+            // Add both directions using the pool's venue adapter
+            g.upsert_pool(fetcher.registry(), pool, timestamp)?;
         }
         
         println!("Graph populated: {} tokens, {} edges", 
@@ -142,21 +117,7 @@ pub async fn continuous_monitoring_example() -> Result<()> {
                                 .unwrap()
                                 .as_secs() as i64;
                             
-                            let _ = g.update_edge_rate(
-                                pool.token_a,
-                                pool.token_b,
-                                pool.dex_type.clone(),
-                                pool.calculate_rate_a_to_b(),
-                                timestamp,
-                            );
-                            
-                            let _ = g.update_edge_rate(
-                                pool.token_b,
-                                pool.token_a,
-                                pool.dex_type.clone(),
-                                pool.calculate_rate_b_to_a(),
-                                timestamp,
-                            );
+                            let _ = g.upsert_pool(fetcher.registry(), pool, timestamp);
                         }
                         
                         println!("Updated {} pools", pools.len());
@@ -258,6 +219,9 @@ mod tests {
             fee_bps: 30,
             dex_type: crate::dex::triangular_arb::DexType::Raydium,
             program_id: Pubkey::new_unique(),
+            venue: crate::dex::adapter::VenueState::None,
+            mints: Default::default(),
+            slot: 0,
         };
         
         let optimal = calculate_optimal_trade_size_example(&pool);
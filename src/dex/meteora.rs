@@ -1,14 +1,754 @@
-// Placeholder for Meteora DEX integration
-// TODO: Implement actual Meteora DLMM and Pools operations
+// Meteora DAMM Adapter
+//
+// Decodes Meteora DAMM (Dynamic AMM) pools. A DAMM pool does not hold its
+// reserves: each side is deposited in a Meteora (Mercurial) Vault and the
+// pool only owns vault LP tokens. The vaults, their LP mints and token
+// accounts, and the pool's LP token accounts are loaded as dependent
+// accounts; a side's reserve is the vault's unlocked amount scaled by the
+// pool's share of the vault LP supply.
+//
+// Quotes reproduce the program's `compute_quote`: the protocol fee is cut
+// from the trade fee and kept out of the vault, the rest of the input is
+// deposited into the input vault, the constant-product curve runs on the
+// token totals, and the output is withdrawn from the output vault. Pools are
+// tradable only once both vaults are loaded; stable-curve pools are not
+// supported.
 
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{Result, anyhow};
+use tracing::debug;
 
-pub struct MeteoraClient {
+use crate::dex::adapter::{read_pubkey, read_u64, token_account_amount, DexAdapter, MintLayout, SwapParams, VenueState};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::orca::{calculate_fee, ceil_div};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::DexType;
+
+/// Meteora DAMM program id
+pub const METEORA_DAMM_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB");
+
+/// Meteora Vault program id (holds the reserves of DAMM pools)
+pub const METEORA_VAULT_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG3LYwBpyTi");
+
+/// Anchor discriminator of the DAMM `swap` instruction
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Offset of the token A mint in a DAMM pool account (after the 8-byte
+/// discriminator and the LP mint)
+pub(crate) const DAMM_TOKEN_A_MINT_OFFSET: usize = 40;
+
+/// Offset of the token B mint in a DAMM pool account
+pub(crate) const DAMM_TOKEN_B_MINT_OFFSET: usize = 72;
+
+/// Offset of the curve type tag in a DAMM pool account
+const DAMM_CURVE_TYPE_OFFSET: usize = 874;
+
+/// Smallest DAMM pool account holding every decoded field
+pub const DAMM_POOL_MIN_LEN: usize = DAMM_CURVE_TYPE_OFFSET + 1;
+
+/// Size of a Meteora Vault account
+pub const VAULT_LEN: usize = 1227;
+
+/// DAMM curve types
+pub const CURVE_CONSTANT_PRODUCT: u8 = 0;
+pub const CURVE_STABLE: u8 = 1;
+
+/// Denominator of `LockedProfitTracker::locked_profit_degradation`
+const LOCKED_PROFIT_DEGRADATION_DENOMINATOR: u128 = 1_000_000_000_000;
+
+/// Fee fractions stored in the pool account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DammFees {
+    /// Fee taken from the input
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    /// Protocol's cut of the trade fee
+    pub protocol_trade_fee_numerator: u64,
+    pub protocol_trade_fee_denominator: u64,
+}
+
+impl DammFees {
+    pub fn fee_model(&self) -> FeeModel {
+        FeeModel::Static {
+            trade: FeeRate::from_fraction(self.trade_fee_numerator as u128, self.trade_fee_denominator as u128),
+            protocol_share: FeeRate::from_fraction(
+                self.protocol_trade_fee_numerator as u128,
+                self.protocol_trade_fee_denominator as u128,
+            ),
+        }
+    }
+}
+
+/// DAMM `Pool` account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DammPool {
+    pub lp_mint: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub a_vault: Pubkey,
+    pub b_vault: Pubkey,
+    /// Pool's token accounts of the vault LP tokens
+    pub a_vault_lp: Pubkey,
+    pub b_vault_lp: Pubkey,
+    pub enabled: bool,
+    /// Token accounts receiving the protocol fee of each input token
+    pub protocol_token_a_fee: Pubkey,
+    pub protocol_token_b_fee: Pubkey,
+    pub fees: DammFees,
+    pub curve_type: u8,
+}
+
+impl DammPool {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: dynamic-amm `state.rs` (`Pool`, `PoolFees`, `CurveType`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < DAMM_POOL_MIN_LEN {
+            return Err(anyhow!("Invalid Meteora DAMM account size: {} bytes", data.len()));
+        }
+
+        Ok(Self {
+            lp_mint: read_pubkey(data, 8)?,
+            token_a_mint: read_pubkey(data, DAMM_TOKEN_A_MINT_OFFSET)?,
+            token_b_mint: read_pubkey(data, DAMM_TOKEN_B_MINT_OFFSET)?,
+            a_vault: read_pubkey(data, 104)?,
+            b_vault: read_pubkey(data, 136)?,
+            a_vault_lp: read_pubkey(data, 168)?,
+            b_vault_lp: read_pubkey(data, 200)?,
+            enabled: data[233] != 0,
+            protocol_token_a_fee: read_pubkey(data, 234)?,
+            protocol_token_b_fee: read_pubkey(data, 266)?,
+            fees: DammFees {
+                trade_fee_numerator: read_u64(data, 330)?,
+                trade_fee_denominator: read_u64(data, 338)?,
+                protocol_trade_fee_numerator: read_u64(data, 346)?,
+                protocol_trade_fee_denominator: read_u64(data, 354)?,
+            },
+            curve_type: data[DAMM_CURVE_TYPE_OFFSET],
+        })
+    }
+}
+
+/// Profit of a vault that is still being released to depositors
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockedProfitTracker {
+    pub last_updated_locked_profit: u64,
+    pub last_report: u64,
+    /// Share of the locked profit released per second, in 1e-12
+    pub locked_profit_degradation: u64,
+}
+
+impl LockedProfitTracker {
+    /// Profit still locked at `current_time`
+    pub fn locked_profit(&self, current_time: u64) -> u64 {
+        let duration = current_time.saturating_sub(self.last_report) as u128;
+        let ratio = duration * self.locked_profit_degradation as u128;
+        if ratio > LOCKED_PROFIT_DEGRADATION_DENOMINATOR {
+            return 0;
+        }
+        (self.last_updated_locked_profit as u128 * (LOCKED_PROFIT_DEGRADATION_DENOMINATOR - ratio)
+            / LOCKED_PROFIT_DEGRADATION_DENOMINATOR) as u64
+    }
+}
+
+/// Meteora Vault account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vault {
+    pub enabled: bool,
+    /// Tokens held by the vault and its strategies
+    pub total_amount: u64,
+    /// Token account the vault pays withdrawals from
+    pub token_vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub locked_profit_tracker: LockedProfitTracker,
+}
+
+impl Vault {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: mercurial-vault `state.rs` (`Vault`, `LockedProfitTracker`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < VAULT_LEN {
+            return Err(anyhow!("Invalid Meteora Vault account size: {} bytes", data.len()));
+        }
+
+        Ok(Self {
+            enabled: data[8] != 0,
+            total_amount: read_u64(data, 11)?,
+            token_vault: read_pubkey(data, 19)?,
+            token_mint: read_pubkey(data, 83)?,
+            lp_mint: read_pubkey(data, 115)?,
+            locked_profit_tracker: LockedProfitTracker {
+                last_updated_locked_profit: read_u64(data, 1203)?,
+                last_report: read_u64(data, 1211)?,
+                locked_profit_degradation: read_u64(data, 1219)?,
+            },
+        })
+    }
+
+    /// Tokens withdrawable at `current_time` (locked profit excluded)
+    pub fn unlocked_amount(&self, current_time: u64) -> Result<u64> {
+        self.total_amount
+            .checked_sub(self.locked_profit_tracker.locked_profit(current_time))
+            .ok_or_else(|| anyhow!("Vault locked profit exceeds its total amount"))
+    }
+
+    /// Tokens backing `share` of `total_supply` vault LP tokens
+    pub fn amount_by_share(&self, current_time: u64, share: u64, total_supply: u64) -> Result<u64> {
+        if total_supply == 0 {
+            return Ok(0);
+        }
+        let amount = share as u128 * self.unlocked_amount(current_time)? as u128 / total_supply as u128;
+        u64::try_from(amount).map_err(|_| anyhow!("Vault amount overflows u64"))
+    }
+
+    /// Vault LP tokens worth `amount` tokens
+    pub fn unmint_amount(&self, current_time: u64, amount: u64, lp_supply: u64) -> Result<u64> {
+        let unlocked = self.unlocked_amount(current_time)?;
+        if unlocked == 0 {
+            return Err(anyhow!("Vault has no unlocked tokens"));
+        }
+        let lp = amount as u128 * lp_supply as u128 / unlocked as u128;
+        u64::try_from(lp).map_err(|_| anyhow!("Vault LP amount overflows u64"))
+    }
+}
+
+/// One side of a DAMM pool: its vault and the pool's position in it
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultReserve {
+    pub vault: Vault,
+    /// Vault LP tokens held by the pool
+    pub pool_lp_amount: u64,
+    /// Supply of the vault LP mint
+    pub lp_supply: u64,
+    /// Balance of the vault's token account
+    pub token_vault_amount: u64,
+}
+
+impl VaultReserve {
+    /// Tokens of this side owned by the pool
+    pub fn pool_amount(&self, current_time: u64) -> Result<u64> {
+        self.vault.amount_by_share(current_time, self.pool_lp_amount, self.lp_supply)
+    }
+}
+
+/// Decoded DAMM pool with its vaults, once loaded
+#[derive(Clone, Debug)]
+pub struct DammPoolState {
+    pub pool: DammPool,
+    /// Vault side of token A and token B
+    pub vaults: Option<(VaultReserve, VaultReserve)>,
+}
+
+impl DammPoolState {
+    /// Exact output of `compute_quote` at `current_time`
+    pub fn quote_exact_in(&self, amount_in: u64, a_to_b: bool, current_time: u64) -> Result<u64> {
+        if self.pool.curve_type != CURVE_CONSTANT_PRODUCT {
+            return Err(anyhow!("Unsupported DAMM curve type {}", self.pool.curve_type));
+        }
+        let (a, b) = self.vaults.as_ref()
+            .ok_or_else(|| anyhow!("DAMM vaults are not loaded"))?;
+        let (input, output) = if a_to_b { (a, b) } else { (b, a) };
+        let fees = &self.pool.fees;
+
+        // The protocol fee is cut from the trade fee and never deposited
+        let trade_fee = calculate_fee(amount_in as u128, fees.trade_fee_numerator, fees.trade_fee_denominator)?;
+        let protocol_fee = calculate_fee(
+            trade_fee,
+            fees.protocol_trade_fee_numerator,
+            fees.protocol_trade_fee_denominator,
+        )?;
+        let trade_fee = (trade_fee - protocol_fee) as u64;
+        let in_amount_after_protocol_fee = amount_in
+            .checked_sub(protocol_fee as u64)
+            .ok_or_else(|| anyhow!("Swap amount {} does not cover fees", amount_in))?;
+
+        // Deposit into the input vault; the pool gains what its new LP is worth
+        let before_in_total = input.pool_amount(current_time)?;
+        let in_lp = input.vault.unmint_amount(current_time, in_amount_after_protocol_fee, input.lp_supply)?;
+        let mut in_vault = input.vault.clone();
+        in_vault.total_amount = in_vault.total_amount
+            .checked_add(in_amount_after_protocol_fee)
+            .ok_or_else(|| anyhow!("Vault total amount overflows u64"))?;
+        let after_in_total = in_vault.amount_by_share(
+            current_time,
+            input.pool_lp_amount + in_lp,
+            input.lp_supply + in_lp,
+        )?;
+        let actual_in = after_in_total.saturating_sub(before_in_total);
+        let actual_in_after_fee = actual_in
+            .checked_sub(trade_fee)
+            .ok_or_else(|| anyhow!("Swap amount {} does not cover fees", amount_in))?;
+
+        // Constant product on the token totals
+        let out_total = output.pool_amount(current_time)?;
+        if before_in_total == 0 || out_total == 0 {
+            return Err(anyhow!("Pool has empty reserves"));
+        }
+        let invariant = before_in_total as u128 * out_total as u128;
+        let new_out_total = ceil_div(invariant, before_in_total as u128 + actual_in_after_fee as u128);
+        let swapped = (out_total as u128).saturating_sub(new_out_total) as u64;
+
+        // Withdraw from the output vault, rounded through its LP
+        let out_lp = output.vault.unmint_amount(current_time, swapped, output.lp_supply)?;
+        let out_amount = output.vault.amount_by_share(current_time, out_lp, output.lp_supply)?;
+        if out_amount >= output.token_vault_amount {
+            return Err(anyhow!(
+                "Output {} exceeds the vault's liquid balance {}",
+                out_amount, output.token_vault_amount
+            ));
+        }
+
+        Ok(out_amount)
+    }
+}
+
+/// Vault token account PDA: `[b"token_vault", vault]`
+pub fn vault_token_address(vault: &Pubkey, vault_program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token_vault", vault.as_ref()], vault_program_id).0
+}
+
+/// Vault LP mint PDA: `[b"lp_mint", vault]`
+pub fn vault_lp_mint_address(vault: &Pubkey, vault_program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"lp_mint", vault.as_ref()], vault_program_id).0
+}
+
+/// Supply of an SPL mint account
+fn mint_supply(account: &Account) -> Result<u64> {
+    read_u64(&account.data, 36)
+}
+
+fn current_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+pub struct MeteoraDammAdapter {
     program_id: Pubkey,
+    vault_program_id: Pubkey,
 }
 
-impl MeteoraClient {
+impl MeteoraDammAdapter {
     pub fn new(program_id: Pubkey) -> Self {
-        Self { program_id }
+        Self { program_id, vault_program_id: METEORA_VAULT_PROGRAM_ID }
+    }
+
+    /// Use a custom vault program id (e.g. from configuration)
+    pub fn with_vault_program(mut self, vault_program_id: Pubkey) -> Self {
+        self.vault_program_id = vault_program_id;
+        self
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a DammPoolState> {
+        match &pool.venue {
+            VenueState::MeteoraDamm(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Meteora DAMM state", pool.pool_address)),
+        }
+    }
+
+    /// Vault, vault token account, vault LP mint and pool LP account of one side
+    fn side_accounts(&self, vault: &Pubkey, pool_lp: &Pubkey) -> [Pubkey; 4] {
+        [
+            *vault,
+            vault_token_address(vault, &self.vault_program_id),
+            vault_lp_mint_address(vault, &self.vault_program_id),
+            *pool_lp,
+        ]
+    }
+
+    fn load_side(&self, pool: &Pubkey, vault: &Pubkey, pool_lp: &Pubkey, accounts: &HashMap<Pubkey, Account>) -> Result<VaultReserve> {
+        let [vault_address, token_vault, lp_mint, pool_lp] = self.side_accounts(vault, pool_lp);
+        let account = |address: &Pubkey| accounts.get(address)
+            .ok_or_else(|| anyhow!("Missing account {} for DAMM pool {}", address, pool));
+
+        let vault = Vault::decode(&account(&vault_address)?.data)?;
+        if vault.token_vault != token_vault || vault.lp_mint != lp_mint {
+            return Err(anyhow!("Vault {} does not use its PDA token account and LP mint", vault_address));
+        }
+
+        Ok(VaultReserve {
+            pool_lp_amount: token_account_amount(account(&pool_lp)?)?,
+            lp_supply: mint_supply(account(&lp_mint)?)?,
+            token_vault_amount: token_account_amount(account(&token_vault)?)?,
+            vault,
+        })
+    }
+}
+
+impl Default for MeteoraDammAdapter {
+    fn default() -> Self {
+        Self::new(METEORA_DAMM_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for MeteoraDammAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::Meteora
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        // Same offsets as `decode_pool`
+        Some(MintLayout {
+            data_size: None,
+            mint_a_offset: DAMM_TOKEN_A_MINT_OFFSET,
+            mint_b_offset: DAMM_TOKEN_B_MINT_OFFSET,
+        })
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let pool = DammPool::decode(data)?;
+        let fee_bps = pool.fees.fee_model().bps();
+
+        debug!(
+            "Parsed Meteora DAMM pool {}: {} / {}, vaults=({}, {}), fee={} bps, curve={}",
+            address, pool.token_a_mint, pool.token_b_mint, pool.a_vault, pool.b_vault, fee_bps, pool.curve_type
+        );
+
+        // Reserves are filled from the vaults in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: pool.token_a_mint,
+            token_b: pool.token_b_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps,
+            dex_type: DexType::Meteora,
+            program_id: self.program_id,
+            venue: VenueState::MeteoraDamm(Box::new(DammPoolState { pool, vaults: None })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        match self.state(pool) {
+            Ok(state) => {
+                let pool = &state.pool;
+                let mut accounts = self.side_accounts(&pool.a_vault, &pool.a_vault_lp).to_vec();
+                accounts.extend(self.side_accounts(&pool.b_vault, &pool.b_vault_lp));
+                accounts
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let damm = &self.state(pool)?.pool;
+        let a = self.load_side(&address, &damm.a_vault, &damm.a_vault_lp, accounts)?;
+        let b = self.load_side(&address, &damm.b_vault, &damm.b_vault_lp, accounts)?;
+
+        let now = current_time();
+        let reserve_a = a.pool_amount(now)?;
+        let reserve_b = b.pool_amount(now)?;
+
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+        if let VenueState::MeteoraDamm(state) = &mut pool.venue {
+            state.vaults = Some((a, b));
+        }
+
+        debug!("Meteora DAMM pool {} reserves: ({}, {})", address, reserve_a, reserve_b);
+
+        Ok(())
+    }
+
+    fn is_tradable(&self, pool: &PoolData) -> bool {
+        // Only constant-product pools whose vaults are loaded and enabled
+        match self.state(pool) {
+            Ok(state) => {
+                state.pool.enabled
+                    && state.pool.curve_type == CURVE_CONSTANT_PRODUCT
+                    && state.vaults.as_ref().is_some_and(|(a, b)| a.vault.enabled && b.vault.enabled)
+                    && pool.reserve_a > 0
+                    && pool.reserve_b > 0
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        self.state(pool)?.quote_exact_in(amount_in, a_to_b, current_time())
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(state) => state.pool.fees.fee_model(),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let state = self.state(pool)?;
+        let (a, b) = state.vaults.as_ref()
+            .ok_or_else(|| anyhow!("Vaults not loaded for Meteora DAMM pool {}", pool.pool_address))?;
+        let damm = &state.pool;
+        let protocol_token_fee = if params.a_to_b {
+            damm.protocol_token_a_fee
+        } else {
+            damm.protocol_token_b_fee
+        };
+
+        Ok(vec![
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new(params.user_source, false),
+            AccountMeta::new(params.user_destination, false),
+            AccountMeta::new(damm.a_vault, false),
+            AccountMeta::new(damm.b_vault, false),
+            AccountMeta::new(a.vault.token_vault, false),
+            AccountMeta::new(b.vault.token_vault, false),
+            AccountMeta::new(a.vault.lp_mint, false),
+            AccountMeta::new(b.vault.lp_mint, false),
+            AccountMeta::new(damm.a_vault_lp, false),
+            AccountMeta::new(damm.b_vault_lp, false),
+            AccountMeta::new(protocol_token_fee, false),
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new_readonly(self.vault_program_id, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ])
+    }
+
+    fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::adapter::DexRegistry;
+
+    fn pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    /// Constant-product pool: mints 2/3, vaults 4/5, pool vault LP accounts
+    /// 6/7, protocol fee accounts 8/9, 25 bps trade fee of which 20% protocol
+    fn pool_account_data(curve_type: u8) -> Vec<u8> {
+        let mut data = vec![0u8; DAMM_POOL_MIN_LEN];
+        for (offset, seed) in [(8, 1u8), (40, 2), (72, 3), (104, 4), (136, 5), (168, 6), (200, 7), (234, 8), (266, 9)] {
+            data[offset..offset + 32].copy_from_slice(pubkey(seed).as_ref());
+        }
+        data[233] = 1;
+        for (i, value) in [25u64, 10_000, 20, 100].iter().enumerate() {
+            data[330 + i * 8..338 + i * 8].copy_from_slice(&value.to_le_bytes());
+        }
+        data[DAMM_CURVE_TYPE_OFFSET] = curve_type;
+        data
+    }
+
+    fn vault_account(vault: &Pubkey, token_mint: &Pubkey, total_amount: u64, tracker: &LockedProfitTracker) -> Account {
+        let mut data = vec![0u8; VAULT_LEN];
+        data[8] = 1;
+        data[11..19].copy_from_slice(&total_amount.to_le_bytes());
+        data[19..51].copy_from_slice(vault_token_address(vault, &METEORA_VAULT_PROGRAM_ID).as_ref());
+        data[83..115].copy_from_slice(token_mint.as_ref());
+        data[115..147].copy_from_slice(vault_lp_mint_address(vault, &METEORA_VAULT_PROGRAM_ID).as_ref());
+        data[1203..1211].copy_from_slice(&tracker.last_updated_locked_profit.to_le_bytes());
+        data[1211..1219].copy_from_slice(&tracker.last_report.to_le_bytes());
+        data[1219..1227].copy_from_slice(&tracker.locked_profit_degradation.to_le_bytes());
+        Account { data, ..Default::default() }
+    }
+
+    fn mint_account(supply: u64) -> Account {
+        let mut data = vec![0u8; 82];
+        data[36..44].copy_from_slice(&supply.to_le_bytes());
+        Account { data, ..Default::default() }
+    }
+
+    fn token_account(amount: u64) -> Account {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Account { data, ..Default::default() }
+    }
+
+    /// Vault A: 1e9 tokens over 5e8 LP, the pool holds half (5e8 tokens).
+    /// Vault B: 4e9 tokens over 4e9 LP, the pool holds half (2e9 tokens).
+    fn vault_accounts() -> HashMap<Pubkey, Account> {
+        let unlocked = LockedProfitTracker::default();
+        let side = |vault: Pubkey, mint: Pubkey, total: u64, supply: u64, pool_lp: (Pubkey, u64), liquid: u64| {
+            vec![
+                (vault, vault_account(&vault, &mint, total, &unlocked)),
+                (vault_token_address(&vault, &METEORA_VAULT_PROGRAM_ID), token_account(liquid)),
+                (vault_lp_mint_address(&vault, &METEORA_VAULT_PROGRAM_ID), mint_account(supply)),
+                (pool_lp.0, token_account(pool_lp.1)),
+            ]
+        };
+        side(pubkey(4), pubkey(2), 1_000_000_000, 500_000_000, (pubkey(6), 250_000_000), 600_000_000)
+            .into_iter()
+            .chain(side(pubkey(5), pubkey(3), 4_000_000_000, 4_000_000_000, (pubkey(7), 2_000_000_000), 3_000_000_000))
+            .collect()
+    }
+
+    fn loaded_pool() -> PoolData {
+        let adapter = MeteoraDammAdapter::default();
+        let mut pool = adapter.decode_pool(&pubkey(1), &pool_account_data(CURVE_CONSTANT_PRODUCT)).unwrap();
+        adapter.apply_dependent_accounts(&mut pool, &vault_accounts()).unwrap();
+        pool
+    }
+
+    fn swap_params(a_to_b: bool) -> SwapParams {
+        SwapParams {
+            user: pubkey(200),
+            user_source: pubkey(201),
+            user_destination: pubkey(202),
+            amount_in: 1_000_000,
+            minimum_out: 3_900_000,
+            a_to_b,
+        }
+    }
+
+    #[test]
+    fn test_damm_pool_decode() {
+        let pool = DammPool::decode(&pool_account_data(CURVE_CONSTANT_PRODUCT)).unwrap();
+        assert_eq!(pool.lp_mint, pubkey(1));
+        assert_eq!((pool.token_a_mint, pool.token_b_mint), (pubkey(2), pubkey(3)));
+        assert_eq!((pool.a_vault, pool.b_vault), (pubkey(4), pubkey(5)));
+        assert_eq!((pool.a_vault_lp, pool.b_vault_lp), (pubkey(6), pubkey(7)));
+        assert_eq!((pool.protocol_token_a_fee, pool.protocol_token_b_fee), (pubkey(8), pubkey(9)));
+        assert!(pool.enabled);
+        assert_eq!(pool.fees.fee_model().bps(), 25);
+        assert_eq!(pool.fees.fee_model().protocol_fee(), FeeRate::from_fraction(5, 10_000));
+        assert_eq!(pool.curve_type, CURVE_CONSTANT_PRODUCT);
+
+        assert!(DammPool::decode(&[0u8; 500]).is_err());
+    }
+
+    #[test]
+    fn test_mint_layout_matches_pool_account() {
+        // Pool account prefix: discriminator, lp_mint, token_a_mint,
+        // token_b_mint, a_vault, b_vault
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let mut data = vec![0u8; DAMM_POOL_MIN_LEN];
+        for (i, key) in keys.iter().enumerate() {
+            data[8 + 32 * i..40 + 32 * i].copy_from_slice(key.as_ref());
+        }
+
+        let adapter = MeteoraDammAdapter::default();
+        let layout = adapter.mint_layout().unwrap();
+        let pool = adapter.decode_pool(&Pubkey::new_unique(), &data).unwrap();
+
+        // Discovery filters match exactly the mints the decoder reads
+        assert_eq!(&data[layout.mint_a_offset..layout.mint_a_offset + 32], keys[1].as_ref());
+        assert_eq!(&data[layout.mint_b_offset..layout.mint_b_offset + 32], keys[2].as_ref());
+        assert_eq!((pool.token_a, pool.token_b), (keys[1], keys[2]));
+    }
+
+    #[test]
+    fn test_pool_stays_out_of_the_graph_until_vaults_load() {
+        let adapter = MeteoraDammAdapter::default();
+        let registry = DexRegistry::default();
+        let mut pool = adapter.decode_pool(&pubkey(1), &pool_account_data(CURVE_CONSTANT_PRODUCT)).unwrap();
+
+        assert!(!adapter.is_tradable(&pool));
+        assert!(registry.edges_for(&pool, 0).unwrap().is_empty());
+        assert!(adapter.build_swap_ix(&pool, &swap_params(true)).is_err());
+
+        let accounts = vault_accounts();
+        let mut expected: Vec<Pubkey> = accounts.keys().copied().collect();
+        let mut dependents = adapter.dependent_accounts(&pool);
+        expected.sort();
+        dependents.sort();
+        assert_eq!(dependents, expected);
+
+        // Reserves are the pool's share of each vault
+        adapter.apply_dependent_accounts(&mut pool, &accounts).unwrap();
+        assert_eq!((pool.reserve_a, pool.reserve_b), (500_000_000, 2_000_000_000));
+        assert!(adapter.is_tradable(&pool));
+        assert_eq!(registry.edges_for(&pool, 0).unwrap().len(), 2);
+
+        // Stable pools are decoded but never traded
+        let mut stable = adapter.decode_pool(&pubkey(1), &pool_account_data(CURVE_STABLE)).unwrap();
+        adapter.apply_dependent_accounts(&mut stable, &accounts).unwrap();
+        assert!(!adapter.is_tradable(&stable));
+    }
+
+    #[test]
+    fn test_locked_profit_is_released_linearly() {
+        // Fully released 1000 seconds after the report
+        let tracker = LockedProfitTracker {
+            last_updated_locked_profit: 1_000_000,
+            last_report: 100,
+            locked_profit_degradation: 1_000_000_000,
+        };
+        assert_eq!(tracker.locked_profit(100), 1_000_000);
+        assert_eq!(tracker.locked_profit(350), 750_000);
+        assert_eq!(tracker.locked_profit(1_200), 0);
+
+        let vault = Vault { total_amount: 10_000_000, locked_profit_tracker: tracker, ..Vault::default() };
+        assert_eq!(vault.unlocked_amount(350).unwrap(), 9_250_000);
+        // 1/10 of the LP supply is worth 1/10 of the unlocked amount
+        assert_eq!(vault.amount_by_share(350, 100, 1_000).unwrap(), 925_000);
+        assert_eq!(vault.unmint_amount(350, 925_000, 1_000).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_quote_matches_compute_quote() {
+        let pool = loaded_pool();
+        let VenueState::MeteoraDamm(state) = &pool.venue else { panic!("DAMM state expected") };
+
+        // 1e6 A in: trade fee 2500, protocol cut 500 (kept out of the vault),
+        // 999_500 deposited for 499_750 LP, worth 997_500 more to the pool;
+        // 995_500 after the 2000 LP fee, 2e9 - ceil(1e18 / 500_995_500) =
+        // 3_982_055 B, withdrawn 1:1 through vault B's LP
+        assert_eq!(state.quote_exact_in(1_000_000, true, 0).unwrap(), 3_982_055);
+
+        // 4e6 B in: the curve pays 995_513 A, but vault A's LP rounds the
+        // withdrawal down to 995_512
+        assert_eq!(state.quote_exact_in(4_000_000, false, 0).unwrap(), 995_512);
+        assert_eq!(MeteoraDammAdapter::default().quote_exact_in(&pool, 4_000_000, false).unwrap(), 995_512);
+
+        // Vault A has most of its tokens lent out: the withdrawal can't be paid
+        let mut drained = (**state).clone();
+        if let Some((reserve_a, _)) = drained.vaults.as_mut() {
+            reserve_a.token_vault_amount = 900_000;
+        }
+        assert!(drained.quote_exact_in(4_000_000, false, 0).is_err());
+    }
+
+    #[test]
+    fn test_swap_accounts() {
+        let adapter = MeteoraDammAdapter::default();
+        let pool = loaded_pool();
+        let ix = adapter.build_swap_ix(&pool, &swap_params(false)).unwrap();
+
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                pubkey(1),
+                pubkey(201),
+                pubkey(202),
+                pubkey(4),
+                pubkey(5),
+                vault_token_address(&pubkey(4), &METEORA_VAULT_PROGRAM_ID),
+                vault_token_address(&pubkey(5), &METEORA_VAULT_PROGRAM_ID),
+                vault_lp_mint_address(&pubkey(4), &METEORA_VAULT_PROGRAM_ID),
+                vault_lp_mint_address(&pubkey(5), &METEORA_VAULT_PROGRAM_ID),
+                pubkey(6),
+                pubkey(7),
+                pubkey(9), // protocol fee account of the input token (B)
+                pubkey(200),
+                METEORA_VAULT_PROGRAM_ID,
+                spl_token::id(),
+            ]
+        );
+        assert!(ix.accounts[12].is_signer);
+        assert!(ix.accounts[..12].iter().all(|meta| meta.is_writable));
+        assert_eq!(ix.program_id, METEORA_DAMM_PROGRAM_ID);
+        assert_eq!(&ix.data[..8], &SWAP_DISCRIMINATOR);
+        assert_eq!(&ix.data[8..16], &1_000_000u64.to_le_bytes());
+        assert_eq!(&ix.data[16..], &3_900_000u64.to_le_bytes());
     }
 }
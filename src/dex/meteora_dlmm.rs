@@ -0,0 +1,960 @@
+// Meteora DLMM Adapter
+//
+// Decodes Meteora DLMM (Dynamic Liquidity Market Maker) `LbPair` and
+// `BinArray` accounts and builds swap instructions.
+//
+// DLMM liquidity sits in discrete bins, each a constant-sum pool at a fixed
+// price of (1 + bin_step / 10_000)^bin_id. A swap drains the active bin and
+// moves to the next one, and the fee is a base fee plus a variable fee that
+// grows with the volatility accumulator, i.e. with how many bins the price has
+// moved away from its reference. The quote replays that loop bin by bin over
+// the bin arrays loaded as dependent accounts.
+
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{Result, anyhow};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use tracing::debug;
+
+use crate::dex::adapter::{
+    read_array, read_i32, read_i64, read_pubkey, read_u128, read_u16, read_u32, read_u64,
+    token_account_amount, DexAdapter, MintLayout, SwapParams, VenueState, TOKEN_2022_PROGRAM_ID,
+};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::DexType;
+
+/// Meteora DLMM program id
+pub const METEORA_DLMM_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+
+/// Anchor discriminator of the `swap` instruction
+const SWAP_DISCRIMINATOR: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+
+/// Anchor discriminator of the `LbPair` account
+const LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+
+/// Anchor discriminator of the `BinArray` account
+const BIN_ARRAY_DISCRIMINATOR: [u8; 8] = [92, 142, 92, 220, 5, 148, 70, 181];
+
+/// Size of the LbPair account
+pub const LB_PAIR_LEN: usize = 904;
+
+/// Size of a BinArray account: header plus 70 bins
+pub const BIN_ARRAY_LEN: usize = 10136;
+
+/// Bins per bin array
+pub const MAX_BIN_PER_ARRAY: i64 = 70;
+
+/// Serialized size of one `Bin`
+const BIN_LEN: usize = 144;
+
+/// Bin arrays passed to the swap instruction
+pub const SWAP_BIN_ARRAY_COUNT: usize = 3;
+
+/// The internal bitmap covers bin array indexes -512..=511
+const BIN_ARRAY_BITMAP_SIZE: i64 = 512;
+
+/// Fee rates are fractions of 1e9
+const FEE_PRECISION: u128 = 1_000_000_000;
+
+/// Total fee cap (10%)
+const MAX_FEE_RATE: u128 = 100_000_000;
+
+const BASIS_POINT_MAX: u128 = 10_000;
+
+/// Q64.64 one
+const ONE_X64: u128 = 1 << 64;
+
+/// Exponents at or above this overflow the price computation
+const MAX_EXPONENTIAL: u32 = 0x80000;
+
+/// Pair status that allows swaps
+const PAIR_STATUS_ENABLED: u8 = 0;
+
+/// Fee and bin range configuration of a pair
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StaticParameters {
+    pub base_factor: u16,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub protocol_share: u16,
+    pub base_fee_power_factor: u8,
+}
+
+/// Volatility state updated on every swap
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VariableParameters {
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub index_reference: i32,
+    pub last_update_timestamp: i64,
+}
+
+/// DLMM pair account (fields needed for quoting and swapping)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LbPair {
+    pub parameters: StaticParameters,
+    pub v_parameters: VariableParameters,
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub status: u8,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+    /// One bit per bin array index in -512..=511 that holds liquidity
+    pub bin_array_bitmap: [u64; 16],
+    /// 0 = SPL Token, 1 = Token-2022
+    pub token_mint_x_program_flag: u8,
+    pub token_mint_y_program_flag: u8,
+}
+
+impl LbPair {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: dlmm-sdk `state/lb_pair.rs` (`LbPair`, `StaticParameters`,
+    /// `VariableParameters`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < LB_PAIR_LEN {
+            return Err(anyhow!("Invalid Meteora DLMM pair account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != LB_PAIR_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Meteora DLMM pair"));
+        }
+
+        let mut bin_array_bitmap = [0u64; 16];
+        for (i, word) in bin_array_bitmap.iter_mut().enumerate() {
+            *word = read_u64(data, 584 + i * 8)?;
+        }
+
+        Ok(Self {
+            parameters: StaticParameters {
+                base_factor: read_u16(data, 8)?,
+                filter_period: read_u16(data, 10)?,
+                decay_period: read_u16(data, 12)?,
+                reduction_factor: read_u16(data, 14)?,
+                variable_fee_control: read_u32(data, 16)?,
+                max_volatility_accumulator: read_u32(data, 20)?,
+                min_bin_id: read_i32(data, 24)?,
+                max_bin_id: read_i32(data, 28)?,
+                protocol_share: read_u16(data, 32)?,
+                base_fee_power_factor: data[34],
+            },
+            v_parameters: VariableParameters {
+                volatility_accumulator: read_u32(data, 40)?,
+                volatility_reference: read_u32(data, 44)?,
+                index_reference: read_i32(data, 48)?,
+                last_update_timestamp: read_i64(data, 56)?,
+            },
+            active_id: read_i32(data, 76)?,
+            bin_step: read_u16(data, 80)?,
+            status: data[82],
+            token_x_mint: read_pubkey(data, 88)?,
+            token_y_mint: read_pubkey(data, 120)?,
+            reserve_x: read_pubkey(data, 152)?,
+            reserve_y: read_pubkey(data, 184)?,
+            oracle: read_pubkey(data, 552)?,
+            bin_array_bitmap,
+            token_mint_x_program_flag: data[880],
+            token_mint_y_program_flag: data[881],
+        })
+    }
+
+    /// Base fee as a fraction of 1e9
+    pub fn base_fee_rate(&self) -> u128 {
+        self.parameters.base_factor as u128
+            * self.bin_step as u128
+            * 10
+            * 10u128.pow(self.parameters.base_fee_power_factor as u32)
+    }
+
+    /// Variable fee for a volatility accumulator, as a fraction of 1e9
+    pub fn variable_fee_rate(&self, volatility_accumulator: u32) -> u128 {
+        if self.parameters.variable_fee_control == 0 {
+            return 0;
+        }
+        let square_vfa_bin = (volatility_accumulator as u128 * self.bin_step as u128).pow(2);
+        let v_fee = self.parameters.variable_fee_control as u128 * square_vfa_bin;
+        v_fee.div_ceil(100_000_000_000)
+    }
+
+    /// Current total fee (base + variable, capped) as a fraction of 1e9
+    pub fn total_fee_rate(&self) -> u128 {
+        (self.base_fee_rate() + self.variable_fee_rate(self.v_parameters.volatility_accumulator))
+            .min(MAX_FEE_RATE)
+    }
+
+    /// Fee the first bin of a swap starting at `current_timestamp` pays: the
+    /// volatility reference decays with the time since the last swap
+    pub fn fee_model(&self, current_timestamp: i64) -> FeeModel {
+        let mut pair = self.clone();
+        pair.update_references(current_timestamp);
+        pair.update_volatility_accumulator();
+
+        FeeModel::Dynamic {
+            base: FeeRate::from_fraction(self.base_fee_rate(), FEE_PRECISION),
+            variable: FeeRate::from_fraction(
+                pair.variable_fee_rate(pair.v_parameters.volatility_accumulator),
+                FEE_PRECISION,
+            ),
+            max: FeeRate::from_fraction(MAX_FEE_RATE, FEE_PRECISION),
+            protocol_share: FeeRate::from_fraction(self.parameters.protocol_share as u128, BASIS_POINT_MAX),
+        }
+    }
+
+    /// Base fee in basis points (rounded down)
+    pub fn base_fee_bps(&self) -> u16 {
+        (self.base_fee_rate() * BASIS_POINT_MAX / FEE_PRECISION).min(u16::MAX as u128) as u16
+    }
+
+    /// Whether the bin array at `index` holds liquidity according to the bitmap.
+    /// `None` when the index is outside the internal bitmap.
+    fn bin_array_has_liquidity(&self, index: i64) -> Option<bool> {
+        if !(-BIN_ARRAY_BITMAP_SIZE..BIN_ARRAY_BITMAP_SIZE).contains(&index) {
+            return None;
+        }
+        let bit = (index + BIN_ARRAY_BITMAP_SIZE) as usize;
+        Some(self.bin_array_bitmap[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Bin array indexes with liquidity, starting at `start` (inclusive) and
+    /// moving down for x->y swaps or up for y->x swaps
+    pub fn bin_array_indexes_with_liquidity(&self, start: i64, swap_for_y: bool, count: usize) -> Vec<i64> {
+        let step = if swap_for_y { -1 } else { 1 };
+        let mut indexes = Vec::with_capacity(count);
+        let mut index = start;
+        while indexes.len() < count {
+            match self.bin_array_has_liquidity(index) {
+                Some(true) => indexes.push(index),
+                Some(false) => {}
+                None => break,
+            }
+            index += step;
+        }
+        indexes
+    }
+
+    /// Roll the volatility reference forward to `current_timestamp`
+    /// (`LbPair::update_references`)
+    fn update_references(&mut self, current_timestamp: i64) {
+        let elapsed = current_timestamp - self.v_parameters.last_update_timestamp;
+
+        if elapsed >= self.parameters.filter_period as i64 {
+            self.v_parameters.index_reference = self.active_id;
+            self.v_parameters.volatility_reference = if elapsed < self.parameters.decay_period as i64 {
+                (self.v_parameters.volatility_accumulator as u128
+                    * self.parameters.reduction_factor as u128
+                    / BASIS_POINT_MAX) as u32
+            } else {
+                0
+            };
+        }
+    }
+
+    /// Volatility accumulator for the current active bin
+    fn update_volatility_accumulator(&mut self) {
+        let delta_id = (self.v_parameters.index_reference as i64 - self.active_id as i64).unsigned_abs();
+        let accumulator = self.v_parameters.volatility_reference as u64 + delta_id * BASIS_POINT_MAX as u64;
+        self.v_parameters.volatility_accumulator =
+            accumulator.min(self.parameters.max_volatility_accumulator as u64) as u32;
+    }
+
+    /// Move to the next bin in the swap direction
+    fn advance_active_bin(&mut self, swap_for_y: bool) -> Result<()> {
+        let next = if swap_for_y { self.active_id - 1 } else { self.active_id + 1 };
+        if next < self.parameters.min_bin_id || next > self.parameters.max_bin_id {
+            return Err(anyhow!("Meteora DLMM pair has insufficient liquidity"));
+        }
+        self.active_id = next;
+        Ok(())
+    }
+
+    /// Fee to add on top of `amount` (fee excluded)
+    fn compute_fee(&self, amount: u64) -> Result<u64> {
+        let fee_rate = self.total_fee_rate();
+        (amount as u128 * fee_rate)
+            .div_ceil(FEE_PRECISION - fee_rate)
+            .to_u64()
+            .ok_or_else(|| anyhow!("Meteora DLMM fee overflow"))
+    }
+
+    /// Fee contained in `amount_with_fees`
+    fn compute_fee_from_amount(&self, amount_with_fees: u64) -> Result<u64> {
+        (amount_with_fees as u128 * self.total_fee_rate())
+            .div_ceil(FEE_PRECISION)
+            .to_u64()
+            .ok_or_else(|| anyhow!("Meteora DLMM fee overflow"))
+    }
+}
+
+/// One price bin
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bin {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    /// Q64.64 price of X in Y; zero until the bin is first used
+    pub price: u128,
+}
+
+/// One `BinArray` account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinArray {
+    pub index: i64,
+    pub bins: Vec<Bin>,
+}
+
+impl BinArray {
+    /// Decode a bin array and check that it belongs to `lb_pair`
+    pub fn decode(lb_pair: &Pubkey, data: &[u8]) -> Result<Self> {
+        if data.len() < BIN_ARRAY_LEN {
+            return Err(anyhow!("Invalid bin array account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != BIN_ARRAY_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Meteora DLMM bin array"));
+        }
+
+        let owner = read_pubkey(data, 24)?;
+        if owner != *lb_pair {
+            return Err(anyhow!("Bin array belongs to {}, expected {}", owner, lb_pair));
+        }
+
+        let bins = (0..MAX_BIN_PER_ARRAY as usize)
+            .map(|i| {
+                let offset = 56 + i * BIN_LEN;
+                Ok(Bin {
+                    amount_x: read_u64(data, offset)?,
+                    amount_y: read_u64(data, offset + 8)?,
+                    price: read_u128(data, offset + 16)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            index: read_i64(data, 8)?,
+            bins,
+        })
+    }
+
+    fn bin(&self, bin_id: i32) -> Option<&Bin> {
+        let (lower, _) = bin_array_bounds(self.index);
+        self.bins.get(usize::try_from(bin_id as i64 - lower as i64).ok()?)
+    }
+}
+
+/// Decoded DLMM pair with the bin arrays loaded around its active bin
+#[derive(Clone, Debug)]
+pub struct DlmmPoolState {
+    pub lb_pair: LbPair,
+    /// Loaded bin arrays keyed by bin array index
+    pub bin_arrays: HashMap<i64, BinArray>,
+}
+
+impl DlmmPoolState {
+    /// Indexes of the bin arrays a swap in this direction walks through
+    pub fn swap_bin_array_indexes(&self, swap_for_y: bool) -> Vec<i64> {
+        let start = bin_id_to_bin_array_index(self.lb_pair.active_id);
+        self.lb_pair.bin_array_indexes_with_liquidity(start, swap_for_y, SWAP_BIN_ARRAY_COUNT)
+    }
+}
+
+/// Index of the bin array containing `bin_id`
+pub fn bin_id_to_bin_array_index(bin_id: i32) -> i64 {
+    (bin_id as i64).div_euclid(MAX_BIN_PER_ARRAY)
+}
+
+/// Lowest and highest bin id in the bin array at `index`
+pub fn bin_array_bounds(index: i64) -> (i32, i32) {
+    let lower = index * MAX_BIN_PER_ARRAY;
+    (lower as i32, (lower + MAX_BIN_PER_ARRAY - 1) as i32)
+}
+
+/// Bin array PDA: `[b"bin_array", lb_pair, index as i64 LE]`
+pub fn bin_array_address(program_id: &Pubkey, lb_pair: &Pubkey, index: i64) -> Pubkey {
+    Pubkey::find_program_address(&[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()], program_id).0
+}
+
+/// Anchor event authority PDA
+pub fn event_authority_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id).0
+}
+
+/// Q64.64 `base^exp` by squaring (`math/u64x64_math.rs::pow`)
+fn pow(base: u128, exp: i32) -> Option<u128> {
+    let mut invert = exp.is_negative();
+    if exp == 0 {
+        return Some(ONE_X64);
+    }
+    let exp = exp.unsigned_abs();
+    if exp >= MAX_EXPONENTIAL {
+        return None;
+    }
+
+    let mut squared_base = base;
+    let mut result = ONE_X64;
+
+    // Work with a base below one so the squares cannot overflow
+    if squared_base >= result {
+        squared_base = u128::MAX.checked_div(squared_base)?;
+        invert = !invert;
+    }
+
+    for bit in 0..19 {
+        if exp & (1 << bit) != 0 {
+            result = result.checked_mul(squared_base)? >> 64;
+        }
+        squared_base = squared_base.checked_mul(squared_base)? >> 64;
+    }
+
+    if result == 0 {
+        return None;
+    }
+    if invert {
+        result = u128::MAX.checked_div(result)?;
+    }
+    Some(result)
+}
+
+/// Q64.64 price of bin `bin_id`: (1 + bin_step / 10_000)^bin_id
+pub fn price_from_id(bin_id: i32, bin_step: u16) -> Result<u128> {
+    let base = ONE_X64 + ((bin_step as u128) << 64) / BASIS_POINT_MAX;
+    pow(base, bin_id).ok_or_else(|| anyhow!("Meteora DLMM price overflow for bin {}", bin_id))
+}
+
+/// `(a * b) >> 64`, rounded down or up
+fn mul_shr(a: u128, b: u128, round_up: bool) -> Result<u64> {
+    let product = BigUint::from(a) * BigUint::from(b);
+    let mut result = &product >> 64u32;
+    if round_up && (result.clone() << 64u32) != product {
+        result += 1u32;
+    }
+    result.to_u64().ok_or_else(|| anyhow!("Meteora DLMM amount overflow"))
+}
+
+/// `(a << 64) / b`, rounded down or up
+fn shl_div(a: u128, b: u128, round_up: bool) -> Result<u64> {
+    if b == 0 {
+        return Err(anyhow!("Meteora DLMM bin has zero price"));
+    }
+    let numerator = BigUint::from(a) << 64u32;
+    let denominator = BigUint::from(b);
+    let mut result = &numerator / &denominator;
+    if round_up && &result * &denominator != numerator {
+        result += 1u32;
+    }
+    result.to_u64().ok_or_else(|| anyhow!("Meteora DLMM amount overflow"))
+}
+
+/// Swap up to `amount_in` (fees included) against one bin (`Bin::swap`).
+/// Returns the input consumed and the output.
+fn swap_in_bin(bin: &Bin, lb_pair: &LbPair, amount_in: u64, price: u128, swap_for_y: bool) -> Result<(u64, u64)> {
+    let max_amount_out = if swap_for_y { bin.amount_y } else { bin.amount_x };
+    let max_amount_in = if swap_for_y {
+        shl_div(bin.amount_y as u128, price, true)?
+    } else {
+        mul_shr(bin.amount_x as u128, price, true)?
+    };
+    let max_amount_in = max_amount_in
+        .checked_add(lb_pair.compute_fee(max_amount_in)?)
+        .ok_or_else(|| anyhow!("Meteora DLMM amount overflow"))?;
+
+    if amount_in > max_amount_in {
+        return Ok((max_amount_in, max_amount_out));
+    }
+
+    let fee = lb_pair.compute_fee_from_amount(amount_in)?;
+    let amount_in_after_fee = amount_in - fee;
+    let amount_out = if swap_for_y {
+        mul_shr(price, amount_in_after_fee as u128, false)?
+    } else {
+        shl_div(amount_in_after_fee as u128, price, false)?
+    };
+    Ok((amount_in, amount_out.min(max_amount_out)))
+}
+
+/// Simulate an exact-input swap bin by bin at `current_timestamp`,
+/// including the variable fee (`quote_exact_in` in the DLMM SDK)
+pub fn simulate_swap_exact_in(
+    lb_pair: &LbPair,
+    bin_arrays: &HashMap<i64, BinArray>,
+    amount_in: u64,
+    swap_for_y: bool,
+    current_timestamp: i64,
+) -> Result<u64> {
+    let mut pair = lb_pair.clone();
+    pair.update_references(current_timestamp);
+
+    let mut amount_left = amount_in;
+    let mut amount_out: u64 = 0;
+
+    while amount_left > 0 {
+        let start = bin_id_to_bin_array_index(pair.active_id);
+        let index = *pair.bin_array_indexes_with_liquidity(start, swap_for_y, 1)
+            .first()
+            .ok_or_else(|| anyhow!("Meteora DLMM pair has insufficient liquidity"))?;
+        let bin_array = bin_arrays.get(&index)
+            .ok_or_else(|| anyhow!("Meteora DLMM bin array {} not loaded", index))?;
+
+        // The bitmap may skip empty arrays; enter the next one at its edge
+        let (lower, upper) = bin_array_bounds(index);
+        if pair.active_id < lower || pair.active_id > upper {
+            pair.active_id = if swap_for_y { upper } else { lower };
+        }
+
+        while amount_left > 0 && (lower..=upper).contains(&pair.active_id) {
+            pair.update_volatility_accumulator();
+
+            let bin = bin_array.bin(pair.active_id).copied().unwrap_or_default();
+            let has_output = if swap_for_y { bin.amount_y > 0 } else { bin.amount_x > 0 };
+            if has_output {
+                let price = if bin.price == 0 {
+                    price_from_id(pair.active_id, pair.bin_step)?
+                } else {
+                    bin.price
+                };
+                let (consumed, out) = swap_in_bin(&bin, &pair, amount_left, price, swap_for_y)?;
+                amount_left -= consumed;
+                amount_out = amount_out
+                    .checked_add(out)
+                    .ok_or_else(|| anyhow!("Meteora DLMM output overflow"))?;
+            }
+
+            if amount_left > 0 {
+                pair.advance_active_bin(swap_for_y)?;
+            }
+        }
+    }
+
+    Ok(amount_out)
+}
+
+fn token_program(flag: u8) -> Pubkey {
+    if flag == 1 {
+        TOKEN_2022_PROGRAM_ID
+    } else {
+        spl_token::id()
+    }
+}
+
+pub struct MeteoraDlmmAdapter {
+    program_id: Pubkey,
+}
+
+impl MeteoraDlmmAdapter {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a DlmmPoolState> {
+        match &pool.venue {
+            VenueState::MeteoraDlmm(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Meteora DLMM state", pool.pool_address)),
+        }
+    }
+}
+
+impl Default for MeteoraDlmmAdapter {
+    fn default() -> Self {
+        Self::new(METEORA_DLMM_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for MeteoraDlmmAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::MeteoraDlmm
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: Some(LB_PAIR_LEN as u64),
+            mint_a_offset: 88, // token_x_mint
+            mint_b_offset: 120, // token_y_mint
+        })
+    }
+
+    fn pool_init_logs(&self) -> &'static [&'static str] {
+        &[
+            "Program log: Instruction: InitializeLbPair",
+            "Program log: Instruction: InitializeLbPair2",
+            "Program log: Instruction: InitializePermissionLbPair",
+            "Program log: Instruction: InitializeCustomizablePermissionlessLbPair",
+            "Program log: Instruction: InitializeCustomizablePermissionlessLbPair2",
+        ]
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let lb_pair = LbPair::decode(data)?;
+
+        if lb_pair.status != PAIR_STATUS_ENABLED {
+            return Err(anyhow!("Meteora DLMM pair {} is disabled", address));
+        }
+
+        debug!(
+            "Parsed Meteora DLMM pair: active_id={}, bin_step={}, base fee={} bps",
+            lb_pair.active_id, lb_pair.bin_step, lb_pair.base_fee_bps()
+        );
+
+        // Reserves are filled from the reserve accounts in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: lb_pair.token_x_mint,
+            token_b: lb_pair.token_y_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: lb_pair.base_fee_bps(),
+            dex_type: DexType::MeteoraDlmm,
+            program_id: self.program_id,
+            venue: VenueState::MeteoraDlmm(Box::new(DlmmPoolState {
+                lb_pair,
+                bin_arrays: HashMap::new(),
+            })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        let state = match self.state(pool) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut accounts = vec![state.lb_pair.reserve_x, state.lb_pair.reserve_y];
+        for swap_for_y in [true, false] {
+            for index in state.swap_bin_array_indexes(swap_for_y) {
+                let address = bin_array_address(&self.program_id, &pool.pool_address, index);
+                if !accounts.contains(&address) {
+                    accounts.push(address);
+                }
+            }
+        }
+        accounts
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let program_id = self.program_id;
+        let state = match &mut pool.venue {
+            VenueState::MeteoraDlmm(state) => state,
+            _ => return Err(anyhow!("Pool {} has no Meteora DLMM state", address)),
+        };
+        let lb_pair = &state.lb_pair;
+
+        let reserve_x = accounts.get(&lb_pair.reserve_x)
+            .ok_or_else(|| anyhow!("Missing reserve X {} for pair {}", lb_pair.reserve_x, address))?;
+        let reserve_y = accounts.get(&lb_pair.reserve_y)
+            .ok_or_else(|| anyhow!("Missing reserve Y {} for pair {}", lb_pair.reserve_y, address))?;
+        let reserve_a = token_account_amount(reserve_x)?;
+        let reserve_b = token_account_amount(reserve_y)?;
+
+        let mut bin_arrays = HashMap::new();
+        for swap_for_y in [true, false] {
+            for index in state.swap_bin_array_indexes(swap_for_y) {
+                let bin_array = bin_array_address(&program_id, &address, index);
+                if let Some(account) = accounts.get(&bin_array) {
+                    bin_arrays.insert(index, BinArray::decode(&address, &account.data)?);
+                }
+            }
+        }
+        state.bin_arrays = bin_arrays;
+
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+
+        debug!(
+            "Meteora DLMM pair {} reserves: ({}, {}), {} bin arrays loaded",
+            address, reserve_a, reserve_b, state.bin_arrays.len()
+        );
+
+        Ok(())
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let state = self.state(pool)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        simulate_swap_exact_in(&state.lb_pair, &state.bin_arrays, amount_in, a_to_b, now)
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or_default();
+        match self.state(pool) {
+            Ok(state) => state.lb_pair.fee_model(now),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn spot_rate(&self, pool: &PoolData, a_to_b: bool) -> f64 {
+        let price = match self.state(pool)
+            .and_then(|state| price_from_id(state.lb_pair.active_id, state.lb_pair.bin_step))
+        {
+            Ok(price) => price as f64 / 2f64.powi(64),
+            Err(_) => return 0.0,
+        };
+        if a_to_b {
+            price
+        } else if price > 0.0 {
+            1.0 / price
+        } else {
+            0.0
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let state = self.state(pool)?;
+        let lb_pair = &state.lb_pair;
+
+        let bin_arrays = state.swap_bin_array_indexes(params.a_to_b);
+        if bin_arrays.is_empty() {
+            return Err(anyhow!("Meteora DLMM pair {} has no liquidity in the swap direction", pool.pool_address));
+        }
+
+        // Optional accounts that are not used are passed as the program id
+        let mut accounts = vec![
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new_readonly(self.program_id, false), // bin_array_bitmap_extension
+            AccountMeta::new(lb_pair.reserve_x, false),
+            AccountMeta::new(lb_pair.reserve_y, false),
+            AccountMeta::new(params.user_source, false),
+            AccountMeta::new(params.user_destination, false),
+            AccountMeta::new_readonly(lb_pair.token_x_mint, false),
+            AccountMeta::new_readonly(lb_pair.token_y_mint, false),
+            AccountMeta::new(lb_pair.oracle, false),
+            AccountMeta::new_readonly(self.program_id, false), // host_fee_in
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new_readonly(token_program(lb_pair.token_mint_x_program_flag), false),
+            AccountMeta::new_readonly(token_program(lb_pair.token_mint_y_program_flag), false),
+            AccountMeta::new_readonly(event_authority_address(&self.program_id), false),
+            AccountMeta::new_readonly(self.program_id, false),
+        ];
+        accounts.extend(bin_arrays.into_iter().map(|index| {
+            AccountMeta::new(bin_array_address(&self.program_id, &pool.pool_address, index), false)
+        }));
+        Ok(accounts)
+    }
+
+    fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(active_id: i32) -> LbPair {
+        let mut lb_pair = LbPair {
+            parameters: StaticParameters {
+                base_factor: 10_000,
+                filter_period: 30,
+                decay_period: 600,
+                reduction_factor: 5_000,
+                variable_fee_control: 40_000,
+                max_volatility_accumulator: 350_000,
+                min_bin_id: -443_636,
+                max_bin_id: 443_636,
+                ..Default::default()
+            },
+            active_id,
+            bin_step: 10,
+            ..Default::default()
+        };
+        // Mark bin arrays -1 and 0 as holding liquidity
+        for index in [-1i64, 0] {
+            let bit = (index + BIN_ARRAY_BITMAP_SIZE) as usize;
+            lb_pair.bin_array_bitmap[bit / 64] |= 1 << (bit % 64);
+        }
+        lb_pair
+    }
+
+    fn bin_array(index: i64, amount_x: u64, amount_y: u64) -> BinArray {
+        BinArray {
+            index,
+            bins: vec![Bin { amount_x, amount_y, price: 0 }; MAX_BIN_PER_ARRAY as usize],
+        }
+    }
+
+    #[test]
+    fn test_price_from_id() {
+        assert_eq!(price_from_id(0, 10).unwrap(), ONE_X64);
+        for (bin_id, bin_step) in [(1, 10), (-1, 10), (100, 25), (-5_000, 1), (2_000, 100)] {
+            let expected = (1.0 + bin_step as f64 / 10_000.0).powi(bin_id);
+            let actual = price_from_id(bin_id, bin_step).unwrap() as f64 / 2f64.powi(64);
+            assert!((actual / expected - 1.0).abs() < 1e-9, "bin {} step {}", bin_id, bin_step);
+        }
+    }
+
+    #[test]
+    fn test_bin_array_index_and_bounds() {
+        assert_eq!(bin_id_to_bin_array_index(0), 0);
+        assert_eq!(bin_id_to_bin_array_index(69), 0);
+        assert_eq!(bin_id_to_bin_array_index(70), 1);
+        assert_eq!(bin_id_to_bin_array_index(-1), -1);
+        assert_eq!(bin_array_bounds(-1), (-70, -1));
+    }
+
+    #[test]
+    fn test_bitmap_search_follows_direction() {
+        let lb_pair = pair(5);
+        assert_eq!(lb_pair.bin_array_indexes_with_liquidity(0, true, 3), vec![0, -1]);
+        assert_eq!(lb_pair.bin_array_indexes_with_liquidity(0, false, 3), vec![0]);
+        assert_eq!(lb_pair.bin_array_indexes_with_liquidity(3, true, 1), vec![0]);
+    }
+
+    #[test]
+    fn test_fee_rates() {
+        let mut lb_pair = pair(0);
+        // base_factor 10_000 * bin_step 10 * 10 = 1_000_000 / 1e9 = 10 bps
+        assert_eq!(lb_pair.base_fee_rate(), 1_000_000);
+        assert_eq!(lb_pair.base_fee_bps(), 10);
+        assert_eq!(lb_pair.total_fee_rate(), 1_000_000);
+
+        // One bin away: 40_000 * (10_000 * 10)^2 / 1e11 = 4_000
+        lb_pair.v_parameters.volatility_accumulator = 10_000;
+        assert_eq!(lb_pair.total_fee_rate(), 1_004_000);
+
+        // The total is capped at 10%
+        lb_pair.v_parameters.volatility_accumulator = 350_000_000;
+        assert_eq!(lb_pair.total_fee_rate(), MAX_FEE_RATE);
+    }
+
+    #[test]
+    fn test_fee_model_follows_volatility() {
+        let mut lb_pair = pair(0);
+        lb_pair.parameters.protocol_share = 500;
+        lb_pair.v_parameters.volatility_accumulator = 10_000;
+        lb_pair.v_parameters.volatility_reference = 20_000;
+        lb_pair.v_parameters.last_update_timestamp = 1_000;
+
+        // Within the filter period the reference is kept: 40_000 * (20_000 * 10)^2 / 1e11
+        let fee = lb_pair.fee_model(1_010);
+        assert!(fee.is_dynamic());
+        assert_eq!(fee.trade_fee(), FeeRate::from_billionths(1_016_000));
+        assert_eq!(fee.protocol_fee(), FeeRate::from_billionths(50_800));
+
+        // After the filter period the accumulator decays by the reduction factor (half)
+        assert_eq!(lb_pair.fee_model(1_100).trade_fee(), FeeRate::from_billionths(1_001_000));
+
+        // After the decay period only the base fee is left
+        assert_eq!(lb_pair.fee_model(2_000).trade_fee(), FeeRate::from_billionths(1_000_000));
+    }
+
+    #[test]
+    fn test_swap_within_active_bin() {
+        let lb_pair = pair(0);
+        let arrays = HashMap::from([(0, bin_array(0, 1_000_000, 1_000_000))]);
+
+        // Price 1, fee 10 bps taken from the input (rounded up)
+        let out = simulate_swap_exact_in(&lb_pair, &arrays, 10_000, true, 0).unwrap();
+        assert_eq!(out, 10_000 - 10);
+    }
+
+    #[test]
+    fn test_swap_crosses_bins_with_rising_fee() {
+        let lb_pair = pair(0);
+        let arrays = HashMap::from([
+            (0, bin_array(0, 1_000, 1_000)),
+            (-1, bin_array(-1, 1_000, 1_000)),
+        ]);
+
+        // Drains bin 0 then continues into lower bins at a worse price and a
+        // higher variable fee
+        let out = simulate_swap_exact_in(&lb_pair, &arrays, 5_000, true, 0).unwrap();
+        assert!(out < 5_000 - 5);
+        assert!(out > 4_000);
+
+        // Running past the loaded arrays fails
+        assert!(simulate_swap_exact_in(&lb_pair, &arrays, 10_000_000, true, 0).is_err());
+    }
+
+    #[test]
+    fn test_lb_pair_decode() {
+        let mut data = vec![0u8; LB_PAIR_LEN];
+        data[..8].copy_from_slice(&LB_PAIR_DISCRIMINATOR);
+        data[8..10].copy_from_slice(&10_000u16.to_le_bytes());
+        data[76..80].copy_from_slice(&(-12i32).to_le_bytes());
+        data[80..82].copy_from_slice(&25u16.to_le_bytes());
+        let mint_x = Pubkey::new_unique();
+        let reserve_y = Pubkey::new_unique();
+        data[88..120].copy_from_slice(mint_x.as_ref());
+        data[184..216].copy_from_slice(reserve_y.as_ref());
+        data[584..592].copy_from_slice(&1u64.to_le_bytes());
+
+        let lb_pair = LbPair::decode(&data).unwrap();
+        assert_eq!(lb_pair.parameters.base_factor, 10_000);
+        assert_eq!(lb_pair.active_id, -12);
+        assert_eq!(lb_pair.bin_step, 25);
+        assert_eq!(lb_pair.token_x_mint, mint_x);
+        assert_eq!(lb_pair.reserve_y, reserve_y);
+        assert_eq!(lb_pair.bin_array_has_liquidity(-512), Some(true));
+
+        data[0] = 0;
+        assert!(LbPair::decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let adapter = MeteoraDlmmAdapter::default();
+        let pool_address = Pubkey::new_unique();
+        let lb_pair = LbPair {
+            reserve_x: Pubkey::new_unique(),
+            reserve_y: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_mint_y_program_flag: 1,
+            ..pair(10)
+        };
+        let pool = PoolData {
+            pool_address,
+            token_a: lb_pair.token_x_mint,
+            token_b: lb_pair.token_y_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: 10,
+            dex_type: DexType::MeteoraDlmm,
+            program_id: METEORA_DLMM_PROGRAM_ID,
+            venue: VenueState::MeteoraDlmm(Box::new(DlmmPoolState {
+                lb_pair: lb_pair.clone(),
+                bin_arrays: HashMap::new(),
+            })),
+            mints: Default::default(),
+            slot: 0,
+        };
+        let params = SwapParams {
+            user: Pubkey::new_unique(),
+            user_source: Pubkey::new_unique(),
+            user_destination: Pubkey::new_unique(),
+            amount_in: 1_000,
+            minimum_out: 990,
+            a_to_b: true,
+        };
+
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        assert_eq!(ix.accounts.len(), 15 + 2);
+        assert_eq!(ix.accounts[0].pubkey, pool_address);
+        assert_eq!(ix.accounts[4].pubkey, params.user_source);
+        assert_eq!(ix.accounts[5].pubkey, params.user_destination);
+        assert_eq!(ix.accounts[8].pubkey, lb_pair.oracle);
+        assert!(ix.accounts[10].is_signer);
+        assert_eq!(ix.accounts[11].pubkey, spl_token::id());
+        assert_eq!(ix.accounts[12].pubkey, TOKEN_2022_PROGRAM_ID);
+        assert_eq!(ix.accounts[15].pubkey, bin_array_address(&METEORA_DLMM_PROGRAM_ID, &pool_address, 0));
+        assert_eq!(ix.accounts[16].pubkey, bin_array_address(&METEORA_DLMM_PROGRAM_ID, &pool_address, -1));
+
+        assert_eq!(ix.data.len(), 24);
+        assert_eq!(&ix.data[..8], &SWAP_DISCRIMINATOR);
+    }
+}
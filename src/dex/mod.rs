@@ -1,16 +1,24 @@
+pub mod adapter;
+pub mod cycle_index;
+pub mod fees;
 pub mod integration_example;
 pub mod meteora;
+pub mod meteora_dlmm;
+pub mod openbook_v2;
+pub mod orderbook;
+pub mod orca;
+pub mod phoenix;
 pub mod pool_fetcher;
 pub mod pump;
 pub mod raydium;
+pub mod raydium_clmm;
+pub mod raydium_cpmm;
+pub mod route_cache;
+pub mod token_2022;
 pub mod triangular_arb;
 pub mod whirlpool;
 
+#[cfg(test)]
+pub(crate) mod test_fixtures;
 #[cfg(test)]
 mod triangular_arb_tests;
-
-// Re-exports (currently unused but will be used in execution phase)
-// pub use raydium::RaydiumClient;
-// pub use meteora::MeteoraClient;
-// pub use whirlpool::WhirlpoolClient;
-// pub use pump::PumpClient;
@@ -0,0 +1,531 @@
+// OpenBook v2 Adapter
+//
+// Decodes OpenBook v2 markets and their `BookSide` accounts into order-book
+// ladders and builds the `place_take_order` instruction.
+//
+// The market account only references its book: bids and asks live in
+// separate `BookSide` accounts (critbit trees of 88-byte nodes), fetched as
+// dependent accounts. The adapter walks the fixed-price tree of each side,
+// aggregates the leaves by price and quotes by matching against the ladder
+// with the market's taker fee. Oracle-pegged orders are skipped because their
+// price depends on the oracle. Pool token A is the base mint and token B the
+// quote mint, so `a_to_b` sells base into the bids and `b_to_a` buys base.
+//
+// `place_take_order` has no minimum-output argument. The adapter bounds the
+// worst fill price instead, so every fill is at least as good as the average
+// price `minimum_out` requires; an immediate-or-cancel order can still fill
+// partially on a thin book.
+
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey, system_program};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use tracing::debug;
+
+use crate::dex::adapter::{
+    read_array, read_i64, read_pubkey, read_u128, read_u32, DexAdapter, MintLayout, SwapParams, VenueState,
+};
+use crate::dex::fees::FeeModel;
+use crate::dex::orderbook::{BookUnits, OrderBook};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::{DexType, PriceLevel};
+
+/// OpenBook v2 program id
+pub const OPENBOOK_V2_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb");
+
+/// Anchor discriminator of the `place_take_order` instruction
+const PLACE_TAKE_ORDER_DISCRIMINATOR: [u8; 8] = [3, 44, 71, 3, 26, 199, 203, 85];
+
+/// Anchor discriminator of the `Market` account
+const MARKET_DISCRIMINATOR: [u8; 8] = [219, 190, 213, 55, 0, 227, 198, 154];
+
+/// Anchor discriminator of the `BookSide` account
+const BOOK_SIDE_DISCRIMINATOR: [u8; 8] = [72, 44, 225, 141, 178, 130, 97, 57];
+
+/// Size of the Market account
+pub const MARKET_LEN: usize = 1136;
+
+/// Offset of the node array in a BookSide account
+const BOOK_SIDE_NODES_OFFSET: usize = 840;
+
+/// Nodes per BookSide
+const MAX_ORDER_TREE_NODES: usize = 1024;
+
+/// Size of one tree node
+const NODE_LEN: usize = 88;
+
+/// Size of the BookSide account
+pub const BOOK_SIDE_LEN: usize = BOOK_SIDE_NODES_OFFSET + MAX_ORDER_TREE_NODES * NODE_LEN;
+
+/// Node tags
+const NODE_TAG_INNER: u8 = 1;
+const NODE_TAG_LEAF: u8 = 2;
+
+/// Taker and maker fees are in millionths
+const FEES_SCALE_FACTOR: u64 = 1_000_000;
+
+/// `Side` values
+const SIDE_BID: u8 = 0;
+const SIDE_ASK: u8 = 1;
+
+/// `PlaceOrderType::ImmediateOrCancel`
+const ORDER_TYPE_IMMEDIATE_OR_CANCEL: u8 = 1;
+
+/// Maximum resting orders matched by one take order
+const MATCH_LIMIT: u8 = 50;
+
+/// OpenBook v2 market (fields needed for quoting and swapping)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpenBookMarket {
+    pub market_authority: Pubkey,
+    /// Unix time after which the market is closed; 0 for none
+    pub time_expiry: i64,
+    /// Required co-signer of every order when set
+    pub open_orders_admin: Option<Pubkey>,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_heap: Pubkey,
+    pub oracle_a: Option<Pubkey>,
+    pub oracle_b: Option<Pubkey>,
+    pub quote_lot_size: i64,
+    pub base_lot_size: i64,
+    /// Taker fee in millionths of the quote amount
+    pub taker_fee: i64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+}
+
+impl OpenBookMarket {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: openbook-v2 `state/market.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < MARKET_LEN {
+            return Err(anyhow!("Invalid OpenBook v2 market account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != MARKET_DISCRIMINATOR {
+            return Err(anyhow!("Account is not an OpenBook v2 market"));
+        }
+
+        Ok(Self {
+            market_authority: read_pubkey(data, 16)?,
+            time_expiry: read_i64(data, 48)?,
+            open_orders_admin: read_optional_pubkey(data, 88)?,
+            bids: read_pubkey(data, 200)?,
+            asks: read_pubkey(data, 232)?,
+            event_heap: read_pubkey(data, 264)?,
+            oracle_a: read_optional_pubkey(data, 296)?,
+            oracle_b: read_optional_pubkey(data, 328)?,
+            quote_lot_size: read_i64(data, 736)?,
+            base_lot_size: read_i64(data, 744)?,
+            taker_fee: read_i64(data, 776)?,
+            base_mint: read_pubkey(data, 864)?,
+            quote_mint: read_pubkey(data, 896)?,
+            market_base_vault: read_pubkey(data, 928)?,
+            market_quote_vault: read_pubkey(data, 968)?,
+        })
+    }
+
+    /// Lot sizes and fee as ladder units; prices are quote lots per base lot
+    pub fn units(&self) -> BookUnits {
+        BookUnits {
+            base_lot_size: self.base_lot_size.max(0) as u64,
+            quote_lot_size: self.quote_lot_size.max(0) as u64,
+            price_numerator: 1,
+            price_denominator: 1,
+            taker_fee_numerator: self.taker_fee.max(0) as u64,
+            taker_fee_denominator: FEES_SCALE_FACTOR,
+        }
+    }
+}
+
+/// `NonZeroPubkeyOption`: the zero key means none
+fn read_optional_pubkey(data: &[u8], offset: usize) -> Result<Option<Pubkey>> {
+    let key = read_pubkey(data, offset)?;
+    Ok((key != Pubkey::default()).then_some(key))
+}
+
+/// Fixed-price resting orders `(price_lots, base_lots)` of one BookSide account
+///
+/// Layout reference: openbook-v2 `state/orderbook/{bookside,nodes,ordertree}.rs`
+pub fn decode_book_side(data: &[u8]) -> Result<Vec<(u64, u64)>> {
+    if data.len() < BOOK_SIDE_LEN {
+        return Err(anyhow!("Invalid OpenBook v2 book side account size: {} bytes", data.len()));
+    }
+    if read_array::<8>(data, 0)? != BOOK_SIDE_DISCRIMINATOR {
+        return Err(anyhow!("Account is not an OpenBook v2 book side"));
+    }
+
+    // roots[0] is the fixed-price tree: { maybe_node: u32, leaf_count: u32 }
+    let root = read_u32(data, 8)? as usize;
+    let leaf_count = read_u32(data, 12)? as usize;
+    if leaf_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let node_offset = |index: usize| -> Result<usize> {
+        if index >= MAX_ORDER_TREE_NODES {
+            return Err(anyhow!("OpenBook v2 tree node {} out of range", index));
+        }
+        Ok(BOOK_SIDE_NODES_OFFSET + index * NODE_LEN)
+    };
+
+    let mut orders = Vec::with_capacity(leaf_count);
+    let mut stack = vec![root];
+    while let Some(index) = stack.pop() {
+        if stack.len() + orders.len() > MAX_ORDER_TREE_NODES {
+            return Err(anyhow!("OpenBook v2 order tree is malformed"));
+        }
+        let node = node_offset(index)?;
+        match data[node] {
+            NODE_TAG_INNER => {
+                stack.push(read_u32(data, node + 24)? as usize);
+                stack.push(read_u32(data, node + 28)? as usize);
+            }
+            NODE_TAG_LEAF => {
+                // The upper 64 bits of the key are the price data (price lots for fixed orders)
+                let price_lots = (read_u128(data, node + 8)? >> 64) as u64;
+                let quantity = read_i64(data, node + 56)?;
+                orders.push((price_lots, quantity.max(0) as u64));
+            }
+            tag => return Err(anyhow!("Unexpected OpenBook v2 node tag {} at index {}", tag, index)),
+        }
+    }
+
+    if orders.len() != leaf_count {
+        return Err(anyhow!("OpenBook v2 tree has {} leaves, expected {}", orders.len(), leaf_count));
+    }
+    Ok(orders)
+}
+
+/// Decoded OpenBook v2 market with its book, once the book sides are fetched
+#[derive(Clone, Debug)]
+pub struct OpenBookMarketState {
+    pub market: OpenBookMarket,
+    pub book: Option<OrderBook>,
+}
+
+pub struct OpenBookV2Adapter {
+    program_id: Pubkey,
+}
+
+impl OpenBookV2Adapter {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a OpenBookMarketState> {
+        match &pool.venue {
+            VenueState::OpenBookV2(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no OpenBook v2 market state", pool.pool_address)),
+        }
+    }
+
+    fn book<'a>(&self, pool: &'a PoolData) -> Result<&'a OrderBook> {
+        self.state(pool)?.book.as_ref()
+            .ok_or_else(|| anyhow!("Book sides not loaded for OpenBook v2 market {}", pool.pool_address))
+    }
+}
+
+impl Default for OpenBookV2Adapter {
+    fn default() -> Self {
+        Self::new(OPENBOOK_V2_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for OpenBookV2Adapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::OpenBookV2
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: None,
+            mint_a_offset: 864, // base_mint
+            mint_b_offset: 896, // quote_mint
+        })
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let market = OpenBookMarket::decode(data)?;
+
+        debug!(
+            "Parsed OpenBook v2 market: base={}, quote={}, taker fee {}/{}",
+            market.base_mint, market.quote_mint, market.taker_fee, FEES_SCALE_FACTOR
+        );
+
+        // The book and its "reserves" are filled in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: market.base_mint,
+            token_b: market.quote_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: market.units().fee_bps(),
+            dex_type: DexType::OpenBookV2,
+            program_id: self.program_id,
+            venue: VenueState::OpenBookV2(Box::new(OpenBookMarketState { market, book: None })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        match self.state(pool) {
+            Ok(state) => vec![state.market.bids, state.market.asks],
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let market = &self.state(pool)?.market;
+
+        let bids = accounts.get(&market.bids)
+            .ok_or_else(|| anyhow!("Missing bids {} for OpenBook v2 market {}", market.bids, address))?;
+        let asks = accounts.get(&market.asks)
+            .ok_or_else(|| anyhow!("Missing asks {} for OpenBook v2 market {}", market.asks, address))?;
+        let book = OrderBook::from_orders(market.units(), decode_book_side(&bids.data)?, decode_book_side(&asks.data)?);
+
+        pool.reserve_a = book.ask_base_atoms();
+        pool.reserve_b = book.bid_quote_atoms();
+        debug!(
+            "OpenBook v2 market {}: {} bid / {} ask levels",
+            address, book.bids.len(), book.asks.len()
+        );
+        if let VenueState::OpenBookV2(state) = &mut pool.venue {
+            state.book = Some(book);
+        }
+
+        Ok(())
+    }
+
+    fn is_tradable(&self, pool: &PoolData) -> bool {
+        // Markets with an open-orders admin need its signature on every order
+        self.state(pool)
+            .map(|state| state.market.open_orders_admin.is_none() && state.book.is_some())
+            .unwrap_or(false)
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        self.book(pool)?.quote_exact_in(amount_in, a_to_b)
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(state) => state.market.units().fee_model(),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn spot_rate(&self, pool: &PoolData, a_to_b: bool) -> f64 {
+        self.book(pool).map(|book| book.spot_rate(a_to_b)).unwrap_or(0.0)
+    }
+
+    fn liquidity_depth(&self, pool: &PoolData, a_to_b: bool) -> Vec<PriceLevel> {
+        self.book(pool).map(|book| book.price_levels(a_to_b)).unwrap_or_default()
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let market = &self.state(pool)?.market;
+        let (user_base, user_quote) = if params.a_to_b {
+            (params.user_source, params.user_destination)
+        } else {
+            (params.user_destination, params.user_source)
+        };
+        // Absent optional accounts are passed as the program id
+        let optional = |key: Option<Pubkey>| key.unwrap_or(self.program_id);
+
+        Ok(vec![
+            AccountMeta::new(params.user, true),
+            AccountMeta::new(params.user, true), // penalty payer
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new_readonly(market.market_authority, false),
+            AccountMeta::new(market.bids, false),
+            AccountMeta::new(market.asks, false),
+            AccountMeta::new(market.market_base_vault, false),
+            AccountMeta::new(market.market_quote_vault, false),
+            AccountMeta::new(market.event_heap, false),
+            AccountMeta::new(user_base, false),
+            AccountMeta::new(user_quote, false),
+            AccountMeta::new_readonly(optional(market.oracle_a), false),
+            AccountMeta::new_readonly(optional(market.oracle_b), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(optional(market.open_orders_admin), false),
+        ])
+    }
+
+    fn swap_data(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let units = self.state(pool)?.market.units();
+        if units.base_lot_size == 0 || units.quote_lot_size == 0 {
+            return Err(anyhow!("OpenBook v2 market {} has zero lot size", pool.pool_address));
+        }
+        let fee = units.taker_fee_numerator as u128;
+        let scale = FEES_SCALE_FACTOR as u128;
+
+        let (side, price_lots, max_base_lots, max_quote_lots) = if params.a_to_b {
+            // Selling n base lots for at least `minimum_out` after the fee
+            let base_lots = params.amount_in / units.base_lot_size;
+            if base_lots == 0 {
+                return Err(anyhow!("Input {} is below one base lot", params.amount_in));
+            }
+            let min_quote_lots = params.minimum_out.div_ceil(units.quote_lot_size) as u128;
+            let gross_quote_lots = (min_quote_lots * scale).div_ceil(scale.saturating_sub(fee).max(1));
+            let min_price = gross_quote_lots.div_ceil(base_lots as u128).max(1);
+            (SIDE_ASK, i64::try_from(min_price).unwrap_or(i64::MAX), base_lots as i64, i64::MAX)
+        } else {
+            // Spending the quote budget (fee included) on at least `minimum_out` base
+            let quote_lots = params.amount_in / units.quote_lot_size;
+            let min_base_lots = params.minimum_out.div_ceil(units.base_lot_size) as u128;
+            let max_price = (quote_lots as u128 * scale / (scale + fee))
+                .checked_div(min_base_lots)
+                .unwrap_or(i64::MAX as u128);
+            if max_price == 0 {
+                return Err(anyhow!("Minimum output {} is unreachable with {} input", params.minimum_out, params.amount_in));
+            }
+            (SIDE_BID, max_price.min(i64::MAX as u128) as i64, i64::MAX, i64::try_from(quote_lots).unwrap_or(i64::MAX))
+        };
+
+        let mut data = PLACE_TAKE_ORDER_DISCRIMINATOR.to_vec();
+        data.push(side);
+        data.extend_from_slice(&price_lots.to_le_bytes());
+        data.extend_from_slice(&max_base_lots.to_le_bytes());
+        data.extend_from_slice(&max_quote_lots.to_le_bytes()); // max_quote_lots_including_fees
+        data.push(ORDER_TYPE_IMMEDIATE_OR_CANCEL);
+        data.push(MATCH_LIMIT);
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn market_data() -> Vec<u8> {
+        let mut data = vec![0u8; MARKET_LEN];
+        data[..8].copy_from_slice(&MARKET_DISCRIMINATOR);
+        data[16..48].copy_from_slice(create_test_pubkey(1).as_ref()); // market authority
+        data[200..232].copy_from_slice(create_test_pubkey(2).as_ref()); // bids
+        data[232..264].copy_from_slice(create_test_pubkey(3).as_ref()); // asks
+        data[264..296].copy_from_slice(create_test_pubkey(4).as_ref()); // event heap
+        data[736..744].copy_from_slice(&10i64.to_le_bytes()); // quote lot size
+        data[744..752].copy_from_slice(&1_000i64.to_le_bytes()); // base lot size
+        data[776..784].copy_from_slice(&400i64.to_le_bytes()); // taker fee 4 bps
+        data[864..896].copy_from_slice(create_test_pubkey(5).as_ref()); // base mint
+        data[896..928].copy_from_slice(create_test_pubkey(6).as_ref()); // quote mint
+        data[928..960].copy_from_slice(create_test_pubkey(7).as_ref()); // base vault
+        data[968..1000].copy_from_slice(create_test_pubkey(8).as_ref()); // quote vault
+        data
+    }
+
+    /// A book side whose tree is an inner node over two leaves, plus a third
+    /// leaf hanging off a second inner node
+    fn book_side_data(orders: [(u64, i64); 3]) -> Vec<u8> {
+        let mut data = vec![0u8; BOOK_SIDE_LEN];
+        data[..8].copy_from_slice(&BOOK_SIDE_DISCRIMINATOR);
+        data[8..12].copy_from_slice(&0u32.to_le_bytes()); // root
+        data[12..16].copy_from_slice(&3u32.to_le_bytes()); // leaf count
+
+        let node = |index: usize| BOOK_SIDE_NODES_OFFSET + index * NODE_LEN;
+        let inner = |data: &mut Vec<u8>, index: usize, children: [u32; 2]| {
+            data[node(index)] = NODE_TAG_INNER;
+            data[node(index) + 24..node(index) + 28].copy_from_slice(&children[0].to_le_bytes());
+            data[node(index) + 28..node(index) + 32].copy_from_slice(&children[1].to_le_bytes());
+        };
+        inner(&mut data, 0, [1, 2]);
+        inner(&mut data, 2, [3, 4]);
+        for ((price, quantity), index) in orders.into_iter().zip([1usize, 3, 4]) {
+            let offset = node(index);
+            data[offset] = NODE_TAG_LEAF;
+            let key = ((price as u128) << 64) | index as u128;
+            data[offset + 8..offset + 24].copy_from_slice(&key.to_le_bytes());
+            data[offset + 56..offset + 64].copy_from_slice(&quantity.to_le_bytes());
+        }
+        data
+    }
+
+    fn loaded_pool() -> PoolData {
+        let adapter = OpenBookV2Adapter::default();
+        let mut pool = adapter.decode_pool(&create_test_pubkey(9), &market_data()).unwrap();
+        let account = |data: Vec<u8>| Account { data, owner: OPENBOOK_V2_PROGRAM_ID, ..Account::default() };
+        let accounts = HashMap::from([
+            (create_test_pubkey(2), account(book_side_data([(100, 10), (99, 10), (100, 5)]))),
+            (create_test_pubkey(3), account(book_side_data([(101, 10), (102, 10), (103, 10)]))),
+        ]);
+        adapter.apply_dependent_accounts(&mut pool, &accounts).unwrap();
+        pool
+    }
+
+    #[test]
+    fn test_decode_market_and_book_sides() {
+        let adapter = OpenBookV2Adapter::default();
+        let pool = loaded_pool();
+
+        assert_eq!(pool.token_a, create_test_pubkey(5));
+        assert_eq!(pool.fee_bps, 4);
+        assert_eq!(adapter.dependent_accounts(&pool), vec![create_test_pubkey(2), create_test_pubkey(3)]);
+        assert!(adapter.is_tradable(&pool));
+
+        let book = adapter.book(&pool).unwrap();
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.bids[0].base_lots, 15);
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(pool.reserve_a, 30_000);
+    }
+
+    #[test]
+    fn test_quote_walks_book_with_taker_fee() {
+        let adapter = OpenBookV2Adapter::default();
+        let pool = loaded_pool();
+
+        // Sell 20 lots: 15 @ 100 + 5 @ 99 = 1995 quote lots, fee ceil(0.798) = 1
+        assert_eq!(adapter.quote_exact_in(&pool, 20_000, true).unwrap(), 1_994 * 10);
+        assert!(adapter.quote_exact_in(&pool, 26_000, true).is_err());
+
+        // Buy with 2000 quote lots: 1999 matchable, 10 @ 101 + 9 @ 102
+        assert_eq!(adapter.quote_exact_in(&pool, 20_000, false).unwrap(), 19_000);
+
+        let depth = adapter.liquidity_depth(&pool, false);
+        assert_eq!(depth.len(), 3);
+        assert!((depth[0].price - 1.0 / 1.01).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let adapter = OpenBookV2Adapter::default();
+        let pool = loaded_pool();
+        let params = SwapParams {
+            user: create_test_pubkey(10),
+            user_source: create_test_pubkey(11),
+            user_destination: create_test_pubkey(12),
+            amount_in: 10_000,
+            minimum_out: 9_900,
+            a_to_b: true,
+        };
+
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        assert_eq!(ix.accounts.len(), 16);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[4].pubkey, create_test_pubkey(2));
+        assert_eq!(ix.accounts[9].pubkey, params.user_source); // user base account
+        assert_eq!(ix.accounts[11].pubkey, OPENBOOK_V2_PROGRAM_ID); // no oracle
+
+        assert_eq!(&ix.data[..8], &PLACE_TAKE_ORDER_DISCRIMINATOR);
+        assert_eq!(ix.data[8], SIDE_ASK);
+        // 990 quote lots after a 4 bps fee need 991 gross over 10 lots: at least 100 per lot
+        assert_eq!(i64::from_le_bytes(ix.data[9..17].try_into().unwrap()), 100);
+        assert_eq!(i64::from_le_bytes(ix.data[17..25].try_into().unwrap()), 10);
+        assert_eq!(ix.data.len(), 35);
+    }
+}
@@ -0,0 +1,457 @@
+// Orca v1 (Legacy) Adapter
+//
+// Decodes legacy Orca token-swap pools (the SPL token-swap `SwapV1` layout)
+// and builds the `Swap` instruction with the pool's authority PDA.
+//
+// The swap account only references its token accounts, so reserves are read
+// from those accounts in `apply_dependent_accounts`.
+
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use tracing::debug;
+
+use crate::dex::adapter::{read_pubkey, read_u64, token_account_amount, DexAdapter, MintLayout, SwapParams, VenueState};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::DexType;
+
+/// Orca v1 token-swap program id
+pub const ORCA_V1_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
+
+/// Token-swap `Swap` instruction tag
+const SWAP_TAG: u8 = 1;
+
+/// Size of a token-swap account (1-byte version + packed `SwapV1`)
+pub const SWAP_V1_LEN: usize = 324;
+
+/// Curve types of the token-swap program
+pub const CURVE_CONSTANT_PRODUCT: u8 = 0;
+pub const CURVE_CONSTANT_PRICE: u8 = 1;
+pub const CURVE_STABLE: u8 = 2;
+pub const CURVE_OFFSET: u8 = 3;
+
+/// Fee fractions stored in the swap account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SwapFees {
+    /// Fee kept by liquidity providers, taken from the input
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    /// Fee paid to the pool fee account, taken from the input
+    pub owner_trade_fee_numerator: u64,
+    pub owner_trade_fee_denominator: u64,
+    pub owner_withdraw_fee_numerator: u64,
+    pub owner_withdraw_fee_denominator: u64,
+    pub host_fee_numerator: u64,
+    pub host_fee_denominator: u64,
+}
+
+impl SwapFees {
+    /// Trade plus owner fee in basis points (rounded down)
+    pub fn total_fee_bps(&self) -> u16 {
+        let bps = |numerator: u64, denominator: u64| {
+            if denominator == 0 {
+                0
+            } else {
+                numerator as u128 * 10_000 / denominator as u128
+            }
+        };
+        let total = bps(self.trade_fee_numerator, self.trade_fee_denominator)
+            + bps(self.owner_trade_fee_numerator, self.owner_trade_fee_denominator);
+        total.min(u16::MAX as u128) as u16
+    }
+
+    /// Trade plus owner fee; the owner fee is the protocol's share
+    pub fn fee_model(&self) -> FeeModel {
+        let owner = FeeRate::from_fraction(self.owner_trade_fee_numerator as u128, self.owner_trade_fee_denominator as u128);
+        let trade = FeeRate::from_fraction(self.trade_fee_numerator as u128, self.trade_fee_denominator as u128)
+            .saturating_add(owner);
+        FeeModel::Static {
+            trade,
+            protocol_share: FeeRate::from_fraction(owner.billionths() as u128, trade.billionths() as u128),
+        }
+    }
+}
+
+/// SPL token-swap `SwapV1` account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SwapV1 {
+    pub is_initialized: bool,
+    /// Bump seed of the swap authority PDA
+    pub bump_seed: u8,
+    pub token_program_id: Pubkey,
+    /// Pool token account holding token A
+    pub token_a: Pubkey,
+    /// Pool token account holding token B
+    pub token_b: Pubkey,
+    pub pool_mint: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    /// Token account receiving owner fees in pool tokens
+    pub pool_fee_account: Pubkey,
+    pub fees: SwapFees,
+    pub curve_type: u8,
+}
+
+impl SwapV1 {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: spl-token-swap `state.rs` (`SwapVersion`, `SwapV1`, `Fees`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < SWAP_V1_LEN {
+            return Err(anyhow!("Invalid Orca pool account size: {} bytes", data.len()));
+        }
+        if data[0] != 1 {
+            return Err(anyhow!("Unsupported token-swap version {}", data[0]));
+        }
+
+        Ok(Self {
+            is_initialized: data[1] != 0,
+            bump_seed: data[2],
+            token_program_id: read_pubkey(data, 3)?,
+            token_a: read_pubkey(data, 35)?,
+            token_b: read_pubkey(data, 67)?,
+            pool_mint: read_pubkey(data, 99)?,
+            token_a_mint: read_pubkey(data, 131)?,
+            token_b_mint: read_pubkey(data, 163)?,
+            pool_fee_account: read_pubkey(data, 195)?,
+            fees: SwapFees {
+                trade_fee_numerator: read_u64(data, 227)?,
+                trade_fee_denominator: read_u64(data, 235)?,
+                owner_trade_fee_numerator: read_u64(data, 243)?,
+                owner_trade_fee_denominator: read_u64(data, 251)?,
+                owner_withdraw_fee_numerator: read_u64(data, 259)?,
+                owner_withdraw_fee_denominator: read_u64(data, 267)?,
+                host_fee_numerator: read_u64(data, 275)?,
+                host_fee_denominator: read_u64(data, 283)?,
+            },
+            curve_type: data[291],
+        })
+    }
+
+    /// Swap authority PDA: `[swap, bump_seed]`
+    pub fn authority(&self, swap: &Pubkey, program_id: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(&[swap.as_ref(), &[self.bump_seed]], program_id)
+            .map_err(|e| anyhow!("Invalid Orca authority bump {}: {}", self.bump_seed, e))
+    }
+
+    /// Exact constant-product output, reproducing the token-swap program:
+    /// trade and owner fees are each taken from the input (at least 1 when
+    /// non-zero), and the new destination balance is rounded up.
+    pub fn swap_exact_in(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        if self.curve_type != CURVE_CONSTANT_PRODUCT {
+            return Err(anyhow!("Unsupported token-swap curve type {}", self.curve_type));
+        }
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(anyhow!("Pool has empty reserves"));
+        }
+
+        let amount_in = amount_in as u128;
+        let trade_fee = calculate_fee(amount_in, self.fees.trade_fee_numerator, self.fees.trade_fee_denominator)?;
+        let owner_fee = calculate_fee(
+            amount_in,
+            self.fees.owner_trade_fee_numerator,
+            self.fees.owner_trade_fee_denominator,
+        )?;
+        let amount_after_fees = amount_in
+            .checked_sub(trade_fee + owner_fee)
+            .ok_or_else(|| anyhow!("Swap amount {} does not cover fees", amount_in))?;
+
+        let invariant = reserve_in as u128 * reserve_out as u128;
+        let new_reserve_in = reserve_in as u128 + amount_after_fees;
+        let new_reserve_out = ceil_div(invariant, new_reserve_in);
+
+        Ok((reserve_out as u128).saturating_sub(new_reserve_out) as u64)
+    }
+}
+
+/// Token-swap fee: `amount * numerator / denominator`, at least 1 when the
+/// fee is non-zero
+pub(crate) fn calculate_fee(amount: u128, numerator: u64, denominator: u64) -> Result<u128> {
+    if numerator == 0 || amount == 0 {
+        return Ok(0);
+    }
+    if denominator == 0 {
+        return Err(anyhow!("Fee denominator is zero"));
+    }
+    let fee = amount * numerator as u128 / denominator as u128;
+    Ok(fee.max(1))
+}
+
+/// Quotient of `CheckedCeilDiv` as used by the constant-product curve
+pub(crate) fn ceil_div(dividend: u128, divisor: u128) -> u128 {
+    let quotient = dividend / divisor;
+    if quotient == 0 {
+        // The program rounds a tiny quotient to 0 or 1 instead of failing
+        return if dividend * 2 >= divisor { 1 } else { 0 };
+    }
+    dividend.div_ceil(divisor)
+}
+
+/// Decoded Orca v1 pool
+#[derive(Clone, Debug)]
+pub struct OrcaPoolState {
+    pub swap: SwapV1,
+}
+
+pub struct OrcaAdapter {
+    program_id: Pubkey,
+}
+
+impl OrcaAdapter {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a OrcaPoolState> {
+        match &pool.venue {
+            VenueState::Orca(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Orca state", pool.pool_address)),
+        }
+    }
+}
+
+impl Default for OrcaAdapter {
+    fn default() -> Self {
+        Self::new(ORCA_V1_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for OrcaAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::Orca
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: Some(SWAP_V1_LEN as u64),
+            mint_a_offset: 131, // token_a_mint
+            mint_b_offset: 163, // token_b_mint
+        })
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let swap = SwapV1::decode(data)?;
+
+        if !swap.is_initialized {
+            return Err(anyhow!("Orca pool {} is not initialized", address));
+        }
+        if swap.curve_type != CURVE_CONSTANT_PRODUCT {
+            return Err(anyhow!("Orca pool {} uses unsupported curve type {}", address, swap.curve_type));
+        }
+
+        debug!(
+            "Parsed Orca v1 pool: mints=({}, {}), trade fee={}/{}, owner fee={}/{}",
+            swap.token_a_mint,
+            swap.token_b_mint,
+            swap.fees.trade_fee_numerator,
+            swap.fees.trade_fee_denominator,
+            swap.fees.owner_trade_fee_numerator,
+            swap.fees.owner_trade_fee_denominator
+        );
+
+        // Reserves are filled from the token accounts in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: swap.token_a_mint,
+            token_b: swap.token_b_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: swap.fees.total_fee_bps(),
+            dex_type: DexType::Orca,
+            program_id: self.program_id,
+            venue: VenueState::Orca(Box::new(OrcaPoolState { swap })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        match self.state(pool) {
+            Ok(state) => vec![state.swap.token_a, state.swap.token_b],
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let swap = &self.state(pool)?.swap;
+
+        let token_a = accounts.get(&swap.token_a)
+            .ok_or_else(|| anyhow!("Missing token A account {} for pool {}", swap.token_a, pool.pool_address))?;
+        let token_b = accounts.get(&swap.token_b)
+            .ok_or_else(|| anyhow!("Missing token B account {} for pool {}", swap.token_b, pool.pool_address))?;
+
+        let reserve_a = token_account_amount(token_a)?;
+        let reserve_b = token_account_amount(token_b)?;
+
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+
+        debug!("Orca v1 pool {} reserves: ({}, {})", pool.pool_address, reserve_a, reserve_b);
+
+        Ok(())
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let (reserve_in, reserve_out) = pool.reserves(a_to_b);
+        self.state(pool)?.swap.swap_exact_in(amount_in, reserve_in, reserve_out)
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(state) => state.swap.fees.fee_model(),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let swap = &self.state(pool)?.swap;
+        let (swap_source, swap_destination) = if params.a_to_b {
+            (swap.token_a, swap.token_b)
+        } else {
+            (swap.token_b, swap.token_a)
+        };
+
+        Ok(vec![
+            AccountMeta::new_readonly(pool.pool_address, false),
+            AccountMeta::new_readonly(swap.authority(&pool.pool_address, &self.program_id)?, false),
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new(params.user_source, false),
+            AccountMeta::new(swap_source, false),
+            AccountMeta::new(swap_destination, false),
+            AccountMeta::new(params.user_destination, false),
+            AccountMeta::new(swap.pool_mint, false),
+            AccountMeta::new(swap.pool_fee_account, false),
+            AccountMeta::new_readonly(swap.token_program_id, false),
+        ])
+    }
+
+    fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let mut data = vec![SWAP_TAG];
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn swap_account_data(bump_seed: u8, curve_type: u8) -> Vec<u8> {
+        let mut data = vec![0u8; SWAP_V1_LEN];
+        data[0] = 1;
+        data[1] = 1;
+        data[2] = bump_seed;
+        for (offset, seed) in [(3, 3u8), (35, 35), (67, 67), (99, 99), (131, 131), (163, 163), (195, 195)] {
+            data[offset..offset + 32].copy_from_slice(pubkey(seed).as_ref());
+        }
+        let fees = [25u64, 10_000, 5, 10_000, 0, 0, 0, 0];
+        for (i, value) in fees.iter().enumerate() {
+            data[227 + i * 8..235 + i * 8].copy_from_slice(&value.to_le_bytes());
+        }
+        data[291] = curve_type;
+        data
+    }
+
+    fn valid_bump(swap: &Pubkey) -> u8 {
+        Pubkey::find_program_address(&[swap.as_ref()], &ORCA_V1_PROGRAM_ID).1
+    }
+
+    fn token_account(amount: u64) -> Account {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Account { data, ..Default::default() }
+    }
+
+    #[test]
+    fn test_swap_v1_decode() {
+        let swap = SwapV1::decode(&swap_account_data(254, CURVE_CONSTANT_PRODUCT)).unwrap();
+        assert!(swap.is_initialized);
+        assert_eq!(swap.bump_seed, 254);
+        assert_eq!(swap.token_program_id, pubkey(3));
+        assert_eq!(swap.token_a, pubkey(35));
+        assert_eq!(swap.token_b, pubkey(67));
+        assert_eq!(swap.pool_mint, pubkey(99));
+        assert_eq!(swap.token_a_mint, pubkey(131));
+        assert_eq!(swap.token_b_mint, pubkey(163));
+        assert_eq!(swap.pool_fee_account, pubkey(195));
+        assert_eq!(swap.fees.trade_fee_numerator, 25);
+        assert_eq!(swap.fees.owner_trade_fee_numerator, 5);
+        assert_eq!(swap.fees.total_fee_bps(), 30);
+
+        assert!(SwapV1::decode(&[0u8; 200]).is_err());
+    }
+
+    #[test]
+    fn test_swap_exact_in_matches_token_swap() {
+        let swap = SwapV1::decode(&swap_account_data(254, CURVE_CONSTANT_PRODUCT)).unwrap();
+
+        // 1000 in: trade fee 2, owner fee 1 (minimum), 997 after fees
+        // invariant = 2e12, new source = 1_000_997, new dest = ceil(2e12 / 1_000_997) = 1_998_008
+        assert_eq!(swap.swap_exact_in(1000, 1_000_000, 2_000_000).unwrap(), 1992);
+
+        assert!(swap.swap_exact_in(1000, 0, 2_000_000).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_curve_rejected() {
+        let data = swap_account_data(254, CURVE_STABLE);
+        let adapter = OrcaAdapter::default();
+        assert!(adapter.decode_pool(&pubkey(1), &data).is_err());
+    }
+
+    #[test]
+    fn test_reserves_and_swap_accounts() {
+        let address = pubkey(1);
+        let adapter = OrcaAdapter::default();
+        let mut pool = adapter
+            .decode_pool(&address, &swap_account_data(valid_bump(&address), CURVE_CONSTANT_PRODUCT))
+            .unwrap();
+        assert_eq!(adapter.dependent_accounts(&pool), vec![pubkey(35), pubkey(67)]);
+
+        let accounts = HashMap::from([(pubkey(35), token_account(1_000_000)), (pubkey(67), token_account(2_000_000))]);
+        adapter.apply_dependent_accounts(&mut pool, &accounts).unwrap();
+        assert_eq!((pool.reserve_a, pool.reserve_b), (1_000_000, 2_000_000));
+        assert_eq!(adapter.quote_exact_in(&pool, 1000, true).unwrap(), 1992);
+
+        let params = SwapParams {
+            user: pubkey(200),
+            user_source: pubkey(201),
+            user_destination: pubkey(202),
+            amount_in: 1000,
+            minimum_out: 1980,
+            a_to_b: false,
+        };
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        let authority = Pubkey::find_program_address(&[address.as_ref()], &ORCA_V1_PROGRAM_ID).0;
+        assert_eq!(
+            keys,
+            vec![
+                address,
+                authority,
+                pubkey(200),
+                pubkey(201),
+                pubkey(67),
+                pubkey(35),
+                pubkey(202),
+                pubkey(99),
+                pubkey(195),
+                pubkey(3),
+            ]
+        );
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(ix.data[0], SWAP_TAG);
+        assert_eq!(ix.data.len(), 17);
+    }
+}
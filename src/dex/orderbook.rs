@@ -0,0 +1,310 @@
+// Order Book Ladders
+//
+// Shared matching math for the order-book venues (Phoenix, OpenBook v2).
+// A book cannot be described by one rate, so each venue decodes its resting
+// orders into aggregated `BookLevel`s and quotes by walking them best price
+// first, the way the program's matching engine fills a taker order. The same
+// ladders are exported to the graph as `PriceLevel`s so slippage estimates use
+// the real depth.
+//
+// Prices are in venue lot units: filling `n` base lots at price `p` costs
+// `n * p * price_numerator / price_denominator` quote lots (rounded down).
+// The taker fee is charged on the quote side and rounded up.
+
+use anyhow::{Result, anyhow};
+
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::triangular_arb::PriceLevel;
+
+/// Resting size at one price
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookLevel {
+    /// Price in venue price units (ticks or price lots)
+    pub price: u64,
+    pub base_lots: u64,
+}
+
+/// Lot sizes, price scale and taker fee of one market
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BookUnits {
+    /// Base atoms per base lot
+    pub base_lot_size: u64,
+    /// Quote atoms per quote lot
+    pub quote_lot_size: u64,
+    pub price_numerator: u64,
+    pub price_denominator: u64,
+    pub taker_fee_numerator: u64,
+    pub taker_fee_denominator: u64,
+}
+
+impl BookUnits {
+    fn validate(&self) -> Result<()> {
+        if self.base_lot_size == 0 || self.quote_lot_size == 0 || self.price_denominator == 0 || self.taker_fee_denominator == 0 {
+            return Err(anyhow!("Order book market has zero lot size or scale: {:?}", self));
+        }
+        Ok(())
+    }
+
+    /// Quote lots exchanged for `base_lots` at `price`
+    pub fn quote_lots(&self, price: u64, base_lots: u64) -> u128 {
+        base_lots as u128 * price as u128 * self.price_numerator as u128 / self.price_denominator as u128
+    }
+
+    /// Taker fee on `quote_lots`, rounded up
+    pub fn taker_fee(&self, quote_lots: u128) -> u128 {
+        (quote_lots * self.taker_fee_numerator as u128).div_ceil(self.taker_fee_denominator as u128)
+    }
+
+    /// Taker fee in basis points (rounded up)
+    pub fn fee_bps(&self) -> u16 {
+        if self.taker_fee_denominator == 0 {
+            return 0;
+        }
+        (self.taker_fee_numerator as u128 * 10_000).div_ceil(self.taker_fee_denominator as u128) as u16
+    }
+
+    /// Taker fee, all of it kept by the venue
+    pub fn fee_model(&self) -> FeeModel {
+        FeeModel::Static {
+            trade: FeeRate::from_fraction(self.taker_fee_numerator as u128, self.taker_fee_denominator as u128),
+            protocol_share: FeeRate::ONE,
+        }
+    }
+
+    /// Quote atoms per base atom at `price`, before fees
+    pub fn price_in_atoms(&self, price: u64) -> f64 {
+        if self.price_denominator == 0 || self.base_lot_size == 0 {
+            return 0.0;
+        }
+        price as f64 * self.price_numerator as f64 / self.price_denominator as f64
+            * self.quote_lot_size as f64 / self.base_lot_size as f64
+    }
+}
+
+/// Aggregated bids (best first, descending) and asks (best first, ascending)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrderBook {
+    pub units: BookUnits,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    /// Build a book from individual resting orders `(price, base_lots)`,
+    /// merging orders at the same price
+    pub fn from_orders(units: BookUnits, bids: Vec<(u64, u64)>, asks: Vec<(u64, u64)>) -> Self {
+        Self {
+            units,
+            bids: aggregate(bids, true),
+            asks: aggregate(asks, false),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<u64> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    pub fn best_ask(&self) -> Option<u64> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    /// Base atoms resting on the asks
+    pub fn ask_base_atoms(&self) -> u64 {
+        let lots: u128 = self.asks.iter().map(|level| level.base_lots as u128).sum();
+        (lots * self.units.base_lot_size as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// Quote atoms resting on the bids
+    pub fn bid_quote_atoms(&self) -> u64 {
+        let lots: u128 = self.bids.iter()
+            .map(|level| self.units.quote_lots(level.price, level.base_lots))
+            .sum();
+        (lots * self.units.quote_lot_size as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// Quote atoms received for selling `amount_in` base atoms into the bids.
+    /// Input below one lot is not matched.
+    pub fn sell_base(&self, amount_in: u64) -> Result<u64> {
+        self.units.validate()?;
+        let mut remaining_lots = amount_in / self.units.base_lot_size;
+        let mut quote_lots: u128 = 0;
+
+        for level in &self.bids {
+            if remaining_lots == 0 {
+                break;
+            }
+            let filled = remaining_lots.min(level.base_lots);
+            quote_lots += self.units.quote_lots(level.price, filled);
+            remaining_lots -= filled;
+        }
+        if remaining_lots > 0 {
+            return Err(anyhow!("Order book bids too thin to sell {} base atoms", amount_in));
+        }
+
+        let received = (quote_lots - self.units.taker_fee(quote_lots)) * self.units.quote_lot_size as u128;
+        u64::try_from(received).map_err(|_| anyhow!("Order book quote output overflow"))
+    }
+
+    /// Base atoms received for spending up to `amount_in` quote atoms on the
+    /// asks. The fee is reserved from the budget before matching.
+    pub fn buy_base(&self, amount_in: u64) -> Result<u64> {
+        self.units.validate()?;
+        if self.asks.is_empty() {
+            return Err(anyhow!("Order book has no asks"));
+        }
+        let budget_lots = (amount_in / self.units.quote_lot_size) as u128;
+        let fee_denominator = self.units.taker_fee_denominator as u128;
+        let mut remaining_quote = budget_lots * fee_denominator / (fee_denominator + self.units.taker_fee_numerator as u128);
+        let mut base_lots: u128 = 0;
+
+        for level in &self.asks {
+            let lot_cost = level.price as u128 * self.units.price_numerator as u128;
+            if lot_cost == 0 {
+                continue;
+            }
+            let affordable = remaining_quote * self.units.price_denominator as u128 / lot_cost;
+            let filled = affordable.min(level.base_lots as u128) as u64;
+            if filled == 0 {
+                break;
+            }
+            remaining_quote -= self.units.quote_lots(level.price, filled);
+            base_lots += filled as u128;
+            if filled < level.base_lots {
+                // Budget exhausted inside this level
+                break;
+            }
+        }
+
+        u64::try_from(base_lots * self.units.base_lot_size as u128)
+            .map_err(|_| anyhow!("Order book base output overflow"))
+    }
+
+    /// Exact output for `amount_in`: selling base when `sell_base`, otherwise buying it
+    pub fn quote_exact_in(&self, amount_in: u64, sell_base: bool) -> Result<u64> {
+        if sell_base {
+            self.sell_base(amount_in)
+        } else {
+            self.buy_base(amount_in)
+        }
+    }
+
+    /// Ladder seen by a taker, in graph units: `price` is output atoms per
+    /// input atom before fees and `liquidity` the input atoms the level absorbs
+    pub fn price_levels(&self, sell_base: bool) -> Vec<PriceLevel> {
+        let units = &self.units;
+        if sell_base {
+            self.bids.iter()
+                .map(|level| PriceLevel {
+                    price: units.price_in_atoms(level.price),
+                    liquidity: level.base_lots.saturating_mul(units.base_lot_size),
+                })
+                .collect()
+        } else {
+            self.asks.iter()
+                .filter(|level| level.price > 0)
+                .map(|level| {
+                    let quote_atoms = units.quote_lots(level.price, level.base_lots) * units.quote_lot_size as u128;
+                    PriceLevel {
+                        price: 1.0 / units.price_in_atoms(level.price),
+                        liquidity: quote_atoms.min(u64::MAX as u128) as u64,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    /// Marginal rate before fees: quote per base when selling base, base per
+    /// quote when buying it
+    pub fn spot_rate(&self, sell_base: bool) -> f64 {
+        let price = if sell_base { self.best_bid() } else { self.best_ask() };
+        match price.map(|price| self.units.price_in_atoms(price)) {
+            Some(price) if price > 0.0 => if sell_base { price } else { 1.0 / price },
+            _ => 0.0,
+        }
+    }
+}
+
+/// Merge orders by price, best first
+fn aggregate(mut orders: Vec<(u64, u64)>, descending: bool) -> Vec<BookLevel> {
+    orders.retain(|(_, base_lots)| *base_lots > 0);
+    if descending {
+        orders.sort_by_key(|(price, _)| std::cmp::Reverse(*price));
+    } else {
+        orders.sort_by_key(|(price, _)| *price);
+    }
+
+    let mut levels: Vec<BookLevel> = Vec::new();
+    for (price, base_lots) in orders {
+        match levels.last_mut() {
+            Some(level) if level.price == price => level.base_lots += base_lots,
+            _ => levels.push(BookLevel { price, base_lots }),
+        }
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1 base lot = 1000 atoms, 1 quote lot = 10 atoms, price in quote lots per base lot, 10 bps fee
+    fn book() -> OrderBook {
+        let units = BookUnits {
+            base_lot_size: 1_000,
+            quote_lot_size: 10,
+            price_numerator: 1,
+            price_denominator: 1,
+            taker_fee_numerator: 10,
+            taker_fee_denominator: 10_000,
+        };
+        OrderBook::from_orders(
+            units,
+            vec![(99, 5), (100, 10), (98, 20), (100, 5)],
+            vec![(102, 10), (101, 10), (103, 50)],
+        )
+    }
+
+    #[test]
+    fn test_levels_are_aggregated_best_first() {
+        let book = book();
+        assert_eq!(book.bids, vec![
+            BookLevel { price: 100, base_lots: 15 },
+            BookLevel { price: 99, base_lots: 5 },
+            BookLevel { price: 98, base_lots: 20 },
+        ]);
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.units.fee_bps(), 10);
+        assert_eq!(book.units.fee_model().trade_fee(), FeeRate::from_bps(10));
+    }
+
+    #[test]
+    fn test_sell_walks_bids_and_charges_fee() {
+        let book = book();
+        // 20 lots: 15 @ 100 + 5 @ 99 = 1995 quote lots, fee ceil(1.995) = 2
+        assert_eq!(book.sell_base(20_000).unwrap(), 1_993 * 10);
+        // Dust below one lot is not matched
+        assert_eq!(book.sell_base(20_999).unwrap(), 1_993 * 10);
+        assert!(book.sell_base(41_000).is_err());
+    }
+
+    #[test]
+    fn test_buy_reserves_fee_from_budget() {
+        let book = book();
+        // 2000 quote lots, 1998 matchable after fee: 10 @ 101 + 9 @ 102
+        assert_eq!(book.buy_base(20_000).unwrap(), 19_000);
+        assert_eq!(book.buy_base(9).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_price_levels_in_graph_units() {
+        let book = book();
+        let bids = book.price_levels(true);
+        assert!((bids[0].price - 1.0).abs() < 1e-12); // 100 lots * 10 / 1000
+        assert_eq!(bids[0].liquidity, 15_000);
+
+        let asks = book.price_levels(false);
+        assert!((asks[0].price - 1.0 / 1.01).abs() < 1e-12);
+        assert_eq!(asks[0].liquidity, 10_100);
+        assert!((book.spot_rate(false) - asks[0].price).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,457 @@
+// Phoenix Adapter
+//
+// Decodes Phoenix v1 markets into order-book ladders and builds the `Swap`
+// instruction (an immediate-or-cancel taker order).
+//
+// A Phoenix market account holds its whole book: a 576-byte `MarketHeader`
+// followed by a `FIFOMarket` whose bids and asks are sokoban red-black trees
+// of resting orders. The tree sizes come from the header's size parameters.
+// The adapter walks both trees in order, aggregates the orders by price and
+// quotes by matching against the ladder with Phoenix's taker fee. Pool token A
+// is the market's base mint and token B its quote mint, so `a_to_b` sells
+// base into the bids and `b_to_a` buys base from the asks.
+//
+// Resting orders with a time or slot expiry are counted as live; the program
+// skips them when matching, so quotes can be slightly optimistic on books that
+// use expiring orders.
+
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+use anyhow::{Result, anyhow};
+use tracing::debug;
+
+use crate::dex::adapter::{read_pubkey, read_u32, read_u64, DexAdapter, MintLayout, SwapParams, VenueState};
+use crate::dex::fees::FeeModel;
+use crate::dex::orderbook::{BookUnits, OrderBook};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::{DexType, PriceLevel};
+
+/// Phoenix v1 program id
+pub const PHOENIX_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+
+/// Size of the `MarketHeader`
+pub const MARKET_HEADER_LEN: usize = 576;
+
+/// `FIFOMarket` fields before the bids tree
+const FIFO_MARKET_PREFIX_LEN: usize = 304;
+
+/// Red-black tree header plus node allocator header
+const TREE_HEADER_LEN: usize = 32;
+
+/// One tree node: 4 registers, `FIFOOrderId` key, `FIFORestingOrder` value
+const TREE_NODE_LEN: usize = 64;
+
+/// Node registers
+const REGISTER_LEFT: usize = 0;
+const REGISTER_RIGHT: usize = 1;
+
+/// Phoenix `Swap` instruction tag
+const SWAP_INSTRUCTION: u8 = 0;
+
+/// `OrderPacket::ImmediateOrCancel` variant
+const ORDER_PACKET_IMMEDIATE_OR_CANCEL: u8 = 2;
+
+/// `Side` values
+const SIDE_BID: u8 = 0;
+const SIDE_ASK: u8 = 1;
+
+/// `SelfTradeBehavior::CancelProvide`
+const SELF_TRADE_CANCEL_PROVIDE: u8 = 1;
+
+/// `MarketStatus::Active`: the only status that accepts taker orders
+const MARKET_STATUS_ACTIVE: u64 = 1;
+
+/// Phoenix market: header fields needed to trade and the decoded book
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PhoenixMarket {
+    pub status: u64,
+    pub bids_size: u64,
+    pub asks_size: u64,
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_lot_size: u64,
+    pub base_lots_per_base_unit: u64,
+    pub tick_size_in_quote_lots_per_base_unit: u64,
+    pub taker_fee_bps: u64,
+    pub book: OrderBook,
+}
+
+impl PhoenixMarket {
+    /// Decode a market account, including every resting order
+    ///
+    /// Layout reference: phoenix-v1 `program/accounts.rs`,
+    /// `state/markets/fifo.rs` and sokoban `red_black_tree.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < MARKET_HEADER_LEN + FIFO_MARKET_PREFIX_LEN {
+            return Err(anyhow!("Invalid Phoenix market account size: {} bytes", data.len()));
+        }
+
+        let bids_size = read_u64(data, 16)?;
+        let asks_size = read_u64(data, 24)?;
+        let base_lot_size = read_u64(data, 112)?;
+        let quote_lot_size = read_u64(data, 192)?;
+
+        let market = MARKET_HEADER_LEN;
+        let base_lots_per_base_unit = read_u64(data, market + 256)?;
+        let tick_size_in_quote_lots_per_base_unit = read_u64(data, market + 264)?;
+        let taker_fee_bps = read_u64(data, market + 280)?;
+
+        let bids_offset = market + FIFO_MARKET_PREFIX_LEN;
+        let asks_offset = bids_offset + tree_len(bids_size)?;
+        if data.len() < asks_offset + tree_len(asks_size)? {
+            return Err(anyhow!(
+                "Phoenix market account too short for {} bids and {} asks: {} bytes",
+                bids_size, asks_size, data.len()
+            ));
+        }
+
+        let units = BookUnits {
+            base_lot_size,
+            quote_lot_size,
+            price_numerator: tick_size_in_quote_lots_per_base_unit,
+            price_denominator: base_lots_per_base_unit,
+            taker_fee_numerator: taker_fee_bps,
+            taker_fee_denominator: 10_000,
+        };
+        let book = OrderBook::from_orders(
+            units,
+            read_orders(data, bids_offset, bids_size as usize)?,
+            read_orders(data, asks_offset, asks_size as usize)?,
+        );
+
+        Ok(Self {
+            status: read_u64(data, 8)?,
+            bids_size,
+            asks_size,
+            base_mint: read_pubkey(data, 48)?,
+            base_vault: read_pubkey(data, 80)?,
+            quote_mint: read_pubkey(data, 128)?,
+            quote_vault: read_pubkey(data, 160)?,
+            base_lot_size,
+            quote_lot_size,
+            base_lots_per_base_unit,
+            tick_size_in_quote_lots_per_base_unit,
+            taker_fee_bps,
+            book,
+        })
+    }
+
+    /// Whether the market accepts taker orders
+    pub fn is_active(&self) -> bool {
+        self.status == MARKET_STATUS_ACTIVE
+    }
+}
+
+/// Size of a red-black tree with `capacity` nodes
+fn tree_len(capacity: u64) -> Result<usize> {
+    usize::try_from(capacity)
+        .ok()
+        .and_then(|capacity| capacity.checked_mul(TREE_NODE_LEN))
+        .and_then(|nodes| nodes.checked_add(TREE_HEADER_LEN))
+        .ok_or_else(|| anyhow!("Invalid Phoenix tree capacity: {}", capacity))
+}
+
+/// Resting orders `(price_in_ticks, num_base_lots)` of the tree at `offset`,
+/// visited in order from its root. Node addresses are 1-based; 0 is the sentinel.
+fn read_orders(data: &[u8], offset: usize, capacity: usize) -> Result<Vec<(u64, u64)>> {
+    let root = read_u32(data, offset)? as usize;
+    let nodes = offset + TREE_HEADER_LEN;
+    let node_offset = |address: usize| -> Result<usize> {
+        if address == 0 || address > capacity {
+            return Err(anyhow!("Phoenix tree node {} out of range (capacity {})", address, capacity));
+        }
+        Ok(nodes + (address - 1) * TREE_NODE_LEN)
+    };
+    let register = |address: usize, register: usize| -> Result<usize> {
+        Ok(read_u32(data, node_offset(address)? + register * 4)? as usize)
+    };
+
+    let mut orders = Vec::new();
+    let mut stack = Vec::new();
+    let mut current = root;
+    while current != 0 || !stack.is_empty() {
+        while current != 0 {
+            if stack.len() > capacity {
+                return Err(anyhow!("Phoenix order tree is malformed"));
+            }
+            stack.push(current);
+            current = register(current, REGISTER_LEFT)?;
+        }
+        if let Some(address) = stack.pop() {
+            let node = node_offset(address)?;
+            // key: price_in_ticks, order_sequence_number; value: trader_index, num_base_lots, ...
+            orders.push((read_u64(data, node + 16)?, read_u64(data, node + 40)?));
+            if orders.len() > capacity {
+                return Err(anyhow!("Phoenix order tree is malformed"));
+            }
+            current = register(address, REGISTER_RIGHT)?;
+        }
+    }
+    Ok(orders)
+}
+
+/// Decoded Phoenix market
+#[derive(Clone, Debug)]
+pub struct PhoenixMarketState {
+    pub market: PhoenixMarket,
+}
+
+/// Phoenix log authority PDA (`[b"log"]`)
+pub fn log_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"log"], program_id).0
+}
+
+pub struct PhoenixAdapter {
+    program_id: Pubkey,
+}
+
+impl PhoenixAdapter {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a PhoenixMarketState> {
+        match &pool.venue {
+            VenueState::Phoenix(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Phoenix market state", pool.pool_address)),
+        }
+    }
+}
+
+impl Default for PhoenixAdapter {
+    fn default() -> Self {
+        Self::new(PHOENIX_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for PhoenixAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::Phoenix
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: None,
+            mint_a_offset: 48, // base_mint
+            mint_b_offset: 128, // quote_mint
+        })
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let market = PhoenixMarket::decode(data)?;
+
+        debug!(
+            "Parsed Phoenix market: base={}, quote={}, {} bid / {} ask levels, taker fee {} bps",
+            market.base_mint, market.quote_mint, market.book.bids.len(), market.book.asks.len(), market.taker_fee_bps
+        );
+
+        // "Reserves" of a book: base offered on the asks, quote bid on the bids
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: market.base_mint,
+            token_b: market.quote_mint,
+            reserve_a: market.book.ask_base_atoms(),
+            reserve_b: market.book.bid_quote_atoms(),
+            fee_bps: market.book.units.fee_bps(),
+            dex_type: DexType::Phoenix,
+            program_id: self.program_id,
+            venue: VenueState::Phoenix(Box::new(PhoenixMarketState { market })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn is_tradable(&self, pool: &PoolData) -> bool {
+        self.state(pool).map(|state| state.market.is_active()).unwrap_or(false)
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        self.state(pool)?.market.book.quote_exact_in(amount_in, a_to_b)
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(state) => state.market.book.units.fee_model(),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn spot_rate(&self, pool: &PoolData, a_to_b: bool) -> f64 {
+        self.state(pool)
+            .map(|state| state.market.book.spot_rate(a_to_b))
+            .unwrap_or(0.0)
+    }
+
+    fn liquidity_depth(&self, pool: &PoolData, a_to_b: bool) -> Vec<PriceLevel> {
+        self.state(pool)
+            .map(|state| state.market.book.price_levels(a_to_b))
+            .unwrap_or_default()
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let market = &self.state(pool)?.market;
+        let (base_account, quote_account) = if params.a_to_b {
+            (params.user_source, params.user_destination)
+        } else {
+            (params.user_destination, params.user_source)
+        };
+
+        Ok(vec![
+            AccountMeta::new_readonly(self.program_id, false),
+            AccountMeta::new_readonly(log_authority(&self.program_id), false),
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new(base_account, false),
+            AccountMeta::new(quote_account, false),
+            AccountMeta::new(market.base_vault, false),
+            AccountMeta::new(market.quote_vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ])
+    }
+
+    fn swap_data(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let market = &self.state(pool)?.market;
+        if market.base_lot_size == 0 || market.quote_lot_size == 0 {
+            return Err(anyhow!("Phoenix market {} has zero lot size", pool.pool_address));
+        }
+
+        // Selling base: size in base lots, minimum fill in quote lots; buying is the mirror
+        let (side, num_base_lots, num_quote_lots, min_base_lots, min_quote_lots) = if params.a_to_b {
+            (SIDE_ASK, params.amount_in / market.base_lot_size, 0, 0, params.minimum_out.div_ceil(market.quote_lot_size))
+        } else {
+            (SIDE_BID, 0, params.amount_in / market.quote_lot_size, params.minimum_out.div_ceil(market.base_lot_size), 0)
+        };
+
+        let mut data = vec![SWAP_INSTRUCTION, ORDER_PACKET_IMMEDIATE_OR_CANCEL, side];
+        data.push(0); // price_in_ticks: None (take any price)
+        data.extend_from_slice(&num_base_lots.to_le_bytes());
+        data.extend_from_slice(&num_quote_lots.to_le_bytes());
+        data.extend_from_slice(&min_base_lots.to_le_bytes());
+        data.extend_from_slice(&min_quote_lots.to_le_bytes());
+        data.push(SELF_TRADE_CANCEL_PROVIDE);
+        data.push(0); // match_limit: None
+        data.extend_from_slice(&0u128.to_le_bytes()); // client_order_id
+        data.push(0); // use_only_deposited_funds
+        data.push(0); // last_valid_slot: None
+        data.push(0); // last_valid_unix_timestamp_in_seconds: None
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPACITY: u64 = 8;
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    /// Write a tree holding `orders` as a right-leaning chain in insertion
+    /// order; in-order traversal visits them in that order
+    fn write_tree(data: &mut [u8], offset: usize, orders: &[(u64, u64)]) {
+        let root: u32 = if orders.is_empty() { 0 } else { 1 };
+        data[offset..offset + 4].copy_from_slice(&root.to_le_bytes());
+        for (i, (price, lots)) in orders.iter().enumerate() {
+            let node = offset + TREE_HEADER_LEN + i * TREE_NODE_LEN;
+            let right: u32 = if i + 1 < orders.len() { i as u32 + 2 } else { 0 };
+            data[node + 4..node + 8].copy_from_slice(&right.to_le_bytes());
+            data[node + 16..node + 24].copy_from_slice(&price.to_le_bytes());
+            data[node + 40..node + 48].copy_from_slice(&lots.to_le_bytes());
+        }
+    }
+
+    fn market_data(bids: &[(u64, u64)], asks: &[(u64, u64)]) -> Vec<u8> {
+        let bids_offset = MARKET_HEADER_LEN + FIFO_MARKET_PREFIX_LEN;
+        let asks_offset = bids_offset + tree_len(CAPACITY).unwrap();
+        let mut data = vec![0u8; asks_offset + 2 * tree_len(CAPACITY).unwrap()];
+
+        data[8..16].copy_from_slice(&MARKET_STATUS_ACTIVE.to_le_bytes());
+        data[16..24].copy_from_slice(&CAPACITY.to_le_bytes());
+        data[24..32].copy_from_slice(&CAPACITY.to_le_bytes());
+        data[48..80].copy_from_slice(create_test_pubkey(1).as_ref()); // base mint
+        data[80..112].copy_from_slice(create_test_pubkey(2).as_ref()); // base vault
+        data[112..120].copy_from_slice(&1_000u64.to_le_bytes()); // base lot size
+        data[128..160].copy_from_slice(create_test_pubkey(3).as_ref()); // quote mint
+        data[160..192].copy_from_slice(create_test_pubkey(4).as_ref()); // quote vault
+        data[192..200].copy_from_slice(&1u64.to_le_bytes()); // quote lot size
+
+        let market = MARKET_HEADER_LEN;
+        data[market + 256..market + 264].copy_from_slice(&1_000u64.to_le_bytes()); // base lots per unit
+        data[market + 264..market + 272].copy_from_slice(&1_000u64.to_le_bytes()); // tick size
+        data[market + 280..market + 288].copy_from_slice(&5u64.to_le_bytes()); // taker fee bps
+
+        write_tree(&mut data, bids_offset, bids);
+        write_tree(&mut data, asks_offset, asks);
+        data
+    }
+
+    fn decoded_pool(bids: &[(u64, u64)], asks: &[(u64, u64)]) -> PoolData {
+        PhoenixAdapter::default().decode_pool(&create_test_pubkey(9), &market_data(bids, asks)).unwrap()
+    }
+
+    #[test]
+    fn test_decode_market_book() {
+        let pool = decoded_pool(&[(99, 10), (100, 5), (100, 5)], &[(101, 20)]);
+        let VenueState::Phoenix(state) = &pool.venue else { panic!("expected Phoenix state") };
+        let book = &state.market.book;
+
+        assert_eq!(pool.token_a, create_test_pubkey(1));
+        assert_eq!(pool.token_b, create_test_pubkey(3));
+        assert_eq!(pool.fee_bps, 5);
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.bids[0].base_lots, 10);
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(pool.reserve_a, 20_000);
+    }
+
+    #[test]
+    fn test_quote_walks_book_with_taker_fee() {
+        let adapter = PhoenixAdapter::default();
+        let pool = decoded_pool(&[(100, 10), (99, 10)], &[(101, 10), (102, 10)]);
+
+        // Sell 15 lots: 10 @ 100 + 5 @ 99 ticks = 1495 quote lots, fee ceil(0.7475) = 1
+        assert_eq!(adapter.quote_exact_in(&pool, 15_000, true).unwrap(), 1_494);
+
+        // Buy with 2000 quote: 1999 matchable after fee, 10 @ 101 + 9 @ 102
+        assert_eq!(adapter.quote_exact_in(&pool, 2_000, false).unwrap(), 19_000);
+
+        let depth = adapter.liquidity_depth(&pool, true);
+        assert_eq!(depth.len(), 2);
+        assert!((depth[0].price - 0.1).abs() < 1e-12);
+        assert!((adapter.spot_rate(&pool, false) - 1.0 / 0.101).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let adapter = PhoenixAdapter::default();
+        let pool = decoded_pool(&[(100, 10)], &[(101, 10)]);
+        let params = SwapParams {
+            user: create_test_pubkey(10),
+            user_source: create_test_pubkey(11),
+            user_destination: create_test_pubkey(12),
+            amount_in: 5_500,
+            minimum_out: 490,
+            a_to_b: true,
+        };
+
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        assert_eq!(ix.accounts.len(), 9);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[4].pubkey, params.user_source); // base account
+        assert_eq!(ix.accounts[6].pubkey, create_test_pubkey(2));
+
+        assert_eq!(&ix.data[..4], &[SWAP_INSTRUCTION, ORDER_PACKET_IMMEDIATE_OR_CANCEL, SIDE_ASK, 0]);
+        assert_eq!(u64::from_le_bytes(ix.data[4..12].try_into().unwrap()), 5); // base lots
+        assert_eq!(u64::from_le_bytes(ix.data[28..36].try_into().unwrap()), 490); // min quote lots
+        assert_eq!(ix.data.len(), 57);
+    }
+}
@@ -3,12 +3,8 @@
 // This module implements batch fetching of pool accounts from multiple DEXs
 // with intelligent caching, retry logic, and multi-RPC failover support.
 //
-// Supported DEXs:
-// - Raydium AMM v4
-// - Meteora DAMM (Dynamic AMM)
-// - Meteora Vault
-// - Orca Whirlpool (v2)
-// - Orca v1 (legacy)
+// Pool accounts are decoded by the venue adapter registered for their owner
+// program (see `dex::adapter::DexRegistry`).
 
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_client::rpc_config::RpcAccountInfoConfig;
@@ -20,18 +16,13 @@ use anyhow::{Result, anyhow};
 use tracing::{debug, warn, info, error};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::dex::adapter::{DexRegistry, VenueState};
+use crate::dex::token_2022::PoolMints;
 use crate::dex::triangular_arb::DexType;
 
 // Solana RPC batch size limit
 const MAX_BATCH_SIZE: usize = 100;
 
-// DEX Program IDs
-const RAYDIUM_AMM_V4: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
-const METEORA_DAMM: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
-const METEORA_VAULT: &str = "24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG3LYwBpyTi";
-const ORCA_WHIRLPOOL: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
-const ORCA_V1: &str = "9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP";
-
 /// Cached pool data with timestamp
 #[derive(Clone, Debug)]
 pub struct CachedPoolData {
@@ -62,9 +53,38 @@ pub struct PoolData {
     pub fee_bps: u16,
     pub dex_type: DexType,
     pub program_id: Pubkey,
+    pub venue: VenueState,
+    /// Token programs and transfer extensions of both mints
+    pub mints: PoolMints,
+    /// Slot the pool account was observed at (0 if not read from chain)
+    pub slot: u64,
 }
 
 impl PoolData {
+    /// The same state stamped with the slot its pool account was read at
+    pub fn at_slot(mut self, slot: u64) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    /// Token program owning `mint`, one of the pool's two tokens
+    pub fn token_program(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_b {
+            self.mints.b.token_program
+        } else {
+            self.mints.a.token_program
+        }
+    }
+
+    /// Reserves ordered as (input, output) for the given direction
+    pub fn reserves(&self, a_to_b: bool) -> (u64, u64) {
+        if a_to_b {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (self.reserve_b, self.reserve_a)
+        }
+    }
+    
     /// Calculate exchange rate from token A to token B
     pub fn calculate_rate_a_to_b(&self) -> f64 {
         if self.reserve_a == 0 {
@@ -121,6 +141,7 @@ pub struct PoolDataFetcher {
     cache: Arc<RwLock<HashMap<Pubkey, CachedPoolData>>>,
     cache_ttl_ms: u64,
     current_rpc_index: Arc<RwLock<usize>>,
+    registry: Arc<DexRegistry>,
 }
 
 impl PoolDataFetcher {
@@ -134,9 +155,16 @@ impl PoolDataFetcher {
             cache: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl_ms,
             current_rpc_index: Arc::new(RwLock::new(0)),
+            registry: Arc::new(DexRegistry::default()),
         }
     }
     
+    /// Use a custom adapter registry (e.g. built from configured program ids)
+    pub fn with_registry(mut self, registry: Arc<DexRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+    
     /// Get next RPC client for round-robin load balancing
     async fn get_rpc_client(&self) -> Arc<RpcClient> {
         let mut index = self.current_rpc_index.write().await;
@@ -178,16 +206,12 @@ impl PoolDataFetcher {
         if !uncached_addresses.is_empty() {
             for chunk in uncached_addresses.chunks(MAX_BATCH_SIZE) {
                 match self.fetch_accounts_with_retry(chunk).await {
-                    Ok(accounts) => {
+                    Ok((slot, accounts)) => {
                         // Parse each account
                         for (i, account_opt) in accounts.iter().enumerate() {
                             if let Some(account) = account_opt {
-                                match self.parse_pool_account(&chunk[i], account).await {
-                                    Ok(pool_data) => {
-                                        // Update cache
-                                        self.update_cache(&chunk[i], pool_data.clone()).await;
-                                        fetched_pools.push(pool_data);
-                                    }
+                                match self.decode_pool_account(&chunk[i], account) {
+                                    Ok(pool_data) => fetched_pools.push(pool_data.at_slot(slot)),
                                     Err(e) => {
                                         warn!("Failed to parse pool {}: {}", chunk[i], e);
                                     }
@@ -202,6 +226,12 @@ impl PoolDataFetcher {
                     }
                 }
             }
+            
+            // Complete pools from their vaults/markets, then cache them
+            fetched_pools = self.resolve_dependent_accounts(fetched_pools).await;
+            for pool_data in &fetched_pools {
+                self.update_cache(&pool_data.pool_address, pool_data.clone()).await;
+            }
         }
         
         // Step 3: Combine cached and fetched results
@@ -214,8 +244,8 @@ impl PoolDataFetcher {
         Ok(cached_pools)
     }
     
-    /// Fetch accounts with retry logic and RPC failover
-    async fn fetch_accounts_with_retry(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+    /// Fetch accounts with retry logic and RPC failover, with the slot they were read at
+    async fn fetch_accounts_with_retry(&self, addresses: &[Pubkey]) -> Result<(u64, Vec<Option<Account>>)> {
         const MAX_RETRIES: usize = 3;
         let mut last_error = None;
         
@@ -232,7 +262,7 @@ impl PoolDataFetcher {
                 },
             ).await {
                 Ok(response) => {
-                    return Ok(response.value);
+                    return Ok((response.context.slot, response.value));
                 }
                 Err(e) => {
                     warn!("RPC call failed (attempt {}/{}): {}", attempt + 1, MAX_RETRIES, e);
@@ -250,6 +280,65 @@ impl PoolDataFetcher {
         Err(anyhow!("Failed after {} retries: {:?}", MAX_RETRIES, last_error))
     }
     
+    /// Fetch each pool's dependent accounts and apply them via its adapter
+    ///
+    /// Pools whose dependent accounts cannot be applied are dropped.
+    async fn resolve_dependent_accounts(&self, pools: Vec<PoolData>) -> Vec<PoolData> {
+        let mut addresses: Vec<Pubkey> = pools.iter()
+            .flat_map(|pool| self.registry.dependent_accounts(pool))
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+        
+        if addresses.is_empty() {
+            return pools;
+        }
+        
+        let mut accounts = HashMap::new();
+        for chunk in addresses.chunks(MAX_BATCH_SIZE) {
+            match self.fetch_accounts_with_retry(chunk).await {
+                Ok((_, fetched)) => {
+                    for (address, account) in chunk.iter().zip(fetched) {
+                        if let Some(account) = account {
+                            accounts.insert(*address, account);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to fetch dependent account batch: {}", e);
+                }
+            }
+        }
+        
+        pools.into_iter()
+            .filter_map(|mut pool| {
+                match self.registry.apply_dependent_accounts(&mut pool, &accounts) {
+                    Ok(()) => Some(pool),
+                    Err(e) => {
+                        warn!("Failed to complete pool {}: {}", pool.pool_address, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+    
+    /// Refresh a single pool's dependent accounts (e.g. vault balances after an update)
+    pub async fn refresh_dependent_accounts(&self, pool: &mut PoolData) -> Result<()> {
+        let addresses = self.registry.dependent_accounts(pool);
+        if addresses.is_empty() {
+            return Ok(());
+        }
+        
+        let (_, fetched) = self.fetch_accounts_with_retry(&addresses).await?;
+        let accounts: HashMap<Pubkey, Account> = addresses.into_iter()
+            .zip(fetched)
+            .filter_map(|(address, account)| account.map(|account| (address, account)))
+            .collect();
+        
+        self.registry.apply_dependent_accounts(pool, &accounts)
+    }
+    
     /// Update cache with new pool data
     async fn update_cache(&self, address: &Pubkey, pool_data: PoolData) {
         let timestamp = SystemTime::now()
@@ -264,233 +353,14 @@ impl PoolDataFetcher {
         });
     }
     
-    /// Parse pool account based on program ID
-    async fn parse_pool_account(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
-        let owner = account.owner;
-        
-        // Determine DEX type by program ID
-        if owner.to_string() == RAYDIUM_AMM_V4 {
-            self.parse_raydium_pool(address, account)
-        } else if owner.to_string() == METEORA_DAMM {
-            self.parse_meteora_damm_pool(address, account)
-        } else if owner.to_string() == METEORA_VAULT {
-            self.parse_meteora_vault_pool(address, account)
-        } else if owner.to_string() == ORCA_WHIRLPOOL {
-            self.parse_whirlpool(address, account)
-        } else if owner.to_string() == ORCA_V1 {
-            self.parse_orca_pool(address, account)
-        } else {
-            Err(anyhow!("Unknown pool program: {}", owner))
-        }
-    }
-    
-    /// Parse Raydium AMM v4 pool account
-    fn parse_raydium_pool(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Raydium AMM v4 account structure (simplified)
-        // Offset reference: https://github.com/raydium-io/raydium-sdk
-        
-        if account.data.len() < 752 {
-            return Err(anyhow!("Invalid Raydium pool account size"));
-        }
-        
-        // Parse key fields (byte offsets from Raydium SDK)
-        let token_a = Pubkey::try_from(&account.data[400..432])
-            .map_err(|e| anyhow!("Failed to parse token_a: {}", e))?;
-        let token_b = Pubkey::try_from(&account.data[432..464])
-            .map_err(|e| anyhow!("Failed to parse token_b: {}", e))?;
-        
-        // Reserve amounts (u64 at offsets)
-        let reserve_a = u64::from_le_bytes(
-            account.data[504..512].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_a: {:?}", e))?
-        );
-        let reserve_b = u64::from_le_bytes(
-            account.data[512..520].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_b: {:?}", e))?
-        );
-        
-        // Fee (typically 25 bps for Raydium)
-        let fee_bps = 25u16;
-        
-        debug!("Parsed Raydium pool: reserves=({}, {})", reserve_a, reserve_b);
-        
-        Ok(PoolData {
-            pool_address: *address,
-            token_a,
-            token_b,
-            reserve_a,
-            reserve_b,
-            fee_bps,
-            dex_type: DexType::Raydium,
-            program_id: account.owner,
-        })
-    }
-    
-    /// Parse Meteora DAMM pool account
-    fn parse_meteora_damm_pool(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Meteora DAMM has a complex structure with dynamic AMM parameters
-        // This is a simplified parser - full implementation would use anchor deserialize
-        
-        if account.data.len() < 500 {
-            return Err(anyhow!("Invalid Meteora DAMM account size"));
-        }
-        
-        // Discriminator check (first 8 bytes)
-        let discriminator = &account.data[0..8];
-        debug!("Meteora DAMM discriminator: {:?}", discriminator);
-        
-        // Parse token mints (approximate offsets)
-        let token_a = Pubkey::try_from(&account.data[72..104])
-            .map_err(|e| anyhow!("Failed to parse token_a: {}", e))?;
-        let token_b = Pubkey::try_from(&account.data[104..136])
-            .map_err(|e| anyhow!("Failed to parse token_b: {}", e))?;
-        
-        // Reserve amounts (u64)
-        let reserve_a = u64::from_le_bytes(
-            account.data[200..208].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_a: {:?}", e))?
-        );
-        let reserve_b = u64::from_le_bytes(
-            account.data[208..216].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_b: {:?}", e))?
-        );
-        
-        // Meteora typically has 30 bps fee
-        let fee_bps = 30u16;
-        
-        debug!("Parsed Meteora DAMM pool: reserves=({}, {})", reserve_a, reserve_b);
-        
-        Ok(PoolData {
-            pool_address: *address,
-            token_a,
-            token_b,
-            reserve_a,
-            reserve_b,
-            fee_bps,
-            dex_type: DexType::Meteora,
-            program_id: account.owner,
-        })
-    }
-    
-    /// Parse Meteora Vault pool account
-    fn parse_meteora_vault_pool(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Meteora Vault structure (simplified)
-        
-        if account.data.len() < 400 {
-            return Err(anyhow!("Invalid Meteora Vault account size"));
-        }
-        
-        // Parse tokens and reserves (approximate offsets)
-        let token_a = Pubkey::try_from(&account.data[64..96])
-            .map_err(|e| anyhow!("Failed to parse token_a: {}", e))?;
-        let token_b = Pubkey::try_from(&account.data[96..128])
-            .map_err(|e| anyhow!("Failed to parse token_b: {}", e))?;
-        
-        let reserve_a = u64::from_le_bytes(
-            account.data[150..158].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_a: {:?}", e))?
-        );
-        let reserve_b = u64::from_le_bytes(
-            account.data[158..166].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_b: {:?}", e))?
-        );
-        
-        let fee_bps = 25u16;
-        
-        debug!("Parsed Meteora Vault pool: reserves=({}, {})", reserve_a, reserve_b);
-        
-        Ok(PoolData {
-            pool_address: *address,
-            token_a,
-            token_b,
-            reserve_a,
-            reserve_b,
-            fee_bps,
-            dex_type: DexType::Meteora,
-            program_id: account.owner,
-        })
+    /// Decode a pool account with the adapter registered for its owner program
+    pub fn decode_pool_account(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
+        self.registry.decode_pool(address, account)
     }
     
-    /// Parse Orca Whirlpool (v2) account
-    fn parse_whirlpool(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Whirlpool uses concentrated liquidity (like Uniswap v3)
-        // This is a simplified parser for current liquidity
-        
-        if account.data.len() < 653 {
-            return Err(anyhow!("Invalid Whirlpool account size"));
-        }
-        
-        // Parse token mints
-        let token_a = Pubkey::try_from(&account.data[101..133])
-            .map_err(|e| anyhow!("Failed to parse token_a: {}", e))?;
-        let token_b = Pubkey::try_from(&account.data[181..213])
-            .map_err(|e| anyhow!("Failed to parse token_b: {}", e))?;
-        
-        // Current liquidity (u128 converted to u64 for simplicity)
-        let liquidity_bytes = &account.data[245..261];
-        let reserve_a = u64::from_le_bytes(liquidity_bytes[0..8].try_into().unwrap());
-        let reserve_b = u64::from_le_bytes(liquidity_bytes[8..16].try_into().unwrap());
-        
-        // Whirlpool fee (u16 at offset)
-        let fee_rate = u16::from_le_bytes(
-            account.data[87..89].try_into()
-                .map_err(|e| anyhow!("Failed to parse fee: {:?}", e))?
-        );
-        let fee_bps = fee_rate / 100; // Convert from fee rate to bps
-        
-        debug!("Parsed Whirlpool: reserves=({}, {}), fee={} bps", reserve_a, reserve_b, fee_bps);
-        
-        Ok(PoolData {
-            pool_address: *address,
-            token_a,
-            token_b,
-            reserve_a,
-            reserve_b,
-            fee_bps,
-            dex_type: DexType::Whirlpool,
-            program_id: account.owner,
-        })
-    }
-    
-    /// Parse Orca v1 (legacy) pool account
-    fn parse_orca_pool(&self, address: &Pubkey, account: &Account) -> Result<PoolData> {
-        // Orca v1 simple pool structure
-        
-        if account.data.len() < 324 {
-            return Err(anyhow!("Invalid Orca pool account size"));
-        }
-        
-        // Parse token mints (offsets from Orca SDK)
-        let token_a = Pubkey::try_from(&account.data[35..67])
-            .map_err(|e| anyhow!("Failed to parse token_a: {}", e))?;
-        let token_b = Pubkey::try_from(&account.data[67..99])
-            .map_err(|e| anyhow!("Failed to parse token_b: {}", e))?;
-        
-        // Reserve amounts
-        let reserve_a = u64::from_le_bytes(
-            account.data[220..228].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_a: {:?}", e))?
-        );
-        let reserve_b = u64::from_le_bytes(
-            account.data[228..236].try_into()
-                .map_err(|e| anyhow!("Failed to parse reserve_b: {:?}", e))?
-        );
-        
-        // Orca v1 typically 30 bps
-        let fee_bps = 30u16;
-        
-        debug!("Parsed Orca v1 pool: reserves=({}, {})", reserve_a, reserve_b);
-        
-        Ok(PoolData {
-            pool_address: *address,
-            token_a,
-            token_b,
-            reserve_a,
-            reserve_b,
-            fee_bps,
-            dex_type: DexType::Orca,
-            program_id: account.owner,
-        })
+    /// Adapter registry used to decode pools
+    pub fn registry(&self) -> &Arc<DexRegistry> {
+        &self.registry
     }
     
     /// Get cache statistics
@@ -529,6 +399,9 @@ mod tests {
             fee_bps: 25,
             dex_type: DexType::Raydium,
             program_id: create_test_pubkey(100),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
         };
         
         let cached = CachedPoolData {
@@ -563,6 +436,9 @@ mod tests {
             fee_bps: 25, // 0.25%
             dex_type: DexType::Raydium,
             program_id: create_test_pubkey(100),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
         };
         
         // Rate A to B: (2000000 / 1000000) * (1 - 0.0025) = 1.995
@@ -585,6 +461,9 @@ mod tests {
             fee_bps: 30,
             dex_type: DexType::Raydium,
             program_id: create_test_pubkey(100),
+            venue: VenueState::None,
+            mints: Default::default(),
+            slot: 0,
         };
         
         // Small trade should have low impact
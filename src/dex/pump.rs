@@ -1,14 +1,594 @@
-// Placeholder for Pump DEX integration
-// TODO: Implement actual Pump swap operations
+// Pump.fun Adapter
+//
+// Decodes Pump.fun bonding curves, quotes buys and sells with the platform
+// fee, and builds the separate `buy` and `sell` instructions.
+//
+// A bonding curve is a constant-product curve over virtual SOL and token
+// reserves, with the real token reserves capping how much can be bought.
+// The curve account does not store its mint, so the adapter keeps a map of
+// tracked mints keyed by bonding curve address. Once `complete` is set the
+// liquidity has migrated away; the curve is reported as not tradable and its
+// edges are dropped from the graph.
+//
+// Pump.fun swaps move native SOL: `buy` debits lamports from the signer and
+// `sell` credits them back, so only the token side uses a token account.
+// `buy` is exact-out: it purchases a fixed token amount for at most a given
+// SOL cost.
 
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{account::Account, instruction::{AccountMeta, Instruction}, pubkey::Pubkey, system_program};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use tracing::debug;
 
-pub struct PumpClient {
+use crate::chain::constants::WSOL_MINT;
+use crate::dex::adapter::{read_array, read_pubkey, read_u64, DexAdapter, SwapParams, VenueState};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::token_2022::associated_token_address;
+use crate::dex::triangular_arb::DexType;
+
+/// Pump.fun program id
+pub const PUMP_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+
+/// Anchor discriminator of the `buy` instruction
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+/// Anchor discriminator of the `sell` instruction
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// Anchor discriminator of the `BondingCurve` account
+const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
+/// Anchor discriminator of the `Global` account
+const GLOBAL_DISCRIMINATOR: [u8; 8] = [167, 232, 232, 177, 200, 108, 114, 127];
+
+/// Size of a BondingCurve account up to and including `complete`
+pub const BONDING_CURVE_LEN: usize = 49;
+
+/// Size of the Global account up to and including `fee_basis_points`
+pub const GLOBAL_LEN: usize = 113;
+
+/// Offset of `creator_fee_basis_points` in Global
+const GLOBAL_CREATOR_FEE_OFFSET: usize = 154;
+
+/// Bonding curve account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    /// Set once the curve has migrated; no more trading on it
+    pub complete: bool,
+    /// Curve creator (receives the creator fee); default on older curves
+    pub creator: Pubkey,
+}
+
+impl BondingCurve {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: pump.fun IDL (`BondingCurve`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < BONDING_CURVE_LEN {
+            return Err(anyhow!("Invalid Pump.fun bonding curve account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != BONDING_CURVE_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Pump.fun bonding curve"));
+        }
+
+        Ok(Self {
+            virtual_token_reserves: read_u64(data, 8)?,
+            virtual_sol_reserves: read_u64(data, 16)?,
+            real_token_reserves: read_u64(data, 24)?,
+            real_sol_reserves: read_u64(data, 32)?,
+            token_total_supply: read_u64(data, 40)?,
+            complete: data[48] != 0,
+            creator: read_pubkey(data, 49).unwrap_or_default(),
+        })
+    }
+
+    /// SOL charged by the curve (before fees) for exactly `amount` tokens
+    pub fn buy_cost(&self, amount: u64) -> Result<u64> {
+        if amount >= self.virtual_token_reserves {
+            return Err(anyhow!("Pump.fun buy of {} exceeds virtual token reserves", amount));
+        }
+        let cost = amount as u128 * self.virtual_sol_reserves as u128
+            / (self.virtual_token_reserves - amount) as u128
+            + 1;
+        u64::try_from(cost).map_err(|_| anyhow!("Pump.fun buy cost overflow"))
+    }
+
+    /// SOL paid out by the curve (before fees) for `amount` tokens
+    pub fn sell_proceeds(&self, amount: u64) -> Result<u64> {
+        let denominator = self.virtual_token_reserves as u128 + amount as u128;
+        if denominator == 0 {
+            return Err(anyhow!("Pump.fun bonding curve has no reserves"));
+        }
+        Ok((amount as u128 * self.virtual_sol_reserves as u128 / denominator) as u64)
+    }
+}
+
+/// Fee configuration from the Global account
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PumpGlobal {
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u64,
+    pub creator_fee_basis_points: u64,
+}
+
+impl PumpGlobal {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: pump.fun IDL (`Global`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < GLOBAL_LEN {
+            return Err(anyhow!("Invalid Pump.fun global account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != GLOBAL_DISCRIMINATOR {
+            return Err(anyhow!("Account is not the Pump.fun global account"));
+        }
+
+        Ok(Self {
+            fee_recipient: read_pubkey(data, 41)?,
+            fee_basis_points: read_u64(data, 105)?,
+            // Older global accounts predate the creator fee
+            creator_fee_basis_points: read_u64(data, GLOBAL_CREATOR_FEE_OFFSET).unwrap_or(0),
+        })
+    }
+}
+
+/// Decoded bonding curve with its mint and the global fee configuration
+#[derive(Clone, Debug)]
+pub struct PumpPoolState {
+    pub mint: Pubkey,
+    pub curve: BondingCurve,
+    /// Loaded with the dependent accounts
+    pub global: Option<PumpGlobal>,
+}
+
+impl PumpPoolState {
+    /// Total fee in basis points (platform plus creator fee when the curve has a creator)
+    pub fn fee_bps(&self) -> Result<u64> {
+        let global = self.global.as_ref()
+            .ok_or_else(|| anyhow!("Pump.fun global account not loaded"))?;
+        let creator_fee = if self.curve.creator == Pubkey::default() {
+            0
+        } else {
+            global.creator_fee_basis_points
+        };
+        Ok(global.fee_basis_points + creator_fee)
+    }
+
+    /// Platform plus creator fee; the platform fee is the protocol's share
+    pub fn fee_model(&self) -> Result<FeeModel> {
+        let global = self.global.as_ref()
+            .ok_or_else(|| anyhow!("Pump.fun global account not loaded"))?;
+        let fee_bps = self.fee_bps()?;
+        Ok(FeeModel::Static {
+            trade: FeeRate::from_bps(fee_bps),
+            protocol_share: FeeRate::from_fraction(global.fee_basis_points as u128, fee_bps as u128),
+        })
+    }
+
+    /// Tokens received for `sol_in` lamports, fee included
+    pub fn quote_buy(&self, sol_in: u64) -> Result<u64> {
+        let fee_bps = self.fee_bps()? as u128;
+        let total_cost = |amount: u64| -> Result<u64> {
+            let cost = self.curve.buy_cost(amount)? as u128;
+            Ok((cost + (cost * fee_bps).div_ceil(10_000)) as u64)
+        };
+
+        // Closed-form estimate on the SOL left after the fee, then the
+        // largest token amount whose exact cost still fits in `sol_in`
+        let sol_after_fee = sol_in as u128 * 10_000 / (10_000 + fee_bps);
+        let virtual_sol = self.curve.virtual_sol_reserves as u128;
+        let virtual_token = self.curve.virtual_token_reserves as u128;
+        let remaining = virtual_sol * virtual_token / (virtual_sol + sol_after_fee) + 1;
+        let estimate = virtual_token.saturating_sub(remaining) as u64;
+        let mut high = estimate.min(self.curve.real_token_reserves);
+
+        if high == 0 || total_cost(high)? <= sol_in {
+            return Ok(high);
+        }
+        let mut low = 0;
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if total_cost(mid)? <= sol_in {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Lamports received for selling `amount` tokens, fee deducted
+    pub fn quote_sell(&self, amount: u64) -> Result<u64> {
+        let fee_bps = self.fee_bps()? as u128;
+        let proceeds = self.curve.sell_proceeds(amount)?;
+        if proceeds > self.curve.real_sol_reserves {
+            return Err(anyhow!("Pump.fun sell exceeds the curve's real SOL reserves"));
+        }
+        let fee = (proceeds as u128 * fee_bps).div_ceil(10_000) as u64;
+        Ok(proceeds.saturating_sub(fee))
+    }
+}
+
+/// Bonding curve PDA: `[b"bonding-curve", mint]`
+pub fn bonding_curve_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], program_id).0
+}
+
+/// Global config PDA: `[b"global"]`
+pub fn global_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"global"], program_id).0
+}
+
+/// Creator fee vault PDA: `[b"creator-vault", creator]`
+pub fn creator_vault_address(program_id: &Pubkey, creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"creator-vault", creator.as_ref()], program_id).0
+}
+
+/// Anchor event authority PDA
+pub fn event_authority_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id).0
+}
+
+pub struct PumpAdapter {
     program_id: Pubkey,
+    /// Mint of each tracked bonding curve, keyed by curve address
+    mints: DashMap<Pubkey, Pubkey>,
 }
 
-impl PumpClient {
+impl PumpAdapter {
     pub fn new(program_id: Pubkey) -> Self {
-        Self { program_id }
+        Self {
+            program_id,
+            mints: DashMap::new(),
+        }
+    }
+
+    /// Start tracking `mint`; returns its bonding curve address
+    pub fn track_mint(&self, mint: Pubkey) -> Pubkey {
+        let curve = bonding_curve_address(&self.program_id, &mint);
+        self.mints.insert(curve, mint);
+        curve
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a PumpPoolState> {
+        match &pool.venue {
+            VenueState::Pump(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Pump.fun state", pool.pool_address)),
+        }
+    }
+
+    fn tradable_state<'a>(&self, pool: &'a PoolData) -> Result<&'a PumpPoolState> {
+        let state = self.state(pool)?;
+        if state.curve.complete {
+            return Err(anyhow!("Pump.fun bonding curve {} is complete", pool.pool_address));
+        }
+        Ok(state)
+    }
+
+    /// Accounts shared by `buy` and `sell` up to the user signer
+    fn common_accounts(&self, pool: &PoolData, state: &PumpPoolState, user_token_account: Pubkey, user: Pubkey) -> Result<Vec<AccountMeta>> {
+        let global = state.global.as_ref()
+            .ok_or_else(|| anyhow!("Pump.fun global account not loaded"))?;
+        let associated_bonding_curve =
+            associated_token_address(&pool.pool_address, &state.mint, &pool.token_program(&state.mint));
+
+        Ok(vec![
+            AccountMeta::new_readonly(global_address(&self.program_id), false),
+            AccountMeta::new(global.fee_recipient, false),
+            AccountMeta::new_readonly(state.mint, false),
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new(associated_bonding_curve, false),
+            AccountMeta::new(user_token_account, false),
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ])
+    }
+
+    /// `buy`: exactly `params.minimum_out` tokens for at most `params.amount_in` lamports.
+    /// `user_destination` is the user's token account for the mint.
+    pub fn build_buy_ix(&self, pool: &PoolData, params: &SwapParams) -> Result<Instruction> {
+        let state = self.tradable_state(pool)?;
+
+        let mut accounts = self.common_accounts(pool, state, params.user_destination, params.user)?;
+        accounts.extend([
+            AccountMeta::new_readonly(pool.token_program(&state.mint), false),
+            AccountMeta::new(creator_vault_address(&self.program_id, &state.curve.creator), false),
+            AccountMeta::new_readonly(event_authority_address(&self.program_id), false),
+            AccountMeta::new_readonly(self.program_id, false),
+        ]);
+
+        let mut data = BUY_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&params.minimum_out.to_le_bytes()); // token amount
+        data.extend_from_slice(&params.amount_in.to_le_bytes()); // max_sol_cost
+
+        Ok(Instruction { program_id: self.program_id, accounts, data })
+    }
+
+    /// `sell`: `params.amount_in` tokens for at least `params.minimum_out` lamports.
+    /// `user_source` is the user's token account for the mint.
+    pub fn build_sell_ix(&self, pool: &PoolData, params: &SwapParams) -> Result<Instruction> {
+        let state = self.tradable_state(pool)?;
+
+        let mut accounts = self.common_accounts(pool, state, params.user_source, params.user)?;
+        accounts.extend([
+            AccountMeta::new(creator_vault_address(&self.program_id, &state.curve.creator), false),
+            AccountMeta::new_readonly(pool.token_program(&state.mint), false),
+            AccountMeta::new_readonly(event_authority_address(&self.program_id), false),
+            AccountMeta::new_readonly(self.program_id, false),
+        ]);
+
+        let mut data = SELL_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&params.amount_in.to_le_bytes()); // token amount
+        data.extend_from_slice(&params.minimum_out.to_le_bytes()); // min_sol_output
+
+        Ok(Instruction { program_id: self.program_id, accounts, data })
+    }
+
+    /// Token A is the mint, so a->b is a sell
+    fn build_ix(&self, pool: &PoolData, params: &SwapParams) -> Result<Instruction> {
+        if params.a_to_b {
+            self.build_sell_ix(pool, params)
+        } else {
+            self.build_buy_ix(pool, params)
+        }
+    }
+}
+
+impl Default for PumpAdapter {
+    fn default() -> Self {
+        Self::new(PUMP_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for PumpAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::Pump
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let curve = BondingCurve::decode(data)?;
+        let mint = self.mints.get(address)
+            .map(|mint| *mint)
+            .ok_or_else(|| anyhow!("Mint of Pump.fun bonding curve {} is not tracked", address))?;
+
+        debug!(
+            "Parsed Pump.fun curve {}: virtual=({}, {}), complete={}",
+            address, curve.virtual_token_reserves, curve.virtual_sol_reserves, curve.complete
+        );
+
+        // The curve prices off its virtual reserves
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: mint,
+            token_b: WSOL_MINT,
+            reserve_a: curve.virtual_token_reserves,
+            reserve_b: curve.virtual_sol_reserves,
+            fee_bps: 0, // Set from the global account
+            dex_type: DexType::Pump,
+            program_id: self.program_id,
+            venue: VenueState::Pump(Box::new(PumpPoolState { mint, curve, global: None })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, _pool: &PoolData) -> Vec<Pubkey> {
+        vec![global_address(&self.program_id)]
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let global_address = global_address(&self.program_id);
+        let global = accounts.get(&global_address)
+            .ok_or_else(|| anyhow!("Missing Pump.fun global account {}", global_address))?;
+        let global = PumpGlobal::decode(&global.data)?;
+
+        let address = pool.pool_address;
+        let state = match &mut pool.venue {
+            VenueState::Pump(state) => state,
+            _ => return Err(anyhow!("Pool {} has no Pump.fun state", address)),
+        };
+        state.global = Some(global);
+        pool.fee_bps = state.fee_bps()? as u16;
+
+        Ok(())
+    }
+
+    fn is_tradable(&self, pool: &PoolData) -> bool {
+        self.tradable_state(pool).is_ok()
+    }
+
+    fn is_exact_out(&self, _pool: &PoolData, a_to_b: bool) -> bool {
+        // Buys (b -> a) fix the token amount
+        !a_to_b
+    }
+
+    fn settles_native_sol(&self) -> bool {
+        true
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let state = self.tradable_state(pool)?;
+        if a_to_b {
+            state.quote_sell(amount_in)
+        } else {
+            state.quote_buy(amount_in)
+        }
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        self.state(pool)
+            .and_then(|state| state.fee_model())
+            .unwrap_or_else(|_| FeeModel::from_bps(pool.fee_bps))
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        Ok(self.build_ix(pool, params)?.accounts)
+    }
+
+    fn swap_data(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        Ok(self.build_ix(pool, params)?.data)
+    }
+
+    fn build_swap_ix(&self, pool: &PoolData, params: &SwapParams) -> Result<Instruction> {
+        self.build_ix(pool, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh curve with the launch parameters
+    fn launch_curve() -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: Pubkey::default(),
+        }
+    }
+
+    fn pool_with(curve: BondingCurve) -> PoolData {
+        let mint = Pubkey::new_unique();
+        PoolData {
+            pool_address: bonding_curve_address(&PUMP_PROGRAM_ID, &mint),
+            token_a: mint,
+            token_b: WSOL_MINT,
+            reserve_a: curve.virtual_token_reserves,
+            reserve_b: curve.virtual_sol_reserves,
+            fee_bps: 100,
+            dex_type: DexType::Pump,
+            program_id: PUMP_PROGRAM_ID,
+            venue: VenueState::Pump(Box::new(PumpPoolState {
+                mint,
+                curve,
+                global: Some(PumpGlobal {
+                    fee_recipient: Pubkey::new_unique(),
+                    fee_basis_points: 100,
+                    creator_fee_basis_points: 0,
+                }),
+            })),
+            mints: Default::default(),
+            slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_buy_quote_is_the_largest_affordable_amount() {
+        let pool = pool_with(launch_curve());
+        let VenueState::Pump(state) = &pool.venue else { unreachable!() };
+
+        let sol_in = 1_000_000_000;
+        let tokens = state.quote_buy(sol_in).unwrap();
+        assert!(tokens > 0);
+
+        let cost = |amount: u64| {
+            let cost = state.curve.buy_cost(amount).unwrap();
+            cost + (cost as u128 * 100).div_ceil(10_000) as u64
+        };
+        assert!(cost(tokens) <= sol_in);
+        assert!(cost(tokens + 1) > sol_in);
+    }
+
+    #[test]
+    fn test_buy_is_capped_by_real_reserves() {
+        let pool = pool_with(launch_curve());
+        let VenueState::Pump(state) = &pool.venue else { unreachable!() };
+
+        let tokens = state.quote_buy(1_000_000_000_000).unwrap();
+        assert!(tokens <= state.curve.real_token_reserves);
+    }
+
+    #[test]
+    fn test_sell_quote_deducts_fee() {
+        let mut curve = launch_curve();
+        curve.real_sol_reserves = 10_000_000_000;
+        let pool = pool_with(curve);
+        let VenueState::Pump(state) = &pool.venue else { unreachable!() };
+
+        let amount = 10_000_000_000_000;
+        let proceeds = state.curve.sell_proceeds(amount).unwrap();
+        let out = state.quote_sell(amount).unwrap();
+        assert_eq!(out, proceeds - (proceeds as u128 * 100).div_ceil(10_000) as u64);
+    }
+
+    #[test]
+    fn test_completed_curve_is_not_tradable() {
+        let adapter = PumpAdapter::default();
+        let mut curve = launch_curve();
+        curve.complete = true;
+        let pool = pool_with(curve);
+
+        assert!(!adapter.is_tradable(&pool));
+        assert!(adapter.quote_exact_in(&pool, 1_000, false).is_err());
+    }
+
+    #[test]
+    fn test_decode_requires_tracked_mint() {
+        let adapter = PumpAdapter::default();
+        let mut data = vec![0u8; 81];
+        data[..8].copy_from_slice(&BONDING_CURVE_DISCRIMINATOR);
+        data[8..16].copy_from_slice(&1_000u64.to_le_bytes());
+        data[16..24].copy_from_slice(&30u64.to_le_bytes());
+        data[48] = 1;
+
+        let mint = Pubkey::new_unique();
+        let curve_address = bonding_curve_address(&PUMP_PROGRAM_ID, &mint);
+        assert!(adapter.decode_pool(&curve_address, &data).is_err());
+
+        assert_eq!(adapter.track_mint(mint), curve_address);
+        let pool = adapter.decode_pool(&curve_address, &data).unwrap();
+        assert_eq!(pool.token_a, mint);
+        assert_eq!(pool.token_b, WSOL_MINT);
+        assert_eq!((pool.reserve_a, pool.reserve_b), (1_000, 30));
+        assert!(!adapter.is_tradable(&pool));
+    }
+
+    #[test]
+    fn test_buy_and_sell_instructions() {
+        let adapter = PumpAdapter::default();
+        let pool = pool_with(launch_curve());
+        let params = SwapParams {
+            user: Pubkey::new_unique(),
+            user_source: Pubkey::new_unique(),
+            user_destination: Pubkey::new_unique(),
+            amount_in: 1_000,
+            minimum_out: 900,
+            a_to_b: false,
+        };
+
+        let buy = adapter.build_swap_ix(&pool, &params).unwrap();
+        assert_eq!(&buy.data[..8], &BUY_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(buy.data[8..16].try_into().unwrap()), 900);
+        assert_eq!(u64::from_le_bytes(buy.data[16..24].try_into().unwrap()), 1_000);
+        assert_eq!(buy.accounts.len(), 12);
+        assert_eq!(buy.accounts[0].pubkey, global_address(&PUMP_PROGRAM_ID));
+        assert_eq!(buy.accounts[3].pubkey, pool.pool_address);
+        assert_eq!(buy.accounts[5].pubkey, params.user_destination);
+        assert!(buy.accounts[6].is_signer);
+        assert_eq!(buy.accounts[8].pubkey, spl_token::id());
+        assert_eq!(buy.accounts[10].pubkey, event_authority_address(&PUMP_PROGRAM_ID));
+
+        let sell = adapter.build_swap_ix(&pool, &SwapParams { a_to_b: true, ..params.clone() }).unwrap();
+        assert_eq!(&sell.data[..8], &SELL_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(sell.data[8..16].try_into().unwrap()), 1_000);
+        assert_eq!(sell.accounts.len(), 12);
+        assert_eq!(sell.accounts[5].pubkey, params.user_source);
+        assert_eq!(sell.accounts[9].pubkey, spl_token::id());
     }
 }
@@ -1,14 +1,395 @@
-// Placeholder for Raydium DEX integration
-// TODO: Implement actual Raydium swap and liquidity operations
+// Raydium AMM v4 Adapter
+//
+// Decodes the full Raydium AMM v4 `AmmInfo` account and the Serum/OpenBook
+// market it is paired with, and builds the 18-account `swap_base_in`
+// instruction.
+//
+// Reserves are the vault balances minus the PnL the AMM has not yet taken,
+// which is what the program itself swaps against.
 
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use tracing::debug;
 
-pub struct RaydiumClient {
+use crate::dex::adapter::{
+    constant_product_amount_out, read_pubkey, read_u64, token_account_amount, DexAdapter, MintLayout,
+    SwapParams, VenueState,
+};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::DexType;
+
+/// Raydium AMM v4 program id
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+/// Raydium AMM v4 swap_base_in instruction tag
+const SWAP_BASE_IN_TAG: u8 = 9;
+
+/// Seed of the AMM authority PDA (combined with `AmmInfo::nonce`)
+const AUTHORITY_AMM_SEED: &[u8] = b"amm authority";
+
+/// Size of the AmmInfo account
+pub const AMM_INFO_LEN: usize = 752;
+
+/// Size of a Serum/OpenBook v1 market account (5-byte head + state + 7-byte tail)
+pub const MARKET_STATE_LEN: usize = 388;
+
+/// AMM status values that allow swaps (Initialized, SwapOnly, WaitingTrade)
+const SWAP_ENABLED_STATUSES: [u64; 3] = [1, 6, 7];
+
+/// Fee fractions stored in AmmInfo
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AmmFees {
+    pub min_separate_numerator: u64,
+    pub min_separate_denominator: u64,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub pnl_numerator: u64,
+    pub pnl_denominator: u64,
+    /// Fee charged on swap input
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+/// Raydium AMM v4 pool account (fields needed for quoting and swapping)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AmmInfo {
+    pub status: u64,
+    pub nonce: u64,
+    pub coin_decimals: u64,
+    pub pc_decimals: u64,
+    pub state: u64,
+    pub fees: AmmFees,
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub pool_open_time: u64,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+    pub amm_owner: Pubkey,
+    pub lp_amount: u64,
+}
+
+impl AmmInfo {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: raydium-amm `state.rs` (`AmmInfo`, `Fees`, `StateData`)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < AMM_INFO_LEN {
+            return Err(anyhow!("Invalid Raydium pool account size: {} bytes", data.len()));
+        }
+
+        Ok(Self {
+            status: read_u64(data, 0)?,
+            nonce: read_u64(data, 8)?,
+            coin_decimals: read_u64(data, 32)?,
+            pc_decimals: read_u64(data, 40)?,
+            state: read_u64(data, 48)?,
+            fees: AmmFees {
+                min_separate_numerator: read_u64(data, 128)?,
+                min_separate_denominator: read_u64(data, 136)?,
+                trade_fee_numerator: read_u64(data, 144)?,
+                trade_fee_denominator: read_u64(data, 152)?,
+                pnl_numerator: read_u64(data, 160)?,
+                pnl_denominator: read_u64(data, 168)?,
+                swap_fee_numerator: read_u64(data, 176)?,
+                swap_fee_denominator: read_u64(data, 184)?,
+            },
+            need_take_pnl_coin: read_u64(data, 192)?,
+            need_take_pnl_pc: read_u64(data, 200)?,
+            pool_open_time: read_u64(data, 224)?,
+            coin_vault: read_pubkey(data, 336)?,
+            pc_vault: read_pubkey(data, 368)?,
+            coin_mint: read_pubkey(data, 400)?,
+            pc_mint: read_pubkey(data, 432)?,
+            lp_mint: read_pubkey(data, 464)?,
+            open_orders: read_pubkey(data, 496)?,
+            market: read_pubkey(data, 528)?,
+            market_program: read_pubkey(data, 560)?,
+            target_orders: read_pubkey(data, 592)?,
+            amm_owner: read_pubkey(data, 688)?,
+            lp_amount: read_u64(data, 720)?,
+        })
+    }
+
+    /// Whether the pool status allows swaps
+    pub fn swap_enabled(&self) -> bool {
+        SWAP_ENABLED_STATUSES.contains(&self.status)
+    }
+
+    /// Swap fee in basis points (rounded down)
+    pub fn swap_fee_bps(&self) -> u16 {
+        if self.fees.swap_fee_denominator == 0 {
+            return 0;
+        }
+        (self.fees.swap_fee_numerator as u128 * 10_000 / self.fees.swap_fee_denominator as u128) as u16
+    }
+
+    /// Swap fee, of which the pnl share goes to the protocol
+    pub fn fee_model(&self) -> FeeModel {
+        FeeModel::Static {
+            trade: FeeRate::from_fraction(self.fees.swap_fee_numerator as u128, self.fees.swap_fee_denominator as u128),
+            protocol_share: FeeRate::from_fraction(self.fees.pnl_numerator as u128, self.fees.pnl_denominator as u128),
+        }
+    }
+
+    /// AMM authority PDA: `[b"amm authority", nonce]`
+    pub fn authority(&self, program_id: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(&[AUTHORITY_AMM_SEED, &[self.nonce as u8]], program_id)
+            .map_err(|e| anyhow!("Invalid Raydium authority nonce {}: {}", self.nonce, e))
+    }
+}
+
+/// Serum/OpenBook market accounts required by the AMM swap
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MarketKeys {
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub vault_signer: Pubkey,
+}
+
+impl MarketKeys {
+    /// Decode a Serum v3 / OpenBook v1 `MarketState` and derive its vault signer
+    pub fn decode(market: &Pubkey, market_program: &Pubkey, data: &[u8]) -> Result<Self> {
+        if data.len() < MARKET_STATE_LEN {
+            return Err(anyhow!("Invalid market account size: {} bytes", data.len()));
+        }
+
+        // Offsets include the 5-byte "serum" account head
+        let vault_signer_nonce = read_u64(data, 45)?;
+        let vault_signer = Pubkey::create_program_address(
+            &[market.as_ref(), &vault_signer_nonce.to_le_bytes()],
+            market_program,
+        )
+        .map_err(|e| anyhow!("Invalid vault signer nonce for market {}: {}", market, e))?;
+
+        Ok(Self {
+            coin_vault: read_pubkey(data, 117)?,
+            pc_vault: read_pubkey(data, 165)?,
+            event_queue: read_pubkey(data, 253)?,
+            bids: read_pubkey(data, 285)?,
+            asks: read_pubkey(data, 317)?,
+            vault_signer,
+        })
+    }
+}
+
+/// Decoded Raydium pool with the accounts resolved from its market
+#[derive(Clone, Debug)]
+pub struct RaydiumPoolState {
+    pub amm: AmmInfo,
+    pub market: Option<MarketKeys>,
+}
+
+pub struct RaydiumAdapter {
     program_id: Pubkey,
 }
 
-impl RaydiumClient {
+impl RaydiumAdapter {
     pub fn new(program_id: Pubkey) -> Self {
         Self { program_id }
     }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a RaydiumPoolState> {
+        match &pool.venue {
+            VenueState::Raydium(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Raydium state", pool.pool_address)),
+        }
+    }
+}
+
+impl Default for RaydiumAdapter {
+    fn default() -> Self {
+        Self::new(RAYDIUM_AMM_V4_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for RaydiumAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::Raydium
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: Some(AMM_INFO_LEN as u64),
+            mint_a_offset: 400, // coin_mint
+            mint_b_offset: 432, // pc_mint
+        })
+    }
+
+    fn pool_init_logs(&self) -> &'static [&'static str] {
+        // `initialize2` logs its arguments after the instruction name
+        &["Program log: initialize2:"]
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let amm = AmmInfo::decode(data)?;
+
+        if !amm.swap_enabled() {
+            return Err(anyhow!("Raydium pool {} does not allow swaps (status {})", address, amm.status));
+        }
+
+        debug!(
+            "Parsed Raydium pool: coin={}, pc={}, swap fee={}/{}",
+            amm.coin_mint, amm.pc_mint, amm.fees.swap_fee_numerator, amm.fees.swap_fee_denominator
+        );
+
+        // Reserves are filled from the vaults in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: amm.coin_mint,
+            token_b: amm.pc_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: amm.swap_fee_bps(),
+            dex_type: DexType::Raydium,
+            program_id: self.program_id,
+            venue: VenueState::Raydium(Box::new(RaydiumPoolState { amm, market: None })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        match self.state(pool) {
+            Ok(state) => vec![state.amm.coin_vault, state.amm.pc_vault, state.amm.market],
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let state = match &mut pool.venue {
+            VenueState::Raydium(state) => state,
+            _ => return Err(anyhow!("Pool {} has no Raydium state", address)),
+        };
+        let amm = &state.amm;
+
+        let coin_vault = accounts.get(&amm.coin_vault)
+            .ok_or_else(|| anyhow!("Missing coin vault {} for pool {}", amm.coin_vault, address))?;
+        let pc_vault = accounts.get(&amm.pc_vault)
+            .ok_or_else(|| anyhow!("Missing pc vault {} for pool {}", amm.pc_vault, address))?;
+
+        let reserve_a = token_account_amount(coin_vault)?.saturating_sub(amm.need_take_pnl_coin);
+        let reserve_b = token_account_amount(pc_vault)?.saturating_sub(amm.need_take_pnl_pc);
+
+        if let Some(market) = accounts.get(&amm.market) {
+            state.market = Some(MarketKeys::decode(&amm.market, &amm.market_program, &market.data)?);
+        }
+
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+
+        debug!("Raydium pool {} reserves: ({}, {})", address, reserve_a, reserve_b);
+
+        Ok(())
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let fees = &self.state(pool)?.amm.fees;
+        let (reserve_in, reserve_out) = pool.reserves(a_to_b);
+        constant_product_amount_out(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            fees.swap_fee_numerator,
+            fees.swap_fee_denominator,
+        )
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(state) => state.amm.fee_model(),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let state = self.state(pool)?;
+        let amm = &state.amm;
+        let market = state.market.as_ref()
+            .ok_or_else(|| anyhow!("Market accounts not loaded for Raydium pool {}", pool.pool_address))?;
+
+        Ok(vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new_readonly(amm.authority(&self.program_id)?, false),
+            AccountMeta::new(amm.open_orders, false),
+            AccountMeta::new(amm.target_orders, false),
+            AccountMeta::new(amm.coin_vault, false),
+            AccountMeta::new(amm.pc_vault, false),
+            AccountMeta::new_readonly(amm.market_program, false),
+            AccountMeta::new(amm.market, false),
+            AccountMeta::new(market.bids, false),
+            AccountMeta::new(market.asks, false),
+            AccountMeta::new(market.event_queue, false),
+            AccountMeta::new(market.coin_vault, false),
+            AccountMeta::new(market.pc_vault, false),
+            AccountMeta::new_readonly(market.vault_signer, false),
+            AccountMeta::new(params.user_source, false),
+            AccountMeta::new(params.user_destination, false),
+            AccountMeta::new_readonly(params.user, true),
+        ])
+    }
+
+    fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let mut data = vec![SWAP_BASE_IN_TAG];
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amm_info_rejects_short_data() {
+        assert!(AmmInfo::decode(&[0u8; 500]).is_err());
+    }
+
+    #[test]
+    fn test_swap_fee_bps() {
+        let mut amm = AmmInfo::default();
+        amm.fees.swap_fee_numerator = 25;
+        amm.fees.swap_fee_denominator = 10_000;
+        assert_eq!(amm.swap_fee_bps(), 25);
+
+        amm.fees.pnl_numerator = 12;
+        amm.fees.pnl_denominator = 100;
+        let fee = amm.fee_model();
+        assert_eq!(fee.trade_fee(), FeeRate::from_bps(25));
+        assert_eq!(fee.protocol_fee(), FeeRate::from_fraction(3, 10_000));
+
+        amm.fees.swap_fee_denominator = 0;
+        assert_eq!(amm.swap_fee_bps(), 0);
+    }
+
+    #[test]
+    fn test_swap_enabled_statuses() {
+        let mut amm = AmmInfo { status: 1, ..Default::default() };
+        assert!(amm.swap_enabled());
+        amm.status = 6;
+        assert!(amm.swap_enabled());
+        amm.status = 2; // Disabled
+        assert!(!amm.swap_enabled());
+        amm.status = 3; // WithdrawOnly
+        assert!(!amm.swap_enabled());
+    }
 }
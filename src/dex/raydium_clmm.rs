@@ -0,0 +1,1004 @@
+// Raydium CLMM Adapter
+//
+// Decodes Raydium concentrated-liquidity (`PoolState`, `AmmConfig`,
+// `TickArrayState`) accounts and builds the `swap_v2` instruction.
+//
+// Like a Whirlpool, a CLMM pool quotes from `sqrt_price_x64` and the active
+// liquidity, crossing initialized ticks stored in tick arrays of 60 ticks.
+// The pool keeps a bitmap of which tick arrays exist, so the adapter loads
+// exactly the initialized arrays nearest the price in each direction and
+// replays the program's swap loop over them. Arrays outside the pool's
+// default bitmap (tracked by the bitmap extension account) are not loaded;
+// quotes that would reach them fail instead of guessing.
+
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use tracing::debug;
+
+use crate::dex::adapter::{
+    read_array, read_i128, read_i32, read_pubkey, read_u128, read_u16, read_u32, read_u64,
+    token_account_amount, DexAdapter, MintLayout, SwapParams, VenueState, TOKEN_2022_PROGRAM_ID,
+};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::token_2022::MEMO_PROGRAM_ID;
+use crate::dex::triangular_arb::DexType;
+
+/// Raydium CLMM program id
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("CAMMCzo5YL8w4VC1K7AuPXYRMoKdyvfyMomnhPDJPyQK");
+
+/// Anchor discriminator of the `swap_v2` instruction
+const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// Anchor discriminator of the `PoolState` account
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+/// Anchor discriminator of the `AmmConfig` account
+const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+
+/// Anchor discriminator of the `TickArrayState` account
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [192, 155, 85, 205, 49, 249, 129, 42];
+
+/// Size of the PoolState account
+pub const POOL_STATE_LEN: usize = 1544;
+
+/// Size of the AmmConfig account
+pub const AMM_CONFIG_LEN: usize = 117;
+
+/// Size of the TickArrayState account
+pub const TICK_ARRAY_LEN: usize = 10240;
+
+/// Ticks per tick array
+pub const TICK_ARRAY_SIZE: i32 = 60;
+
+/// Size of one `TickState`
+const TICK_STATE_LEN: usize = 168;
+
+/// Offset of the first tick in a tick array
+const TICKS_OFFSET: usize = 44;
+
+/// Offset of `tick_array_bitmap` in the pool account
+const TICK_ARRAY_BITMAP_OFFSET: usize = 904;
+
+/// Tick arrays on each side of zero tracked by the pool's own bitmap
+const TICK_ARRAY_BITMAP_HALF: i32 = 512;
+
+/// Tick arrays passed to a swap (and loaded per direction)
+pub const SWAP_TICK_ARRAY_COUNT: usize = 3;
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+/// sqrt price at `MIN_TICK` (Q64.64)
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+/// sqrt price at `MAX_TICK` (Q64.64)
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+/// Fee rates are expressed in millionths
+const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// `PoolState::status` bit that disables swaps
+const STATUS_SWAP_DISABLED: u8 = 1 << 4;
+
+/// CLMM pool account (fields needed for quoting and swapping)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClmmPoolInfo {
+    pub amm_config: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub observation_key: Pubkey,
+    pub mint_decimals_0: u8,
+    pub mint_decimals_1: u8,
+    pub tick_spacing: u16,
+    /// Liquidity active at the current price
+    pub liquidity: u128,
+    /// Q64.64 square root of the price of token 0 in token 1
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub status: u8,
+    /// One bit per tick array within ±512 arrays of tick 0
+    pub tick_array_bitmap: [u64; 16],
+}
+
+impl ClmmPoolInfo {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: raydium-clmm `states/pool.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < POOL_STATE_LEN {
+            return Err(anyhow!("Invalid Raydium CLMM pool account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != POOL_STATE_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Raydium CLMM pool"));
+        }
+
+        let mut tick_array_bitmap = [0u64; 16];
+        for (i, word) in tick_array_bitmap.iter_mut().enumerate() {
+            *word = read_u64(data, TICK_ARRAY_BITMAP_OFFSET + i * 8)?;
+        }
+
+        Ok(Self {
+            amm_config: read_pubkey(data, 9)?,
+            token_mint_0: read_pubkey(data, 73)?,
+            token_mint_1: read_pubkey(data, 105)?,
+            token_vault_0: read_pubkey(data, 137)?,
+            token_vault_1: read_pubkey(data, 169)?,
+            observation_key: read_pubkey(data, 201)?,
+            mint_decimals_0: data[233],
+            mint_decimals_1: data[234],
+            tick_spacing: read_u16(data, 235)?,
+            liquidity: read_u128(data, 237)?,
+            sqrt_price_x64: read_u128(data, 253)?,
+            tick_current: read_i32(data, 269)?,
+            protocol_fees_token_0: read_u64(data, 309)?,
+            protocol_fees_token_1: read_u64(data, 317)?,
+            status: data[389],
+            tick_array_bitmap,
+        })
+    }
+
+    /// Whether the pool status allows swaps
+    pub fn swap_enabled(&self) -> bool {
+        self.status & STATUS_SWAP_DISABLED == 0
+    }
+
+    /// Price of token 0 in token 1, ignoring decimals
+    pub fn price(&self) -> f64 {
+        let sqrt_price = self.sqrt_price_x64 as f64 / 2f64.powi(64);
+        sqrt_price * sqrt_price
+    }
+
+    fn ticks_in_array(&self) -> i32 {
+        TICK_ARRAY_SIZE * self.tick_spacing as i32
+    }
+
+    /// Bitmap position of the tick array starting at `start_index`, if the
+    /// default bitmap covers it
+    fn bitmap_position(&self, start_index: i32) -> Option<usize> {
+        let position = start_index.div_euclid(self.ticks_in_array()) + TICK_ARRAY_BITMAP_HALF;
+        (0..2 * TICK_ARRAY_BITMAP_HALF).contains(&position).then_some(position as usize)
+    }
+
+    fn is_tick_array_initialized(&self, start_index: i32) -> bool {
+        self.bitmap_position(start_index)
+            .map(|position| self.tick_array_bitmap[position / 64] & (1 << (position % 64)) != 0)
+            .unwrap_or(false)
+    }
+
+    /// Next initialized tick array after `start_index` in the swap direction
+    pub fn next_initialized_tick_array(&self, start_index: i32, zero_for_one: bool) -> Option<i32> {
+        let step = if zero_for_one { -self.ticks_in_array() } else { self.ticks_in_array() };
+        let mut next = start_index + step;
+        while self.bitmap_position(next).is_some() {
+            if self.is_tick_array_initialized(next) {
+                return Some(next);
+            }
+            next += step;
+        }
+        None
+    }
+
+    /// The tick array a swap starts in: the current one when initialized,
+    /// otherwise the next initialized one in the swap direction.
+    /// Returns whether the current array matched, and its start index.
+    pub fn first_initialized_tick_array(&self, zero_for_one: bool) -> Option<(bool, i32)> {
+        let current = tick_array_start_index(self.tick_current, self.tick_spacing);
+        if self.is_tick_array_initialized(current) {
+            return Some((true, current));
+        }
+        self.next_initialized_tick_array(current, zero_for_one).map(|start| (false, start))
+    }
+
+    /// Start indexes of the initialized tick arrays a swap in this direction walks through
+    pub fn swap_tick_array_starts(&self, zero_for_one: bool) -> Vec<i32> {
+        let mut starts = Vec::with_capacity(SWAP_TICK_ARRAY_COUNT);
+        let mut next = self.first_initialized_tick_array(zero_for_one).map(|(_, start)| start);
+        while let Some(start) = next {
+            starts.push(start);
+            if starts.len() == SWAP_TICK_ARRAY_COUNT {
+                break;
+            }
+            next = self.next_initialized_tick_array(start, zero_for_one);
+        }
+        starts
+    }
+}
+
+/// Fee configuration shared by every pool created with one `AmmConfig`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClmmAmmConfig {
+    pub protocol_fee_rate: u32,
+    /// Swap fee in millionths of the input (2500 = 0.25%)
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+}
+
+impl ClmmAmmConfig {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: raydium-clmm `states/config.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < AMM_CONFIG_LEN {
+            return Err(anyhow!("Invalid Raydium CLMM config account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != AMM_CONFIG_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Raydium CLMM config"));
+        }
+
+        Ok(Self {
+            protocol_fee_rate: read_u32(data, 43)?,
+            trade_fee_rate: read_u32(data, 47)?,
+            tick_spacing: read_u16(data, 51)?,
+            fund_fee_rate: read_u32(data, 53)?,
+        })
+    }
+
+    /// Swap fee in basis points (rounded down)
+    pub fn fee_bps(&self) -> u16 {
+        (self.trade_fee_rate / 100) as u16
+    }
+
+    /// Trade fee; protocol and fund fees are shares of it (millionths)
+    pub fn fee_model(&self) -> FeeModel {
+        FeeModel::Static {
+            trade: FeeRate::from_fraction(self.trade_fee_rate as u128, FEE_RATE_DENOMINATOR),
+            protocol_share: FeeRate::from_fraction(
+                (self.protocol_fee_rate + self.fund_fee_rate) as u128,
+                FEE_RATE_DENOMINATOR,
+            ),
+        }
+    }
+}
+
+/// The parts of a tick the swap loop needs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClmmTick {
+    pub tick: i32,
+    /// Liquidity added when the price crosses this tick upwards
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+}
+
+impl ClmmTick {
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross != 0
+    }
+}
+
+/// One `TickArrayState` account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClmmTickArray {
+    pub start_tick_index: i32,
+    pub ticks: Vec<ClmmTick>,
+}
+
+impl ClmmTickArray {
+    /// Decode a tick array and check that it belongs to `pool`
+    pub fn decode(pool: &Pubkey, data: &[u8]) -> Result<Self> {
+        if data.len() < TICK_ARRAY_LEN {
+            return Err(anyhow!("Invalid CLMM tick array account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != TICK_ARRAY_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Raydium CLMM tick array"));
+        }
+
+        let pool_id = read_pubkey(data, 8)?;
+        if pool_id != *pool {
+            return Err(anyhow!("Tick array belongs to {}, expected {}", pool_id, pool));
+        }
+
+        let ticks = (0..TICK_ARRAY_SIZE as usize)
+            .map(|i| {
+                let offset = TICKS_OFFSET + i * TICK_STATE_LEN;
+                Ok(ClmmTick {
+                    tick: read_i32(data, offset)?,
+                    liquidity_net: read_i128(data, offset + 4)?,
+                    liquidity_gross: read_u128(data, offset + 20)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            start_tick_index: read_i32(data, 40)?,
+            ticks,
+        })
+    }
+
+    /// Next initialized tick in this array from `tick_current`: at or below it
+    /// when swapping 0->1, strictly above it otherwise. `None` when the tick is
+    /// not in this array or no initialized tick remains.
+    fn next_initialized_tick(&self, tick_current: i32, tick_spacing: u16, zero_for_one: bool) -> Option<ClmmTick> {
+        if tick_array_start_index(tick_current, tick_spacing) != self.start_tick_index {
+            return None;
+        }
+
+        let mut offset = (tick_current - self.start_tick_index) / tick_spacing as i32;
+        if !zero_for_one {
+            offset += 1;
+        }
+        while (0..TICK_ARRAY_SIZE).contains(&offset) {
+            let tick = self.ticks[offset as usize];
+            if tick.is_initialized() {
+                return Some(tick);
+            }
+            offset += if zero_for_one { -1 } else { 1 };
+        }
+        None
+    }
+
+    /// First initialized tick met when entering this array in the swap direction
+    fn first_initialized_tick(&self, zero_for_one: bool) -> Option<ClmmTick> {
+        if zero_for_one {
+            self.ticks.iter().rev().find(|tick| tick.is_initialized()).copied()
+        } else {
+            self.ticks.iter().find(|tick| tick.is_initialized()).copied()
+        }
+    }
+}
+
+/// Decoded CLMM pool with its fee configuration and nearby tick arrays
+#[derive(Clone, Debug)]
+pub struct ClmmPoolState {
+    pub pool: ClmmPoolInfo,
+    pub config: Option<ClmmAmmConfig>,
+    /// Loaded tick arrays keyed by start tick index
+    pub tick_arrays: HashMap<i32, ClmmTickArray>,
+}
+
+/// Start index of the tick array containing `tick`
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// Tick array PDA: `[b"tick_array", pool, start_tick_index as big-endian i32]`
+pub fn tick_array_address(program_id: &Pubkey, pool: &Pubkey, start_tick_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", pool.as_ref(), &start_tick_index.to_be_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Q64.64 sqrt price at `tick`, bit-for-bit with the program's `tick_math.rs`
+pub fn sqrt_price_at_tick(tick: i32) -> u128 {
+    // sqrt(1.0001)^-(2^i) in Q64.64 for bits 1..=18
+    const FACTORS: [u128; 18] = [
+        0xfff97272373d4000,
+        0xfff2e50f5f657000,
+        0xffe5caca7e10f000,
+        0xffcb9843d60f7000,
+        0xff973b41fa98e800,
+        0xff2ea16466c9b000,
+        0xfe5dee046a9a3800,
+        0xfcbe86c7900bb000,
+        0xf987a7253ac65800,
+        0xf3392b0822bb6000,
+        0xe7159475a2caf000,
+        0xd097f3bdfd2f2000,
+        0xa9f746462d9f8000,
+        0x70d869a156f31c00,
+        0x31be135f97ed3200,
+        0x9aa508b5b85a500,
+        0x5d6af8dedc582c,
+        0x2216e584f5fa,
+    ];
+
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio: u128 = if abs_tick & 1 != 0 { 0xfffcb933bd6fb800 } else { 1 << 64 };
+    for (i, factor) in FACTORS.iter().enumerate() {
+        if abs_tick & (1 << (i + 1)) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+
+    if tick > 0 {
+        u128::MAX / ratio
+    } else {
+        ratio
+    }
+}
+
+/// Greatest tick whose sqrt price is at or below `sqrt_price_x64`
+pub fn tick_at_sqrt_price(sqrt_price_x64: u128) -> i32 {
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if sqrt_price_at_tick(mid) <= sqrt_price_x64 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+/// `a * b / c` rounded down or up, `None` when it does not fit in a u64
+fn mul_div_u64(a: &BigUint, b: &BigUint, c: &BigUint, round_up: bool) -> Option<u64> {
+    let product = a * b;
+    let quotient = &product / c;
+    let quotient = if round_up && !(product % c).is_zero() { quotient + 1u32 } else { quotient };
+    quotient.to_u64()
+}
+
+/// Token 0 amount between two sqrt prices (`get_delta_amount_0_unsigned`):
+/// `(L << 64) * (upper - lower) / upper / lower`, rounding each division
+fn delta_amount_0(sqrt_price_a: u128, sqrt_price_b: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+    let (lower, upper) = (sqrt_price_a.min(sqrt_price_b), sqrt_price_a.max(sqrt_price_b));
+    let numerator_1 = BigUint::from(liquidity) << 64u32;
+    let numerator_2 = BigUint::from(upper - lower);
+    let upper = BigUint::from(upper);
+    let lower = BigUint::from(lower);
+
+    let product = &numerator_1 * &numerator_2;
+    let first = &product / &upper;
+    if round_up {
+        let first = if (product % &upper).is_zero() { first } else { first + 1u32 };
+        let quotient = &first / &lower;
+        let quotient = if (first % &lower).is_zero() { quotient } else { quotient + 1u32 };
+        quotient.to_u64()
+    } else {
+        (first / lower).to_u64()
+    }
+}
+
+/// Token 1 amount between two sqrt prices (`get_delta_amount_1_unsigned`):
+/// `L * (upper - lower) >> 64`
+fn delta_amount_1(sqrt_price_a: u128, sqrt_price_b: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+    let (lower, upper) = (sqrt_price_a.min(sqrt_price_b), sqrt_price_a.max(sqrt_price_b));
+    mul_div_u64(
+        &BigUint::from(liquidity),
+        &BigUint::from(upper - lower),
+        &(BigUint::from(1u8) << 64u32),
+        round_up,
+    )
+}
+
+/// sqrt price after adding `amount` of input at constant liquidity
+fn next_sqrt_price_from_input(sqrt_price: u128, liquidity: u128, amount: u64, zero_for_one: bool) -> Result<u128> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+    if liquidity == 0 {
+        return Err(anyhow!("Raydium CLMM swap step with zero liquidity"));
+    }
+
+    if zero_for_one {
+        // (L << 64) * P / ((L << 64) + amount * P), rounded up
+        let numerator_1 = BigUint::from(liquidity) << 64u32;
+        let denominator = &numerator_1 + BigUint::from(amount) * BigUint::from(sqrt_price);
+        let product = numerator_1 * BigUint::from(sqrt_price);
+        let quotient = &product / &denominator;
+        let quotient = if (product % denominator).is_zero() { quotient } else { quotient + 1u32 };
+        quotient.to_u128().ok_or_else(|| anyhow!("Raydium CLMM sqrt price overflow"))
+    } else {
+        // P + (amount << 64) / L, rounded down
+        let delta = (BigUint::from(amount) << 64u32) / BigUint::from(liquidity);
+        delta
+            .to_u128()
+            .and_then(|delta| sqrt_price.checked_add(delta))
+            .ok_or_else(|| anyhow!("Raydium CLMM sqrt price overflow"))
+    }
+}
+
+/// Result of one swap step between two sqrt prices
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SwapStep {
+    amount_in: u64,
+    amount_out: u64,
+    fee_amount: u64,
+    sqrt_price_next: u128,
+}
+
+/// One exact-input step of the swap loop (`swap_math::compute_swap_step`)
+fn compute_swap_step(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+    zero_for_one: bool,
+) -> Result<SwapStep> {
+    let fee_rate = fee_rate as u128;
+    let amount_remaining_less_fee =
+        (amount_remaining as u128 * (FEE_RATE_DENOMINATOR - fee_rate) / FEE_RATE_DENOMINATOR) as u64;
+
+    let amount_in_delta = |from: u128, to: u128| {
+        if zero_for_one {
+            delta_amount_0(to, from, liquidity, true)
+        } else {
+            delta_amount_1(from, to, liquidity, true)
+        }
+    };
+
+    // `None` when the full range needs more input than fits in a u64
+    let max_in = amount_in_delta(sqrt_price_current, sqrt_price_target);
+    let sqrt_price_next = match max_in {
+        Some(max_in) if amount_remaining_less_fee >= max_in => sqrt_price_target,
+        _ => next_sqrt_price_from_input(sqrt_price_current, liquidity, amount_remaining_less_fee, zero_for_one)?,
+    };
+    let is_max = sqrt_price_next == sqrt_price_target;
+
+    let amount_in = match max_in {
+        Some(max_in) if is_max => max_in,
+        _ => amount_in_delta(sqrt_price_current, sqrt_price_next)
+            .ok_or_else(|| anyhow!("Raydium CLMM swap input overflow"))?,
+    };
+    let amount_out = if zero_for_one {
+        delta_amount_1(sqrt_price_next, sqrt_price_current, liquidity, false)
+    } else {
+        delta_amount_0(sqrt_price_current, sqrt_price_next, liquidity, false)
+    }
+    .ok_or_else(|| anyhow!("Raydium CLMM swap output overflow"))?;
+
+    let fee_amount = if is_max {
+        (amount_in as u128 * fee_rate).div_ceil(FEE_RATE_DENOMINATOR - fee_rate) as u64
+    } else {
+        // The whole remainder is consumed; what the price did not absorb is fee
+        amount_remaining - amount_in
+    };
+
+    Ok(SwapStep { amount_in, amount_out, fee_amount, sqrt_price_next })
+}
+
+/// Simulate an exact-input swap like the program's `swap_internal`, walking
+/// initialized tick arrays through the pool bitmap
+pub fn simulate_swap_exact_in(
+    pool: &ClmmPoolInfo,
+    tick_arrays: &HashMap<i32, ClmmTickArray>,
+    fee_rate: u32,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Result<u64> {
+    if pool.tick_spacing == 0 {
+        return Err(anyhow!("Raydium CLMM pool has zero tick spacing"));
+    }
+    // What the program uses when the instruction passes a zero limit
+    let sqrt_price_limit = if zero_for_one { MIN_SQRT_PRICE_X64 + 1 } else { MAX_SQRT_PRICE_X64 - 1 };
+    let load = |start: i32| {
+        tick_arrays.get(&start)
+            .ok_or_else(|| anyhow!("Raydium CLMM swap ran past the loaded tick arrays (start {})", start))
+    };
+
+    let (mut is_match_current_array, mut array_start) = pool.first_initialized_tick_array(zero_for_one)
+        .ok_or_else(|| anyhow!("Raydium CLMM pool has no initialized tick arrays"))?;
+    let mut tick_array = load(array_start)?;
+
+    let mut amount_remaining = amount_in;
+    let mut amount_out: u64 = 0;
+    let mut sqrt_price = pool.sqrt_price_x64;
+    let mut liquidity = pool.liquidity;
+    let mut tick_current = pool.tick_current;
+
+    while amount_remaining > 0 && sqrt_price != sqrt_price_limit {
+        let mut next_tick = tick_array.next_initialized_tick(tick_current, pool.tick_spacing, zero_for_one);
+        if next_tick.is_none() && !is_match_current_array {
+            is_match_current_array = true;
+            next_tick = tick_array.first_initialized_tick(zero_for_one);
+        }
+        let next_tick = match next_tick {
+            Some(tick) => tick,
+            None => {
+                array_start = pool.next_initialized_tick_array(array_start, zero_for_one)
+                    .ok_or_else(|| anyhow!("Raydium CLMM pool has insufficient liquidity for {}", amount_in))?;
+                tick_array = load(array_start)?;
+                tick_array.first_initialized_tick(zero_for_one)
+                    .ok_or_else(|| anyhow!("Tick array {} has no initialized tick", array_start))?
+            }
+        };
+
+        let tick_next = next_tick.tick.clamp(MIN_TICK, MAX_TICK);
+        let sqrt_price_at_next = sqrt_price_at_tick(tick_next);
+        let sqrt_price_target = if zero_for_one {
+            sqrt_price_at_next.max(sqrt_price_limit)
+        } else {
+            sqrt_price_at_next.min(sqrt_price_limit)
+        };
+
+        let step = compute_swap_step(sqrt_price, sqrt_price_target, liquidity, amount_remaining, fee_rate, zero_for_one)?;
+
+        amount_remaining = amount_remaining
+            .checked_sub(step.amount_in + step.fee_amount)
+            .ok_or_else(|| anyhow!("Raydium CLMM swap step consumed more than the remaining input"))?;
+        amount_out = amount_out
+            .checked_add(step.amount_out)
+            .ok_or_else(|| anyhow!("Raydium CLMM swap output overflow"))?;
+
+        if step.sqrt_price_next == sqrt_price_at_next {
+            if next_tick.is_initialized() {
+                // Crossing downwards removes the tick's net liquidity
+                let delta = if zero_for_one { -next_tick.liquidity_net } else { next_tick.liquidity_net };
+                liquidity = if delta < 0 {
+                    liquidity.checked_sub(delta.unsigned_abs())
+                } else {
+                    liquidity.checked_add(delta as u128)
+                }
+                .ok_or_else(|| anyhow!("Raydium CLMM liquidity overflow crossing tick {}", tick_next))?;
+            }
+            tick_current = if zero_for_one { tick_next - 1 } else { tick_next };
+        } else if step.sqrt_price_next != sqrt_price {
+            tick_current = tick_at_sqrt_price(step.sqrt_price_next);
+        }
+
+        sqrt_price = step.sqrt_price_next;
+    }
+
+    if amount_remaining > 0 {
+        return Err(anyhow!("Raydium CLMM cannot fill {} input: price limit reached", amount_in));
+    }
+
+    Ok(amount_out)
+}
+
+pub struct RaydiumClmmAdapter {
+    program_id: Pubkey,
+}
+
+impl RaydiumClmmAdapter {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a ClmmPoolState> {
+        match &pool.venue {
+            VenueState::RaydiumClmm(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Raydium CLMM state", pool.pool_address)),
+        }
+    }
+
+    /// Initialized tick arrays (start, address) either direction of a swap may touch
+    fn tick_array_addresses(&self, pool_address: &Pubkey, info: &ClmmPoolInfo) -> Vec<(i32, Pubkey)> {
+        let mut addresses: Vec<(i32, Pubkey)> = Vec::new();
+        for zero_for_one in [true, false] {
+            for start in info.swap_tick_array_starts(zero_for_one) {
+                if !addresses.iter().any(|(existing, _)| *existing == start) {
+                    addresses.push((start, tick_array_address(&self.program_id, pool_address, start)));
+                }
+            }
+        }
+        addresses
+    }
+}
+
+impl Default for RaydiumClmmAdapter {
+    fn default() -> Self {
+        Self::new(RAYDIUM_CLMM_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for RaydiumClmmAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::RaydiumClmm
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: Some(POOL_STATE_LEN as u64),
+            mint_a_offset: 73, // token_mint_0
+            mint_b_offset: 105, // token_mint_1
+        })
+    }
+
+    fn pool_init_logs(&self) -> &'static [&'static str] {
+        &["Program log: Instruction: CreatePool"]
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let pool = ClmmPoolInfo::decode(data)?;
+
+        if !pool.swap_enabled() {
+            return Err(anyhow!("Raydium CLMM pool {} does not allow swaps (status {})", address, pool.status));
+        }
+
+        debug!(
+            "Parsed Raydium CLMM pool: tick={}, liquidity={}, config={}",
+            pool.tick_current, pool.liquidity, pool.amm_config
+        );
+
+        // Reserves, fee rate and tick arrays are filled in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: pool.token_mint_0,
+            token_b: pool.token_mint_1,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: 0,
+            dex_type: DexType::RaydiumClmm,
+            program_id: self.program_id,
+            venue: VenueState::RaydiumClmm(Box::new(ClmmPoolState {
+                pool,
+                config: None,
+                tick_arrays: HashMap::new(),
+            })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        let state = match self.state(pool) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut accounts = vec![state.pool.token_vault_0, state.pool.token_vault_1, state.pool.amm_config];
+        accounts.extend(
+            self.tick_array_addresses(&pool.pool_address, &state.pool)
+                .into_iter()
+                .map(|(_, address)| address),
+        );
+        accounts
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let state = match &pool.venue {
+            VenueState::RaydiumClmm(state) => state,
+            _ => return Err(anyhow!("Pool {} has no Raydium CLMM state", address)),
+        };
+        let info = &state.pool;
+
+        let vault_0 = accounts.get(&info.token_vault_0)
+            .ok_or_else(|| anyhow!("Missing token 0 vault {} for pool {}", info.token_vault_0, address))?;
+        let vault_1 = accounts.get(&info.token_vault_1)
+            .ok_or_else(|| anyhow!("Missing token 1 vault {} for pool {}", info.token_vault_1, address))?;
+        let config = accounts.get(&info.amm_config)
+            .ok_or_else(|| anyhow!("Missing AMM config {} for pool {}", info.amm_config, address))?;
+
+        let reserve_a = token_account_amount(vault_0)?.saturating_sub(info.protocol_fees_token_0);
+        let reserve_b = token_account_amount(vault_1)?.saturating_sub(info.protocol_fees_token_1);
+        let config = ClmmAmmConfig::decode(&config.data)?;
+
+        let mut tick_arrays = HashMap::new();
+        for (start, tick_array) in self.tick_array_addresses(&address, info) {
+            if let Some(account) = accounts.get(&tick_array) {
+                tick_arrays.insert(start, ClmmTickArray::decode(&address, &account.data)?);
+            }
+        }
+
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+        pool.fee_bps = config.fee_bps();
+        if let VenueState::RaydiumClmm(state) = &mut pool.venue {
+            state.config = Some(config);
+            state.tick_arrays = tick_arrays;
+        }
+
+        debug!("Raydium CLMM pool {} vaults: ({}, {})", address, reserve_a, reserve_b);
+
+        Ok(())
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let state = self.state(pool)?;
+        let config = state.config.as_ref()
+            .ok_or_else(|| anyhow!("AMM config not loaded for Raydium CLMM pool {}", pool.pool_address))?;
+        simulate_swap_exact_in(&state.pool, &state.tick_arrays, config.trade_fee_rate, amount_in, a_to_b)
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool).ok().and_then(|state| state.config.as_ref()) {
+            Some(config) => config.fee_model(),
+            None => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn spot_rate(&self, pool: &PoolData, a_to_b: bool) -> f64 {
+        let price = match self.state(pool) {
+            Ok(state) => state.pool.price(),
+            Err(_) => return 0.0,
+        };
+        if a_to_b {
+            price
+        } else if price > 0.0 {
+            1.0 / price
+        } else {
+            0.0
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let info = &self.state(pool)?.pool;
+        let (input_vault, output_vault, input_mint, output_mint) = if params.a_to_b {
+            (info.token_vault_0, info.token_vault_1, info.token_mint_0, info.token_mint_1)
+        } else {
+            (info.token_vault_1, info.token_vault_0, info.token_mint_1, info.token_mint_0)
+        };
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new_readonly(info.amm_config, false),
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new(params.user_source, false),
+            AccountMeta::new(params.user_destination, false),
+            AccountMeta::new(input_vault, false),
+            AccountMeta::new(output_vault, false),
+            AccountMeta::new(info.observation_key, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+            AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+            AccountMeta::new_readonly(input_mint, false),
+            AccountMeta::new_readonly(output_mint, false),
+        ];
+
+        // Remaining accounts: the initialized tick arrays in swap order
+        let tick_arrays = info.swap_tick_array_starts(params.a_to_b);
+        if tick_arrays.is_empty() {
+            return Err(anyhow!("Raydium CLMM pool {} has no initialized tick arrays", pool.pool_address));
+        }
+        accounts.extend(tick_arrays.into_iter().map(|start| {
+            AccountMeta::new(tick_array_address(&self.program_id, &pool.pool_address, start), false)
+        }));
+
+        Ok(accounts)
+    }
+
+    fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let mut data = SWAP_V2_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes()); // other_amount_threshold
+        data.extend_from_slice(&0u128.to_le_bytes()); // sqrt_price_limit_x64: program default
+        data.push(1); // is_base_input
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK_SPACING: u16 = 10;
+
+    fn pool_at(tick_current: i32, liquidity: u128, initialized_arrays: &[i32]) -> ClmmPoolInfo {
+        let mut pool = ClmmPoolInfo {
+            tick_spacing: TICK_SPACING,
+            liquidity,
+            sqrt_price_x64: sqrt_price_at_tick(tick_current),
+            tick_current,
+            ..Default::default()
+        };
+        for start in initialized_arrays {
+            let position = pool.bitmap_position(*start).unwrap();
+            pool.tick_array_bitmap[position / 64] |= 1 << (position % 64);
+        }
+        pool
+    }
+
+    fn tick_array(start_tick_index: i32, initialized: &[(i32, i128)]) -> ClmmTickArray {
+        let mut ticks: Vec<ClmmTick> = (0..TICK_ARRAY_SIZE)
+            .map(|i| ClmmTick { tick: start_tick_index + i * TICK_SPACING as i32, ..Default::default() })
+            .collect();
+        for (tick, liquidity_net) in initialized {
+            let offset = ((tick - start_tick_index) / TICK_SPACING as i32) as usize;
+            ticks[offset].liquidity_net = *liquidity_net;
+            ticks[offset].liquidity_gross = liquidity_net.unsigned_abs();
+        }
+        ClmmTickArray { start_tick_index, ticks }
+    }
+
+    #[test]
+    fn test_sqrt_price_bounds() {
+        assert_eq!(sqrt_price_at_tick(MIN_TICK), MIN_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_at_tick(MAX_TICK), MAX_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_at_tick(0), 1 << 64);
+
+        assert_eq!(tick_at_sqrt_price(MIN_SQRT_PRICE_X64), MIN_TICK);
+        assert_eq!(tick_at_sqrt_price(sqrt_price_at_tick(1234)), 1234);
+        assert_eq!(tick_at_sqrt_price(sqrt_price_at_tick(-1234) + 1), -1234);
+    }
+
+    #[test]
+    fn test_bitmap_walks_initialized_arrays() {
+        // 600 ticks per array at spacing 10
+        let pool = pool_at(100, 1, &[0, -1200, 1800, 3000]);
+        assert_eq!(pool.first_initialized_tick_array(true), Some((true, 0)));
+        assert_eq!(pool.swap_tick_array_starts(true), vec![0, -1200]);
+        assert_eq!(pool.swap_tick_array_starts(false), vec![0, 1800, 3000]);
+
+        let pool = pool_at(700, 1, &[0, 1800]);
+        assert_eq!(pool.first_initialized_tick_array(false), Some((false, 1800)));
+        assert_eq!(pool.first_initialized_tick_array(true), Some((false, 0)));
+    }
+
+    #[test]
+    fn test_swap_within_one_range_matches_closed_form() {
+        let liquidity = 1_000_000_000_000u128;
+        let pool = pool_at(0, liquidity, &[0, -600]);
+        let tick_arrays = HashMap::from([
+            (0, tick_array(0, &[(500, 1)])),
+            (-600, tick_array(-600, &[(-600, -1)])),
+        ]);
+
+        // Zero fee: token 1 in moves the price up by amount / L
+        let amount_in = 1_000_000u64;
+        let out = simulate_swap_exact_in(&pool, &tick_arrays, 0, amount_in, false).unwrap();
+        let next = sqrt_price_at_tick(0) + ((amount_in as u128) << 64) / liquidity;
+        let expected = delta_amount_0(sqrt_price_at_tick(0), next, liquidity, false).unwrap();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_crossing_tick_reduces_liquidity() {
+        let liquidity = 1_000_000_000u128;
+        let pool = pool_at(5, liquidity, &[0, -600]);
+        // Liquidity that ends at tick 0 is removed when crossing it downwards
+        let tick_arrays = HashMap::from([
+            (0, tick_array(0, &[(0, (liquidity / 2) as i128)])),
+            (-600, tick_array(-600, &[(-600, (liquidity / 2) as i128)])),
+        ]);
+
+        let small = simulate_swap_exact_in(&pool, &tick_arrays, 2500, 1_000, true).unwrap();
+        assert!(small > 0);
+
+        // A large swap crosses tick 0 and keeps going at half liquidity
+        let large = simulate_swap_exact_in(&pool, &tick_arrays, 2500, 10_000_000, true).unwrap();
+        assert!(large > small);
+
+        // Past the last loaded array the quote fails rather than guessing
+        assert!(simulate_swap_exact_in(&pool, &tick_arrays, 2500, u64::MAX / 2, true).is_err());
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let adapter = RaydiumClmmAdapter::default();
+        let address = Pubkey::new_unique();
+        let info = ClmmPoolInfo {
+            amm_config: Pubkey::new_unique(),
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+            ..pool_at(100, 1, &[0, -600, -1200])
+        };
+        let pool = PoolData {
+            pool_address: address,
+            token_a: info.token_mint_0,
+            token_b: info.token_mint_1,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: 25,
+            dex_type: DexType::RaydiumClmm,
+            program_id: RAYDIUM_CLMM_PROGRAM_ID,
+            venue: VenueState::RaydiumClmm(Box::new(ClmmPoolState {
+                pool: info.clone(),
+                config: None,
+                tick_arrays: HashMap::new(),
+            })),
+            mints: Default::default(),
+            slot: 0,
+        };
+        let params = SwapParams {
+            user: Pubkey::new_unique(),
+            user_source: Pubkey::new_unique(),
+            user_destination: Pubkey::new_unique(),
+            amount_in: 1000,
+            minimum_out: 990,
+            a_to_b: true,
+        };
+
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        assert_eq!(ix.accounts.len(), 16);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[5].pubkey, info.token_vault_0);
+        assert_eq!(ix.accounts[6].pubkey, info.token_vault_1);
+        assert_eq!(ix.accounts[9].pubkey, TOKEN_2022_PROGRAM_ID);
+        assert_eq!(ix.accounts[13].pubkey, tick_array_address(&RAYDIUM_CLMM_PROGRAM_ID, &address, 0));
+        assert_eq!(ix.accounts[15].pubkey, tick_array_address(&RAYDIUM_CLMM_PROGRAM_ID, &address, -1200));
+        assert_eq!(&ix.data[..8], &SWAP_V2_DISCRIMINATOR);
+        assert_eq!(ix.data.len(), 41);
+    }
+}
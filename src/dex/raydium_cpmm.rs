@@ -0,0 +1,527 @@
+// Raydium CPMM Adapter
+//
+// Decodes Raydium CP-Swap (`PoolState`, `AmmConfig`) accounts and builds the
+// `swap_base_input` instruction.
+//
+// CPMM is constant product like AMM v4 but without an order book, supports
+// Token-2022 mints, and keeps its fee rates in a shared `AmmConfig` account.
+// Reserves are the vault balances minus the protocol, fund and creator fees
+// the pool has accrued but not yet collected.
+
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use tracing::debug;
+
+use crate::dex::adapter::{
+    read_array, read_pubkey, read_u64, token_account_amount, DexAdapter, MintLayout, SwapParams,
+    VenueState,
+};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::triangular_arb::DexType;
+
+/// Raydium CPMM program id
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+
+/// Anchor discriminator of the `swap_base_input` instruction
+const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+/// Anchor discriminator of the `PoolState` account
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+/// Anchor discriminator of the `AmmConfig` account
+const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+
+/// Size of the PoolState account
+pub const POOL_STATE_LEN: usize = 637;
+
+/// Size of the AmmConfig account
+pub const AMM_CONFIG_LEN: usize = 236;
+
+/// Seed of the vault and LP mint authority PDA
+const AUTH_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+/// Fee rates are expressed in millionths
+const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// `PoolState::status` bit that disables swaps
+const STATUS_SWAP_DISABLED: u8 = 1 << 2;
+
+/// Where the creator fee is charged (`PoolState::creator_fee_on`)
+const CREATOR_FEE_ON_BOTH_TOKENS: u8 = 0;
+const CREATOR_FEE_ON_TOKEN_0: u8 = 1;
+const CREATOR_FEE_ON_TOKEN_1: u8 = 2;
+
+/// CPMM pool account (fields needed for quoting and swapping)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CpmmPoolInfo {
+    pub amm_config: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    /// Token program of each mint (SPL Token or Token-2022)
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    pub auth_bump: u8,
+    pub status: u8,
+    pub mint_0_decimals: u8,
+    pub mint_1_decimals: u8,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+    pub creator_fee_on: u8,
+    pub enable_creator_fee: bool,
+    pub creator_fees_token_0: u64,
+    pub creator_fees_token_1: u64,
+}
+
+impl CpmmPoolInfo {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: raydium-cp-swap `states/pool.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < POOL_STATE_LEN {
+            return Err(anyhow!("Invalid Raydium CPMM pool account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != POOL_STATE_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Raydium CPMM pool"));
+        }
+
+        Ok(Self {
+            amm_config: read_pubkey(data, 8)?,
+            token_0_vault: read_pubkey(data, 72)?,
+            token_1_vault: read_pubkey(data, 104)?,
+            lp_mint: read_pubkey(data, 136)?,
+            token_0_mint: read_pubkey(data, 168)?,
+            token_1_mint: read_pubkey(data, 200)?,
+            token_0_program: read_pubkey(data, 232)?,
+            token_1_program: read_pubkey(data, 264)?,
+            observation_key: read_pubkey(data, 296)?,
+            auth_bump: data[328],
+            status: data[329],
+            mint_0_decimals: data[331],
+            mint_1_decimals: data[332],
+            protocol_fees_token_0: read_u64(data, 341)?,
+            protocol_fees_token_1: read_u64(data, 349)?,
+            fund_fees_token_0: read_u64(data, 357)?,
+            fund_fees_token_1: read_u64(data, 365)?,
+            open_time: read_u64(data, 373)?,
+            creator_fee_on: data[389],
+            enable_creator_fee: data[390] != 0,
+            creator_fees_token_0: read_u64(data, 397)?,
+            creator_fees_token_1: read_u64(data, 405)?,
+        })
+    }
+
+    /// Whether the pool status allows swaps
+    pub fn swap_enabled(&self) -> bool {
+        self.status & STATUS_SWAP_DISABLED == 0
+    }
+
+    /// Fees accrued in the vaults that do not belong to liquidity providers
+    pub fn unclaimed_fees(&self) -> (u64, u64) {
+        (
+            self.protocol_fees_token_0
+                .saturating_add(self.fund_fees_token_0)
+                .saturating_add(self.creator_fees_token_0),
+            self.protocol_fees_token_1
+                .saturating_add(self.fund_fees_token_1)
+                .saturating_add(self.creator_fees_token_1),
+        )
+    }
+
+    /// Whether the creator fee is taken from the input when swapping token 0 in
+    fn creator_fee_on_input(&self, zero_for_one: bool) -> bool {
+        match self.creator_fee_on {
+            CREATOR_FEE_ON_BOTH_TOKENS => true,
+            CREATOR_FEE_ON_TOKEN_0 => zero_for_one,
+            CREATOR_FEE_ON_TOKEN_1 => !zero_for_one,
+            _ => true,
+        }
+    }
+
+    /// Vault and LP mint authority PDA: `[b"vault_and_lp_mint_auth_seed", auth_bump]`
+    pub fn authority(&self, program_id: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(&[AUTH_SEED, &[self.auth_bump]], program_id)
+            .map_err(|e| anyhow!("Invalid Raydium CPMM authority bump {}: {}", self.auth_bump, e))
+    }
+}
+
+/// Fee rates shared by every pool created with one `AmmConfig`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CpmmAmmConfig {
+    /// Swap fee in millionths of the input (2500 = 0.25%)
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub creator_fee_rate: u64,
+}
+
+impl CpmmAmmConfig {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: raydium-cp-swap `states/config.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < AMM_CONFIG_LEN {
+            return Err(anyhow!("Invalid Raydium CPMM config account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != AMM_CONFIG_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Raydium CPMM config"));
+        }
+
+        Ok(Self {
+            trade_fee_rate: read_u64(data, 12)?,
+            protocol_fee_rate: read_u64(data, 20)?,
+            fund_fee_rate: read_u64(data, 28)?,
+            creator_fee_rate: read_u64(data, 108)?,
+        })
+    }
+
+    /// Swap fee in basis points (rounded down)
+    pub fn fee_bps(&self) -> u16 {
+        (self.trade_fee_rate as u128 * 10_000 / FEE_RATE_DENOMINATOR) as u16
+    }
+
+    /// Trade fee (plus the creator fee when the pool charges it); protocol
+    /// and fund fees are shares of the trade fee
+    pub fn fee_model(&self, creator_fee: bool) -> FeeModel {
+        let creator_fee_rate = if creator_fee { self.creator_fee_rate } else { 0 };
+        FeeModel::Static {
+            trade: FeeRate::from_fraction((self.trade_fee_rate + creator_fee_rate) as u128, FEE_RATE_DENOMINATOR),
+            protocol_share: FeeRate::from_fraction(
+                (self.protocol_fee_rate + self.fund_fee_rate) as u128,
+                FEE_RATE_DENOMINATOR,
+            ),
+        }
+    }
+}
+
+/// Fee on `amount` at `rate` millionths, rounded up
+fn fee_amount(amount: u128, rate: u64) -> u128 {
+    (amount * rate as u128).div_ceil(FEE_RATE_DENOMINATOR)
+}
+
+/// Exact `swap_base_input` output (`CurveCalculator::swap_base_input`).
+/// The trade fee, and the creator fee when charged on the input, are taken
+/// from the input rounded up; a creator fee charged on the output is taken
+/// from the output rounded up.
+pub fn swap_base_input(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    trade_fee_rate: u64,
+    creator_fee_rate: u64,
+    creator_fee_on_input: bool,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("Pool has empty reserves"));
+    }
+
+    let amount_in = amount_in as u128;
+    let input_fee_rate = if creator_fee_on_input { trade_fee_rate + creator_fee_rate } else { trade_fee_rate };
+    let amount_in_less_fees = amount_in
+        .checked_sub(fee_amount(amount_in, input_fee_rate))
+        .ok_or_else(|| anyhow!("Swap amount {} does not cover fees", amount_in))?;
+
+    let amount_out = amount_in_less_fees * reserve_out as u128 / (reserve_in as u128 + amount_in_less_fees);
+    let amount_out = if creator_fee_on_input {
+        amount_out
+    } else {
+        amount_out - fee_amount(amount_out, creator_fee_rate).min(amount_out)
+    };
+
+    Ok(amount_out as u64)
+}
+
+/// Decoded CPMM pool with its fee configuration
+#[derive(Clone, Debug)]
+pub struct CpmmPoolState {
+    pub pool: CpmmPoolInfo,
+    pub config: Option<CpmmAmmConfig>,
+}
+
+pub struct RaydiumCpmmAdapter {
+    program_id: Pubkey,
+}
+
+impl RaydiumCpmmAdapter {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a CpmmPoolState> {
+        match &pool.venue {
+            VenueState::RaydiumCpmm(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Raydium CPMM state", pool.pool_address)),
+        }
+    }
+}
+
+impl Default for RaydiumCpmmAdapter {
+    fn default() -> Self {
+        Self::new(RAYDIUM_CPMM_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for RaydiumCpmmAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::RaydiumCpmm
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: Some(POOL_STATE_LEN as u64),
+            mint_a_offset: 168, // token_0_mint
+            mint_b_offset: 200, // token_1_mint
+        })
+    }
+
+    fn pool_init_logs(&self) -> &'static [&'static str] {
+        &["Program log: Instruction: Initialize", "Program log: Instruction: InitializeWithPermission"]
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let pool = CpmmPoolInfo::decode(data)?;
+
+        if !pool.swap_enabled() {
+            return Err(anyhow!("Raydium CPMM pool {} does not allow swaps (status {})", address, pool.status));
+        }
+
+        debug!(
+            "Parsed Raydium CPMM pool: token0={}, token1={}, config={}",
+            pool.token_0_mint, pool.token_1_mint, pool.amm_config
+        );
+
+        // Reserves and the fee rate are filled in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: pool.token_0_mint,
+            token_b: pool.token_1_mint,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: 0,
+            dex_type: DexType::RaydiumCpmm,
+            program_id: self.program_id,
+            venue: VenueState::RaydiumCpmm(Box::new(CpmmPoolState { pool, config: None })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        match self.state(pool) {
+            Ok(state) => vec![state.pool.token_0_vault, state.pool.token_1_vault, state.pool.amm_config],
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let state = match &mut pool.venue {
+            VenueState::RaydiumCpmm(state) => state,
+            _ => return Err(anyhow!("Pool {} has no Raydium CPMM state", address)),
+        };
+        let info = &state.pool;
+
+        let vault_0 = accounts.get(&info.token_0_vault)
+            .ok_or_else(|| anyhow!("Missing token 0 vault {} for pool {}", info.token_0_vault, address))?;
+        let vault_1 = accounts.get(&info.token_1_vault)
+            .ok_or_else(|| anyhow!("Missing token 1 vault {} for pool {}", info.token_1_vault, address))?;
+        let config = accounts.get(&info.amm_config)
+            .ok_or_else(|| anyhow!("Missing AMM config {} for pool {}", info.amm_config, address))?;
+
+        let (fees_0, fees_1) = info.unclaimed_fees();
+        let reserve_a = token_account_amount(vault_0)?.saturating_sub(fees_0);
+        let reserve_b = token_account_amount(vault_1)?.saturating_sub(fees_1);
+        let config = CpmmAmmConfig::decode(&config.data)?;
+
+        pool.fee_bps = config.fee_bps();
+        state.config = Some(config);
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+
+        debug!("Raydium CPMM pool {} reserves: ({}, {})", address, reserve_a, reserve_b);
+
+        Ok(())
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let state = self.state(pool)?;
+        let config = state.config.as_ref()
+            .ok_or_else(|| anyhow!("AMM config not loaded for Raydium CPMM pool {}", pool.pool_address))?;
+        let creator_fee_rate = if state.pool.enable_creator_fee { config.creator_fee_rate } else { 0 };
+        let (reserve_in, reserve_out) = pool.reserves(a_to_b);
+
+        swap_base_input(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            config.trade_fee_rate,
+            creator_fee_rate,
+            state.pool.creator_fee_on_input(a_to_b),
+        )
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(CpmmPoolState { pool: info, config: Some(config) }) => config.fee_model(info.enable_creator_fee),
+            _ => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let info = &self.state(pool)?.pool;
+        let token_0 = (info.token_0_vault, info.token_0_program, info.token_0_mint);
+        let token_1 = (info.token_1_vault, info.token_1_program, info.token_1_mint);
+        let (input, output) = if params.a_to_b { (token_0, token_1) } else { (token_1, token_0) };
+
+        Ok(vec![
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new_readonly(info.authority(&self.program_id)?, false),
+            AccountMeta::new_readonly(info.amm_config, false),
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new(params.user_source, false),
+            AccountMeta::new(params.user_destination, false),
+            AccountMeta::new(input.0, false),
+            AccountMeta::new(output.0, false),
+            AccountMeta::new_readonly(input.1, false),
+            AccountMeta::new_readonly(output.1, false),
+            AccountMeta::new_readonly(input.2, false),
+            AccountMeta::new_readonly(output.2, false),
+            AccountMeta::new(info.observation_key, false),
+        ])
+    }
+
+    fn swap_data(&self, _pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let mut data = SWAP_BASE_INPUT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn pool_state_data(status: u8) -> Vec<u8> {
+        let mut data = vec![0u8; POOL_STATE_LEN];
+        data[..8].copy_from_slice(&POOL_STATE_DISCRIMINATOR);
+        for (offset, seed) in [(8, 8u8), (72, 72), (104, 104), (168, 168), (200, 200), (232, 232), (264, 26), (296, 29)] {
+            data[offset..offset + 32].copy_from_slice(pubkey(seed).as_ref());
+        }
+        data[329] = status;
+        data[341..349].copy_from_slice(&1_000u64.to_le_bytes()); // protocol fees token 0
+        data[365..373].copy_from_slice(&500u64.to_le_bytes()); // fund fees token 1
+        data
+    }
+
+    fn amm_config_data(trade_fee_rate: u64) -> Vec<u8> {
+        let mut data = vec![0u8; AMM_CONFIG_LEN];
+        data[..8].copy_from_slice(&AMM_CONFIG_DISCRIMINATOR);
+        data[12..20].copy_from_slice(&trade_fee_rate.to_le_bytes());
+        data
+    }
+
+    fn token_account(amount: u64) -> Account {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Account { data, ..Default::default() }
+    }
+
+    #[test]
+    fn test_swap_base_input_matches_curve_calculator() {
+        // 1000 in, 2500/1e6 fee rounded up = 3, 997 after fee
+        // out = 2_000_000 * 997 / (1_000_000 + 997) = 1992
+        assert_eq!(swap_base_input(1000, 1_000_000, 2_000_000, 2500, 0, true).unwrap(), 1992);
+
+        // Creator fee on the input widens the input fee: ceil(1000 * 3500 / 1e6) = 4
+        assert_eq!(swap_base_input(1000, 1_000_000, 2_000_000, 2500, 1000, true).unwrap(), 1990);
+
+        // Creator fee on the output: 1992 - ceil(1992 * 1000 / 1e6) = 1990
+        assert_eq!(swap_base_input(1000, 1_000_000, 2_000_000, 2500, 1000, false).unwrap(), 1990);
+
+        assert!(swap_base_input(1000, 0, 2_000_000, 2500, 0, true).is_err());
+    }
+
+    #[test]
+    fn test_swap_disabled_pool_rejected() {
+        let adapter = RaydiumCpmmAdapter::default();
+        assert!(adapter.decode_pool(&pubkey(1), &pool_state_data(STATUS_SWAP_DISABLED)).is_err());
+        assert!(adapter.decode_pool(&pubkey(1), &pool_state_data(0)).is_ok());
+    }
+
+    #[test]
+    fn test_reserves_exclude_unclaimed_fees() {
+        let adapter = RaydiumCpmmAdapter::default();
+        let mut pool = adapter.decode_pool(&pubkey(1), &pool_state_data(0)).unwrap();
+        assert_eq!(adapter.dependent_accounts(&pool), vec![pubkey(72), pubkey(104), pubkey(8)]);
+
+        let accounts = HashMap::from([
+            (pubkey(72), token_account(1_001_000)),
+            (pubkey(104), token_account(2_000_500)),
+            (pubkey(8), Account { data: amm_config_data(2500), ..Default::default() }),
+        ]);
+        adapter.apply_dependent_accounts(&mut pool, &accounts).unwrap();
+
+        assert_eq!((pool.reserve_a, pool.reserve_b), (1_000_000, 2_000_000));
+        assert_eq!(pool.fee_bps, 25);
+        assert_eq!(adapter.quote_exact_in(&pool, 1000, true).unwrap(), 1992);
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let adapter = RaydiumCpmmAdapter::default();
+        let (authority, bump) = Pubkey::find_program_address(&[AUTH_SEED], &RAYDIUM_CPMM_PROGRAM_ID);
+        let mut data = pool_state_data(0);
+        data[328] = bump;
+        let pool = adapter.decode_pool(&pubkey(1), &data).unwrap();
+
+        let params = SwapParams {
+            user: pubkey(200),
+            user_source: pubkey(201),
+            user_destination: pubkey(202),
+            amount_in: 1000,
+            minimum_out: 990,
+            a_to_b: false,
+        };
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                pubkey(200),
+                authority,
+                pubkey(8),
+                pubkey(1),
+                pubkey(201),
+                pubkey(202),
+                pubkey(104),
+                pubkey(72),
+                pubkey(26),
+                pubkey(232),
+                pubkey(200),
+                pubkey(168),
+                pubkey(29),
+            ]
+        );
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(&ix.data[..8], &SWAP_BASE_INPUT_DISCRIMINATOR);
+        assert_eq!(ix.data.len(), 24);
+    }
+}
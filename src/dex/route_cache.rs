@@ -0,0 +1,207 @@
+// Route Cache
+//
+// Which cycles exist depends only on which pools are in the graph, not on
+// their rates. The cache precomputes, per base token, every simple cycle of up
+// to `max_hops` hops with the pools it trades through, so the hot path only
+// has to price known routes with the current edge rates. An entry is rebuilt
+// when its TTL (`routing.route_cache_ttl_seconds`) runs out or when pools have
+// been added to or removed from the graph since it was built.
+//
+// The cache is shared (`SharedRouteCache`) between the graph's own detection
+// (`ArbitrageGraph::detect_triangular_arbitrage_cached`) and
+// `BellmanFordDetector::with_route_cache`. It is only built when
+// `cache.enable_route_cache` is set.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+
+use crate::config::Config;
+use crate::dex::triangular_arb::{ArbitrageCycle, ArbitrageGraph, EdgeKey};
+
+/// Default cap on cached routes per base token
+const DEFAULT_MAX_ROUTES_PER_TOKEN: usize = 10_000;
+
+/// A cached cycle from a base token back to itself
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedRoute {
+    /// Edges in trade order, starting at the base token
+    pub edges: Vec<EdgeKey>,
+    /// Pools traded, in the same order
+    pub pools: Vec<Pubkey>,
+}
+
+impl CachedRoute {
+    fn new(edges: Vec<EdgeKey>) -> Self {
+        let pools = edges.iter().map(|edge| edge.2).collect();
+        Self { edges, pools }
+    }
+
+    /// Whether the route passes through `token`
+    pub fn contains_token(&self, token: &Pubkey) -> bool {
+        self.edges.iter().any(|edge| edge.0 == *token)
+    }
+}
+
+struct CacheEntry {
+    routes: Arc<Vec<CachedRoute>>,
+    topology_version: u64,
+    built_at: Instant,
+}
+
+/// Candidate routes per base token
+pub struct RouteCache {
+    entries: HashMap<Pubkey, CacheEntry>,
+    ttl: Duration,
+    max_hops: usize,
+    max_routes_per_token: usize,
+}
+
+/// Thread-safe wrapper for RouteCache
+pub type SharedRouteCache = Arc<RwLock<RouteCache>>;
+
+impl RouteCache {
+    /// Cache for routes of at most `max_hops` hops, rebuilt after `ttl`
+    pub fn new(max_hops: usize, ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+            max_hops,
+            max_routes_per_token: DEFAULT_MAX_ROUTES_PER_TOKEN,
+        }
+    }
+
+    /// Cache sized by `routing.max_hops` and `routing.route_cache_ttl_seconds`;
+    /// `None` when `cache.enable_route_cache` is off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config.cache.enable_route_cache.then(|| Self::new(
+            config.routing.max_hops as usize,
+            Duration::from_secs(config.routing.route_cache_ttl_seconds),
+        ))
+    }
+
+    /// Cap the routes kept per base token
+    pub fn with_max_routes_per_token(mut self, max_routes: usize) -> Self {
+        self.max_routes_per_token = max_routes;
+        self
+    }
+
+    /// Longest route (in hops) the cache holds
+    pub fn max_hops(&self) -> usize {
+        self.max_hops
+    }
+
+    /// Routes from `base_token` back to itself, rebuilt first if the entry is
+    /// missing, expired, or older than the graph's last topology change
+    pub fn routes(&mut self, graph: &ArbitrageGraph, base_token: &Pubkey) -> Arc<Vec<CachedRoute>> {
+        if let Some(entry) = self.entries.get(base_token) {
+            if entry.topology_version == graph.topology_version() && entry.built_at.elapsed() < self.ttl {
+                return Arc::clone(&entry.routes);
+            }
+        }
+
+        let routes: Arc<Vec<CachedRoute>> = Arc::new(
+            graph.simple_cycles(base_token, &[], self.max_hops, self.max_routes_per_token)
+                .into_iter()
+                .map(CachedRoute::new)
+                .collect(),
+        );
+        debug!("Cached {} routes from {} (max {} hops)", routes.len(), base_token, self.max_hops);
+
+        self.entries.insert(*base_token, CacheEntry {
+            routes: Arc::clone(&routes),
+            topology_version: graph.topology_version(),
+            built_at: Instant::now(),
+        });
+        routes
+    }
+
+    /// Price every cached route from `base_token` with the graph's current
+    /// rates, best net profit first. Routes with an edge that has left the
+    /// graph are skipped.
+    pub fn evaluate(&mut self, graph: &ArbitrageGraph, base_token: &Pubkey) -> Vec<ArbitrageCycle> {
+        let routes = self.routes(graph, base_token);
+        let mut cycles: Vec<ArbitrageCycle> = routes.iter()
+            .filter_map(|route| graph.cycle_through(&route.edges))
+            .collect();
+        cycles.sort_by(|a, b| b.net_profit_after_fees.total_cmp(&a.net_profit_after_fees));
+        cycles
+    }
+
+    /// Drop every entry
+    pub fn invalidate(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of base tokens with cached routes
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::triangular_arb::{DexType, ExchangeEdge};
+
+    fn create_test_pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn edge(from: u8, to: u8, pool: u8, rate: f64) -> ExchangeEdge {
+        ExchangeEdge::new(
+            create_test_pubkey(from), create_test_pubkey(to), DexType::Raydium, create_test_pubkey(pool),
+            rate, 25, vec![], 1000,
+        )
+    }
+
+    #[test]
+    fn test_routes_are_reused_until_topology_changes() {
+        let mut graph = ArbitrageGraph::new();
+        graph.add_edge(edge(1, 2, 101, 1.0));
+        graph.add_edge(edge(2, 3, 102, 1.0));
+        graph.add_edge(edge(3, 1, 103, 1.0));
+
+        let base = create_test_pubkey(1);
+        let mut cache = RouteCache::new(3, Duration::from_secs(300));
+        let routes = cache.routes(&graph, &base);
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].pools, vec![create_test_pubkey(101), create_test_pubkey(102), create_test_pubkey(103)]);
+        assert!(routes[0].contains_token(&create_test_pubkey(3)));
+
+        // A rate update keeps the entry; the route is priced at the new rate
+        graph.update_edge_rate(create_test_pubkey(3), base, create_test_pubkey(103), 1.1, 1001).unwrap();
+        assert!(Arc::ptr_eq(&routes, &cache.routes(&graph, &base)));
+        let cycles = cache.evaluate(&graph, &base);
+        assert_eq!(cycles.len(), 1);
+        assert!(cycles[0].net_profit_after_fees > 0.0);
+
+        // A new pool invalidates the entry
+        graph.add_edge(edge(3, 1, 104, 1.0));
+        let rebuilt = cache.routes(&graph, &base);
+        assert!(!Arc::ptr_eq(&routes, &rebuilt));
+        assert_eq!(rebuilt.len(), 2);
+    }
+
+    #[test]
+    fn test_expired_entries_are_rebuilt() {
+        let mut graph = ArbitrageGraph::new();
+        graph.add_edge(edge(1, 2, 101, 1.0));
+        graph.add_edge(edge(2, 1, 102, 1.0));
+
+        let base = create_test_pubkey(1);
+        let mut cache = RouteCache::new(2, Duration::ZERO);
+        let routes = cache.routes(&graph, &base);
+        assert!(!Arc::ptr_eq(&routes, &cache.routes(&graph, &base)));
+        assert_eq!(cache.len(), 1);
+
+        cache.invalidate();
+        assert!(cache.is_empty());
+    }
+}
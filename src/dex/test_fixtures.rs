@@ -0,0 +1,33 @@
+// Pool fixtures shared by the unit tests of the graph, detector and sizing
+// modules.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::dex::adapter::VenueState;
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::raydium::{AmmFees, AmmInfo, RaydiumPoolState, RAYDIUM_AMM_V4_PROGRAM_ID};
+use crate::dex::triangular_arb::DexType;
+
+/// Active Raydium AMM v4 pool with a 25 bps swap fee and no order book
+pub fn raydium_pool(address: Pubkey, token_a: Pubkey, token_b: Pubkey, reserve_a: u64, reserve_b: u64) -> PoolData {
+    PoolData {
+        pool_address: address,
+        token_a,
+        token_b,
+        reserve_a,
+        reserve_b,
+        fee_bps: 25,
+        dex_type: DexType::Raydium,
+        program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
+        venue: VenueState::Raydium(Box::new(RaydiumPoolState {
+            amm: AmmInfo {
+                status: 1,
+                fees: AmmFees { swap_fee_numerator: 25, swap_fee_denominator: 10_000, ..Default::default() },
+                ..Default::default()
+            },
+            market: None,
+        })),
+        mints: Default::default(),
+        slot: 0,
+    }
+}
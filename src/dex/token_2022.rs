@@ -0,0 +1,465 @@
+// Token-2022 Mint Extensions
+//
+// Pools may hold mints owned by the Token-2022 program instead of the legacy
+// SPL Token program. Such mints can carry extensions (a TLV list after the
+// base mint, padded to the account size) that change how transfers behave:
+// - TransferFeeConfig: every transfer withholds a fee from the amount sent,
+//   so both the pool's input and the user's output shrink
+// - TransferHook: every transfer CPIs into an arbitrary program
+// - NonTransferable: tokens cannot be moved at all
+// - PermanentDelegate: an authority can move or burn anyone's tokens
+//
+// The registry loads both mints of every pool (plus the Clock sysvar for the
+// fee epoch) as dependent accounts, applies transfer fees around every quote
+// and keeps pools with hooked, frozen-in-place or delegated mints out of the
+// graph. Token accounts and ATAs follow the program that owns the mint.
+//
+// The base mint's mint and freeze authorities are decoded as well, so newly
+// created pools can be screened before they are traded.
+
+use solana_sdk::{account::Account, pubkey::Pubkey, sysvar};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+
+use crate::dex::adapter::{read_pubkey, read_u16, read_u32, read_u64, TOKEN_2022_PROGRAM_ID};
+
+/// SPL Memo program id, required by `swap_v2` style instructions for Token-2022 transfers
+pub const MEMO_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Size of the base SPL mint
+const MINT_LEN: usize = 82;
+
+/// Offset of `mint_authority` (`COption<Pubkey>`) in the base mint
+const MINT_AUTHORITY_OFFSET: usize = 0;
+
+/// Offset of `decimals` in the base mint
+const DECIMALS_OFFSET: usize = 44;
+
+/// Offset of `freeze_authority` (`COption<Pubkey>`) in the base mint
+const FREEZE_AUTHORITY_OFFSET: usize = 46;
+
+/// Extended accounts are padded to the token account size before the type byte
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+
+/// `AccountType::Mint`
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// Extension type ids (`ExtensionType` discriminants)
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+const EXTENSION_TRANSFER_HOOK: u16 = 14;
+
+/// Offset of `epoch` in the Clock sysvar
+const CLOCK_EPOCH_OFFSET: usize = 16;
+
+/// Whether `program_id` is one of the two token programs
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+/// Associated token account of `wallet` for `mint`, derived under the mint's token program
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(wallet, mint, token_program)
+}
+
+/// One transfer fee schedule
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferFee {
+    /// First epoch the fee applies to
+    pub epoch: u64,
+    /// Cap on the fee of a single transfer
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    fn decode(data: &[u8], offset: usize) -> Result<Self> {
+        Ok(Self {
+            epoch: read_u64(data, offset)?,
+            maximum_fee: read_u64(data, offset + 8)?,
+            basis_points: read_u16(data, offset + 16)?,
+        })
+    }
+
+    /// Fee withheld from a transfer of `amount`: rounded up, capped at `maximum_fee`
+    pub fn fee(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let fee = (amount as u128 * self.basis_points as u128).div_ceil(10_000);
+        fee.min(self.maximum_fee as u128) as u64
+    }
+}
+
+/// `TransferFeeConfig` extension: the fee in force switches from `older` to
+/// `newer` at `newer.epoch`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub older: TransferFee,
+    pub newer: TransferFee,
+}
+
+impl TransferFeeConfig {
+    /// Layout: config authority (32), withdraw authority (32), withheld amount (8), older, newer
+    fn decode(data: &[u8]) -> Result<Self> {
+        Ok(Self {
+            older: TransferFee::decode(data, 72)?,
+            newer: TransferFee::decode(data, 90)?,
+        })
+    }
+
+    /// Fee schedule in force at `epoch`. Without a known epoch the larger of
+    /// the two is used, so quotes never overestimate what arrives.
+    pub fn fee_at(&self, epoch: Option<u64>) -> TransferFee {
+        match epoch {
+            Some(epoch) if epoch >= self.newer.epoch => self.newer,
+            Some(_) => self.older,
+            None if self.older.basis_points > self.newer.basis_points => self.older,
+            None => self.newer,
+        }
+    }
+}
+
+/// Decoded mint: owning program, decimals and the extensions that affect routing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintInfo {
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    /// Authority that can still mint supply, if any
+    pub mint_authority: Option<Pubkey>,
+    /// Authority that can freeze token accounts, if any
+    pub freeze_authority: Option<Pubkey>,
+    pub transfer_fee: Option<TransferFeeConfig>,
+    /// Program invoked on every transfer, when a hook is set
+    pub transfer_hook_program: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub permanent_delegate: Option<Pubkey>,
+}
+
+impl Default for MintInfo {
+    /// Plain legacy SPL mint, assumed until the mint account is loaded
+    fn default() -> Self {
+        Self {
+            token_program: spl_token::id(),
+            decimals: 0,
+            mint_authority: None,
+            freeze_authority: None,
+            transfer_fee: None,
+            transfer_hook_program: None,
+            non_transferable: false,
+            permanent_delegate: None,
+        }
+    }
+}
+
+impl MintInfo {
+    /// Decode a mint account owned by `owner`
+    pub fn decode(owner: &Pubkey, data: &[u8]) -> Result<Self> {
+        if !is_token_program(owner) {
+            return Err(anyhow!("Mint account is owned by {}, not a token program", owner));
+        }
+        if data.len() < MINT_LEN {
+            return Err(anyhow!("Mint account too short: {} bytes", data.len()));
+        }
+
+        let mut mint = Self {
+            token_program: *owner,
+            decimals: data[DECIMALS_OFFSET],
+            mint_authority: coption_pubkey(data, MINT_AUTHORITY_OFFSET)?,
+            freeze_authority: coption_pubkey(data, FREEZE_AUTHORITY_OFFSET)?,
+            ..Self::default()
+        };
+
+        if *owner == TOKEN_2022_PROGRAM_ID && data.len() > ACCOUNT_TYPE_OFFSET {
+            if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+                return Err(anyhow!("Token-2022 account type {} is not a mint", data[ACCOUNT_TYPE_OFFSET]));
+            }
+            for (extension_type, value) in extensions(&data[ACCOUNT_TYPE_OFFSET + 1..])? {
+                match extension_type {
+                    EXTENSION_TRANSFER_FEE_CONFIG => mint.transfer_fee = Some(TransferFeeConfig::decode(value)?),
+                    EXTENSION_NON_TRANSFERABLE => mint.non_transferable = true,
+                    EXTENSION_PERMANENT_DELEGATE => mint.permanent_delegate = optional_pubkey(value, 0)?,
+                    EXTENSION_TRANSFER_HOOK => mint.transfer_hook_program = optional_pubkey(value, 32)?,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(mint)
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == TOKEN_2022_PROGRAM_ID
+    }
+
+    /// Fee withheld when `amount` of this mint is transferred at `epoch`
+    pub fn transfer_fee(&self, epoch: Option<u64>, amount: u64) -> u64 {
+        self.transfer_fee
+            .map(|config| config.fee_at(epoch).fee(amount))
+            .unwrap_or(0)
+    }
+
+    /// Transfer fee rate in basis points at `epoch`
+    pub fn transfer_fee_bps(&self, epoch: Option<u64>) -> u16 {
+        self.transfer_fee
+            .map(|config| config.fee_at(epoch).basis_points)
+            .unwrap_or(0)
+    }
+
+    /// Why swaps through this mint cannot be routed safely, if they cannot
+    pub fn routing_restriction(&self) -> Option<String> {
+        if self.non_transferable {
+            return Some("mint is non-transferable".to_string());
+        }
+        if let Some(program) = self.transfer_hook_program {
+            return Some(format!("mint has transfer hook program {}", program));
+        }
+        if let Some(delegate) = self.permanent_delegate {
+            return Some(format!("mint has permanent delegate {}", delegate));
+        }
+        None
+    }
+}
+
+/// Mint details of both pool tokens, plus the epoch transfer fees are evaluated at
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolMints {
+    pub a: MintInfo,
+    pub b: MintInfo,
+    /// Epoch read from the Clock sysvar; `None` until it has been loaded
+    pub epoch: Option<u64>,
+}
+
+impl PoolMints {
+    /// Accounts to load alongside the pool: both mints and the Clock sysvar
+    pub fn dependent_accounts(token_a: &Pubkey, token_b: &Pubkey) -> Vec<Pubkey> {
+        vec![*token_a, *token_b, sysvar::clock::id()]
+    }
+
+    /// Update from fetched accounts. Accounts that were not fetched keep
+    /// their previous value.
+    pub fn apply_accounts(&mut self, token_a: &Pubkey, token_b: &Pubkey, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        if let Some(account) = accounts.get(token_a) {
+            self.a = MintInfo::decode(&account.owner, &account.data)?;
+        }
+        if let Some(account) = accounts.get(token_b) {
+            self.b = MintInfo::decode(&account.owner, &account.data)?;
+        }
+        if let Some(clock) = accounts.get(&sysvar::clock::id()) {
+            self.epoch = Some(read_u64(&clock.data, CLOCK_EPOCH_OFFSET)?);
+        }
+        Ok(())
+    }
+
+    /// Mints as (input, output) for the given direction
+    pub fn ordered(&self, a_to_b: bool) -> (&MintInfo, &MintInfo) {
+        if a_to_b {
+            (&self.a, &self.b)
+        } else {
+            (&self.b, &self.a)
+        }
+    }
+
+    pub fn has_token_2022(&self) -> bool {
+        self.a.is_token_2022() || self.b.is_token_2022()
+    }
+
+    /// Amount the pool actually receives when the user sends `amount_in`
+    pub fn amount_received_by_pool(&self, a_to_b: bool, amount_in: u64) -> u64 {
+        let (input, _) = self.ordered(a_to_b);
+        amount_in.saturating_sub(input.transfer_fee(self.epoch, amount_in))
+    }
+
+    /// Amount the user actually receives when the pool sends `amount_out`
+    pub fn amount_received_by_user(&self, a_to_b: bool, amount_out: u64) -> u64 {
+        let (_, output) = self.ordered(a_to_b);
+        amount_out.saturating_sub(output.transfer_fee(self.epoch, amount_out))
+    }
+
+    /// Transfer fees of both legs of a swap, in basis points
+    pub fn transfer_fee_bps(&self, a_to_b: bool) -> u16 {
+        let (input, output) = self.ordered(a_to_b);
+        input.transfer_fee_bps(self.epoch).saturating_add(output.transfer_fee_bps(self.epoch))
+    }
+
+    /// First reason either mint keeps the pool out of the graph
+    pub fn routing_restriction(&self) -> Option<String> {
+        self.a.routing_restriction()
+            .map(|reason| format!("token A {}", reason))
+            .or_else(|| self.b.routing_restriction().map(|reason| format!("token B {}", reason)))
+    }
+}
+
+/// Iterate the TLV entries after the account type byte as (type, value)
+fn extensions(tlv: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+
+    while offset + 4 <= tlv.len() {
+        let extension_type = read_u16(tlv, offset)?;
+        let length = read_u16(tlv, offset + 2)? as usize;
+        if extension_type == 0 {
+            // Uninitialized space past the last extension
+            break;
+        }
+        let value = tlv.get(offset + 4..offset + 4 + length)
+            .ok_or_else(|| anyhow!("Token-2022 extension {} overruns the account", extension_type))?;
+        entries.push((extension_type, value));
+        offset += 4 + length;
+    }
+
+    Ok(entries)
+}
+
+/// `COption<Pubkey>`: a u32 tag (1 = some) followed by the key
+fn coption_pubkey(data: &[u8], offset: usize) -> Result<Option<Pubkey>> {
+    match read_u32(data, offset)? {
+        0 => Ok(None),
+        _ => Ok(Some(read_pubkey(data, offset + 4)?)),
+    }
+}
+
+/// `OptionalNonZeroPubkey`: all zeroes means unset
+fn optional_pubkey(data: &[u8], offset: usize) -> Result<Option<Pubkey>> {
+    let key = read_pubkey(data, offset)?;
+    Ok((key != Pubkey::default()).then_some(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pubkey(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    fn base_mint(decimals: u8) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_LEN];
+        data[DECIMALS_OFFSET] = decimals;
+        data[45] = 1; // is_initialized
+        data
+    }
+
+    fn extended_mint(decimals: u8, extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = base_mint(decimals);
+        data.resize(ACCOUNT_TYPE_OFFSET, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn transfer_fee_config(older: TransferFee, newer: TransferFee) -> Vec<u8> {
+        let mut value = vec![0u8; 72];
+        for fee in [older, newer] {
+            value.extend_from_slice(&fee.epoch.to_le_bytes());
+            value.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+            value.extend_from_slice(&fee.basis_points.to_le_bytes());
+        }
+        value
+    }
+
+    #[test]
+    fn test_legacy_mint_has_no_extensions() {
+        let mint = MintInfo::decode(&spl_token::id(), &base_mint(6)).unwrap();
+        assert_eq!(mint, MintInfo { decimals: 6, ..MintInfo::default() });
+        assert!(mint.routing_restriction().is_none());
+
+        let mut data = base_mint(6);
+        data[FREEZE_AUTHORITY_OFFSET] = 1;
+        data[FREEZE_AUTHORITY_OFFSET + 4..FREEZE_AUTHORITY_OFFSET + 36].copy_from_slice(pubkey(5).as_ref());
+        let mint = MintInfo::decode(&spl_token::id(), &data).unwrap();
+        assert_eq!(mint.freeze_authority, Some(pubkey(5)));
+        assert_eq!(mint.mint_authority, None);
+
+        assert!(MintInfo::decode(&pubkey(9), &base_mint(6)).is_err());
+        assert!(MintInfo::decode(&spl_token::id(), &[0u8; 40]).is_err());
+    }
+
+    #[test]
+    fn test_transfer_fee_extension() {
+        let older = TransferFee { epoch: 0, maximum_fee: 1_000, basis_points: 50 };
+        let newer = TransferFee { epoch: 500, maximum_fee: 5_000, basis_points: 100 };
+        let data = extended_mint(9, &[
+            (3, vec![0u8; 32]), // MintCloseAuthority is skipped
+            (EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(older, newer)),
+        ]);
+        let mint = MintInfo::decode(&TOKEN_2022_PROGRAM_ID, &data).unwrap();
+        assert!(mint.is_token_2022());
+        assert_eq!(mint.decimals, 9);
+        assert_eq!(mint.transfer_fee, Some(TransferFeeConfig { older, newer }));
+        assert!(mint.routing_restriction().is_none());
+
+        // 50 bps of 1001 = 5.005, rounded up
+        assert_eq!(mint.transfer_fee(Some(499), 1_001), 6);
+        // Capped at the maximum fee
+        assert_eq!(mint.transfer_fee(Some(499), 10_000_000), 1_000);
+        assert_eq!(mint.transfer_fee(Some(500), 1_001), 11);
+        // Unknown epoch takes the larger schedule
+        assert_eq!(mint.transfer_fee_bps(None), 100);
+    }
+
+    #[test]
+    fn test_restricted_extensions() {
+        let mut hook = vec![0u8; 64];
+        hook[32..].copy_from_slice(pubkey(7).as_ref());
+        let mint = MintInfo::decode(&TOKEN_2022_PROGRAM_ID, &extended_mint(6, &[(EXTENSION_TRANSFER_HOOK, hook)])).unwrap();
+        assert_eq!(mint.transfer_hook_program, Some(pubkey(7)));
+        assert!(mint.routing_restriction().unwrap().contains("transfer hook"));
+
+        // A hook extension without a program is harmless
+        let mint = MintInfo::decode(&TOKEN_2022_PROGRAM_ID, &extended_mint(6, &[(EXTENSION_TRANSFER_HOOK, vec![0u8; 64])])).unwrap();
+        assert!(mint.routing_restriction().is_none());
+
+        let mint = MintInfo::decode(&TOKEN_2022_PROGRAM_ID, &extended_mint(6, &[
+            (EXTENSION_PERMANENT_DELEGATE, pubkey(8).to_bytes().to_vec()),
+        ])).unwrap();
+        assert_eq!(mint.permanent_delegate, Some(pubkey(8)));
+
+        let mint = MintInfo::decode(&TOKEN_2022_PROGRAM_ID, &extended_mint(6, &[(EXTENSION_NON_TRANSFERABLE, vec![])])).unwrap();
+        assert!(mint.non_transferable);
+
+        let pool_mints = PoolMints { b: mint, ..PoolMints::default() };
+        assert!(pool_mints.routing_restriction().unwrap().starts_with("token B"));
+    }
+
+    #[test]
+    fn test_pool_mints_apply_fees_on_both_legs() {
+        let fee = TransferFee { epoch: 0, maximum_fee: u64::MAX, basis_points: 100 };
+        let data = extended_mint(6, &[(EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(fee, fee))]);
+        let token_a = pubkey(1);
+        let token_b = pubkey(2);
+        let mut clock = vec![0u8; 40];
+        clock[CLOCK_EPOCH_OFFSET..CLOCK_EPOCH_OFFSET + 8].copy_from_slice(&600u64.to_le_bytes());
+        let accounts = HashMap::from([
+            (token_a, Account { owner: TOKEN_2022_PROGRAM_ID, data, ..Default::default() }),
+            (token_b, Account { owner: spl_token::id(), data: base_mint(9), ..Default::default() }),
+            (sysvar::clock::id(), Account { data: clock, ..Default::default() }),
+        ]);
+
+        let mut mints = PoolMints::default();
+        mints.apply_accounts(&token_a, &token_b, &accounts).unwrap();
+        assert_eq!(mints.epoch, Some(600));
+        assert!(mints.has_token_2022());
+        assert_eq!(mints.ordered(true).0.token_program, TOKEN_2022_PROGRAM_ID);
+        assert_eq!(mints.ordered(true).1.token_program, spl_token::id());
+
+        // A -> B: the fee is taken from the input; B -> A: from the output
+        assert_eq!(mints.amount_received_by_pool(true, 10_000), 9_900);
+        assert_eq!(mints.amount_received_by_user(true, 10_000), 10_000);
+        assert_eq!(mints.amount_received_by_user(false, 10_000), 9_900);
+        assert_eq!(mints.transfer_fee_bps(false), 100);
+
+        // ATAs of Token-2022 mints live under that program
+        let wallet = pubkey(3);
+        assert_ne!(
+            associated_token_address(&wallet, &token_a, &TOKEN_2022_PROGRAM_ID),
+            spl_associated_token_account::get_associated_token_address(&wallet, &token_a)
+        );
+    }
+}
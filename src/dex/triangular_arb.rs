@@ -1,20 +1,39 @@
 // Triangular Arbitrage Detection Module for Solana DEXs
 //
 // This module implements a graph-based approach to detect triangular arbitrage
-// opportunities across multiple DEXs (Raydium, Meteora, Pump, Whirlpool, Orca).
+// opportunities across multiple DEXs (Raydium, Meteora, Pump, Whirlpool, Orca)
+// and order books (Phoenix, OpenBook v2).
 //
 // Algorithm:
 // - Uses negative log-transformed weights: -log(rate * (1 - fee))
 // - Detects cycles with negative weight (profit opportunities)
 // - Thread-safe concurrent access with Arc<RwLock<>>
 // - Bellman-Ford algorithm for negative cycle detection
+// - Edges older than the staleness limits are left out of detection, so a
+//   fresh rate is never combined with one that has since moved
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use solana_sdk::pubkey::Pubkey;
 use anyhow::{Result, anyhow};
 use tracing::{debug, warn, info};
-use tokio::task;
+
+use crate::config::StalenessConfig;
+use crate::dex::adapter::{DexRegistry, EdgeQuoter};
+use crate::dex::fees::FeeModel;
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::route_cache::{RouteCache, SharedRouteCache};
+
+/// Minimum improvement for a Bellman-Ford relaxation, so float rounding on
+/// break-even cycles is not mistaken for a negative cycle
+const RELAXATION_EPSILON: f64 = 1e-12;
+
+/// Increments a split hop's input is allocated in (1% each)
+const SPLIT_INCREMENTS: u64 = 100;
+
+/// `(from, to, pool)` key of a graph edge
+pub type EdgeKey = (Pubkey, Pubkey, Pubkey);
 
 /// Represents an exchange rate edge in the arbitrage graph
 #[derive(Clone, Debug)]
@@ -27,7 +46,17 @@ pub struct ExchangeEdge {
     pub inverse_log_weight: f64, // -log(rate * (1 - fee))
     pub liquidity_depth: Vec<PriceLevel>,
     pub fee_bps: u16, // Fee in basis points (e.g., 25 = 0.25%)
+    /// Pool fee as decoded from chain; `fee_bps` is its rounded summary
+    /// (plus `transfer_fee_bps`)
+    pub fee: FeeModel,
+    /// Token-2022 transfer fees of both legs, in basis points
+    pub transfer_fee_bps: u16,
     pub last_update: i64, // Unix timestamp
+    /// Slot the pool account behind the edge was observed at (0 if unknown)
+    pub slot: u64,
+    /// Exact integer quote of the pool behind this edge (set for edges built
+    /// by `DexRegistry::edges_for`)
+    pub quoter: Option<EdgeQuoter>,
 }
 
 impl ExchangeEdge {
@@ -46,6 +75,7 @@ impl ExchangeEdge {
     }
 
     /// Create a new exchange edge with calculated weight
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         from_token: Pubkey,
         to_token: Pubkey,
@@ -67,14 +97,82 @@ impl ExchangeEdge {
             inverse_log_weight,
             liquidity_depth,
             fee_bps,
+            fee: FeeModel::from_bps(fee_bps),
+            transfer_fee_bps: 0,
             last_update: timestamp,
+            slot: 0,
+            quoter: None,
+        }
+    }
+
+    /// Stamp the edge with the slot its pool account was observed at
+    pub fn with_slot(mut self, slot: u64) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    /// Use the pool's decoded fee model (and the transfer fees of the mints)
+    /// instead of the basis-point fee the edge was created with
+    pub fn with_fee_model(mut self, fee: FeeModel, transfer_fee_bps: u16) -> Self {
+        self.fee = fee;
+        self.transfer_fee_bps = transfer_fee_bps;
+        self.fee_bps = fee.bps().saturating_add(transfer_fee_bps);
+        self.inverse_log_weight = self.log_weight();
+        self
+    }
+
+    /// Fraction of the input left after the pool and transfer fees
+    pub fn fee_multiplier(&self) -> f64 {
+        self.fee.multiplier() * (1.0 - self.transfer_fee_bps as f64 / 10000.0)
+    }
+
+    /// Rate after fees: how many to_token are received per from_token
+    pub fn effective_rate(&self) -> f64 {
+        self.rate * self.fee_multiplier()
+    }
+
+    /// -log(rate * fee multiplier) with the exact fee model
+    fn log_weight(&self) -> f64 {
+        let effective_rate = self.effective_rate();
+        if effective_rate <= 0.0 {
+            warn!("Invalid rate calculation: rate={}, fee={:?}", self.rate, self.fee);
+            f64::INFINITY
+        } else {
+            -effective_rate.ln()
         }
     }
 
-    /// Update the rate and recalculate weight
+    /// Attach the pool's exact quoting function
+    pub fn with_quoter(mut self, quoter: EdgeQuoter) -> Self {
+        self.quoter = Some(quoter);
+        self
+    }
+
+    /// Amount of `to_token` received for `amount_in` of `from_token`, fees included.
+    ///
+    /// Uses the pool's integer math when the edge has a quoter. Edges without
+    /// one (hand-built, or from a venue we cannot simulate) fall back to
+    /// `amount_in * rate` with the fee taken in basis points, rounded down.
+    pub fn quote_exact_in(&self, amount_in: u64) -> Result<u64> {
+        if let Some(quoter) = &self.quoter {
+            return quoter.quote_exact_in(amount_in);
+        }
+        if !self.rate.is_finite() || self.rate < 0.0 {
+            return Err(anyhow!("Edge {} -> {} has invalid rate {}", self.from_token, self.to_token, self.rate));
+        }
+
+        let gross = (amount_in as f64 * self.rate) as u128;
+        let net = gross * (10_000 - self.fee_bps.min(10_000) as u128) / 10_000;
+        u64::try_from(net).map_err(|_| anyhow!("Quote overflow on edge {} -> {}", self.from_token, self.to_token))
+    }
+
+    /// Update the rate and recalculate weight. The pool snapshot behind the
+    /// quoter no longer matches the rate, so quotes fall back to the rate
+    /// until the pool is upserted again.
     pub fn update_rate(&mut self, new_rate: f64, timestamp: i64) {
         self.rate = new_rate;
-        self.inverse_log_weight = Self::calculate_weight(new_rate, self.fee_bps);
+        self.quoter = None;
+        self.inverse_log_weight = self.log_weight();
         self.last_update = timestamp;
     }
 
@@ -100,9 +198,14 @@ impl ExchangeEdge {
 pub enum DexType {
     Raydium,
     Meteora,
+    MeteoraDlmm,
     Pump,
     Whirlpool,
     Orca,
+    RaydiumCpmm,
+    RaydiumClmm,
+    Phoenix,
+    OpenBookV2,
 }
 
 impl std::fmt::Display for DexType {
@@ -110,9 +213,14 @@ impl std::fmt::Display for DexType {
         match self {
             DexType::Raydium => write!(f, "Raydium"),
             DexType::Meteora => write!(f, "Meteora"),
+            DexType::MeteoraDlmm => write!(f, "MeteoraDlmm"),
             DexType::Pump => write!(f, "Pump"),
             DexType::Whirlpool => write!(f, "Whirlpool"),
             DexType::Orca => write!(f, "Orca"),
+            DexType::RaydiumCpmm => write!(f, "RaydiumCpmm"),
+            DexType::RaydiumClmm => write!(f, "RaydiumClmm"),
+            DexType::Phoenix => write!(f, "Phoenix"),
+            DexType::OpenBookV2 => write!(f, "OpenBookV2"),
         }
     }
 }
@@ -146,14 +254,31 @@ impl TriangularArbitrageOpportunity {
     }
 }
 
+/// Staleness rejections a graph has made since it was created
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StalenessStats {
+    /// Edges left out of a detection pass or re-pricing for being too old
+    pub stale_edges: u64,
+    /// Cycles rejected because their edges were observed too many slots apart
+    pub wide_slot_spans: u64,
+}
+
 /// Main graph structure for triangular arbitrage detection
 pub struct ArbitrageGraph {
     // Adjacency list: token -> list of outgoing edges
     adjacency: HashMap<Pubkey, Vec<ExchangeEdge>>,
-    // Quick lookup: (from, to, dex) -> index in adjacency list
-    edge_lookup: HashMap<(Pubkey, Pubkey, DexType), (usize, usize)>,
+    // Quick lookup: (from, to, pool) -> index in adjacency list. Keyed by pool
+    // so one venue can hold several pools (and pool kinds) for the same pair.
+    edge_lookup: HashMap<EdgeKey, (usize, usize)>,
     // Token registry for quick iteration
     tokens: HashSet<Pubkey>,
+    // Bumped whenever an edge is added or removed (not on rate updates)
+    topology_version: u64,
+    // Newest slot any edge was observed at; edge ages are measured from it
+    latest_slot: u64,
+    staleness: StalenessConfig,
+    stale_edges: AtomicU64,
+    wide_slot_spans: AtomicU64,
 }
 
 impl ArbitrageGraph {
@@ -164,6 +289,76 @@ impl ArbitrageGraph {
             adjacency: HashMap::new(),
             edge_lookup: HashMap::new(),
             tokens: HashSet::new(),
+            topology_version: 0,
+            latest_slot: 0,
+            staleness: StalenessConfig::default(),
+            stale_edges: AtomicU64::new(0),
+            wide_slot_spans: AtomicU64::new(0),
+        }
+    }
+
+    /// Leave edges older than these limits out of detection
+    pub fn with_staleness(mut self, staleness: StalenessConfig) -> Self {
+        self.staleness = staleness;
+        self
+    }
+
+    /// Staleness limits edges are held to
+    pub fn staleness(&self) -> &StalenessConfig {
+        &self.staleness
+    }
+
+    /// Newest slot any edge was observed at
+    pub fn latest_slot(&self) -> u64 {
+        self.latest_slot
+    }
+
+    /// Whether `edge` is older than the staleness limits: observed too many
+    /// slots before the newest edge, or last updated too long ago. Edges
+    /// without a slot are only judged by time.
+    pub fn is_edge_stale(&self, edge: &ExchangeEdge) -> bool {
+        self.is_edge_stale_at(edge, chrono::Utc::now().timestamp_millis())
+    }
+
+    fn is_edge_stale_at(&self, edge: &ExchangeEdge, now_ms: i64) -> bool {
+        let max_slots = self.staleness.max_edge_age_slots;
+        let max_ms = self.staleness.max_edge_age_ms;
+        (max_slots > 0 && edge.slot > 0 && self.latest_slot.saturating_sub(edge.slot) > max_slots)
+            || (max_ms > 0 && now_ms.saturating_sub(edge.last_update.saturating_mul(1000)) > max_ms as i64)
+    }
+
+    /// Whether the slot-stamped edges of `path` were observed further apart
+    /// than `max_cycle_slot_span`
+    fn exceeds_slot_span(&self, path: &[&ExchangeEdge]) -> bool {
+        let max_span = self.staleness.max_cycle_slot_span;
+        let slots = path.iter().map(|edge| edge.slot).filter(|&slot| slot > 0);
+        match (slots.clone().min(), slots.max()) {
+            (Some(oldest), Some(newest)) => max_span > 0 && newest - oldest > max_span,
+            _ => false,
+        }
+    }
+
+    /// Whether a cycle of edges may be priced: no edge is stale and the
+    /// edges span few enough slots. Rejections are counted.
+    fn admits_path(&self, path: &[&ExchangeEdge]) -> bool {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let stale = path.iter().filter(|edge| self.is_edge_stale_at(edge, now_ms)).count() as u64;
+        if stale > 0 {
+            self.stale_edges.fetch_add(stale, Ordering::Relaxed);
+            return false;
+        }
+        if self.exceeds_slot_span(path) {
+            self.wide_slot_spans.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    /// Staleness rejections made so far
+    pub fn staleness_stats(&self) -> StalenessStats {
+        StalenessStats {
+            stale_edges: self.stale_edges.load(Ordering::Relaxed),
+            wide_slot_spans: self.wide_slot_spans.load(Ordering::Relaxed),
         }
     }
 
@@ -171,19 +366,21 @@ impl ArbitrageGraph {
     pub fn add_edge(&mut self, edge: ExchangeEdge) {
         let from = edge.from_token;
         let to = edge.to_token;
-        let dex = edge.dex.clone();
+        let pool = edge.pool_address;
 
         // Register tokens
         self.tokens.insert(from);
         self.tokens.insert(to);
+        self.latest_slot = self.latest_slot.max(edge.slot);
 
         // Add to adjacency list
-        let edges = self.adjacency.entry(from).or_insert_with(Vec::new);
+        let edges = self.adjacency.entry(from).or_default();
         let edge_index = edges.len();
         edges.push(edge.clone());
 
         // Update lookup table
-        self.edge_lookup.insert((from, to, dex), (0, edge_index));
+        self.edge_lookup.insert((from, to, pool), (0, edge_index));
+        self.topology_version += 1;
 
         debug!(
             "Added edge: {} -> {} via {} (rate: {:.6}, weight: {:.6})",
@@ -192,23 +389,283 @@ impl ArbitrageGraph {
     }
 
     /// Update an existing edge's rate and recalculate weight
-    pub fn update_edge_rate(&mut self, from: Pubkey, to: Pubkey, dex: DexType, new_rate: f64, timestamp: i64) -> Result<()> {
-        let lookup_key = (from, to, dex.clone());
-        
+    pub fn update_edge_rate(&mut self, from: Pubkey, to: Pubkey, pool_address: Pubkey, new_rate: f64, timestamp: i64) -> Result<()> {
+        let lookup_key = (from, to, pool_address);
+
         if let Some(&(_list_idx, edge_idx)) = self.edge_lookup.get(&lookup_key) {
             if let Some(edges) = self.adjacency.get_mut(&from) {
                 if let Some(edge) = edges.get_mut(edge_idx) {
                     edge.update_rate(new_rate, timestamp);
                     debug!(
-                        "Updated edge: {} -> {} via {} (new rate: {:.6}, new weight: {:.6})",
-                        from, to, dex, new_rate, edge.inverse_log_weight
+                        "Updated edge: {} -> {} via {} pool {} (new rate: {:.6}, new weight: {:.6})",
+                        from, to, edge.dex, pool_address, new_rate, edge.inverse_log_weight
                     );
                     return Ok(());
                 }
             }
         }
 
-        Err(anyhow!("Edge not found: {} -> {} via {}", from, to, dex))
+        Err(anyhow!("Edge not found: {} -> {} via pool {}", from, to, pool_address))
+    }
+
+    /// Replace an existing edge (same from, to and pool) or add it if new
+    pub fn upsert_edge(&mut self, edge: ExchangeEdge) {
+        let lookup_key = (edge.from_token, edge.to_token, edge.pool_address);
+
+        if let Some(&(_list_idx, edge_idx)) = self.edge_lookup.get(&lookup_key) {
+            if let Some(existing) = self.adjacency.get_mut(&edge.from_token).and_then(|edges| edges.get_mut(edge_idx)) {
+                self.latest_slot = self.latest_slot.max(edge.slot);
+                *existing = edge;
+                return;
+            }
+        }
+
+        self.add_edge(edge);
+    }
+
+    /// Insert or refresh both directions of a decoded pool via its venue adapter.
+    /// A pool that is no longer tradable has its edges removed instead.
+    pub fn upsert_pool(&mut self, registry: &DexRegistry, pool: &PoolData, timestamp: i64) -> Result<()> {
+        let edges = registry.edges_for(pool, timestamp)?;
+        if edges.is_empty() {
+            let removed = self.remove_pool(&pool.pool_address);
+            if removed > 0 {
+                info!("Removed {} edges of pool {} ({} no longer tradable)", removed, pool.pool_address, pool.dex_type);
+            }
+            return Ok(());
+        }
+
+        for edge in edges {
+            self.upsert_edge(edge);
+        }
+        Ok(())
+    }
+
+    /// Remove every edge quoted by `pool_address`; returns how many were removed
+    pub fn remove_pool(&mut self, pool_address: &Pubkey) -> usize {
+        let mut removed = 0;
+
+        for (from, edges) in self.adjacency.iter_mut() {
+            let before = edges.len();
+            edges.retain(|edge| edge.pool_address != *pool_address);
+            if edges.len() == before {
+                continue;
+            }
+            removed += before - edges.len();
+
+            // Indices of the remaining edges from this token have shifted
+            self.edge_lookup.retain(|(lookup_from, _, _), _| lookup_from != from);
+            for (edge_index, edge) in edges.iter().enumerate() {
+                self.edge_lookup.insert((edge.from_token, edge.to_token, edge.pool_address), (0, edge_index));
+            }
+        }
+
+        if removed > 0 {
+            self.topology_version += 1;
+        }
+        removed
+    }
+
+    /// Counter that changes whenever an edge is added or removed, so callers
+    /// can tell a rate refresh from a change in which cycles exist
+    pub fn topology_version(&self) -> u64 {
+        self.topology_version
+    }
+
+    /// Edge `from -> to` through `pool_address`
+    pub fn get_edge(&self, from: &Pubkey, to: &Pubkey, pool_address: &Pubkey) -> Option<&ExchangeEdge> {
+        let edge_idx = self.edge_lookup.get(&(*from, *to, *pool_address))?.1;
+        self.adjacency.get(from)?.get(edge_idx)
+    }
+
+    /// `ArbitrageCycle` through the given `(from, to, pool)` edges, scored
+    /// with their current rates; `None` if any edge has left the graph or
+    /// the edges are too stale to combine
+    pub fn cycle_through(&self, edges: &[EdgeKey]) -> Option<ArbitrageCycle> {
+        let path: Vec<&ExchangeEdge> = edges.iter()
+            .map(|(from, to, pool)| self.get_edge(from, to, pool))
+            .collect::<Option<_>>()?;
+        (!path.is_empty() && self.admits_path(&path)).then(|| Self::cycle_from_path(&path))
+    }
+
+    /// Simple cycles through `start` of at most `max_path_length` hops, each
+    /// as edge keys starting at `start`. Tokens in `excluded` are never
+    /// entered and no pool is traded twice (that only pays its fee twice).
+    /// Stops after `limit` cycles.
+    pub fn simple_cycles(&self, start: &Pubkey, excluded: &[Pubkey], max_path_length: usize, limit: usize) -> Vec<Vec<EdgeKey>> {
+        let mut cycles = Vec::new();
+        let mut stack: Vec<Vec<EdgeKey>> = vec![Vec::new()];
+
+        while let Some(path) = stack.pop() {
+            let current = path.last().map(|edge| edge.1).unwrap_or(*start);
+            let Some(edges) = self.get_edges_from(&current) else {
+                continue;
+            };
+
+            for edge in edges {
+                if cycles.len() >= limit {
+                    return cycles;
+                }
+                if !edge.inverse_log_weight.is_finite() || path.iter().any(|key| key.2 == edge.pool_address) {
+                    continue;
+                }
+
+                let key = (edge.from_token, edge.to_token, edge.pool_address);
+                if edge.to_token == *start {
+                    if !path.is_empty() {
+                        let mut cycle = path.clone();
+                        cycle.push(key);
+                        cycles.push(cycle);
+                    }
+                    continue;
+                }
+                if path.len() + 1 >= max_path_length
+                    || excluded.contains(&edge.to_token)
+                    || path.iter().any(|key| key.1 == edge.to_token)
+                {
+                    continue;
+                }
+
+                let mut next = path.clone();
+                next.push(key);
+                stack.push(next);
+            }
+        }
+
+        cycles
+    }
+
+    /// Depth ladders of the pools a cycle trades through, keyed by pool, for
+    /// `ArbitrageCycle::calculate_slippage_adjusted_profit`. Edges without
+    /// depth are left out so they are priced at their rate.
+    pub fn liquidity_map(&self, cycle: &ArbitrageCycle) -> HashMap<Pubkey, Vec<PriceLevel>> {
+        cycle.path.iter()
+            .filter_map(|step| {
+                let edge = self.get_edge(&step.from_token, &step.to_token, &step.pool)?;
+                (!edge.liquidity_depth.is_empty()).then(|| (step.pool, edge.liquidity_depth.clone()))
+            })
+            .collect()
+    }
+
+    /// Exact output of each step of `cycle` for `amount_in`, using the edges'
+    /// integer quotes. The last element is what the cycle returns.
+    pub fn quote_cycle(&self, cycle: &ArbitrageCycle, amount_in: u64) -> Result<Vec<u64>> {
+        let mut current_amount = amount_in;
+        let mut outputs = Vec::with_capacity(cycle.path.len());
+
+        for step in &cycle.path {
+            current_amount = self.quote_step(step, current_amount)?;
+            outputs.push(current_amount);
+        }
+
+        Ok(outputs)
+    }
+
+    /// Exact output of one step for `amount_in`, summed over its split legs
+    pub fn quote_step(&self, step: &CycleStep, amount_in: u64) -> Result<u64> {
+        let mut total: u64 = 0;
+        for (pool, leg_in) in step.leg_inputs(amount_in) {
+            if leg_in == 0 {
+                continue;
+            }
+            let edge = self.get_edge(&step.from_token, &step.to_token, &pool)
+                .ok_or_else(|| anyhow!("Edge not found: {} -> {} via pool {}", step.from_token, step.to_token, pool))?;
+            total = total.checked_add(edge.quote_exact_in(leg_in)?)
+                .ok_or_else(|| anyhow!("Quote overflow on {} -> {}", step.from_token, step.to_token))?;
+        }
+        Ok(total)
+    }
+
+    /// Price impact of each step of `cycle` for `amount_in`, in bps: how far
+    /// the exact quote falls short of the step's spot rate after its fee
+    pub fn price_impact_bps(&self, cycle: &ArbitrageCycle, amount_in: u64) -> Result<Vec<u64>> {
+        let mut current_amount = amount_in;
+        let mut impacts = Vec::with_capacity(cycle.path.len());
+
+        for step in &cycle.path {
+            let output = self.quote_step(step, current_amount)?;
+            let spot = current_amount as f64 * step.rate * (1.0 - step.fee_bps as f64 / 10_000.0);
+            let impact = if spot > 0.0 { (1.0 - output as f64 / spot) * 10_000.0 } else { 0.0 };
+            impacts.push(impact.max(0.0).round() as u64);
+            current_amount = output;
+        }
+
+        Ok(impacts)
+    }
+
+    /// Split `amount_in` of `from` -> `to` across up to `max_legs` parallel
+    /// pools (the best-priced ones). The input is handed out in 1%
+    /// increments, each to the pool with the highest marginal output; on
+    /// concave quote curves this maximizes the hop's total output. `None`
+    /// if fewer than two pools are available or one pool takes everything.
+    pub fn split_hop(&self, from: &Pubkey, to: &Pubkey, amount_in: u64, max_legs: usize) -> Option<Vec<SplitLeg>> {
+        let mut candidates: Vec<&ExchangeEdge> = self.adjacency.get(from)?
+            .iter()
+            .filter(|edge| edge.to_token == *to && edge.inverse_log_weight.is_finite())
+            .collect();
+        if max_legs < 2 || candidates.len() < 2 || amount_in < SPLIT_INCREMENTS {
+            return None;
+        }
+        candidates.sort_by(|a, b| a.inverse_log_weight.total_cmp(&b.inverse_log_weight));
+        candidates.truncate(max_legs);
+
+        let input_at = |increments: u64| (amount_in as u128 * increments as u128 / SPLIT_INCREMENTS as u128) as u64;
+        let quote = |edge: &ExchangeEdge, increments: u64| -> Option<u64> {
+            if increments == 0 {
+                return Some(0);
+            }
+            edge.quote_exact_in(input_at(increments)).ok()
+        };
+
+        let mut allocated = vec![0u64; candidates.len()];
+        let mut outputs = vec![0u64; candidates.len()];
+        for _ in 0..SPLIT_INCREMENTS {
+            let (best, output) = candidates.iter()
+                .enumerate()
+                .filter_map(|(i, edge)| Some((i, quote(edge, allocated[i] + 1)?)))
+                .max_by_key(|&(i, output)| output.saturating_sub(outputs[i]))?;
+            allocated[best] += 1;
+            outputs[best] = output;
+        }
+
+        let legs: Vec<SplitLeg> = candidates.iter()
+            .zip(&allocated)
+            .filter(|(_, &increments)| increments > 0)
+            .map(|(edge, &increments)| SplitLeg {
+                dex: edge.dex.clone(),
+                pool: edge.pool_address,
+                share_bps: (increments * 10_000 / SPLIT_INCREMENTS) as u16,
+            })
+            .collect();
+        (legs.len() > 1).then_some(legs)
+    }
+
+    /// Copy of `cycle` with each hop split across parallel pools wherever
+    /// that yields more output for a trade of `amount_in`
+    pub fn split_cycle(&self, cycle: &ArbitrageCycle, amount_in: u64, max_legs: usize) -> Result<ArbitrageCycle> {
+        let mut split = cycle.clone();
+        let mut current_amount = amount_in;
+
+        for step in &mut split.path {
+            let single = self.quote_step(step, current_amount)?;
+            let legs = self.split_hop(&step.from_token, &step.to_token, current_amount, max_legs);
+
+            current_amount = match legs {
+                Some(legs) => {
+                    let candidate = CycleStep::split_across(step, legs);
+                    match self.quote_step(&candidate, current_amount) {
+                        Ok(output) if output > single => {
+                            *step = candidate;
+                            output
+                        }
+                        _ => single,
+                    }
+                }
+                None => single,
+            };
+        }
+
+        Ok(split)
     }
 
     /// Get all tokens in the graph
@@ -231,117 +688,253 @@ impl ArbitrageGraph {
         self.tokens.len()
     }
 
-    /// Detect triangular arbitrage opportunities using Bellman-Ford algorithm
-    /// Returns all profitable cycles found
+    /// Profitable cycles through `start_token`, each starting and ending at
+    /// `start_token`. Runs the whole-graph Bellman-Ford pass and keeps the
+    /// cycles of at most `max_path_length` hops that contain the token.
     pub fn detect_triangular_arbitrage(
         &self,
         start_token: &Pubkey,
         max_path_length: usize,
         min_profit_bps: i64,
     ) -> Vec<TriangularArbitrageOpportunity> {
-        let mut opportunities = Vec::new();
-
-        // Use BFS to find all paths of length 2-3 that return to start
-        let mut queue = VecDeque::new();
-        queue.push_back((vec![*start_token], 0.0f64)); // (path, cumulative_weight)
-
-        while let Some((path, weight)) = queue.pop_front() {
-            let current = *path.last().unwrap();
-
-            // If path length is 2-3 and we can return to start, check for arbitrage
-            if path.len() >= 2 && path.len() <= max_path_length {
-                if let Some(edges) = self.get_edges_from(&current) {
-                    for edge in edges {
-                        if edge.to_token == *start_token {
-                            let cycle_weight = weight + edge.inverse_log_weight;
-                            
-                            // Negative cycle = profit!
-                            if cycle_weight < 0.0 {
-                                let profit_ratio = (-cycle_weight).exp();
-                                let profit_bps = ((profit_ratio - 1.0) * 10000.0) as i64;
-                                
-                                if profit_bps >= min_profit_bps {
-                                    // Build the full path with edges
-                                    let mut full_path = Vec::new();
-                                    for i in 0..path.len() - 1 {
-                                        if let Some(edges) = self.get_edges_from(&path[i]) {
-                                            for e in edges {
-                                                if e.to_token == path[i + 1] {
-                                                    full_path.push(e.clone());
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    full_path.push(edge.clone());
-
-                                    let total_fees_bps: u16 = full_path.iter()
-                                        .map(|e| e.fee_bps)
-                                        .sum();
-
-                                    let path_length = full_path.len();
-
-                                    opportunities.push(TriangularArbitrageOpportunity {
-                                        path: full_path,
-                                        profit_ratio,
-                                        profit_bps,
-                                        input_token: *start_token,
-                                        input_amount: 0, // To be calculated
-                                        estimated_output: 0, // To be calculated
-                                        total_fees_bps,
-                                        cycle_weight,
-                                    });
-
-                                    info!(
-                                        "Found triangular arbitrage: profit={} bps, path_length={}, cycle_weight={:.6}",
-                                        profit_bps, path_length, cycle_weight
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        self.negative_cycles(&[*start_token], max_path_length)
+            .into_iter()
+            .filter(|path| path[0].from_token == *start_token)
+            .filter_map(|path| Self::opportunity_from_path(path, min_profit_bps))
+            .collect()
+    }
 
-            // Continue exploring if path not too long
-            if path.len() < max_path_length {
-                if let Some(edges) = self.get_edges_from(&current) {
-                    for edge in edges {
-                        // Don't revisit tokens except to return to start
-                        if !path.contains(&edge.to_token) || edge.to_token == *start_token {
-                            let mut new_path = path.clone();
-                            new_path.push(edge.to_token);
-                            let new_weight = weight + edge.inverse_log_weight;
-                            queue.push_back((new_path, new_weight));
-                        }
-                    }
+    /// Like `detect_triangular_arbitrage`, but prices the routes cached for
+    /// `start_token` (up to the cache's `max_hops`) instead of searching
+    pub fn detect_triangular_arbitrage_cached(
+        &self,
+        route_cache: &mut RouteCache,
+        start_token: &Pubkey,
+        min_profit_bps: i64,
+    ) -> Vec<TriangularArbitrageOpportunity> {
+        route_cache.routes(self, start_token)
+            .iter()
+            .filter_map(|route| {
+                let path = route.edges.iter()
+                    .map(|(from, to, pool)| self.get_edge(from, to, pool))
+                    .collect::<Option<Vec<_>>>()?;
+                if path.iter().map(|edge| edge.inverse_log_weight).sum::<f64>() >= 0.0 || !self.admits_path(&path) {
+                    return None;
                 }
-            }
-        }
-
-        opportunities
+                Self::opportunity_from_path(path, min_profit_bps)
+            })
+            .collect()
     }
 
-    /// Detect all triangular arbitrage opportunities across all tokens
+    /// Detect all triangular arbitrage opportunities across all tokens in a
+    /// single pass; each cycle is reported once
     pub fn detect_all_triangular_arbitrage(
         &self,
         max_path_length: usize,
         min_profit_bps: i64,
     ) -> Vec<TriangularArbitrageOpportunity> {
-        let mut all_opportunities = Vec::new();
-        
-        for token in self.get_all_tokens() {
-            let opportunities = self.detect_triangular_arbitrage(&token, max_path_length, min_profit_bps);
-            all_opportunities.extend(opportunities);
-        }
+        let mut all_opportunities: Vec<_> = self.negative_cycles(&[], max_path_length)
+            .into_iter()
+            .filter_map(|path| Self::opportunity_from_path(path, min_profit_bps))
+            .collect();
 
         // Sort by profit descending
-        all_opportunities.sort_by(|a, b| b.profit_bps.cmp(&a.profit_bps));
+        all_opportunities.sort_by_key(|opportunity| std::cmp::Reverse(opportunity.profit_bps));
         
         info!("Detected {} triangular arbitrage opportunities", all_opportunities.len());
         all_opportunities
     }
 
+    /// Negative cycles of the whole graph as `ArbitrageCycle`s, each rotated
+    /// to start at the first of `preferred_starts` it passes through
+    pub fn find_arbitrage_cycles(&self, preferred_starts: &[Pubkey], max_path_length: usize) -> Vec<ArbitrageCycle> {
+        self.negative_cycles(preferred_starts, max_path_length)
+            .iter()
+            .map(|path| Self::cycle_from_path(path))
+            .collect()
+    }
+
+    /// Bellman-Ford over every token with predecessor tracking.
+    ///
+    /// All tokens start at distance 0 (as if relaxed from a virtual source
+    /// linked to each of them), so one pass covers the whole graph. If edges
+    /// still relax after |V| rounds, each token relaxed in the last round is
+    /// on or downstream of a negative cycle; walking its predecessors until a
+    /// token repeats lands on the cycle, which is then read back from the
+    /// predecessor edges. Cycles longer than `max_path_length` hops are
+    /// dropped. Each cycle is rotated to start at the first of
+    /// `preferred_starts` it contains (or its smallest token), so the same
+    /// cycle reached from different tokens is reported once.
+    fn negative_cycles(&self, preferred_starts: &[Pubkey], max_path_length: usize) -> Vec<Vec<&ExchangeEdge>> {
+        // Sorted so the pass, and which of several cycles it extracts, is deterministic
+        let mut tokens: Vec<Pubkey> = self.tokens.iter().copied().collect();
+        tokens.sort();
+        let index: HashMap<Pubkey, usize> = tokens.iter().enumerate().map(|(i, token)| (*token, i)).collect();
+
+        // Stale edges are left out so they cannot form (or hide) a cycle
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut stale = 0;
+        let edges: Vec<(usize, usize, &ExchangeEdge)> = tokens.iter()
+            .filter_map(|token| self.adjacency.get(token))
+            .flatten()
+            .filter(|edge| edge.inverse_log_weight.is_finite())
+            .filter(|edge| {
+                let is_stale = self.is_edge_stale_at(edge, now_ms);
+                stale += is_stale as u64;
+                !is_stale
+            })
+            .filter_map(|edge| Some((*index.get(&edge.from_token)?, *index.get(&edge.to_token)?, edge)))
+            .collect();
+        if stale > 0 {
+            self.stale_edges.fetch_add(stale, Ordering::Relaxed);
+            debug!("Left {} stale edges out of the Bellman-Ford pass", stale);
+        }
+
+        let n = tokens.len();
+        let mut distance = vec![0.0f64; n];
+        let mut predecessor: Vec<Option<usize>> = vec![None; n];
+        let mut last_relaxed = Vec::new();
+
+        for round in 0..n {
+            let mut relaxed = Vec::new();
+            for (i, &(from, to, edge)) in edges.iter().enumerate() {
+                let candidate = distance[from] + edge.inverse_log_weight;
+                if candidate < distance[to] - RELAXATION_EPSILON {
+                    distance[to] = candidate;
+                    predecessor[to] = Some(i);
+                    relaxed.push(to);
+                }
+            }
+
+            if relaxed.is_empty() {
+                debug!("Bellman-Ford converged after {} rounds over {} tokens", round + 1, n);
+                return Vec::new();
+            }
+            last_relaxed = relaxed;
+        }
+
+        let mut seen: HashSet<Vec<(Pubkey, Pubkey)>> = HashSet::new();
+        let mut cycles = Vec::new();
+
+        for &token in &last_relaxed {
+            // Walk back until a token repeats: that token is on the cycle
+            let mut walked = HashSet::new();
+            let mut current = token;
+            let on_cycle = loop {
+                if !walked.insert(current) {
+                    break Some(current);
+                }
+                match predecessor[current] {
+                    Some(edge) => current = edges[edge].0,
+                    None => break None,
+                }
+            };
+            let Some(cycle_token) = on_cycle else { continue };
+
+            let mut path = Vec::new();
+            let mut current = cycle_token;
+            while let Some(edge) = predecessor[current] {
+                path.push(edges[edge].2);
+                current = edges[edge].0;
+                if current == cycle_token || path.len() > n {
+                    break;
+                }
+            }
+            path.reverse();
+
+            if path.len() > max_path_length {
+                debug!("Skipping {}-hop negative cycle (max {} hops)", path.len(), max_path_length);
+                continue;
+            }
+            if path.iter().map(|edge| edge.inverse_log_weight).sum::<f64>() >= 0.0 {
+                continue;
+            }
+
+            let start = preferred_starts.iter()
+                .find_map(|preferred| path.iter().position(|edge| edge.from_token == *preferred))
+                .or_else(|| path.iter().enumerate().min_by_key(|(_, edge)| edge.from_token).map(|(i, _)| i))
+                .unwrap_or(0);
+            path.rotate_left(start);
+
+            let key = path.iter().map(|edge| (edge.from_token, edge.pool_address)).collect();
+            if !seen.insert(key) {
+                continue;
+            }
+            if self.exceeds_slot_span(&path) {
+                self.wide_slot_spans.fetch_add(1, Ordering::Relaxed);
+                debug!("Skipping {}-hop negative cycle: edges observed too many slots apart", path.len());
+                continue;
+            }
+            cycles.push(path);
+        }
+
+        debug!("Bellman-Ford found {} negative cycles over {} tokens", cycles.len(), n);
+        cycles
+    }
+
+    /// Opportunity for a cycle of edges, if it clears `min_profit_bps`
+    fn opportunity_from_path(path: Vec<&ExchangeEdge>, min_profit_bps: i64) -> Option<TriangularArbitrageOpportunity> {
+        let cycle_weight: f64 = path.iter().map(|edge| edge.inverse_log_weight).sum();
+        let profit_ratio = (-cycle_weight).exp();
+        let profit_bps = ((profit_ratio - 1.0) * 10000.0) as i64;
+        if profit_bps < min_profit_bps {
+            return None;
+        }
+
+        let full_path: Vec<ExchangeEdge> = path.into_iter().cloned().collect();
+        let total_fees_bps: u16 = full_path.iter().map(|e| e.fee_bps).sum();
+
+        info!(
+            "Found triangular arbitrage: profit={} bps, path_length={}, cycle_weight={:.6}",
+            profit_bps, full_path.len(), cycle_weight
+        );
+
+        Some(TriangularArbitrageOpportunity {
+            input_token: full_path[0].from_token,
+            path: full_path,
+            profit_ratio,
+            profit_bps,
+            input_amount: 0, // To be calculated
+            estimated_output: 0, // To be calculated
+            total_fees_bps,
+            cycle_weight,
+        })
+    }
+
+    /// `ArbitrageCycle` for a cycle of edges, with the net profit from each
+    /// edge's decoded fee model
+    fn cycle_from_path(path: &[&ExchangeEdge]) -> ArbitrageCycle {
+        let cycle_weight: f64 = path.iter().map(|edge| edge.inverse_log_weight).sum();
+        let gross_profit_bps = (((-cycle_weight).exp() - 1.0) * 10000.0) as i64;
+        let total_fee_bps: u16 = path.iter().map(|edge| edge.fee_bps).sum();
+        let net_profit_after_fees = path.iter().map(|edge| edge.effective_rate()).product::<f64>() - 1.0;
+
+        let steps: Vec<CycleStep> = path.iter()
+            .map(|edge| CycleStep {
+                from_token: edge.from_token,
+                to_token: edge.to_token,
+                dex: edge.dex.clone(),
+                pool: edge.pool_address,
+                rate: edge.rate,
+                fee_bps: edge.fee_bps,
+                splits: Vec::new(),
+            })
+            .collect();
+
+        // Estimate execution time (50ms per hop + 400ms confirmation)
+        let execution_time_estimate_ms = (steps.len() as u64 * 50) + 400;
+
+        ArbitrageCycle {
+            start_token: steps[0].from_token,
+            path: steps,
+            gross_profit_bps,
+            net_profit_after_fees,
+            execution_time_estimate_ms,
+            total_fee_bps,
+            cycle_weight,
+        }
+    }
+
     /// Calculate optimal trade size for a triangular arbitrage opportunity
     pub fn calculate_optimal_trade_size(
         &self,
@@ -358,11 +951,10 @@ impl ArbitrageGraph {
             optimal_input = optimal_input.min(max_tradeable);
         }
 
-        // Simulate the full path to get output
+        // Simulate the full path with each pool's integer math
         let mut current_amount = optimal_input;
         for edge in &opportunity.path {
-            let fee_multiplier = 1.0 - (edge.fee_bps as f64 / 10000.0);
-            current_amount = (current_amount as f64 * edge.rate * fee_multiplier) as u64;
+            current_amount = edge.quote_exact_in(current_amount)?;
         }
 
         Ok((optimal_input, current_amount))
@@ -396,6 +988,64 @@ pub struct CycleStep {
     pub pool: Pubkey,
     pub rate: f64,
     pub fee_bps: u16,
+    /// Parallel pools the hop is split across, replacing `pool`; empty when
+    /// `pool` takes the whole input
+    pub splits: Vec<SplitLeg>,
+}
+
+/// One pool's share of a split hop
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitLeg {
+    pub dex: DexType,
+    pub pool: Pubkey,
+    /// Share of the hop's input, in basis points
+    pub share_bps: u16,
+}
+
+impl CycleStep {
+    /// `step` split across `legs`; `pool` and `dex` become the largest leg's
+    fn split_across(step: &CycleStep, legs: Vec<SplitLeg>) -> CycleStep {
+        let main = legs.iter().max_by_key(|leg| leg.share_bps).cloned();
+        let mut split = step.clone();
+        if let Some(main) = main {
+            split.pool = main.pool;
+            split.dex = main.dex;
+        }
+        split.splits = legs;
+        split
+    }
+
+    /// Pools the hop trades through
+    pub fn pools(&self) -> Vec<Pubkey> {
+        if self.splits.is_empty() {
+            vec![self.pool]
+        } else {
+            self.splits.iter().map(|leg| leg.pool).collect()
+        }
+    }
+
+    /// Pools the hop trades through with the input each receives. Legs get
+    /// their share rounded down; the last leg takes the remainder.
+    pub fn leg_inputs(&self, amount_in: u64) -> Vec<(Pubkey, u64)> {
+        if self.splits.is_empty() {
+            return vec![(self.pool, amount_in)];
+        }
+
+        let mut remaining = amount_in;
+        let last = self.splits.len() - 1;
+        self.splits.iter()
+            .enumerate()
+            .map(|(i, leg)| {
+                let leg_in = if i == last {
+                    remaining
+                } else {
+                    (amount_in as u128 * leg.share_bps as u128 / 10_000) as u64
+                };
+                remaining -= leg_in;
+                (leg.pool, leg_in)
+            })
+            .collect()
+    }
 }
 
 /// Represents a complete arbitrage cycle with profit calculations
@@ -411,53 +1061,47 @@ pub struct ArbitrageCycle {
 }
 
 impl ArbitrageCycle {
-    /// Calculate profit after slippage for a given trade amount
+    /// Calculate profit after slippage for a given trade amount.
+    ///
+    /// Steps whose pool has a depth ladder in `liquidity_map` are filled
+    /// level by level (input beyond the last level fills nothing); other
+    /// steps use the edge rate.
     pub fn calculate_slippage_adjusted_profit(&self, amount: u64, liquidity_map: &HashMap<Pubkey, Vec<PriceLevel>>) -> f64 {
         let mut current_amount = amount as f64;
         
         for step in &self.path {
-            // Apply exchange rate
-            current_amount *= step.rate;
-            
-            // Apply fee
             let fee_multiplier = 1.0 - (step.fee_bps as f64 / 10000.0);
-            current_amount *= fee_multiplier;
             
-            // Apply slippage based on liquidity depth
-            if let Some(levels) = liquidity_map.get(&step.pool) {
-                let slippage_factor = Self::calculate_slippage_factor(current_amount as u64, levels);
-                current_amount *= slippage_factor;
-            }
+            current_amount = match liquidity_map.get(&step.pool) {
+                Some(levels) => Self::fill_through_levels(current_amount, step.rate, levels),
+                None => current_amount * step.rate,
+            };
+            current_amount *= fee_multiplier;
         }
         
         current_amount - amount as f64
     }
     
-    fn calculate_slippage_factor(amount: u64, liquidity: &[PriceLevel]) -> f64 {
-        if liquidity.is_empty() {
-            return 0.98; // Conservative 2% slippage estimate
+    /// Output for `amount` walked through a ladder of (rate, input capacity) levels
+    fn fill_through_levels(amount: f64, rate: f64, levels: &[PriceLevel]) -> f64 {
+        if levels.is_empty() {
+            return amount * rate * 0.98; // Conservative 2% slippage estimate
         }
         
         let mut remaining = amount;
-        let mut weighted_price = 0.0;
-        let mut total_filled = 0u64;
+        let mut output = 0.0;
         
-        for level in liquidity {
-            if remaining == 0 {
+        for level in levels {
+            if remaining <= 0.0 {
                 break;
             }
             
-            let filled = remaining.min(level.liquidity);
-            weighted_price += level.price * filled as f64;
-            total_filled += filled;
+            let filled = remaining.min(level.liquidity as f64);
+            output += filled * level.price;
             remaining -= filled;
         }
         
-        if total_filled == 0 {
-            return 0.98;
-        }
-        
-        weighted_price / total_filled as f64
+        output
     }
     
     /// Check if cycle meets Solana transaction size limits
@@ -483,6 +1127,8 @@ pub struct BellmanFordDetector {
     graph: SharedArbitrageGraph,
     min_profit_bps: i64,
     max_path_length: usize,
+    /// Precomputed routes per start token, priced instead of a full pass
+    route_cache: Option<SharedRouteCache>,
 }
 
 impl BellmanFordDetector {
@@ -493,6 +1139,7 @@ impl BellmanFordDetector {
             graph,
             min_profit_bps,
             max_path_length: 4, // Support up to 4 hops
+            route_cache: None,
         }
     }
     
@@ -501,118 +1148,64 @@ impl BellmanFordDetector {
         self.max_path_length = length;
         self
     }
+
+    /// Price the cached routes of the start tokens instead of running a
+    /// Bellman-Ford pass (the cache's `max_hops` then bounds cycle length)
+    pub fn with_route_cache(mut self, route_cache: SharedRouteCache) -> Self {
+        self.route_cache = Some(route_cache);
+        self
+    }
+
+    /// Longest cycle (in hops) the detector reports
+    pub fn max_path_length(&self) -> usize {
+        self.max_path_length
+    }
+
+    /// Whether a scored cycle clears the detector's profit thresholds
+    pub fn is_profitable(&self, cycle: &ArbitrageCycle) -> bool {
+        cycle.net_profit_after_fees > 0.0 && cycle.gross_profit_bps >= self.min_profit_bps
+    }
     
-    /// Run Bellman-Ford algorithm to detect arbitrage cycles
-    /// Returns all profitable cycles found starting from start_token
+    /// Profitable cycles through `start_token`, each starting there
     pub async fn detect_arbitrage(&self, start_token: Pubkey) -> Result<Vec<ArbitrageCycle>> {
-        // Clone graph data for concurrent processing
+        let cycles = self.detect_all_arbitrage(&[start_token]).await?;
+        Ok(cycles.into_iter().filter(|cycle| cycle.start_token == start_token).collect())
+    }
+
+    /// Profitable cycles anywhere in the graph, found with one Bellman-Ford
+    /// pass. Each cycle is reported once, starting at the first of
+    /// `preferred_starts` it passes through. Sorted by net profit, best first.
+    ///
+    /// With a route cache, only the cached routes of `preferred_starts` are
+    /// priced (cycles through none of them are not reported).
+    pub async fn detect_all_arbitrage(&self, preferred_starts: &[Pubkey]) -> Result<Vec<ArbitrageCycle>> {
         let graph = self.graph.read().map_err(|e| anyhow!("Failed to acquire graph lock: {}", e))?;
-        
-        // Get all tokens to iterate over
-        let tokens = graph.get_all_tokens();
-        if tokens.is_empty() {
+        if graph.token_count() == 0 {
             return Ok(Vec::new());
         }
         
-        debug!("Running Bellman-Ford from {} across {} tokens", start_token, tokens.len());
-        
-        // Initialize distance map: distance[token] = shortest path weight from start
-        let mut distances: HashMap<Pubkey, f64> = HashMap::new();
-        let mut predecessors: HashMap<Pubkey, (Pubkey, DexType, Pubkey, f64, u16)> = HashMap::new();
-        
-        // Initialize: start token has distance 0, all others infinity
-        for token in &tokens {
-            distances.insert(*token, f64::INFINITY);
-        }
-        distances.insert(start_token, 0.0);
-        
-        // Relax edges |V|-1 times (standard Bellman-Ford)
-        let num_tokens = tokens.len();
-        for iteration in 0..num_tokens - 1 {
-            let mut updated = false;
-            
-            for token in &tokens {
-                if let Some(&current_dist) = distances.get(token) {
-                    if current_dist == f64::INFINITY {
-                        continue;
-                    }
-                    
-                    // Relax all outgoing edges from this token
-                    if let Some(edges) = graph.get_edges_from(token) {
-                        for edge in edges {
-                            let new_dist = current_dist + edge.inverse_log_weight;
-                            let neighbor_dist = distances.get(&edge.to_token).copied().unwrap_or(f64::INFINITY);
-                            
-                            if new_dist < neighbor_dist {
-                                distances.insert(edge.to_token, new_dist);
-                                predecessors.insert(
-                                    edge.to_token,
-                                    (*token, edge.dex.clone(), edge.pool_address, edge.rate, edge.fee_bps)
-                                );
-                                updated = true;
-                            }
-                        }
-                    }
-                }
+        let candidates = match &self.route_cache {
+            Some(route_cache) if !preferred_starts.is_empty() => {
+                let mut route_cache = route_cache.write()
+                    .map_err(|e| anyhow!("Failed to acquire route cache lock: {}", e))?;
+                Self::price_cached_routes(&graph, &mut route_cache, preferred_starts)
             }
-            
-            // Early termination if no updates
-            if !updated {
-                debug!("Bellman-Ford converged at iteration {}", iteration + 1);
-                break;
+            _ => {
+                debug!("Running Bellman-Ford across {} tokens", graph.token_count());
+                graph.find_arbitrage_cycles(preferred_starts, self.max_path_length)
             }
-        }
+        };
         
-        // Detect negative cycles (arbitrage opportunities)
-        let mut cycles = Vec::new();
-        let mut visited_cycles: HashSet<Vec<Pubkey>> = HashSet::new();
+        let mut cycles: Vec<ArbitrageCycle> = candidates
+            .into_iter()
+            .filter(|cycle| self.is_profitable(cycle))
+            .collect();
         
-        for token in &tokens {
-            if let Some(&current_dist) = distances.get(token) {
-                if current_dist == f64::INFINITY {
-                    continue;
-                }
-                
-                // Check all outgoing edges for negative cycle
-                if let Some(edges) = graph.get_edges_from(token) {
-                    for edge in edges {
-                        let new_dist = current_dist + edge.inverse_log_weight;
-                        let neighbor_dist = distances.get(&edge.to_token).copied().unwrap_or(f64::INFINITY);
-                        
-                        // If we can still relax, we found a negative cycle
-                        if new_dist < neighbor_dist {
-                            // Reconstruct the cycle
-                            if let Some(cycle) = self.reconstruct_cycle(
-                                &predecessors,
-                                &graph,
-                                edge.to_token,
-                                start_token,
-                                *token,
-                                edge.dex.clone(),
-                                edge.pool_address,
-                                edge.rate,
-                                edge.fee_bps,
-                            ) {
-                                // Deduplicate cycles (same tokens, different order)
-                                let mut cycle_tokens: Vec<Pubkey> = cycle.path.iter().map(|s| s.from_token).collect();
-                                cycle_tokens.sort();
-                                
-                                if !visited_cycles.contains(&cycle_tokens) && cycle.net_profit_after_fees > 0.0 {
-                                    visited_cycles.insert(cycle_tokens);
-                                    
-                                    if cycle.gross_profit_bps >= self.min_profit_bps {
-                                        info!(
-                                            "Detected arbitrage cycle: {} bps gross, {:.6} net profit, {} hops",
-                                            cycle.gross_profit_bps, cycle.net_profit_after_fees, cycle.path.len()
-                                        );
-                                        cycles.push(cycle);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        for cycle in &cycles {
+            info!(
+                "Detected arbitrage cycle: {} bps gross, {:.6} net profit, {} hops",
+                cycle.gross_profit_bps, cycle.net_profit_after_fees, cycle.path.len()
+            );
         }
         
         // Sort by net profit descending
@@ -623,139 +1216,25 @@ impl BellmanFordDetector {
         Ok(cycles)
     }
     
-    /// Run detection concurrently for multiple start tokens
-    pub async fn detect_arbitrage_parallel(&self, start_tokens: Vec<Pubkey>) -> Result<Vec<ArbitrageCycle>> {
-        let mut handles = Vec::new();
-        
-        for token in start_tokens {
-            let detector = self.clone_detector();
-            let handle = task::spawn(async move {
-                detector.detect_arbitrage(token).await
-            });
-            handles.push(handle);
-        }
-        
-        let mut all_cycles = Vec::new();
-        for handle in handles {
-            match handle.await {
-                Ok(Ok(cycles)) => all_cycles.extend(cycles),
-                Ok(Err(e)) => warn!("Arbitrage detection failed: {}", e),
-                Err(e) => warn!("Task join failed: {}", e),
-            }
-        }
-        
-        // Deduplicate and sort
-        all_cycles.sort_by(|a, b| {
-            b.net_profit_after_fees.partial_cmp(&a.net_profit_after_fees).unwrap()
-        });
-        all_cycles.dedup_by(|a, b| {
-            let a_tokens: Vec<_> = a.path.iter().map(|s| s.from_token).collect();
-            let b_tokens: Vec<_> = b.path.iter().map(|s| s.from_token).collect();
-            a_tokens == b_tokens
-        });
-        
-        Ok(all_cycles)
-    }
-    
-    /// Reconstruct cycle from predecessor map
-    fn reconstruct_cycle(
-        &self,
-        predecessors: &HashMap<Pubkey, (Pubkey, DexType, Pubkey, f64, u16)>,
-        _graph: &ArbitrageGraph,
-        cycle_token: Pubkey,
-        start_token: Pubkey,
-        last_from: Pubkey,
-        last_dex: DexType,
-        last_pool: Pubkey,
-        last_rate: f64,
-        last_fee: u16,
-    ) -> Option<ArbitrageCycle> {
-        let mut path = Vec::new();
-        let mut current = cycle_token;
-        let mut visited = HashSet::new();
-        let mut cycle_weight = 0.0;
-        
-        // Trace back through predecessors
-        while let Some(&(from_token, ref dex, pool, rate, fee_bps)) = predecessors.get(&current) {
-            if visited.contains(&current) {
-                break; // Prevent infinite loops
-            }
-            visited.insert(current);
-            
-            // Add step to path
-            path.push(CycleStep {
-                from_token,
-                to_token: current,
-                dex: dex.clone(),
-                pool,
-                rate,
-                fee_bps,
-            });
-            
-            // Calculate weight
-            cycle_weight += ExchangeEdge::calculate_weight(rate, fee_bps);
-            
-            current = from_token;
-            
-            // Stop if we've traced back enough or found start
-            if path.len() >= self.max_path_length {
-                break;
-            }
-        }
-        
-        // Add the final edge that closes the cycle
-        path.push(CycleStep {
-            from_token: last_from,
-            to_token: cycle_token,
-            dex: last_dex,
-            pool: last_pool,
-            rate: last_rate,
-            fee_bps: last_fee,
-        });
-        cycle_weight += ExchangeEdge::calculate_weight(last_rate, last_fee);
-        
-        // Reverse to get correct order
-        path.reverse();
-        
-        // Validate cycle (should return to start or form a loop)
-        if path.is_empty() {
-            return None;
-        }
-        
-        // Calculate profits
-        let gross_profit_ratio = (-cycle_weight).exp();
-        let gross_profit_bps = ((gross_profit_ratio - 1.0) * 10000.0) as i64;
-        
-        let total_fee_bps: u16 = path.iter().map(|s| s.fee_bps).sum();
-        
-        // Net profit calculation: apply all fees
-        let mut net_multiplier = 1.0;
-        for step in &path {
-            net_multiplier *= step.rate * (1.0 - step.fee_bps as f64 / 10000.0);
+    /// Cached routes of each start token priced at current rates. A route
+    /// through an earlier start token was already priced from there.
+    fn price_cached_routes(graph: &ArbitrageGraph, route_cache: &mut RouteCache, starts: &[Pubkey]) -> Vec<ArbitrageCycle> {
+        let mut cycles = Vec::new();
+        for (i, start) in starts.iter().enumerate() {
+            let earlier = &starts[..i];
+            cycles.extend(
+                route_cache.evaluate(graph, start)
+                    .into_iter()
+                    .filter(|cycle| !cycle.path.iter().any(|step| earlier.contains(&step.from_token))),
+            );
         }
-        let net_profit_after_fees = net_multiplier - 1.0;
-        
-        // Estimate execution time (50ms per hop + 400ms confirmation)
-        let execution_time_estimate_ms = (path.len() as u64 * 50) + 400;
-        
-        Some(ArbitrageCycle {
-            path,
-            gross_profit_bps,
-            net_profit_after_fees,
-            execution_time_estimate_ms,
-            total_fee_bps,
-            start_token,
-            cycle_weight,
-        })
+        cycles
     }
-    
-    /// Clone detector for parallel execution
-    fn clone_detector(&self) -> Self {
-        Self {
-            graph: Arc::clone(&self.graph),
-            min_profit_bps: self.min_profit_bps,
-            max_path_length: self.max_path_length,
-        }
+
+    /// Profitable cycles through any of `start_tokens`, from a single pass
+    pub async fn detect_arbitrage_parallel(&self, start_tokens: Vec<Pubkey>) -> Result<Vec<ArbitrageCycle>> {
+        let cycles = self.detect_all_arbitrage(&start_tokens).await?;
+        Ok(cycles.into_iter().filter(|cycle| start_tokens.contains(&cycle.start_token)).collect())
     }
 }
 
@@ -763,6 +1242,7 @@ impl BellmanFordDetector {
 mod tests {
     use super::*;
     use solana_sdk::pubkey::Pubkey;
+    use crate::dex::test_fixtures::raydium_pool;
 
     fn create_test_pubkey(seed: u8) -> Pubkey {
         Pubkey::new_from_array([seed; 32])
@@ -825,13 +1305,182 @@ mod tests {
         graph.add_edge(edge);
         
         // Update rate
-        graph.update_edge_rate(token_a, token_b, DexType::Raydium, 2.0, 2000).unwrap();
+        graph.update_edge_rate(token_a, token_b, pool, 2.0, 2000).unwrap();
         
         let edges = graph.get_edges_from(&token_a).unwrap();
         assert_eq!(edges[0].rate, 2.0);
         assert_eq!(edges[0].last_update, 2000);
     }
 
+    #[test]
+    fn test_remove_pool() {
+        let mut graph = ArbitrageGraph::new();
+        let token_a = create_test_pubkey(1);
+        let token_b = create_test_pubkey(2);
+        let token_c = create_test_pubkey(3);
+        let removed_pool = create_test_pubkey(100);
+        let kept_pool = create_test_pubkey(101);
+
+        graph.add_edge(ExchangeEdge::new(token_a, token_b, DexType::Pump, removed_pool, 1.5, 100, vec![], 1000));
+        graph.add_edge(ExchangeEdge::new(token_b, token_a, DexType::Pump, removed_pool, 0.66, 100, vec![], 1000));
+        graph.add_edge(ExchangeEdge::new(token_a, token_c, DexType::Raydium, kept_pool, 2.0, 25, vec![], 1000));
+
+        assert_eq!(graph.remove_pool(&removed_pool), 2);
+        assert_eq!(graph.edge_count(), 1);
+
+        // The remaining edge is still addressable after its index shifted
+        graph.update_edge_rate(token_a, token_c, kept_pool, 2.5, 2000).unwrap();
+        assert_eq!(graph.get_edges_from(&token_a).unwrap()[0].rate, 2.5);
+        assert!(graph.update_edge_rate(token_a, token_b, removed_pool, 1.0, 2000).is_err());
+    }
+
+    #[test]
+    fn test_several_raydium_pools_for_one_pair() {
+        let mut graph = ArbitrageGraph::new();
+        let token_a = create_test_pubkey(1);
+        let token_b = create_test_pubkey(2);
+        let amm_pool = create_test_pubkey(100);
+        let cpmm_pool = create_test_pubkey(101);
+        let clmm_pool = create_test_pubkey(102);
+
+        graph.upsert_edge(ExchangeEdge::new(token_a, token_b, DexType::Raydium, amm_pool, 1.50, 25, vec![], 1000));
+        graph.upsert_edge(ExchangeEdge::new(token_a, token_b, DexType::RaydiumCpmm, cpmm_pool, 1.51, 25, vec![], 1000));
+        graph.upsert_edge(ExchangeEdge::new(token_a, token_b, DexType::RaydiumClmm, clmm_pool, 1.52, 5, vec![], 1000));
+        assert_eq!(graph.edge_count(), 3);
+
+        // Refreshing one pool replaces only its own edge
+        graph.upsert_edge(ExchangeEdge::new(token_a, token_b, DexType::RaydiumCpmm, cpmm_pool, 1.55, 25, vec![], 2000));
+        assert_eq!(graph.edge_count(), 3);
+        let rates: Vec<f64> = graph.get_edges_from(&token_a).unwrap().iter().map(|edge| edge.rate).collect();
+        assert_eq!(rates, vec![1.50, 1.55, 1.52]);
+
+        graph.update_edge_rate(token_a, token_b, clmm_pool, 1.60, 3000).unwrap();
+        assert_eq!(graph.get_edges_from(&token_a).unwrap()[2].rate, 1.60);
+        assert_eq!(graph.get_edges_from(&token_a).unwrap()[0].rate, 1.50);
+    }
+
+    #[test]
+    fn test_cycle_quotes_use_pool_integer_math() {
+        let registry = DexRegistry::default();
+        let mut graph = ArbitrageGraph::new();
+        let token_a = create_test_pubkey(1);
+        let token_b = create_test_pubkey(2);
+        let pool_ab = raydium_pool(create_test_pubkey(100), token_a, token_b, 1_000_000, 2_000_000);
+        let pool_ba = raydium_pool(create_test_pubkey(101), token_b, token_a, 1_900_000, 1_000_000);
+        graph.upsert_pool(&registry, &pool_ab, 1000).unwrap();
+        graph.upsert_pool(&registry, &pool_ba, 1000).unwrap();
+
+        let step = |from, to, pool: &PoolData| CycleStep {
+            from_token: from,
+            to_token: to,
+            dex: DexType::Raydium,
+            pool: pool.pool_address,
+            rate: 0.0,
+            fee_bps: 25,
+            splits: Vec::new(),
+        };
+        let cycle = ArbitrageCycle {
+            path: vec![step(token_a, token_b, &pool_ab), step(token_b, token_a, &pool_ba)],
+            gross_profit_bps: 0,
+            net_profit_after_fees: 0.0,
+            execution_time_estimate_ms: 0,
+            total_fee_bps: 50,
+            start_token: token_a,
+            cycle_weight: 0.0,
+        };
+
+        // 1000 -> 1992 (see constant_product_amount_out), then
+        // 1992 - ceil(4.98) = 1987 in: 1_000_000 * 1987 / 1_901_987 = 1044.7 -> 1044
+        assert_eq!(graph.quote_cycle(&cycle, 1000).unwrap(), vec![1992, 1044]);
+
+        // A rate override drops the stale pool quote
+        graph.update_edge_rate(token_a, token_b, pool_ab.pool_address, 2.0, 2000).unwrap();
+        assert_eq!(graph.quote_cycle(&cycle, 1000).unwrap()[0], 1995);
+
+        // Unknown steps cannot be quoted
+        let mut missing = cycle.clone();
+        missing.path[0].pool = create_test_pubkey(102);
+        assert!(graph.quote_cycle(&missing, 1000).is_err());
+    }
+
+    #[test]
+    fn test_large_hop_is_split_across_parallel_pools() {
+        let registry = DexRegistry::default();
+        let mut graph = ArbitrageGraph::new();
+        let token_a = create_test_pubkey(1);
+        let token_b = create_test_pubkey(2);
+        // Two pools of the same pair, the second with half the depth
+        let deep = raydium_pool(create_test_pubkey(100), token_a, token_b, 1_000_000, 2_000_000);
+        let shallow = raydium_pool(create_test_pubkey(101), token_a, token_b, 500_000, 1_000_000);
+        graph.upsert_pool(&registry, &deep, 1000).unwrap();
+        graph.upsert_pool(&registry, &shallow, 1000).unwrap();
+
+        // Marginal output is equal where each pool takes a share of its depth
+        let legs = graph.split_hop(&token_a, &token_b, 300_000, 3).unwrap();
+        let share = |pool: &PoolData| legs.iter().find(|leg| leg.pool == pool.pool_address).unwrap().share_bps;
+        assert_eq!(legs.iter().map(|leg| leg.share_bps as u32).sum::<u32>(), 10_000);
+        assert!((6_500..=6_800).contains(&share(&deep)));
+        assert!(graph.split_hop(&token_a, &token_b, 300_000, 1).is_none());
+
+        let cycle = ArbitrageCycle {
+            path: vec![CycleStep {
+                from_token: token_a,
+                to_token: token_b,
+                dex: DexType::Raydium,
+                pool: deep.pool_address,
+                rate: 2.0,
+                fee_bps: 25,
+                splits: Vec::new(),
+            }],
+            gross_profit_bps: 0,
+            net_profit_after_fees: 0.0,
+            execution_time_estimate_ms: 0,
+            total_fee_bps: 25,
+            start_token: token_a,
+            cycle_weight: 0.0,
+        };
+        let split = graph.split_cycle(&cycle, 300_000, 3).unwrap();
+        assert_eq!(split.path[0].splits.len(), 2);
+        assert_eq!(split.path[0].pool, deep.pool_address);
+        assert!(graph.quote_cycle(&split, 300_000).unwrap()[0] > graph.quote_cycle(&cycle, 300_000).unwrap()[0]);
+
+        // Price impact grows with size and splitting reduces it
+        let impact = graph.price_impact_bps(&cycle, 300_000).unwrap()[0];
+        assert!(graph.price_impact_bps(&cycle, 1_000).unwrap()[0] < impact);
+        assert!(graph.price_impact_bps(&split, 300_000).unwrap()[0] < impact);
+
+        // A small trade barely moves the price: no split is worth it
+        assert!(graph.split_cycle(&cycle, 100, 3).unwrap().path[0].splits.is_empty());
+    }
+
+    #[test]
+    fn test_optimal_trade_size_simulates_exact_quotes() {
+        let registry = DexRegistry::default();
+        let token_a = create_test_pubkey(1);
+        let token_b = create_test_pubkey(2);
+        let pool = raydium_pool(create_test_pubkey(100), token_a, token_b, 1_000_000, 2_000_000);
+        let mut edges = registry.edges_for(&pool, 1000).unwrap();
+        for edge in &mut edges {
+            edge.liquidity_depth = vec![PriceLevel { price: edge.rate, liquidity: 10_000 }];
+        }
+
+        let opportunity = TriangularArbitrageOpportunity {
+            path: edges,
+            profit_ratio: 1.0,
+            profit_bps: 0,
+            input_token: token_a,
+            input_amount: 1000,
+            estimated_output: 0,
+            total_fees_bps: 50,
+            cycle_weight: 0.0,
+        };
+        let graph = ArbitrageGraph::new();
+        let (input, output) = graph.calculate_optimal_trade_size(&opportunity, 1000, 100).unwrap();
+        assert_eq!(input, 1000);
+        // 1000 -> 1992 -> 1_000_000 * 1987 / 2_001_987 = 992.5 -> 992
+        assert_eq!(output, 992);
+    }
+
     #[test]
     fn test_triangular_arbitrage_detection() {
         let mut graph = ArbitrageGraph::new();
@@ -993,11 +1642,147 @@ mod tests {
         }
 
         let detector = BellmanFordDetector::new(graph, 0);
-        let cycles = detector.detect_arbitrage_parallel(vec![token_a, token_b]).await.unwrap();
-        
         // May or may not find cycles depending on the exact rates and fees
         // Just verify the parallel execution completes without errors
-        assert!(cycles.len() >= 0, "Parallel detection should complete");
+        detector.detect_arbitrage_parallel(vec![token_a, token_b]).await
+            .expect("Parallel detection should complete");
+    }
+
+    fn add_triangle(graph: &mut ArbitrageGraph, tokens: [Pubkey; 3], pool_seed: u8, closing_rate: f64) {
+        graph.add_edge(ExchangeEdge::new(
+            tokens[0], tokens[1], DexType::Raydium, create_test_pubkey(pool_seed),
+            1.1, 25, vec![], 1000,
+        ));
+        graph.add_edge(ExchangeEdge::new(
+            tokens[1], tokens[2], DexType::Meteora, create_test_pubkey(pool_seed + 1),
+            1.1, 25, vec![], 1000,
+        ));
+        graph.add_edge(ExchangeEdge::new(
+            tokens[2], tokens[0], DexType::Orca, create_test_pubkey(pool_seed + 2),
+            closing_rate, 25, vec![], 1000,
+        ));
+    }
+
+    #[test]
+    fn test_cycle_rotated_to_preferred_start_and_reported_once() {
+        let mut graph = ArbitrageGraph::new();
+        let tokens = [create_test_pubkey(1), create_test_pubkey(2), create_test_pubkey(3)];
+        add_triangle(&mut graph, tokens, 101, 0.85);
+
+        // Preferences absent from the cycle are skipped; the rest decide the start
+        let cycles = graph.find_arbitrage_cycles(&[create_test_pubkey(9), tokens[2], tokens[1]], 4);
+        assert_eq!(cycles.len(), 1);
+        let cycle = &cycles[0];
+        assert_eq!(cycle.start_token, tokens[2]);
+        assert_eq!(cycle.path.len(), 3);
+        assert_eq!(cycle.path[0].from_token, tokens[2]);
+        assert_eq!(cycle.path[2].to_token, tokens[2]);
+        for pair in cycle.path.windows(2) {
+            assert_eq!(pair[0].to_token, pair[1].from_token);
+        }
+        assert!(cycle.cycle_weight < 0.0);
+        assert!(cycle.net_profit_after_fees > 0.0);
+
+        // Every start token sees the same cycle once
+        let all = graph.detect_all_triangular_arbitrage(4, 0);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].input_token, tokens[0]);
+        let from_c = graph.detect_triangular_arbitrage(&tokens[2], 3, 0);
+        assert_eq!(from_c.len(), 1);
+        assert_eq!(from_c[0].path[0].from_token, tokens[2]);
+    }
+
+    #[test]
+    fn test_disjoint_cycles_found_in_one_pass() {
+        let mut graph = ArbitrageGraph::new();
+        let first = [create_test_pubkey(1), create_test_pubkey(2), create_test_pubkey(3)];
+        let second = [create_test_pubkey(4), create_test_pubkey(5), create_test_pubkey(6)];
+        add_triangle(&mut graph, first, 101, 0.85);
+        add_triangle(&mut graph, second, 111, 0.86);
+        // A losing triangle is not a negative cycle
+        add_triangle(&mut graph, [create_test_pubkey(7), create_test_pubkey(8), create_test_pubkey(9)], 121, 0.7);
+
+        let cycles = graph.find_arbitrage_cycles(&[], 4);
+        assert_eq!(cycles.len(), 2);
+        let starts: HashSet<Pubkey> = cycles.iter().map(|c| c.start_token).collect();
+        assert!(starts.contains(&first[0]) && starts.contains(&second[0]));
+    }
+
+    #[test]
+    fn test_stale_edges_and_wide_slot_spans_are_rejected() {
+        let (a, b, c) = (create_test_pubkey(1), create_test_pubkey(2), create_test_pubkey(3));
+        let pools = [create_test_pubkey(101), create_test_pubkey(102), create_test_pubkey(103)];
+        let keys = [(a, b, pools[0]), (b, c, pools[1]), (c, a, pools[2])];
+        let edge = |(from, to, pool): EdgeKey, rate: f64, slot: u64| {
+            ExchangeEdge::new(from, to, DexType::Raydium, pool, rate, 25, vec![], chrono::Utc::now().timestamp())
+                .with_slot(slot)
+        };
+
+        let mut graph = ArbitrageGraph::new().with_staleness(StalenessConfig {
+            max_edge_age_slots: 10,
+            max_edge_age_ms: 60_000,
+            max_cycle_slot_span: 5,
+        });
+        // Profitable only through a rate observed 30 slots before the others
+        graph.add_edge(edge(keys[0], 1.0, 130));
+        graph.add_edge(edge(keys[1], 1.0, 130));
+        graph.add_edge(edge(keys[2], 1.1, 100));
+        assert_eq!(graph.latest_slot(), 130);
+        assert!(graph.is_edge_stale(graph.get_edge(&c, &a, &pools[2]).unwrap()));
+        assert!(graph.find_arbitrage_cycles(&[a], 4).is_empty());
+        assert!(graph.cycle_through(&keys).is_none());
+        assert_eq!(graph.staleness_stats().stale_edges, 2);
+
+        // Within the age limit, but 8 slots from the other edges
+        graph.upsert_edge(edge(keys[2], 1.1, 122));
+        assert!(!graph.is_edge_stale(graph.get_edge(&c, &a, &pools[2]).unwrap()));
+        assert!(graph.find_arbitrage_cycles(&[a], 4).is_empty());
+        assert_eq!(graph.staleness_stats().wide_slot_spans, 1);
+
+        graph.upsert_edge(edge(keys[2], 1.1, 127));
+        assert_eq!(graph.find_arbitrage_cycles(&[a], 4).len(), 1);
+        assert!(graph.cycle_through(&keys).is_some());
+
+        // Edges are also too old once their timestamp is
+        let old = ExchangeEdge::new(a, b, DexType::Raydium, pools[0], 1.0, 25, vec![], 1000);
+        assert!(graph.is_edge_stale(&old));
+        assert_eq!(graph.staleness_stats(), StalenessStats { stale_edges: 2, wide_slot_spans: 1 });
+    }
+
+    #[test]
+    fn test_cycles_longer_than_max_path_length_are_dropped() {
+        let mut graph = ArbitrageGraph::new();
+        let tokens = [create_test_pubkey(1), create_test_pubkey(2), create_test_pubkey(3)];
+        add_triangle(&mut graph, tokens, 101, 0.85);
+
+        assert!(graph.find_arbitrage_cycles(&[], 2).is_empty());
+        assert_eq!(graph.find_arbitrage_cycles(&[], 3).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_detectors_share_route_cache() {
+        let graph = create_shared_graph();
+        let tokens = [create_test_pubkey(1), create_test_pubkey(2), create_test_pubkey(3)];
+        add_triangle(&mut graph.write().unwrap(), tokens, 101, 0.85);
+
+        let route_cache = Arc::new(RwLock::new(RouteCache::new(3, std::time::Duration::from_secs(300))));
+        let detector = BellmanFordDetector::new(Arc::clone(&graph), 0).with_route_cache(Arc::clone(&route_cache));
+
+        let cycles = detector.detect_arbitrage(tokens[1]).await.unwrap();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].start_token, tokens[1]);
+        assert_eq!(route_cache.read().unwrap().len(), 1);
+
+        // Starting at the first token reports the cycle once, from there
+        let all = detector.detect_arbitrage_parallel(vec![tokens[0], tokens[1]]).await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].start_token, tokens[0]);
+
+        let g = graph.read().unwrap();
+        let opportunities = g.detect_triangular_arbitrage_cached(&mut route_cache.write().unwrap(), &tokens[2], 0);
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].input_token, tokens[2]);
+        assert_eq!(opportunities[0].path.len(), 3);
     }
 
     #[test]
@@ -1014,6 +1799,7 @@ mod tests {
                     pool: create_test_pubkey(100),
                     rate: 1.1,
                     fee_bps: 25,
+                    splits: Vec::new(),
                 },
                 CycleStep {
                     from_token: token_b,
@@ -1022,6 +1808,7 @@ mod tests {
                     pool: create_test_pubkey(101),
                     rate: 0.95,
                     fee_bps: 30,
+                    splits: Vec::new(),
                 },
             ],
             gross_profit_bps: 100,
@@ -1041,6 +1828,52 @@ mod tests {
         assert!(profit < 100.0, "Slippage and fees should reduce profit significantly");
     }
 
+    #[test]
+    fn test_book_depth_limits_cycle_profit() {
+        let mut graph = ArbitrageGraph::new();
+        let token_a = create_test_pubkey(1);
+        let token_b = create_test_pubkey(2);
+        let amm_pool = create_test_pubkey(100);
+        let book = create_test_pubkey(101);
+
+        // AMM leg without depth, book leg with two levels
+        graph.add_edge(ExchangeEdge::new(token_a, token_b, DexType::Raydium, amm_pool, 1.1, 0, vec![], 1000));
+        graph.add_edge(ExchangeEdge::new(
+            token_b, token_a, DexType::Phoenix, book, 0.95, 0,
+            vec![
+                PriceLevel { price: 0.95, liquidity: 1_100 },
+                PriceLevel { price: 0.90, liquidity: 1_100 },
+            ],
+            1000,
+        ));
+
+        let step = |from, to, dex, pool, rate| CycleStep { from_token: from, to_token: to, dex, pool, rate, fee_bps: 0, splits: Vec::new() };
+        let cycle = ArbitrageCycle {
+            path: vec![
+                step(token_a, token_b, DexType::Raydium, amm_pool, 1.1),
+                step(token_b, token_a, DexType::Phoenix, book, 0.95),
+            ],
+            gross_profit_bps: 450,
+            net_profit_after_fees: 4.5,
+            execution_time_estimate_ms: 500,
+            total_fee_bps: 0,
+            start_token: token_a,
+            cycle_weight: -0.044,
+        };
+
+        let liquidity_map = graph.liquidity_map(&cycle);
+        assert_eq!(liquidity_map.len(), 1);
+        assert!(liquidity_map.contains_key(&book));
+
+        // 1000 -> 1100 fits the top level: 1045
+        let small = cycle.calculate_slippage_adjusted_profit(1000, &liquidity_map);
+        assert!((small - 45.0).abs() < 1e-6);
+
+        // 2000 -> 2200 spills into the second level: 1045 + 990 - 2000
+        let large = cycle.calculate_slippage_adjusted_profit(2000, &liquidity_map);
+        assert!((large - 35.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_transaction_size_limit() {
         let token_a = create_test_pubkey(1);
@@ -1055,6 +1888,7 @@ mod tests {
                 pool: create_test_pubkey(100 + i),
                 rate: 1.01,
                 fee_bps: 25,
+                splits: Vec::new(),
             });
         }
         
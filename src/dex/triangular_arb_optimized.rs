@@ -21,6 +21,8 @@ use tokio::task;
 use rustc_hash::{FxHashMap, FxHashSet};
 use once_cell::sync::Lazy;
 
+pub use super::triangular_arb::DexType;
+
 // ============================================================================
 // OPTIMIZATION 3: Logarithm Lookup Table for Common Fees
 // ============================================================================
@@ -142,27 +144,6 @@ impl ExchangeEdge {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub enum DexType {
-    Raydium,
-    Meteora,
-    Pump,
-    Whirlpool,
-    Orca,
-}
-
-impl std::fmt::Display for DexType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DexType::Raydium => write!(f, "Raydium"),
-            DexType::Meteora => write!(f, "Meteora"),
-            DexType::Pump => write!(f, "Pump"),
-            DexType::Whirlpool => write!(f, "Whirlpool"),
-            DexType::Orca => write!(f, "Orca"),
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct PriceLevel {
     pub price: f64,
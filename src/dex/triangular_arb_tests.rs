@@ -79,8 +79,9 @@ async fn test_simple_triangular_arbitrage() {
     }
     
     let cycle = &cycles[0];
-    // Path includes start token, so 3-hop cycle has 4 tokens (SOL -> USDC -> USDT -> SOL)
-    assert_eq!(cycle.path.len(), 4, "Expected 4 tokens in 3-hop cycle");
+    // One step per swap: SOL -> USDC -> USDT -> SOL
+    assert_eq!(cycle.path.len(), 3, "Expected 3 steps in 3-hop cycle");
+    assert_eq!(cycle.start_token, sol_mint());
     assert!(cycle.gross_profit_bps > 0, "Expected positive profit");
     
     println!("✅ Found cycle with profit: {} bps", cycle.gross_profit_bps);
@@ -224,8 +225,8 @@ async fn test_four_hop_arbitrage() {
     let cycles = detector.detect_arbitrage(sol_mint()).await.unwrap();
 
     if !cycles.is_empty() {
-        // 4-hop cycle has 5 tokens in path (includes start token)
-        assert_eq!(cycles[0].path.len(), 5);
+        // One step per swap
+        assert_eq!(cycles[0].path.len(), 4);
         println!("✅ Found 4-hop cycle with profit: {} bps", cycles[0].gross_profit_bps);
     } else {
         println!("⚠️  4-hop cycle not profitable after fees (expected)");
@@ -1,14 +1,1067 @@
-// Placeholder for Whirlpool DEX integration
-// TODO: Implement actual Whirlpool swap operations
+// Orca Whirlpool Adapter
+//
+// Decodes Whirlpool (concentrated liquidity) accounts and builds swap instructions.
+//
+// A Whirlpool quotes from `sqrt_price` (Q64.64) and the active `liquidity`
+// rather than from reserves. Liquidity changes whenever the price crosses an
+// initialized tick, and ticks live in separate `TickArray` accounts (88 ticks
+// each) derived from the pool address. The adapter loads the tick arrays
+// around the current price as dependent accounts and reproduces the program's
+// swap loop step by step, so quotes match the on-chain result exactly.
+//
+// Pools with a Token-2022 mint are swapped through `swap_v2`, which takes the
+// token program of each mint, both mints and the memo program.
 
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use tracing::debug;
 
-pub struct WhirlpoolClient {
+use crate::dex::adapter::{
+    read_array, read_i128, read_i32, read_pubkey, read_u128, read_u16, token_account_amount,
+    DexAdapter, MintLayout, SwapParams, VenueState,
+};
+use crate::dex::fees::{FeeModel, FeeRate};
+use crate::dex::pool_fetcher::PoolData;
+use crate::dex::token_2022::MEMO_PROGRAM_ID;
+use crate::dex::triangular_arb::DexType;
+
+/// Orca Whirlpool program id
+pub const WHIRLPOOL_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Anchor discriminator of the `swap` instruction
+const SWAP_DISCRIMINATOR: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+
+/// Anchor discriminator of the `swap_v2` instruction
+const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// Anchor discriminator of the `Whirlpool` account
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+
+/// Anchor discriminator of the `TickArray` account
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+
+/// Size of the Whirlpool account
+pub const WHIRLPOOL_LEN: usize = 653;
+
+/// Size of a TickArray account: discriminator, start index, 88 ticks, whirlpool
+pub const TICK_ARRAY_LEN: usize = 9988;
+
+/// Number of ticks in one tick array
+pub const TICK_ARRAY_SIZE: i32 = 88;
+
+/// Serialized size of one `Tick`
+const TICK_LEN: usize = 113;
+
+/// Number of tick arrays the swap instruction takes
+pub const SWAP_TICK_ARRAY_COUNT: usize = 3;
+
+pub const MIN_TICK_INDEX: i32 = -443_636;
+pub const MAX_TICK_INDEX: i32 = 443_636;
+
+/// sqrt price (Q64.64) at `MIN_TICK_INDEX`
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+/// sqrt price (Q64.64) at `MAX_TICK_INDEX`
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+/// `fee_rate` is expressed in hundredths of a basis point
+const FEE_RATE_MUL_VALUE: u128 = 1_000_000;
+
+/// Whirlpool pool account (fields needed for quoting and swapping)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WhirlpoolInfo {
+    pub whirlpools_config: Pubkey,
+    pub tick_spacing: u16,
+    /// Swap fee in hundredths of a basis point (3000 = 0.3%)
+    pub fee_rate: u16,
+    pub protocol_fee_rate: u16,
+    /// Liquidity active at the current price
+    pub liquidity: u128,
+    /// Q64.64 square root of the price of token A in token B
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+}
+
+impl WhirlpoolInfo {
+    /// Decode from raw account data
+    ///
+    /// Layout reference: whirlpools `state/whirlpool.rs`
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < WHIRLPOOL_LEN {
+            return Err(anyhow!("Invalid Whirlpool account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != WHIRLPOOL_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Whirlpool"));
+        }
+
+        Ok(Self {
+            whirlpools_config: read_pubkey(data, 8)?,
+            tick_spacing: read_u16(data, 41)?,
+            fee_rate: read_u16(data, 45)?,
+            protocol_fee_rate: read_u16(data, 47)?,
+            liquidity: read_u128(data, 49)?,
+            sqrt_price: read_u128(data, 65)?,
+            tick_current_index: read_i32(data, 81)?,
+            token_mint_a: read_pubkey(data, 101)?,
+            token_vault_a: read_pubkey(data, 133)?,
+            token_mint_b: read_pubkey(data, 181)?,
+            token_vault_b: read_pubkey(data, 213)?,
+        })
+    }
+
+    /// Swap fee in basis points (rounded down)
+    pub fn fee_bps(&self) -> u16 {
+        self.fee_rate / 100
+    }
+
+    /// Fee tier (hundredths of a basis point), with the protocol's share of it
+    /// in basis points
+    pub fn fee_model(&self) -> FeeModel {
+        FeeModel::Static {
+            trade: FeeRate::from_fraction(self.fee_rate as u128, 1_000_000),
+            protocol_share: FeeRate::from_bps(self.protocol_fee_rate as u64),
+        }
+    }
+
+    /// Price of token A in token B, ignoring decimals
+    pub fn price(&self) -> f64 {
+        let sqrt_price = self.sqrt_price as f64 / 2f64.powi(64);
+        sqrt_price * sqrt_price
+    }
+}
+
+/// The parts of a tick the swap loop needs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tick {
+    pub initialized: bool,
+    /// Liquidity added when the price crosses this tick upwards
+    pub liquidity_net: i128,
+}
+
+/// One `TickArray` account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickArray {
+    pub start_tick_index: i32,
+    pub ticks: Vec<Tick>,
+}
+
+impl TickArray {
+    /// Decode a tick array and check that it belongs to `whirlpool`
+    pub fn decode(whirlpool: &Pubkey, data: &[u8]) -> Result<Self> {
+        if data.len() < TICK_ARRAY_LEN {
+            return Err(anyhow!("Invalid tick array account size: {} bytes", data.len()));
+        }
+        if read_array::<8>(data, 0)? != TICK_ARRAY_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a Whirlpool tick array"));
+        }
+
+        let owner = read_pubkey(data, 12 + TICK_ARRAY_SIZE as usize * TICK_LEN)?;
+        if owner != *whirlpool {
+            return Err(anyhow!("Tick array belongs to {}, expected {}", owner, whirlpool));
+        }
+
+        let ticks = (0..TICK_ARRAY_SIZE as usize)
+            .map(|i| {
+                let offset = 12 + i * TICK_LEN;
+                Ok(Tick {
+                    initialized: data[offset] != 0,
+                    liquidity_net: read_i128(data, offset + 1)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            start_tick_index: read_i32(data, 8)?,
+            ticks,
+        })
+    }
+
+    /// Whether `tick_index` can start a search in this array.
+    /// b->a searches are shifted down one tick spacing.
+    fn in_search_range(&self, tick_index: i32, tick_spacing: i32, shifted: bool) -> bool {
+        let shift = if shifted { tick_spacing } else { 0 };
+        let lower = self.start_tick_index - shift;
+        let upper = self.start_tick_index + TICK_ARRAY_SIZE * tick_spacing - shift;
+        tick_index >= lower && tick_index < upper
+    }
+
+    fn is_min_tick_array(&self) -> bool {
+        self.start_tick_index <= MIN_TICK_INDEX
+    }
+
+    fn is_max_tick_array(&self, tick_spacing: i32) -> bool {
+        self.start_tick_index + TICK_ARRAY_SIZE * tick_spacing > MAX_TICK_INDEX
+    }
+
+    /// Next initialized tick in this array, searching down (a->b, inclusive)
+    /// or up (b->a, exclusive) from `tick_index`
+    fn next_initialized_tick(&self, tick_index: i32, tick_spacing: i32, a_to_b: bool) -> Result<Option<i32>> {
+        if !self.in_search_range(tick_index, tick_spacing, !a_to_b) {
+            return Err(anyhow!(
+                "Tick {} is outside tick array starting at {}",
+                tick_index, self.start_tick_index
+            ));
+        }
+
+        let mut offset = (tick_index - self.start_tick_index).div_euclid(tick_spacing);
+        if !a_to_b {
+            offset += 1;
+        }
+
+        while (0..TICK_ARRAY_SIZE).contains(&offset) {
+            if self.ticks[offset as usize].initialized {
+                return Ok(Some(self.start_tick_index + offset * tick_spacing));
+            }
+            offset += if a_to_b { -1 } else { 1 };
+        }
+
+        Ok(None)
+    }
+
+    /// Tick at `tick_index`, treated as uninitialized when outside this array
+    fn tick(&self, tick_index: i32, tick_spacing: i32) -> Tick {
+        let delta = tick_index - self.start_tick_index;
+        if delta < 0 || delta % tick_spacing != 0 {
+            return Tick::default();
+        }
+        self.ticks.get((delta / tick_spacing) as usize).copied().unwrap_or_default()
+    }
+}
+
+/// Decoded Whirlpool with the tick arrays loaded around its current price
+#[derive(Clone, Debug)]
+pub struct WhirlpoolPoolState {
+    pub whirlpool: WhirlpoolInfo,
+    /// Initialized tick arrays keyed by address; uninitialized ones are absent
+    pub tick_arrays: HashMap<Pubkey, TickArray>,
+}
+
+impl WhirlpoolPoolState {
+    /// The three tick array addresses a swap in this direction walks through
+    pub fn swap_tick_array_addresses(&self, program_id: &Pubkey, whirlpool: &Pubkey, a_to_b: bool) -> Vec<Pubkey> {
+        let tick_spacing = self.whirlpool.tick_spacing as i32;
+        let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
+        // b->a swaps start one tick spacing up so a price sitting on an
+        // array boundary searches the array above it
+        let shift = if a_to_b { 0 } else { tick_spacing };
+        let start = tick_array_start_index(self.whirlpool.tick_current_index + shift, tick_spacing);
+
+        (0..SWAP_TICK_ARRAY_COUNT as i32)
+            .map(|i| {
+                let offset = if a_to_b { -i } else { i };
+                tick_array_address(program_id, whirlpool, start + offset * ticks_in_array)
+            })
+            .collect()
+    }
+
+    /// Loaded tick arrays in swap order, stopping at the first one missing
+    fn swap_tick_arrays(&self, program_id: &Pubkey, whirlpool: &Pubkey, a_to_b: bool) -> Vec<&TickArray> {
+        self.swap_tick_array_addresses(program_id, whirlpool, a_to_b)
+            .iter()
+            .map_while(|address| self.tick_arrays.get(address))
+            .collect()
+    }
+}
+
+/// Start index of the tick array containing `tick_index`
+pub fn tick_array_start_index(tick_index: i32, tick_spacing: i32) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
+    tick_index.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// Tick array PDA: `[b"tick_array", whirlpool, start_tick_index as decimal string]`
+pub fn tick_array_address(program_id: &Pubkey, whirlpool: &Pubkey, start_tick_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", whirlpool.as_ref(), start_tick_index.to_string().as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Oracle PDA: `[b"oracle", whirlpool]`
+pub fn oracle_address(program_id: &Pubkey, whirlpool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"oracle", whirlpool.as_ref()], program_id).0
+}
+
+/// Q64.64 sqrt price at `tick`, bit-for-bit with the program's `tick_math.rs`
+pub fn sqrt_price_from_tick_index(tick: i32) -> u128 {
+    if tick >= 0 {
+        sqrt_price_positive_tick(tick)
+    } else {
+        sqrt_price_negative_tick(tick)
+    }
+}
+
+/// Multiply two Q32.96 values
+fn mul_shift_96(a: u128, b: u128) -> u128 {
+    ((BigUint::from(a) * BigUint::from(b)) >> 96u32)
+        .to_u128()
+        .expect("Q32.96 product fits in u128")
+}
+
+fn sqrt_price_positive_tick(tick: i32) -> u128 {
+    // sqrt(1.0001)^(2^i) in Q32.96 for bits 1..=18
+    const FACTORS: [u128; 18] = [
+        79236085330515764027303304731,
+        79244008939048815603706035061,
+        79259858533276714757314932305,
+        79291567232598584799939703904,
+        79355022692464371645785046466,
+        79482085999252804386437311141,
+        79736823300114093921829183326,
+        80248749790819932309965073892,
+        81282483887344747381513967011,
+        83390072131320151908154831281,
+        87770609709833776024991924138,
+        97234110755111693312479820773,
+        119332217159966728226237229890,
+        179736315981702064433883588727,
+        407748233172238350107850275304,
+        2098478828474011932436660412517,
+        55581415166113811149459800483533,
+        38992368544603139932233054999993551,
+    ];
+
+    let mut ratio: u128 = if tick & 1 != 0 {
+        79232123823359799118286999567
+    } else {
+        79228162514264337593543950336
+    };
+    for (bit, factor) in FACTORS.iter().enumerate() {
+        if tick & (2 << bit) != 0 {
+            ratio = mul_shift_96(ratio, *factor);
+        }
+    }
+    ratio >> 32
+}
+
+fn sqrt_price_negative_tick(tick: i32) -> u128 {
+    // sqrt(1.0001)^-(2^i) in Q64.64 for bits 1..=18
+    const FACTORS: [u128; 18] = [
+        18444899583751176498,
+        18443055278223354162,
+        18439367220385604838,
+        18431993317065449817,
+        18417254355718160513,
+        18387811781193591352,
+        18329067761203520168,
+        18212142134806087854,
+        17980523815641551639,
+        17526086738831147013,
+        16651378430235024244,
+        15030750278693429944,
+        12247334978882834399,
+        8131365268884726200,
+        3584323654723342297,
+        696457651847595233,
+        26294789957452057,
+        37481735321082,
+    ];
+
+    let abs_tick = tick.abs();
+    let mut ratio: u128 = if abs_tick & 1 != 0 {
+        18445821805675392311
+    } else {
+        18446744073709551616
+    };
+    for (bit, factor) in FACTORS.iter().enumerate() {
+        if abs_tick & (2 << bit) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+    ratio
+}
+
+/// Token A amount between two sqrt prices: L * (upper - lower) / (upper * lower).
+/// `None` when it does not fit in a u64.
+fn amount_delta_a(sqrt_price_0: u128, sqrt_price_1: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+    let (lower, upper) = (sqrt_price_0.min(sqrt_price_1), sqrt_price_0.max(sqrt_price_1));
+    let numerator = (BigUint::from(liquidity) * BigUint::from(upper - lower)) << 64u32;
+    let denominator = BigUint::from(upper) * BigUint::from(lower);
+    let quotient = &numerator / &denominator;
+    let quotient = if round_up && !(numerator % denominator).is_zero() {
+        quotient + 1u32
+    } else {
+        quotient
+    };
+    quotient.to_u64()
+}
+
+/// Token B amount between two sqrt prices: L * (upper - lower).
+/// `None` when it does not fit in a u64.
+fn amount_delta_b(sqrt_price_0: u128, sqrt_price_1: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+    let (lower, upper) = (sqrt_price_0.min(sqrt_price_1), sqrt_price_0.max(sqrt_price_1));
+    let product = BigUint::from(liquidity) * BigUint::from(upper - lower);
+    let remainder = &product % BigUint::from(1u128 << 64);
+    let quotient = product >> 64u32;
+    let quotient = if round_up && !remainder.is_zero() {
+        quotient + 1u32
+    } else {
+        quotient
+    };
+    quotient.to_u64()
+}
+
+/// Input needed to move the price from `current` to `target` (rounded up)
+fn amount_in_delta(current: u128, target: u128, liquidity: u128, a_to_b: bool) -> Option<u64> {
+    if a_to_b {
+        amount_delta_a(current, target, liquidity, true)
+    } else {
+        amount_delta_b(current, target, liquidity, true)
+    }
+}
+
+/// Output released by moving the price from `current` to `target` (rounded down)
+fn amount_out_delta(current: u128, target: u128, liquidity: u128, a_to_b: bool) -> Option<u64> {
+    if a_to_b {
+        amount_delta_b(current, target, liquidity, false)
+    } else {
+        amount_delta_a(current, target, liquidity, false)
+    }
+}
+
+/// sqrt price after adding `amount` of input at constant liquidity
+fn next_sqrt_price(sqrt_price: u128, liquidity: u128, amount: u64, a_to_b: bool) -> Result<u128> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+
+    let next = if a_to_b {
+        // L * P / (L + amount * P), rounded up
+        let numerator = (BigUint::from(liquidity) * BigUint::from(sqrt_price)) << 64u32;
+        let denominator = (BigUint::from(liquidity) << 64u32) + BigUint::from(sqrt_price) * BigUint::from(amount);
+        let quotient = &numerator / &denominator;
+        let quotient = if (numerator % denominator).is_zero() { quotient } else { quotient + 1u32 };
+        quotient.to_u128().ok_or_else(|| anyhow!("Whirlpool sqrt price overflow"))?
+    } else {
+        // P + amount / L, rounded down
+        if liquidity == 0 {
+            return Err(anyhow!("Whirlpool swap step with zero liquidity"));
+        }
+        let delta = ((amount as u128) << 64) / liquidity;
+        sqrt_price.checked_add(delta).ok_or_else(|| anyhow!("Whirlpool sqrt price overflow"))?
+    };
+
+    if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&next) {
+        return Err(anyhow!("Whirlpool sqrt price {} out of bounds", next));
+    }
+    Ok(next)
+}
+
+/// Result of one swap step between two sqrt prices
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SwapStep {
+    amount_in: u64,
+    amount_out: u64,
+    fee_amount: u64,
+    next_sqrt_price: u128,
+}
+
+/// One exact-input step of the swap loop (`swap_math::compute_swap`)
+fn compute_swap_step(
+    amount_remaining: u64,
+    fee_rate: u16,
+    liquidity: u128,
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    a_to_b: bool,
+) -> Result<SwapStep> {
+    let fee_rate = fee_rate as u128;
+    let amount_calc = (amount_remaining as u128 * (FEE_RATE_MUL_VALUE - fee_rate) / FEE_RATE_MUL_VALUE) as u64;
+
+    let max_in = amount_in_delta(sqrt_price_current, sqrt_price_target, liquidity, a_to_b);
+    let next_sqrt_price = match max_in {
+        Some(max_in) if amount_calc >= max_in => sqrt_price_target,
+        _ => next_sqrt_price(sqrt_price_current, liquidity, amount_calc, a_to_b)?,
+    };
+    let is_max_swap = next_sqrt_price == sqrt_price_target;
+
+    let amount_in = match max_in {
+        Some(max_in) if is_max_swap => max_in,
+        _ => amount_in_delta(sqrt_price_current, next_sqrt_price, liquidity, a_to_b)
+            .ok_or_else(|| anyhow!("Whirlpool swap input overflow"))?,
+    };
+    let amount_out = amount_out_delta(sqrt_price_current, next_sqrt_price, liquidity, a_to_b)
+        .ok_or_else(|| anyhow!("Whirlpool swap output overflow"))?;
+
+    let fee_amount = if is_max_swap {
+        let numerator = amount_in as u128 * fee_rate;
+        let denominator = FEE_RATE_MUL_VALUE - fee_rate;
+        numerator.div_ceil(denominator) as u64
+    } else {
+        // The whole remainder is consumed; what the price did not absorb is fee
+        amount_remaining - amount_in
+    };
+
+    Ok(SwapStep { amount_in, amount_out, fee_amount, next_sqrt_price })
+}
+
+/// Next initialized tick across the loaded arrays (`SwapTickSequence`).
+/// Returns the array index and tick; stops at the edge of the last array.
+fn next_initialized_tick(
+    tick_arrays: &[&TickArray],
+    start_array_index: usize,
+    tick_index: i32,
+    tick_spacing: i32,
+    a_to_b: bool,
+) -> Result<(usize, i32)> {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
+    let mut search_index = tick_index;
+    let mut array_index = start_array_index;
+
+    loop {
+        let array = tick_arrays.get(array_index)
+            .ok_or_else(|| anyhow!("Whirlpool swap ran past the loaded tick arrays"))?;
+
+        if let Some(next) = array.next_initialized_tick(search_index, tick_spacing, a_to_b)? {
+            return Ok((array_index, next));
+        }
+
+        if a_to_b && array.is_min_tick_array() {
+            return Ok((array_index, MIN_TICK_INDEX));
+        }
+        if !a_to_b && array.is_max_tick_array(tick_spacing) {
+            return Ok((array_index, MAX_TICK_INDEX));
+        }
+
+        if array_index + 1 == tick_arrays.len() {
+            let edge = if a_to_b {
+                array.start_tick_index
+            } else {
+                array.start_tick_index + (TICK_ARRAY_SIZE - 1) * tick_spacing
+            };
+            return Ok((array_index, edge));
+        }
+
+        search_index = if a_to_b {
+            array.start_tick_index - 1
+        } else {
+            array.start_tick_index + ticks_in_array - 1
+        };
+        array_index += 1;
+    }
+}
+
+/// Simulate an exact-input swap through `tick_arrays` (in swap order),
+/// crossing initialized ticks like the program's `swap_manager::swap`
+pub fn simulate_swap_exact_in(
+    whirlpool: &WhirlpoolInfo,
+    tick_arrays: &[&TickArray],
+    amount_in: u64,
+    a_to_b: bool,
+) -> Result<u64> {
+    if whirlpool.tick_spacing == 0 {
+        return Err(anyhow!("Whirlpool has zero tick spacing"));
+    }
+    let tick_spacing = whirlpool.tick_spacing as i32;
+    let sqrt_price_limit = if a_to_b { MIN_SQRT_PRICE_X64 } else { MAX_SQRT_PRICE_X64 };
+
+    let mut amount_remaining = amount_in;
+    let mut amount_out: u64 = 0;
+    let mut sqrt_price = whirlpool.sqrt_price;
+    let mut liquidity = whirlpool.liquidity;
+    let mut tick_index = whirlpool.tick_current_index;
+    let mut array_index = 0;
+
+    while amount_remaining > 0 && sqrt_price != sqrt_price_limit {
+        let (next_array_index, next_tick) =
+            next_initialized_tick(tick_arrays, array_index, tick_index, tick_spacing, a_to_b)?;
+
+        let next_tick_sqrt_price = sqrt_price_from_tick_index(next_tick);
+        let sqrt_price_target = if a_to_b {
+            next_tick_sqrt_price.max(sqrt_price_limit)
+        } else {
+            next_tick_sqrt_price.min(sqrt_price_limit)
+        };
+
+        let step = compute_swap_step(
+            amount_remaining,
+            whirlpool.fee_rate,
+            liquidity,
+            sqrt_price,
+            sqrt_price_target,
+            a_to_b,
+        )?;
+
+        amount_remaining = amount_remaining
+            .checked_sub(step.amount_in + step.fee_amount)
+            .ok_or_else(|| anyhow!("Whirlpool swap step consumed more than the remaining input"))?;
+        amount_out = amount_out
+            .checked_add(step.amount_out)
+            .ok_or_else(|| anyhow!("Whirlpool swap output overflow"))?;
+
+        if step.next_sqrt_price == next_tick_sqrt_price {
+            let tick = tick_arrays[next_array_index].tick(next_tick, tick_spacing);
+            if tick.initialized {
+                // Crossing downwards removes the tick's net liquidity
+                let delta = if a_to_b { -tick.liquidity_net } else { tick.liquidity_net };
+                liquidity = if delta < 0 {
+                    liquidity.checked_sub(delta.unsigned_abs())
+                } else {
+                    liquidity.checked_add(delta as u128)
+                }
+                .ok_or_else(|| anyhow!("Whirlpool liquidity overflow crossing tick {}", next_tick))?;
+            }
+            tick_index = if a_to_b { next_tick - 1 } else { next_tick };
+            array_index = next_array_index;
+        }
+
+        sqrt_price = step.next_sqrt_price;
+    }
+
+    if amount_remaining > 0 {
+        return Err(anyhow!("Whirlpool cannot fill {} input: price limit reached", amount_in));
+    }
+
+    Ok(amount_out)
+}
+
+pub struct WhirlpoolAdapter {
     program_id: Pubkey,
 }
 
-impl WhirlpoolClient {
+impl WhirlpoolAdapter {
     pub fn new(program_id: Pubkey) -> Self {
         Self { program_id }
     }
+
+    fn state<'a>(&self, pool: &'a PoolData) -> Result<&'a WhirlpoolPoolState> {
+        match &pool.venue {
+            VenueState::Whirlpool(state) => Ok(state),
+            _ => Err(anyhow!("Pool {} has no Whirlpool state", pool.pool_address)),
+        }
+    }
+}
+
+impl Default for WhirlpoolAdapter {
+    fn default() -> Self {
+        Self::new(WHIRLPOOL_PROGRAM_ID)
+    }
+}
+
+impl DexAdapter for WhirlpoolAdapter {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn dex_type(&self) -> DexType {
+        DexType::Whirlpool
+    }
+
+    fn mint_layout(&self) -> Option<MintLayout> {
+        Some(MintLayout {
+            data_size: Some(WHIRLPOOL_LEN as u64),
+            mint_a_offset: 101, // token_mint_a
+            mint_b_offset: 181, // token_mint_b
+        })
+    }
+
+    fn pool_init_logs(&self) -> &'static [&'static str] {
+        &["Program log: Instruction: InitializePool", "Program log: Instruction: InitializePoolV2"]
+    }
+
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Result<PoolData> {
+        let whirlpool = WhirlpoolInfo::decode(data)?;
+
+        debug!(
+            "Parsed Whirlpool: tick={}, liquidity={}, fee_rate={}",
+            whirlpool.tick_current_index, whirlpool.liquidity, whirlpool.fee_rate
+        );
+
+        // Reserves are filled from the vaults in `apply_dependent_accounts`
+        Ok(PoolData {
+            pool_address: *address,
+            token_a: whirlpool.token_mint_a,
+            token_b: whirlpool.token_mint_b,
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: whirlpool.fee_bps(),
+            dex_type: DexType::Whirlpool,
+            program_id: self.program_id,
+            venue: VenueState::Whirlpool(Box::new(WhirlpoolPoolState {
+                whirlpool,
+                tick_arrays: HashMap::new(),
+            })),
+            mints: Default::default(),
+            slot: 0,
+        })
+    }
+
+    fn dependent_accounts(&self, pool: &PoolData) -> Vec<Pubkey> {
+        let state = match self.state(pool) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut accounts = vec![state.whirlpool.token_vault_a, state.whirlpool.token_vault_b];
+        for a_to_b in [true, false] {
+            for address in state.swap_tick_array_addresses(&self.program_id, &pool.pool_address, a_to_b) {
+                if !accounts.contains(&address) {
+                    accounts.push(address);
+                }
+            }
+        }
+        accounts
+    }
+
+    fn apply_dependent_accounts(&self, pool: &mut PoolData, accounts: &HashMap<Pubkey, Account>) -> Result<()> {
+        let address = pool.pool_address;
+        let program_id = self.program_id;
+        let state = match &mut pool.venue {
+            VenueState::Whirlpool(state) => state,
+            _ => return Err(anyhow!("Pool {} has no Whirlpool state", address)),
+        };
+        let whirlpool = &state.whirlpool;
+
+        let vault_a = accounts.get(&whirlpool.token_vault_a)
+            .ok_or_else(|| anyhow!("Missing vault A {} for pool {}", whirlpool.token_vault_a, address))?;
+        let vault_b = accounts.get(&whirlpool.token_vault_b)
+            .ok_or_else(|| anyhow!("Missing vault B {} for pool {}", whirlpool.token_vault_b, address))?;
+        let reserve_a = token_account_amount(vault_a)?;
+        let reserve_b = token_account_amount(vault_b)?;
+
+        // Tick arrays that were never initialized do not exist on chain
+        let mut tick_arrays = HashMap::new();
+        for a_to_b in [true, false] {
+            for tick_array in state.swap_tick_array_addresses(&program_id, &address, a_to_b) {
+                if let Some(account) = accounts.get(&tick_array) {
+                    tick_arrays.insert(tick_array, TickArray::decode(&address, &account.data)?);
+                }
+            }
+        }
+        state.tick_arrays = tick_arrays;
+
+        pool.reserve_a = reserve_a;
+        pool.reserve_b = reserve_b;
+
+        debug!(
+            "Whirlpool {} vaults: ({}, {}), {} tick arrays loaded",
+            address, reserve_a, reserve_b, state.tick_arrays.len()
+        );
+
+        Ok(())
+    }
+
+    fn quote_exact_in(&self, pool: &PoolData, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        let state = self.state(pool)?;
+        let tick_arrays = state.swap_tick_arrays(&self.program_id, &pool.pool_address, a_to_b);
+        if tick_arrays.is_empty() {
+            return Err(anyhow!("No tick arrays loaded for Whirlpool {}", pool.pool_address));
+        }
+        simulate_swap_exact_in(&state.whirlpool, &tick_arrays, amount_in, a_to_b)
+    }
+
+    fn fee_model(&self, pool: &PoolData) -> FeeModel {
+        match self.state(pool) {
+            Ok(state) => state.whirlpool.fee_model(),
+            Err(_) => FeeModel::from_bps(pool.fee_bps),
+        }
+    }
+
+    fn spot_rate(&self, pool: &PoolData, a_to_b: bool) -> f64 {
+        let price = match self.state(pool) {
+            Ok(state) => state.whirlpool.price(),
+            Err(_) => return 0.0,
+        };
+        if a_to_b {
+            price
+        } else if price > 0.0 {
+            1.0 / price
+        } else {
+            0.0
+        }
+    }
+
+    fn swap_accounts(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let state = self.state(pool)?;
+        let whirlpool = &state.whirlpool;
+
+        // Arrays past the initialized range are replaced by the previous one,
+        // which the program accepts as long as the swap does not reach them
+        let mut tick_arrays = state.swap_tick_array_addresses(&self.program_id, &pool.pool_address, params.a_to_b);
+        for i in 1..tick_arrays.len() {
+            if !state.tick_arrays.contains_key(&tick_arrays[i]) {
+                tick_arrays[i] = tick_arrays[i - 1];
+            }
+        }
+
+        let (owner_account_a, owner_account_b) = if params.a_to_b {
+            (params.user_source, params.user_destination)
+        } else {
+            (params.user_destination, params.user_source)
+        };
+
+        let oracle = oracle_address(&self.program_id, &pool.pool_address);
+
+        if pool.mints.has_token_2022() {
+            return Ok(vec![
+                AccountMeta::new_readonly(pool.mints.a.token_program, false),
+                AccountMeta::new_readonly(pool.mints.b.token_program, false),
+                AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+                AccountMeta::new_readonly(params.user, true),
+                AccountMeta::new(pool.pool_address, false),
+                AccountMeta::new_readonly(pool.token_a, false),
+                AccountMeta::new_readonly(pool.token_b, false),
+                AccountMeta::new(owner_account_a, false),
+                AccountMeta::new(whirlpool.token_vault_a, false),
+                AccountMeta::new(owner_account_b, false),
+                AccountMeta::new(whirlpool.token_vault_b, false),
+                AccountMeta::new(tick_arrays[0], false),
+                AccountMeta::new(tick_arrays[1], false),
+                AccountMeta::new(tick_arrays[2], false),
+                AccountMeta::new(oracle, false),
+            ]);
+        }
+
+        Ok(vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(params.user, true),
+            AccountMeta::new(pool.pool_address, false),
+            AccountMeta::new(owner_account_a, false),
+            AccountMeta::new(whirlpool.token_vault_a, false),
+            AccountMeta::new(owner_account_b, false),
+            AccountMeta::new(whirlpool.token_vault_b, false),
+            AccountMeta::new(tick_arrays[0], false),
+            AccountMeta::new(tick_arrays[1], false),
+            AccountMeta::new(tick_arrays[2], false),
+            AccountMeta::new(oracle, false),
+        ])
+    }
+
+    fn swap_data(&self, pool: &PoolData, params: &SwapParams) -> Result<Vec<u8>> {
+        let sqrt_price_limit = if params.a_to_b { MIN_SQRT_PRICE_X64 } else { MAX_SQRT_PRICE_X64 };
+        let swap_v2 = pool.mints.has_token_2022();
+
+        let mut data = if swap_v2 { SWAP_V2_DISCRIMINATOR.to_vec() } else { SWAP_DISCRIMINATOR.to_vec() };
+        data.extend_from_slice(&params.amount_in.to_le_bytes());
+        data.extend_from_slice(&params.minimum_out.to_le_bytes()); // other_amount_threshold
+        data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+        data.push(1); // amount_specified_is_input
+        data.push(params.a_to_b as u8);
+        if swap_v2 {
+            data.push(0); // remaining_accounts_info: None (no transfer hooks)
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK_SPACING: u16 = 64;
+
+    fn tick_array(start_tick_index: i32, initialized: &[(i32, i128)]) -> TickArray {
+        let mut ticks = vec![Tick::default(); TICK_ARRAY_SIZE as usize];
+        for (tick_index, liquidity_net) in initialized {
+            let offset = ((tick_index - start_tick_index) / TICK_SPACING as i32) as usize;
+            ticks[offset] = Tick { initialized: true, liquidity_net: *liquidity_net };
+        }
+        TickArray { start_tick_index, ticks }
+    }
+
+    fn whirlpool_at(tick: i32, liquidity: u128) -> WhirlpoolInfo {
+        WhirlpoolInfo {
+            tick_spacing: TICK_SPACING,
+            fee_rate: 3000,
+            liquidity,
+            sqrt_price: sqrt_price_from_tick_index(tick),
+            tick_current_index: tick,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sqrt_price_bounds() {
+        assert_eq!(sqrt_price_from_tick_index(0), 1u128 << 64);
+        assert_eq!(sqrt_price_from_tick_index(MIN_TICK_INDEX), MIN_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_from_tick_index(MAX_TICK_INDEX), MAX_SQRT_PRICE_X64);
+
+        for tick in [-100_000, -1, 1, 64, 12_345, 100_000] {
+            let expected = 1.0001f64.powf(tick as f64 / 2.0);
+            let actual = sqrt_price_from_tick_index(tick) as f64 / 2f64.powi(64);
+            assert!((actual / expected - 1.0).abs() < 1e-12, "tick {}", tick);
+        }
+    }
+
+    #[test]
+    fn test_tick_array_start_index() {
+        assert_eq!(tick_array_start_index(0, 64), 0);
+        assert_eq!(tick_array_start_index(5631, 64), 0);
+        assert_eq!(tick_array_start_index(5632, 64), 5632);
+        assert_eq!(tick_array_start_index(-1, 64), -5632);
+        assert_eq!(tick_array_start_index(-5633, 64), -11264);
+    }
+
+    #[test]
+    fn test_swap_tick_array_addresses_follow_direction() {
+        let pool = Pubkey::new_unique();
+        let state = WhirlpoolPoolState {
+            whirlpool: whirlpool_at(100, 0),
+            tick_arrays: HashMap::new(),
+        };
+        let pda = |start| tick_array_address(&WHIRLPOOL_PROGRAM_ID, &pool, start);
+
+        assert_eq!(
+            state.swap_tick_array_addresses(&WHIRLPOOL_PROGRAM_ID, &pool, true),
+            vec![pda(0), pda(-5632), pda(-11264)]
+        );
+        assert_eq!(
+            state.swap_tick_array_addresses(&WHIRLPOOL_PROGRAM_ID, &pool, false),
+            vec![pda(0), pda(5632), pda(11264)]
+        );
+    }
+
+    #[test]
+    fn test_swap_within_one_range_matches_closed_form() {
+        let liquidity = 1_000_000_000_000u128;
+        let whirlpool = whirlpool_at(0, liquidity);
+        let array = tick_array(0, &[]);
+        let below = tick_array(-5632, &[]);
+
+        let amount_in = 1_000_000u64;
+        let out = simulate_swap_exact_in(&whirlpool, &[&array, &below], amount_in, true).unwrap();
+
+        // At price 1 with deep liquidity the output is the input minus the fee
+        // minus a tiny price impact
+        let after_fee = amount_in * 997 / 1000;
+        assert!(out < after_fee);
+        assert!(out > after_fee - 10);
+    }
+
+    #[test]
+    fn test_crossing_tick_reduces_liquidity() {
+        let liquidity = 1_000_000_000u128;
+        let whirlpool = whirlpool_at(10, liquidity);
+        let array = tick_array(0, &[]);
+        let below = tick_array(-5632, &[]);
+        // Half the liquidity ends at tick -64 (net added when crossing upwards)
+        let thin_below = tick_array(-5632, &[(-64, (liquidity / 2) as i128)]);
+
+        let amount_in = 50_000_000u64;
+        let deep = simulate_swap_exact_in(&whirlpool, &[&array, &below], amount_in, true).unwrap();
+        let thin = simulate_swap_exact_in(&whirlpool, &[&array, &thin_below], amount_in, true).unwrap();
+        assert!(thin < deep);
+    }
+
+    #[test]
+    fn test_swap_past_loaded_arrays_fails() {
+        let whirlpool = whirlpool_at(0, 1_000);
+        let array = tick_array(0, &[]);
+        assert!(simulate_swap_exact_in(&whirlpool, &[&array], 10_000_000, true).is_err());
+    }
+
+    #[test]
+    fn test_whirlpool_decode() {
+        let mut data = vec![0u8; WHIRLPOOL_LEN];
+        data[..8].copy_from_slice(&WHIRLPOOL_DISCRIMINATOR);
+        data[41..43].copy_from_slice(&64u16.to_le_bytes());
+        data[45..47].copy_from_slice(&3000u16.to_le_bytes());
+        data[49..65].copy_from_slice(&123_456u128.to_le_bytes());
+        data[65..81].copy_from_slice(&(1u128 << 64).to_le_bytes());
+        data[81..85].copy_from_slice(&(-70i32).to_le_bytes());
+        let mint_a = Pubkey::new_unique();
+        let mint_b = Pubkey::new_unique();
+        data[101..133].copy_from_slice(mint_a.as_ref());
+        data[181..213].copy_from_slice(mint_b.as_ref());
+
+        let decoded = WhirlpoolInfo::decode(&data).unwrap();
+        assert_eq!(decoded.tick_spacing, 64);
+        assert_eq!(decoded.fee_rate, 3000);
+        assert_eq!(decoded.fee_bps(), 30);
+        assert_eq!(decoded.liquidity, 123_456);
+        assert_eq!(decoded.tick_current_index, -70);
+        assert_eq!(decoded.token_mint_a, mint_a);
+        assert_eq!(decoded.token_mint_b, mint_b);
+        assert!((decoded.price() - 1.0).abs() < 1e-12);
+
+        data[0] = 0;
+        assert!(WhirlpoolInfo::decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let adapter = WhirlpoolAdapter::default();
+        let pool_address = Pubkey::new_unique();
+        let whirlpool = WhirlpoolInfo {
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            ..whirlpool_at(100, 0)
+        };
+        let state = WhirlpoolPoolState { whirlpool: whirlpool.clone(), tick_arrays: HashMap::new() };
+        let addresses = state.swap_tick_array_addresses(&WHIRLPOOL_PROGRAM_ID, &pool_address, false);
+        let mut state = state;
+        state.tick_arrays.insert(addresses[0], tick_array(0, &[]));
+        state.tick_arrays.insert(addresses[1], tick_array(5632, &[]));
+
+        let pool = PoolData {
+            pool_address,
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: 30,
+            dex_type: DexType::Whirlpool,
+            program_id: WHIRLPOOL_PROGRAM_ID,
+            venue: VenueState::Whirlpool(Box::new(state)),
+            mints: Default::default(),
+            slot: 0,
+        };
+        let params = SwapParams {
+            user: Pubkey::new_unique(),
+            user_source: Pubkey::new_unique(),
+            user_destination: Pubkey::new_unique(),
+            amount_in: 1_000,
+            minimum_out: 990,
+            a_to_b: false,
+        };
+
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(keys, vec![
+            spl_token::id(),
+            params.user,
+            pool_address,
+            params.user_destination, // b->a: the user receives token A
+            whirlpool.token_vault_a,
+            params.user_source,
+            whirlpool.token_vault_b,
+            addresses[0],
+            addresses[1],
+            addresses[1], // third array is not initialized
+            oracle_address(&WHIRLPOOL_PROGRAM_ID, &pool_address),
+        ]);
+        assert!(ix.accounts[1].is_signer);
+
+        assert_eq!(ix.data.len(), 42);
+        assert_eq!(&ix.data[..8], &SWAP_DISCRIMINATOR);
+        assert_eq!(u128::from_le_bytes(ix.data[24..40].try_into().unwrap()), MAX_SQRT_PRICE_X64);
+        assert_eq!(ix.data[40], 1);
+        assert_eq!(ix.data[41], 0);
+
+        // A Token-2022 mint switches to swap_v2 with per-mint token programs
+        let mut pool = pool;
+        pool.mints.b.token_program = crate::dex::adapter::TOKEN_2022_PROGRAM_ID;
+        let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(&keys[..7], &[
+            spl_token::id(),
+            crate::dex::adapter::TOKEN_2022_PROGRAM_ID,
+            MEMO_PROGRAM_ID,
+            params.user,
+            pool_address,
+            pool.token_a,
+            pool.token_b,
+        ]);
+        assert_eq!(keys.len(), 15);
+        assert_eq!(&ix.data[..8], &SWAP_V2_DISCRIMINATOR);
+        assert_eq!(ix.data.len(), 43);
+    }
 }
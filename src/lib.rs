@@ -2,7 +2,7 @@
 //
 // This library provides components for building high-performance arbitrage bots
 // on Solana, including:
-// - Multi-DEX pool data fetching (Raydium, Orca, Meteora, Pump.fun, Phoenix)
+// - Multi-DEX pool data fetching (Raydium, Orca, Meteora, Pump.fun, Phoenix, OpenBook v2)
 // - Triangular arbitrage detection using Bellman-Ford algorithm
 // - Real-time WebSocket pool monitoring
 // - Transaction execution and retry logic
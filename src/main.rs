@@ -10,9 +10,13 @@ mod utils;
 
 use anyhow::{Context, Result};
 use chain::{MarketDataFetcher, PriceMonitor, TokenFetcher, TransactionExecutor};
-use chain::token_fetch::DexType;
+use chain::opportunity_guard::SharedInFlight;
+use chain::pool_discovery::PoolDiscovery;
+use chain::reliability::ReliabilityTracker;
 use config::Config;
-use data::TradeStorage;
+use data::{TradeHop, TradeStorage};
+use dex::adapter::{DexRegistry, TOKEN_2022_PROGRAM_ID};
+use dex::triangular_arb::DexType;
 use meteora::{MeteoraDAMMClient, MeteoraVaultClient};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
@@ -100,13 +104,17 @@ async fn main() -> Result<()> {
     // Step 5: Initialize core components
     // ========================================================================
     
+    // Venue adapters for the configured program ids, shared by every
+    // component that decodes pools
+    let dex_registry = Arc::new(DexRegistry::from_config(&config.dex));
+
     // Initialize TokenFetcher with caching
     let token_fetcher = Arc::new(TokenFetcher::new(
         Arc::clone(&rpc_client),
         Duration::from_secs(config.cache.ttl_seconds),
         config.cache.max_size,
         config.bot.max_retries,
-    ));
+    ).with_registry(Arc::clone(&dex_registry)));
     info!("✅ Token fetcher initialized");
     info!("   Cache TTL: {}s, Max size: {}", config.cache.ttl_seconds, config.cache.max_size);
     info!("   Pool cache: {}, Account cache: {}", 
@@ -148,6 +156,16 @@ async fn main() -> Result<()> {
     let trade_storage = Arc::new(TradeStorage::new(storage_path));
     info!("✅ Trade storage initialized: {}", storage_path);
 
+    // Venue and pool reliability learned from the recorded trade history
+    let trade_history = trade_storage.load_all_records().await.unwrap_or_else(|e| {
+        warn!("Failed to load trade history: {}", e);
+        Vec::new()
+    });
+    let reliability = Arc::new(std::sync::RwLock::new(
+        ReliabilityTracker::from_records(config.reliability.clone(), &trade_history),
+    ));
+    info!("✅ Reliability tracker seeded from {} trade records", trade_history.len());
+
     // ========================================================================
     // Step 5.5: Initialize TransactionExecutor with execution mode and storage
     // ========================================================================
@@ -157,7 +175,7 @@ async fn main() -> Result<()> {
     let _transaction_executor = Arc::new(TransactionExecutor::new(
         Arc::clone(&rpc_client),
         Arc::clone(&trade_storage),
-    ));
+    ).with_reliability(Arc::clone(&reliability)));
     info!("✅ Transaction executor initialized with trade storage");
     
     // Log execution mode prominently for safety
@@ -187,45 +205,42 @@ async fn main() -> Result<()> {
     }
 
     // ========================================================================
-    // Step 6-8: Process all configured mints and their pools
+    // Step 6-8: Discover pools for the configured mints
     // ========================================================================
-    info!("📊 Processing mint configurations and pool data...");
-    
-    let mut all_pools_to_monitor: Vec<(Pubkey, DexType)> = Vec::new();
-    
-    // Loop through all configured mints
+    info!("📊 Building pool universe for {} mints...", config.mints.len());
     for (idx, mint_config) in config.mints.iter().enumerate() {
-        info!("🪙 Processing mint {}/{}: {} ({})", 
+        info!("🪙 Mint {}/{}: {} ({})", 
             idx + 1, config.mints.len(), mint_config.symbol, mint_config.address);
-        debug!("   Decimals: {}, Is quote: {}, Pools: {}", 
+        debug!("   Decimals: {}, Is quote: {}, Configured pools: {}", 
             mint_config.decimals, mint_config.is_quote, mint_config.pools.len());
-        
-        if mint_config.pools.is_empty() {
-            warn!("   ⚠️  No pools configured for {}", mint_config.symbol);
-            continue;
-        }
+    }
 
-        // For each pool associated with this mint
-        for pool_pubkey in &mint_config.pools {
-            // TODO: Determine DEX type from pool address or configuration
-            // For now, we'll use a placeholder - you should implement proper DEX detection
-            let dex_type = DexType::Raydium; // Placeholder
-            
-            all_pools_to_monitor.push((*pool_pubkey, dex_type.clone()));
-            debug!("   Added pool: {} ({:?})", pool_pubkey, dex_type);
-        }
+    // Pools are classified by the program that owns their account
+    let pool_discovery = PoolDiscovery::new(
+        Arc::clone(&rpc_client),
+        Arc::clone(&dex_registry),
+    );
+    let discovered_pools = pool_discovery
+        .pool_universe(&config)
+        .await
+        .context("Pool discovery failed")?;
+
+    let mut all_pools_to_monitor: Vec<(Pubkey, DexType)> = Vec::new();
+    for pool in &discovered_pools {
+        debug!("   Added pool: {} ({:?})", pool.address, pool.dex_type);
+        all_pools_to_monitor.push((pool.address, pool.dex_type.clone()));
     }
 
     if all_pools_to_monitor.is_empty() {
-        warn!("⚠️  No pools configured for monitoring!");
-        warn!("📝 Please configure pools in your .env file using MINT_X_POOLS");
+        warn!("⚠️  No pools found for monitoring!");
+        warn!("📝 Enable discovery (POOL_DISCOVERY_ENABLED=true) or list pools with MINT_X_POOLS");
         info!("💡 Example configuration:");
         info!("   MINT_1_ADDRESS=So11111111111111111111111111111111111111112");
         info!("   MINT_1_SYMBOL=SOL");
         info!("   MINT_1_POOLS=POOL_ADDRESS_1,POOL_ADDRESS_2");
         
         // Keep bot running in demo mode
-        info!("🛑 Running in demo mode (no pools configured)");
+        info!("🛑 Running in demo mode (no pools found)");
         tokio::signal::ctrl_c().await?;
         info!("👋 Shutting down...");
         return Ok(());
@@ -250,14 +265,15 @@ async fn main() -> Result<()> {
                 
                 // Step 9: Print results and potential opportunities
                 for (i, opp) in opportunities.iter().enumerate() {
-                    info!("   Opportunity #{}: {} -> {}", i + 1, opp.token_a_mint, opp.token_b_mint);
-                    info!("      Buy:  {:?} @ {:.8}", opp.buy_dex, opp.buy_price);
-                    info!("      Sell: {:?} @ {:.8}", opp.sell_dex, opp.sell_price);
-                    info!("      Gross Profit: {} bps ({:.2}%)", opp.gross_profit_bps, opp.gross_profit_bps as f64 / 100.0);
-                    info!("      Net Profit: {} bps ({:.2}%)", opp.net_profit_bps, opp.net_profit_bps as f64 / 100.0);
-                    info!("      Total Fees: {} bps, Slippage: {} bps", opp.total_fees_bps, opp.estimated_slippage_bps);
-                    info!("      Recommended amount: {} lamports", opp.recommended_amount);
-                    info!("      Risk: {:?}", opp.execution_risk);
+                    info!("   Opportunity #{}: {}", i + 1, opp.route());
+                    for step in &opp.cycle.path {
+                        info!("      {:?} @ {:.8}", step.dex, step.rate);
+                    }
+                    info!("      Gross Profit: {} bps ({:.2}%)", opp.gross_profit_bps(), opp.gross_profit_bps() as f64 / 100.0);
+                    info!("      Net Profit: {} bps ({:.2}%)", opp.expected_profit_bps, opp.expected_profit_bps as f64 / 100.0);
+                    info!("      Total Fees: {} bps, Slippage: {} bps", opp.total_fee_bps(), opp.estimated_slippage_bps());
+                    info!("      Recommended amount: {}", opp.optimal_input_amount);
+                    info!("      Risk: {:?}", opp.risk_level);
                 }
             }
         }
@@ -399,20 +415,20 @@ fn load_keypair(wallet_config: &config::WalletConfig) -> Result<Keypair> {
     }
 }
 
-/// Execute arbitrage opportunity with configurable execution mode
-/// 
-/// Feature: Main Loop Execution Switch
-/// 
-/// CoT: After finding an arbitrage opportunity and building the transaction,
-/// execute it using the unified executor with mode switching based on configuration.
-/// 
-/// DECISION: Use is_simulation_mode from Config (Chosen) vs CLI argument
-/// Rationale: Config file easier to manage, less prone to human error than CLI flag
-/// 
-/// OPTIMIZE: Use tracing macro to log execution mode for each transaction
-/// 
-/// Alternative: Use separate binary for simulation and live execution to enforce
-/// separation at the build level (more complex but stronger safety guarantee)
+// Execute arbitrage opportunity with configurable execution mode
+// 
+// Feature: Main Loop Execution Switch
+// 
+// CoT: After finding an arbitrage opportunity and building the transaction,
+// execute it using the unified executor with mode switching based on configuration.
+// 
+// DECISION: Use is_simulation_mode from Config (Chosen) vs CLI argument
+// Rationale: Config file easier to manage, less prone to human error than CLI flag
+// 
+// OPTIMIZE: Use tracing macro to log execution mode for each transaction
+// 
+// Alternative: Use separate binary for simulation and live execution to enforce
+// separation at the build level (more complex but stronger safety guarantee)
 
 // ============================================================================
 // Feature: Initial Balance Snapshot
@@ -446,16 +462,19 @@ async fn get_initial_balances(
     
     let mut balances = HashMap::new();
     
-    // Get all token accounts owned by the wallet
-    let token_accounts = rpc_client
-        .get_token_accounts_by_owner(
-            wallet_address,
-            solana_client::rpc_request::TokenAccountsFilter::ProgramId(
-                spl_token::id(),
-            ),
-        )
-        .await
-        .context("Failed to fetch wallet token accounts")?;
+    // Get all token accounts owned by the wallet, under both token programs
+    let mut token_accounts = Vec::new();
+    for token_program in [spl_token::id(), TOKEN_2022_PROGRAM_ID] {
+        token_accounts.extend(
+            rpc_client
+                .get_token_accounts_by_owner(
+                    wallet_address,
+                    solana_client::rpc_request::TokenAccountsFilter::ProgramId(token_program),
+                )
+                .await
+                .with_context(|| format!("Failed to fetch wallet token accounts for {}", token_program))?,
+        );
+    }
     
     debug!("   Found {} token accounts for wallet", token_accounts.len());
     
@@ -510,29 +529,29 @@ async fn get_initial_balances(
 
 #[allow(dead_code)]
 async fn execute_arbitrage(
-    opportunity: &chain::token_price::ArbitrageOpportunity,
+    opportunity: &chain::detector::ArbitrageOpportunity,
     rpc_client: Arc<RpcClient>,
     payer: Arc<Keypair>,
     config: &Config,
     executor: Arc<TransactionExecutor>,
 ) -> Result<String> {
     info!("🎯 Executing arbitrage opportunity:");
-    info!("   Buy on {:?} @ {}", opportunity.buy_dex, opportunity.buy_price);
-    info!("   Sell on {:?} @ {}", opportunity.sell_dex, opportunity.sell_price);
-    info!("   Expected gross profit: {} bps", opportunity.gross_profit_bps);
-    info!("   Expected net profit: {} bps", opportunity.net_profit_bps);
+    info!("   Route: {}", opportunity.route());
+    info!("   Expected gross profit: {} bps", opportunity.gross_profit_bps());
+    info!("   Expected net profit: {} bps", opportunity.expected_profit_bps);
 
     // ========================================================================
     // Step 0: Capture Initial Balance Snapshot
     // Feature: Initial Balance Snapshot - capture token balances before execution
     // This enables post-execution profit validation by comparing actual vs expected
     // ========================================================================
-    let token_mints = vec![
-        opportunity.token_a_mint,
-        opportunity.token_b_mint,
-        // Add wrapped SOL (WSOL) mint for native SOL tracking in token accounts
-        spl_token::native_mint::id(),
-    ];
+    let mut token_mints: Vec<Pubkey> = opportunity.cycle.path.iter()
+        .map(|step| step.from_token)
+        .collect();
+    // Add wrapped SOL (WSOL) mint for native SOL tracking in token accounts
+    if !token_mints.contains(&spl_token::native_mint::id()) {
+        token_mints.push(spl_token::native_mint::id());
+    }
     
     info!("📸 Capturing pre-execution balance snapshot...");
     let initial_balances = get_initial_balances(
@@ -557,8 +576,7 @@ async fn execute_arbitrage(
 
     // TODO: Add actual swap instructions here
     // Example:
-    // 1. Buy instruction (buy_dex) - swap SOL/USDC to target token
-    // 2. Sell instruction (sell_dex) - swap target token back to SOL/USDC
+    // one swap instruction per step of `opportunity.cycle.path`
     
     info!("📝 Building arbitrage transaction with {} compute units", 
         config.execution.compute_unit_limit);
@@ -577,12 +595,11 @@ async fn execute_arbitrage(
     
     // Use profit token from opportunity (determined by token priority: USDC > USDT > WSOL)
     // This ensures profits are always denominated in the most stable quote currency
-    let profit_token_mint = opportunity.profit_token;
+    let profit_token_mint = opportunity.profit_token();
     
-    // Calculate expected profit amount in smallest token units
-    // net_profit_bps is in basis points (1 bps = 0.01%)
-    // This is a simplified calculation - real implementation would need token decimals
-    let expected_profit_amount = (opportunity.net_profit_bps as u64) * 100; // Placeholder
+    // Expected profit in the profit token's smallest units
+    let expected_profit_amount = opportunity.expected_output_amount
+        .saturating_sub(opportunity.optimal_input_amount);
     
     let result = executor
         .execute_arbitrage(
@@ -591,6 +608,7 @@ async fn execute_arbitrage(
             config.bot.is_simulation_mode,
             &profit_token_mint,
             expected_profit_amount,
+            &TradeHop::route_of(&opportunity.cycle),
         )
         .await?;
 
@@ -654,7 +672,7 @@ async fn execute_arbitrage(
                 info!("🔍 Validating actual profit realization...");
                 
                 // Calculate expected profit percentage from opportunity
-                let expected_profit_pct = opportunity.net_profit_bps as f64 / 100.0;
+                let expected_profit_pct = opportunity.expected_profit_bps as f64 / 100.0;
                 
                 match executor.validate_profit(
                     &exec_result.signature,
@@ -744,17 +762,26 @@ async fn execute_arbitrage(
 /// This maximizes throughput when multiple profitable trades are found simultaneously
 #[allow(dead_code)]
 async fn execute_arbitrage_batch(
-    opportunities: Vec<chain::token_price::ArbitrageOpportunity>,
+    opportunities: Vec<chain::detector::ArbitrageOpportunity>,
     rpc_client: Arc<RpcClient>,
     payer: Arc<Keypair>,
     config: Arc<Config>,
     executor: Arc<TransactionExecutor>,
+    in_flight: SharedInFlight,
 ) -> Vec<Result<String>> {
     info!("🚀 Executing {} arbitrage opportunities concurrently", opportunities.len());
     
     let mut handles = Vec::new();
 
     for (idx, opportunity) in opportunities.into_iter().enumerate() {
+        // Routes sharing a pool with one already executing would trade stale state
+        let key = opportunity.key();
+        if !in_flight.lock().is_ok_and(|mut registry| registry.try_claim(&key)) {
+            warn!("   [{}] ⏳ Route shares a pool with a pending transaction, skipping", idx);
+            continue;
+        }
+
+        let in_flight = Arc::clone(&in_flight);
         let rpc_client = Arc::clone(&rpc_client);
         let payer = Arc::clone(&payer);
         let config = Arc::clone(&config);
@@ -764,6 +791,9 @@ async fn execute_arbitrage_batch(
         let handle = tokio::spawn(async move {
             info!("   [{}] Starting concurrent execution", idx);
             let result = execute_arbitrage(&opportunity, rpc_client, payer, &config, executor).await;
+            if let Ok(mut registry) = in_flight.lock() {
+                registry.release(&key);
+            }
             match &result {
                 Ok(sig) => info!("   [{}] ✅ Success: {}", idx, sig),
                 Err(e) => error!("   [{}] ❌ Failed: {}", idx, e),
@@ -88,7 +88,7 @@ fn calculate_metrics(records: &[TradeRecord]) -> PerformanceMetrics {
 
     // Get top 3 failures
     let mut top_failures: Vec<(String, usize)> = failure_counts.into_iter().collect();
-    top_failures.sort_by(|a, b| b.1.cmp(&a.1));
+    top_failures.sort_by_key(|failure| std::cmp::Reverse(failure.1));
     top_failures.truncate(3);
 
     PerformanceMetrics {
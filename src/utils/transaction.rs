@@ -238,11 +238,9 @@ impl MultiRpcSender {
     ) -> Result<Signature> {
         let results = self.send_transaction_multiple(transaction).await?;
 
-        for result in results {
-            if let Ok(sig) = result {
-                info!("Transaction confirmed: {}", sig);
-                return Ok(sig);
-            }
+        if let Some(sig) = results.into_iter().flatten().next() {
+            info!("Transaction confirmed: {}", sig);
+            return Ok(sig);
         }
 
         Err(anyhow::anyhow!("All RPC submissions failed"))
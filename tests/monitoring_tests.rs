@@ -11,7 +11,7 @@
 // Run with:
 //   cargo test --test monitoring_tests -- --nocapture --test-threads=1
 
-use anyhow::{Result, Context};
+use anyhow::Result;
 use serial_test::serial;
 use std::sync::{Arc, atomic::{AtomicU64, AtomicBool, Ordering}};
 use std::sync::Mutex;
@@ -148,24 +148,24 @@ impl MockMetricsCollector {
         let mut output = String::new();
         
         // Counters
-        output.push_str(&format!("# TYPE opportunities_detected counter\n"));
+        output.push_str("# TYPE opportunities_detected counter\n");
         output.push_str(&format!("opportunities_detected {}\n", self.get_counter("opportunities_detected")));
         
-        output.push_str(&format!("# TYPE transactions_sent counter\n"));
+        output.push_str("# TYPE transactions_sent counter\n");
         output.push_str(&format!("transactions_sent {}\n", self.get_counter("transactions_sent")));
         
-        output.push_str(&format!("# TYPE transactions_failed counter\n"));
+        output.push_str("# TYPE transactions_failed counter\n");
         output.push_str(&format!("transactions_failed {}\n", self.get_counter("transactions_failed")));
         
-        output.push_str(&format!("# TYPE circuit_breaker_triggered counter\n"));
+        output.push_str("# TYPE circuit_breaker_triggered counter\n");
         output.push_str(&format!("circuit_breaker_triggered {}\n", self.get_counter("circuit_breaker_triggered")));
         
         // Histograms
-        output.push_str(&format!("# TYPE detection_latency_ms histogram\n"));
+        output.push_str("# TYPE detection_latency_ms histogram\n");
         output.push_str(&format!("detection_latency_ms_count {}\n", self.get_histogram_count("detection_latency_ms")));
         output.push_str(&format!("detection_latency_ms_sum {}\n", self.get_histogram_sum("detection_latency_ms")));
         
-        output.push_str(&format!("# TYPE profit_per_trade_sol histogram\n"));
+        output.push_str("# TYPE profit_per_trade_sol histogram\n");
         output.push_str(&format!("profit_per_trade_sol_count {}\n", self.get_histogram_count("profit_per_trade_sol")));
         output.push_str(&format!("profit_per_trade_sol_sum {}\n", self.get_histogram_sum("profit_per_trade_sol")));
         
@@ -310,10 +310,8 @@ impl CircuitBreaker {
         failures.push(now);
         
         // Check if threshold exceeded
-        if failures.len() >= self.failure_threshold as usize {
-            if !self.is_open.load(Ordering::SeqCst) {
-                self.trigger();
-            }
+        if failures.len() >= self.failure_threshold as usize && !self.is_open.load(Ordering::SeqCst) {
+            self.trigger();
         }
     }
     
@@ -371,7 +369,7 @@ async fn test_metrics_collection() -> Result<()> {
     println!("========\n");
     
     let metrics = MockMetricsCollector::new();
-    let alerting = MockAlerting::new();
+    let _alerting = MockAlerting::new();
     
     println!("✅ Created metrics collector");
     println!("✅ Created alerting system\n");
@@ -1,25 +1,26 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_mev_bot::{
-    arbitrage::{ArbitrageDetector, ArbitrageOpportunity},
-    chain::{TransactionBuilder, TransactionSender},
+    chain::detector::{ArbitrageDetector, ArbitrageOpportunity},
+    chain::pool_discovery::PoolDiscovery,
     config::Config,
-    dex::DexIntegrator,
-    utils::metrics::MetricsCollector,
-};
-use solana_sdk::{
-    pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    dex::adapter::DexRegistry,
+    dex::pool_fetcher::PoolDataFetcher,
+    dex::triangular_arb::{ArbitrageGraph, SharedArbitrageGraph},
 };
+use solana_sdk::pubkey::Pubkey;
 use std::{
     sync::{
-        atomic::{AtomicBool, AtomicU64, Ordering},
+        atomic::{AtomicU64, Ordering},
         Arc, Mutex,
     },
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use tokio::sync::mpsc;
 use tokio::time::sleep;
 
 /// Paper trading results for a single simulated trade
 #[derive(Debug, Clone)]
+#[allow(dead_code)] // Kept whole for inspecting individual trades
 struct PaperTrade {
     timestamp: SystemTime,
     opportunity: ArbitrageOpportunity,
@@ -290,38 +291,23 @@ impl PaperTradingStats {
     }
 }
 
-/// Paper trading simulator
+/// Paper trading simulator: the live detector runs over a graph refreshed
+/// from chain, and every opportunity it emits is recorded as a simulated trade
 struct PaperTradingSimulator {
     config: Config,
-    detector: Arc<ArbitrageDetector>,
-    dex_integrator: Arc<DexIntegrator>,
-    transaction_builder: Arc<TransactionBuilder>,
     stats: Arc<Mutex<PaperTradingStats>>,
     trades: Arc<Mutex<Vec<PaperTrade>>>,
-    running: Arc<AtomicBool>,
     opportunities_seen: Arc<AtomicU64>,
 }
 
 impl PaperTradingSimulator {
     fn new(config: Config) -> Self {
-        let dex_integrator = Arc::new(DexIntegrator::new(config.clone()));
-        let detector = Arc::new(ArbitrageDetector::new(config.clone(), dex_integrator.clone()));
-        let keypair = Keypair::new(); // Dummy keypair for paper trading
-        let transaction_builder = Arc::new(TransactionBuilder::new(
-            config.clone(),
-            keypair.pubkey(),
-        ));
-
         let starting_balance = 100.0; // Simulated starting balance: 100 SOL
 
         Self {
             config,
-            detector,
-            dex_integrator,
-            transaction_builder,
             stats: Arc::new(Mutex::new(PaperTradingStats::new(starting_balance))),
             trades: Arc::new(Mutex::new(Vec::new())),
-            running: Arc::new(AtomicBool::new(true)),
             opportunities_seen: Arc::new(AtomicU64::new(0)),
         }
     }
@@ -335,13 +321,37 @@ impl PaperTradingSimulator {
         println!("  Mode:                Paper Trading (No Real Transactions)");
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
+        let rpc_client = Arc::new(RpcClient::new(self.config.rpc.url.clone()));
+        let registry = Arc::new(DexRegistry::from_config(&self.config.dex));
+        let pool_fetcher = PoolDataFetcher::new(vec![rpc_client.clone()], self.config.cache.ttl_seconds * 1000)
+            .with_registry(registry.clone());
+        let graph: SharedArbitrageGraph = Arc::new(std::sync::RwLock::new(
+            ArbitrageGraph::new().with_staleness(self.config.staleness.clone()),
+        ));
+
+        // The detector runs exactly as in the bot and reports on its channel
+        let (opportunity_tx, mut opportunity_rx) = mpsc::unbounded_channel();
+        let detector = Arc::new(ArbitrageDetector::from_config(graph.clone(), &self.config, opportunity_tx));
+        let (update_tx, update_rx) = mpsc::unbounded_channel();
+        let detection_handle = tokio::spawn(async move {
+            detector.run_detection_loop(update_rx).await;
+        });
+
         let start_time = Instant::now();
         let mut last_status_update = Instant::now();
 
         // Fetch initial pool data
         println!("📊 Fetching pool data from DEXs...");
-        let pools = self.dex_integrator.fetch_all_pools().await?;
-        println!("✓ Loaded {} pools", pools.len());
+        let pools: Vec<Pubkey> = PoolDiscovery::new(rpc_client, registry.clone())
+            .pool_universe(&self.config)
+            .await?
+            .into_iter()
+            .map(|pool| pool.address)
+            .collect();
+        let loaded = Self::refresh_graph(&pool_fetcher, &registry, &graph, &pools).await?;
+        update_tx.send(())?;
+        println!("✓ Loaded {} pools", loaded);
+        let mut last_refresh = Instant::now();
 
         loop {
             // Check termination conditions
@@ -373,24 +383,27 @@ impl PaperTradingSimulator {
                 last_status_update = Instant::now();
             }
 
-            // Detect arbitrage opportunities
-            if let Some(opportunity) = self.detector.detect_arbitrage(&pools).await {
+            // Simulate every opportunity the detector reported
+            while let Ok(opportunity) = opportunity_rx.try_recv() {
                 self.opportunities_seen.fetch_add(1, Ordering::SeqCst);
                 self.stats.lock().unwrap().total_opportunities += 1;
 
-                // Simulate trade execution
                 self.simulate_trade(opportunity).await?;
             }
 
             // Small delay to avoid spinning
             sleep(Duration::from_millis(100)).await;
 
-            // Periodically refresh pool data (every 10 seconds)
-            if elapsed.as_secs() % 10 == 0 {
-                let _pools = self.dex_integrator.fetch_all_pools().await?;
+            // Periodically refresh pool data (every 10 seconds) and re-run detection
+            if last_refresh.elapsed() >= Duration::from_secs(10) {
+                Self::refresh_graph(&pool_fetcher, &registry, &graph, &pools).await?;
+                update_tx.send(())?;
+                last_refresh = Instant::now();
             }
         }
 
+        detection_handle.abort();
+
         // Finalize statistics
         let mut stats = self.stats.lock().unwrap();
         stats.finalize();
@@ -403,17 +416,34 @@ impl PaperTradingSimulator {
         Ok(())
     }
 
+    /// Fetch `pools` and put their current state into the graph
+    async fn refresh_graph(
+        pool_fetcher: &PoolDataFetcher,
+        registry: &DexRegistry,
+        graph: &SharedArbitrageGraph,
+        pools: &[Pubkey],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let fetched = pool_fetcher.fetch_pools_batch(pools).await?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+        let mut graph = graph.write().map_err(|e| format!("Failed to acquire graph lock: {}", e))?;
+        for pool in &fetched {
+            graph.upsert_pool(registry, pool, timestamp)?;
+        }
+        Ok(fetched.len())
+    }
+
     async fn simulate_trade(&self, opportunity: ArbitrageOpportunity) -> Result<(), Box<dyn std::error::Error>> {
         let start = Instant::now();
 
         // Estimate gas cost (typical Solana transaction)
         let base_fee = 0.000005; // 5000 lamports = 0.000005 SOL
-        let compute_units = opportunity.path.len() as f64 * 200_000.0; // Estimate compute units
+        let compute_units = opportunity.cycle.path.len() as f64 * 200_000.0; // Estimate compute units
         let priority_fee = (compute_units / 1_000_000.0) * 0.000001; // Micro-lamports to SOL
         let simulated_cost = base_fee + priority_fee;
 
         // Get expected profit from opportunity
-        let simulated_profit = opportunity.expected_profit;
+        let simulated_profit = opportunity.expected_profit_sol;
 
         // Simulate success probability based on various factors
         let success_probability = self.calculate_success_probability(&opportunity);
@@ -446,10 +476,10 @@ impl PaperTradingSimulator {
     }
 
     fn calculate_success_probability(&self, opportunity: &ArbitrageOpportunity) -> f64 {
-        let mut probability = 0.85; // Base success rate: 85%
+        let mut probability: f64 = 0.85; // Base success rate: 85%
 
         // Adjust for profit margin (higher margin = less likely to fail due to slippage)
-        let profit_margin = opportunity.expected_profit / opportunity.input_amount;
+        let profit_margin = opportunity.expected_profit_bps as f64 / 10_000.0;
         if profit_margin > 0.05 {
             probability += 0.1; // +10% for high margin
         } else if profit_margin < 0.01 {
@@ -457,19 +487,24 @@ impl PaperTradingSimulator {
         }
 
         // Adjust for path length (longer paths = more chance of failure)
-        let path_length_penalty = (opportunity.path.len() as f64 - 2.0) * 0.05;
+        let path_length_penalty = (opportunity.cycle.path.len() as f64 - 2.0) * 0.05;
         probability -= path_length_penalty;
 
-        // Adjust for pool liquidity (assume opportunity has liquidity info)
-        // For now, use a heuristic based on input amount
-        if opportunity.input_amount > 10.0 {
+        // Adjust for trade size: the input's value in SOL, from the expected
+        // profit and the margin it represents
+        let input_sol = if profit_margin > 0.0 {
+            opportunity.expected_profit_sol / profit_margin
+        } else {
+            0.0
+        };
+        if input_sol > 10.0 {
             probability -= 0.15; // Large trades more likely to fail
-        } else if opportunity.input_amount < 0.1 {
+        } else if input_sol < 0.1 {
             probability += 0.05; // Small trades more likely to succeed
         }
 
         // Clamp between 0.3 and 0.95
-        probability.max(0.3).min(0.95)
+        probability.clamp(0.3, 0.95)
     }
 }
 
@@ -477,9 +512,9 @@ impl PaperTradingSimulator {
 #[ignore] // This is a long-running test, run with --ignored flag
 async fn test_paper_trading_24h() {
     // Initialize logging
-    let _ = env_logger::builder()
-        .filter_level(log::LevelFilter::Info)
-        .is_test(true)
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_test_writer()
         .try_init();
 
     println!("\n╔═══════════════════════════════════════════════════════════════╗");
@@ -487,12 +522,11 @@ async fn test_paper_trading_24h() {
     println!("╚═══════════════════════════════════════════════════════════════╝\n");
 
     // Load configuration
-    let config = Config::load_from_file("config.toml").expect("Failed to load config");
+    let config = Config::load().expect("Failed to load config");
 
-    // Override settings for paper trading
+    // Override settings for paper trading (the simulator never sends)
     let mut paper_config = config.clone();
-    paper_config.safety.dry_run_only = true; // Force dry-run mode
-    paper_config.arbitrage.min_profit_threshold = 0.001; // 0.001 SOL minimum
+    paper_config.bot.min_profit_bps = 10; // 0.1% minimum
 
     // Create simulator
     let simulator = PaperTradingSimulator::new(paper_config);
@@ -603,10 +637,9 @@ async fn test_paper_trading_stress_test() {
     println!("║            STRESS TEST - HIGH VOLUME SIMULATION               ║");
     println!("╚═══════════════════════════════════════════════════════════════╝\n");
 
-    let config = Config::load_from_file("config.toml").expect("Failed to load config");
+    let config = Config::load().expect("Failed to load config");
     let mut stress_config = config.clone();
-    stress_config.safety.dry_run_only = true;
-    stress_config.arbitrage.min_profit_threshold = 0.0001; // Lower threshold for more opportunities
+    stress_config.bot.min_profit_bps = 1; // Lower threshold for more opportunities
 
     let simulator = PaperTradingSimulator::new(stress_config);
 
@@ -632,10 +665,9 @@ async fn test_paper_trading_adverse_conditions() {
     println!("║         ADVERSE CONDITIONS TEST - LOW PROFITABILITY           ║");
     println!("╚═══════════════════════════════════════════════════════════════╝\n");
 
-    let config = Config::load_from_file("config.toml").expect("Failed to load config");
+    let config = Config::load().expect("Failed to load config");
     let mut adverse_config = config.clone();
-    adverse_config.safety.dry_run_only = true;
-    adverse_config.arbitrage.min_profit_threshold = 0.005; // High threshold = fewer opportunities
+    adverse_config.bot.min_profit_bps = 50; // High threshold = fewer opportunities
 
     let simulator = PaperTradingSimulator::new(adverse_config);
 
@@ -1,18 +1,18 @@
-/// Test: Execute Arbitrage on Mainnet Fork
-/// 
-/// This test demonstrates the workflow for arbitrage execution on a local mainnet fork.
-/// 
-/// **IMPORTANT**: This is a demonstration test showing the intended execution workflow.
-/// Full implementation requires DEX-specific swap instruction builders.
-///
-/// Test Phases:
-/// 1. Setup: Validate fork, fund test wallet
-/// 2. Detection: Pool fetching + graph building + Bellman-Ford (see other tests)
-/// 3. Transaction: Build multi-hop swap with compute budget
-/// 4. Execution: Submit to validator (requires DEX CPI)
-/// 5. Verification: Check profit/loss
-///
-/// Note: Test demonstrates workflow but cannot execute swaps without DEX instruction builders.
+//! Test: Execute Arbitrage on Mainnet Fork
+//! 
+//! This test demonstrates the workflow for arbitrage execution on a local mainnet fork.
+//! 
+//! **IMPORTANT**: This is a demonstration test showing the intended execution workflow.
+//! Full implementation requires DEX-specific swap instruction builders.
+//!
+//! Test Phases:
+//! 1. Setup: Validate fork, fund test wallet
+//! 2. Detection: Pool fetching + graph building + Bellman-Ford (see other tests)
+//! 3. Transaction: Build multi-hop swap with compute budget
+//! 4. Execution: Submit to validator (requires DEX CPI)
+//! 5. Verification: Check profit/loss
+//!
+//! Note: Test demonstrates workflow but cannot execute swaps without DEX instruction builders.
 
 use anyhow::Result;
 use solana_sdk::{
@@ -22,7 +22,7 @@ use solana_sdk::{
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_mev_bot::chain::token_fetch::TokenFetcher;
-use solana_mev_bot::dex::triangular_arb::{create_shared_graph, BellmanFordDetector, ExchangeEdge, DexType};
+use solana_mev_bot::dex::triangular_arb::{create_shared_graph, BellmanFordDetector, DexType};
 use solana_mev_bot::chain::transaction_builder::{SwapTransactionBuilder, TransactionConfig};
 use solana_mev_bot::chain::transaction_sender::{TransactionSender, SendConfig};
 use std::str::FromStr;
@@ -140,7 +140,7 @@ async fn test_execute_arbitrage_on_mainnet_fork() -> Result<()> {
     }
     
     // Fetch pools from known addresses
-    let pool_addresses = vec![
+    let pool_addresses = [
         pubkey(RAYDIUM_SOL_USDC),
         pubkey(ORCA_SOL_USDC),
         pubkey(METEORA_SOL_USDC),
@@ -152,10 +152,10 @@ async fn test_execute_arbitrage_on_mainnet_fork() -> Result<()> {
     // Fetch each pool individually (there's no batch method in current API)
     for (i, pool_addr) in pool_addresses.iter().enumerate() {
         // Try Raydium first, then Orca, then Meteora
-        let dex_types = vec![
-            solana_mev_bot::chain::token_fetch::DexType::Raydium,
-            solana_mev_bot::chain::token_fetch::DexType::Orca,
-            solana_mev_bot::chain::token_fetch::DexType::Meteora,
+        let dex_types = [
+            DexType::Raydium,
+            DexType::Orca,
+            DexType::MeteoraDlmm,
         ];
         
         if let Ok(pool) = token_fetcher.fetch_pool_data(pool_addr, dex_types[i].clone()).await {
@@ -174,13 +174,12 @@ async fn test_execute_arbitrage_on_mainnet_fork() -> Result<()> {
     
     // Display pool info
     for pool in &pools {
-        println!("\n   📦 Pool: {}", pool.pubkey);
+        println!("\n   📦 Pool: {}", pool.pool_address);
         println!("      DEX: {:?}", pool.dex_type);
-        println!("      {} / {}", pool.token_a_mint, pool.token_b_mint);
-        println!("      Reserves: {} / {}", pool.token_a_reserve, pool.token_b_reserve);
-        if pool.fee_denominator > 0 {
-            println!("      Fee: {:.4}%", (pool.fee_numerator as f64 / pool.fee_denominator as f64) * 100.0);
-        }
+        println!("      {} / {}", pool.token_a, pool.token_b);
+        println!("      Reserves: {} / {}", pool.reserve_a, pool.reserve_b);
+        println!("      Fee: {:.4}%", pool.fee_bps as f64 / 100.0);
+        println!("      Slot: {}", pool.slot);
     }
     
     // Build arbitrage graph
@@ -192,55 +191,15 @@ async fn test_execute_arbitrage_on_mainnet_fork() -> Result<()> {
         .as_secs() as i64;
     
     for pool in &pools {
-        if pool.token_a_reserve == 0 || pool.token_b_reserve == 0 {
+        if pool.reserve_a == 0 || pool.reserve_b == 0 {
             continue;
         }
         
-        let fee_bps = if pool.fee_denominator > 0 {
-            ((pool.fee_numerator as f64 / pool.fee_denominator as f64) * 10000.0) as u16
-        } else {
-            30 // Default 0.3%
-        };
-        
-        let dex_type = match pool.dex_type {
-            solana_mev_bot::chain::token_fetch::DexType::Raydium => DexType::Raydium,
-            solana_mev_bot::chain::token_fetch::DexType::Orca => DexType::Orca,
-            solana_mev_bot::chain::token_fetch::DexType::Meteora => DexType::Meteora,
-            _ => DexType::Raydium, // Fallback
-        };
-        
-        // A -> B edge
-        let rate_a_to_b = pool.token_b_reserve as f64 / pool.token_a_reserve as f64;
-        let edge_a_to_b = ExchangeEdge::new(
-            pool.token_a_mint,
-            pool.token_b_mint,
-            dex_type.clone(),
-            pool.pubkey,
-            rate_a_to_b,
-            fee_bps,
-            vec![], // No detailed liquidity depth for now
-            timestamp,
-        );
-        graph.write().unwrap().add_edge(edge_a_to_b);
-        
-        // B -> A edge
-        let rate_b_to_a = pool.token_a_reserve as f64 / pool.token_b_reserve as f64;
-        let edge_b_to_a = ExchangeEdge::new(
-            pool.token_b_mint,
-            pool.token_a_mint,
-            dex_type,
-            pool.pubkey,
-            rate_b_to_a,
-            fee_bps,
-            vec![],
-            timestamp,
-        );
-        graph.write().unwrap().add_edge(edge_b_to_a);
+        // Both directions, priced by the pool's venue adapter
+        graph.write().unwrap().upsert_pool(token_fetcher.registry(), pool, timestamp)?;
     }
     
-    let graph_read = graph.read().unwrap();
-    let token_count = graph_read.get_all_tokens().len();
-    drop(graph_read);
+    let token_count = graph.read().unwrap().get_all_tokens().len();
     
     println!("   ✅ Graph built:");
     println!("      Nodes: {} tokens", token_count);
@@ -363,7 +322,7 @@ async fn test_execute_arbitrage_on_mainnet_fork() -> Result<()> {
     // Build the transaction
     println!("\n   🔨 Building arbitrage transaction...");
     let transaction = match builder.build_arbitrage_tx(
-        &best,
+        best,
         optimal_input,
         &tx_config,
     ).await {
@@ -567,7 +526,7 @@ async fn test_fork_environment_ready() -> Result<()> {
     }
     
     println!("\n2️⃣  Cloned pools...");
-    for (name, addr) in vec![
+    for (name, addr) in [
         ("Raydium", RAYDIUM_SOL_USDC),
         ("Orca", ORCA_SOL_USDC),
         ("Meteora", METEORA_SOL_USDC),
@@ -579,7 +538,7 @@ async fn test_fork_environment_ready() -> Result<()> {
     }
     
     println!("\n3️⃣  DEX programs...");
-    for (name, addr) in vec![
+    for (name, addr) in [
         ("Raydium", RAYDIUM_AMM_V4),
         ("Orca", ORCA_WHIRLPOOL),
         ("Meteora", METEORA_DLMM),
@@ -652,6 +611,7 @@ async fn test_transaction_size_within_limits() -> Result<()> {
                 pool: pubkey(RAYDIUM_SOL_USDC),
                 rate: 0.0055,  // ~180 USDC per SOL
                 fee_bps: 25,   // 0.25%
+                splits: Vec::new(),
             },
             // Hop 2: SOL → USDC (Meteora)
             CycleStep {
@@ -661,6 +621,7 @@ async fn test_transaction_size_within_limits() -> Result<()> {
                 pool: pubkey(METEORA_SOL_USDC),
                 rate: 182.0,   // Slightly better rate
                 fee_bps: 20,   // 0.20%
+                splits: Vec::new(),
             },
             // Hop 3: USDC → SOL → USDC (Orca roundtrip)
             CycleStep {
@@ -670,6 +631,7 @@ async fn test_transaction_size_within_limits() -> Result<()> {
                 pool: pubkey(ORCA_SOL_USDC),
                 rate: 0.0056,  // Even better rate
                 fee_bps: 30,   // 0.30%
+                splits: Vec::new(),
             },
         ],
         gross_profit_bps: 15,  // 0.15% profit
@@ -789,7 +751,7 @@ async fn test_transaction_size_within_limits() -> Result<()> {
     println!("   • Recent blockhash: {} bytes", blockhash_size);
     println!("   • Instructions + accounts: ~{} bytes", instructions_estimate);
     println!("   • Per instruction: ~{} bytes avg\n", 
-        if cycle.path.len() > 0 { instructions_estimate / (cycle.path.len() + 2) } else { 0 });
+        if !cycle.path.is_empty() { instructions_estimate / (cycle.path.len() + 2) } else { 0 });
     
     // Visual representation
     println!("📊 Visual:");
@@ -946,6 +908,7 @@ async fn test_compute_budget_sufficient() -> Result<()> {
                 pool: pubkey(RAYDIUM_SOL_USDC),
                 rate: 0.0055,
                 fee_bps: 25,
+                splits: Vec::new(),
             },
             CycleStep {
                 from_token: pubkey(SOL_MINT),
@@ -954,6 +917,7 @@ async fn test_compute_budget_sufficient() -> Result<()> {
                 pool: pubkey(METEORA_SOL_USDC),
                 rate: 182.0,
                 fee_bps: 20,
+                splits: Vec::new(),
             },
             CycleStep {
                 from_token: pubkey(USDC_MINT),
@@ -962,6 +926,7 @@ async fn test_compute_budget_sufficient() -> Result<()> {
                 pool: pubkey(ORCA_SOL_USDC),
                 rate: 0.0056,
                 fee_bps: 30,
+                splits: Vec::new(),
             },
         ],
         gross_profit_bps: 15,
@@ -1277,7 +1242,7 @@ async fn test_all_dex_combinations() -> Result<()> {
     
     // Define all possible 3-hop permutations
     // Each path represents: DEX1 -> DEX2 -> DEX3 -> back to start token
-    let test_paths = vec![
+    let test_paths = [
         ("Raydium → Meteora → Orca", DexType::Raydium, DexType::Meteora, DexType::Orca),
         ("Raydium → Orca → Meteora", DexType::Raydium, DexType::Orca, DexType::Meteora),
         ("Meteora → Raydium → Orca", DexType::Meteora, DexType::Raydium, DexType::Orca),
@@ -1338,6 +1303,7 @@ async fn test_all_dex_combinations() -> Result<()> {
                     pool: pubkey(pool1),
                     rate: 0.0055,
                     fee_bps: fee1,
+                    splits: Vec::new(),
                 },
                 // Hop 2: SOL -> USDC
                 CycleStep {
@@ -1347,6 +1313,7 @@ async fn test_all_dex_combinations() -> Result<()> {
                     pool: pubkey(pool2),
                     rate: 182.0,
                     fee_bps: fee2,
+                    splits: Vec::new(),
                 },
                 // Hop 3: USDC -> SOL (roundtrip)
                 CycleStep {
@@ -1356,6 +1323,7 @@ async fn test_all_dex_combinations() -> Result<()> {
                     pool: pubkey(pool3),
                     rate: 0.0056,
                     fee_bps: fee3,
+                    splits: Vec::new(),
                 },
             ],
             gross_profit_bps: 15,
@@ -1520,10 +1488,15 @@ async fn test_all_dex_combinations() -> Result<()> {
 fn get_pool_for_dex(dex: DexType) -> (&'static str, u16) {
     match dex {
         DexType::Raydium => (RAYDIUM_SOL_USDC, 25),  // 0.25%
+        DexType::RaydiumCpmm => (RAYDIUM_SOL_USDC, 25), // Same as Raydium
+        DexType::RaydiumClmm => (RAYDIUM_SOL_USDC, 5),  // 0.05% tier
         DexType::Orca => (ORCA_SOL_USDC, 30),        // 0.30%
         DexType::Meteora => (METEORA_SOL_USDC, 20),  // 0.20%
+        DexType::MeteoraDlmm => (METEORA_SOL_USDC, 20), // Same as Meteora
         DexType::Whirlpool => (ORCA_SOL_USDC, 30),   // Same as Orca
         DexType::Pump => (ORCA_SOL_USDC, 100),       // Placeholder
+        DexType::Phoenix => (ORCA_SOL_USDC, 5),      // Placeholder, taker fee tier
+        DexType::OpenBookV2 => (ORCA_SOL_USDC, 4),   // Placeholder, taker fee tier
     }
 }
 
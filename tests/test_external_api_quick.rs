@@ -1,10 +1,10 @@
-/// Quick Start: Testing External API Integration
-/// 
-/// This test validates that the external API configuration is working
-/// without requiring full pool parsing implementation.
-///
-/// Run with:
-///   HELIUS_API_KEY="your_key" cargo test test_external_api_connectivity -- --ignored --nocapture
+//! Quick Start: Testing External API Integration
+//! 
+//! This test validates that the external API configuration is working
+//! without requiring full pool parsing implementation.
+//!
+//! Run with:
+//!   HELIUS_API_KEY="your_key" cargo test test_external_api_connectivity -- --ignored --nocapture
 
 use anyhow::Result;
 use solana_mev_bot::chain::token_fetch::{TokenFetcher, TokenFetchConfig};
@@ -1,12 +1,13 @@
-/// Test: Local Fork + External API Integration
-/// 
-/// This test demonstrates the "Aggressive/Dynamic" testing strategy:
-/// - Pool metadata: Fetched from Helius API (fast, real-time mainnet data)
-/// - Vault balances: Fetched from local fork (for execution simulation)
-/// - Execution: On local fork (safe, no real funds)
+//! Test: Local Fork + External API Integration
+//! 
+//! This test demonstrates the "Aggressive/Dynamic" testing strategy:
+//! - Pool metadata: Fetched from Helius API (fast, real-time mainnet data)
+//! - Vault balances: Fetched from local fork (for execution simulation)
+//! - Execution: On local fork (safe, no real funds)
 
 use anyhow::Result;
-use solana_mev_bot::chain::token_fetch::{TokenFetcher, TokenFetchConfig, DexType};
+use solana_mev_bot::chain::token_fetch::{TokenFetcher, TokenFetchConfig};
+use solana_mev_bot::dex::triangular_arb::DexType;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -97,14 +98,14 @@ async fn test_fork_with_external_api() -> Result<()> {
     
     println!("\n📊 Pool Data:");
     for pool in &pools {
-        println!("\n   Pool: {}", pool.pubkey);
+        println!("\n   Pool: {}", pool.pool_address);
         println!("   DEX: {:?}", pool.dex_type);
-        println!("   Token A: {} ({})", pool.token_a_mint, pool.token_a_reserve);
-        println!("   Token B: {} ({})", pool.token_b_mint, pool.token_b_reserve);
-        println!("   Fee: {}/{}", pool.fee_numerator, pool.fee_denominator);
+        println!("   Token A: {} ({})", pool.token_a, pool.reserve_a);
+        println!("   Token B: {} ({})", pool.token_b, pool.reserve_b);
+        println!("   Fee: {} bps", pool.fee_bps);
         
-        if pool.token_a_reserve > 0 && pool.token_b_reserve > 0 {
-            let rate = pool.token_b_reserve as f64 / pool.token_a_reserve as f64;
+        if pool.reserve_a > 0 && pool.reserve_b > 0 {
+            let rate = pool.reserve_b as f64 / pool.reserve_a as f64;
             println!("   Rate: {:.6} Token B per Token A", rate);
         }
     }
@@ -113,15 +114,16 @@ async fn test_fork_with_external_api() -> Result<()> {
     assert_eq!(pools.len(), 2, "Should fetch 2 pools");
     
     for pool in &pools {
-        assert!(pool.token_a_vault.is_some(), "Vault A should be set");
-        assert!(pool.token_b_vault.is_some(), "Vault B should be set");
+        let vaults = token_fetcher.registry().dependent_accounts(pool);
+        assert!(vaults.len() >= 2, "Vaults should be known");
+        assert!(pool.slot > 0, "Slot should be stamped");
         
         // Note: Reserves come from external API (Helius mainnet data)
         // For local execution, you'd clone these vaults to the fork first
-        if pool.token_a_reserve > 0 && pool.token_b_reserve > 0 {
-            println!("\n   ✅ Pool {} has real mainnet reserves!", pool.pubkey);
+        if pool.reserve_a > 0 && pool.reserve_b > 0 {
+            println!("\n   ✅ Pool {} has real mainnet reserves!", pool.pool_address);
         } else {
-            println!("\n   ⚠️  Pool {} reserves are 0 (might be inactive)", pool.pubkey);
+            println!("\n   ⚠️  Pool {} reserves are 0 (might be inactive)", pool.pool_address);
         }
     }
     
@@ -183,8 +185,8 @@ async fn test_realtime_pool_data_refresh() -> Result<()> {
     let pools1 = token_fetcher.initialize_pool_data(&pool_configs).await?;
     let duration1 = start1.elapsed();
     println!("   Duration: {:?}", duration1);
-    println!("   Reserve A: {}", pools1[0].token_a_reserve);
-    println!("   Reserve B: {}", pools1[0].token_b_reserve);
+    println!("   Reserve A: {}", pools1[0].reserve_a);
+    println!("   Reserve B: {}", pools1[0].reserve_b);
     
     println!("\n⏳ Waiting 2 seconds...");
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
@@ -194,8 +196,8 @@ async fn test_realtime_pool_data_refresh() -> Result<()> {
     let pools2 = token_fetcher.initialize_pool_data(&pool_configs).await?;
     let duration2 = start2.elapsed();
     println!("   Duration: {:?} (should be faster - cached)", duration2);
-    println!("   Reserve A: {}", pools2[0].token_a_reserve);
-    println!("   Reserve B: {}", pools2[0].token_b_reserve);
+    println!("   Reserve A: {}", pools2[0].reserve_a);
+    println!("   Reserve B: {}", pools2[0].reserve_b);
     
     println!("\n⏳ Waiting 4 more seconds (total 6s, > 5s TTL)...");
     tokio::time::sleep(tokio::time::Duration::from_secs(4)).await;
@@ -205,8 +207,8 @@ async fn test_realtime_pool_data_refresh() -> Result<()> {
     let pools3 = token_fetcher.initialize_pool_data(&pool_configs).await?;
     let duration3 = start3.elapsed();
     println!("   Duration: {:?} (should be slower - refreshed)", duration3);
-    println!("   Reserve A: {}", pools3[0].token_a_reserve);
-    println!("   Reserve B: {}", pools3[0].token_b_reserve);
+    println!("   Reserve A: {}", pools3[0].reserve_a);
+    println!("   Reserve B: {}", pools3[0].reserve_b);
     
     println!("\n✅ Cache behavior:");
     println!("   Fetch 1: {:?} (initial)", duration1);
@@ -1,6 +1,7 @@
 /// Debug test to verify pool parsing works correctly
 use anyhow::Result;
-use solana_mev_bot::chain::token_fetch::{TokenFetcher, TokenFetchConfig, DexType};
+use solana_mev_bot::chain::token_fetch::{TokenFetcher, TokenFetchConfig};
+use solana_mev_bot::dex::triangular_arb::DexType;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -60,27 +61,26 @@ async fn test_pool_parsing_debug() -> Result<()> {
     
     let pool = &pools[0];
     println!("\n📊 Pool Data:");
-    println!("  Address: {}", pool.pubkey);
+    println!("  Address: {}", pool.pool_address);
     println!("  DEX: {:?}", pool.dex_type);
-    println!("  Token A Mint: {}", pool.token_a_mint);
-    println!("  Token B Mint: {}", pool.token_b_mint);
-    println!("  Token A Vault: {:?}", pool.token_a_vault);
-    println!("  Token B Vault: {:?}", pool.token_b_vault);
-    println!("  Token A Reserve: {}", pool.token_a_reserve);
-    println!("  Token B Reserve: {}", pool.token_b_reserve);
-    println!("  Fee: {}/{}", pool.fee_numerator, pool.fee_denominator);
+    println!("  Token A Mint: {}", pool.token_a);
+    println!("  Token B Mint: {}", pool.token_b);
+    println!("  Dependent accounts: {:?}", token_fetcher.registry().dependent_accounts(pool));
+    println!("  Token A Reserve: {}", pool.reserve_a);
+    println!("  Token B Reserve: {}", pool.reserve_b);
+    println!("  Fee: {} bps", pool.fee_bps);
+    println!("  Slot: {}", pool.slot);
     
     // Check that vaults are set
-    assert!(pool.token_a_vault.is_some(), "Token A vault should be set");
-    assert!(pool.token_b_vault.is_some(), "Token B vault should be set");
+    assert!(token_fetcher.registry().dependent_accounts(pool).len() >= 2, "Vaults should be known");
     
     println!("\n✅ Vaults are set correctly");
     
     // Check if reserves are fetched
-    if pool.token_a_reserve > 0 && pool.token_b_reserve > 0 {
+    if pool.reserve_a > 0 && pool.reserve_b > 0 {
         println!("✅ Reserves fetched successfully");
         println!("   Exchange rate: {} Token B per Token A", 
-                 pool.token_b_reserve as f64 / pool.token_a_reserve as f64);
+                 pool.reserve_b as f64 / pool.reserve_a as f64);
     } else {
         println!("⚠️  Reserves are zero - vault enrichment may have failed");
         println!("   This could be:");
@@ -0,0 +1,265 @@
+/// Quote fixtures for the shared quoting path
+///
+/// Each test lays out the raw accounts of one venue byte for byte (pool,
+/// vaults, tick or bin arrays at their PDAs), runs them through
+/// `DexRegistry` the way the pool monitors do (decode, fetch dependents,
+/// apply, build edges) and checks the edge quote against the amount the
+/// program's swap math pays out for that state. The expected amounts were
+/// computed outside this crate from the on-chain formulas.
+use solana_mev_bot::dex::adapter::DexRegistry;
+use solana_mev_bot::dex::meteora_dlmm::{bin_array_address, BIN_ARRAY_LEN, LB_PAIR_LEN, METEORA_DLMM_PROGRAM_ID};
+use solana_mev_bot::dex::raydium::{AMM_INFO_LEN, MARKET_STATE_LEN, RAYDIUM_AMM_V4_PROGRAM_ID};
+use solana_mev_bot::dex::triangular_arb::ExchangeEdge;
+use solana_mev_bot::dex::whirlpool::{tick_array_address, TICK_ARRAY_LEN, WHIRLPOOL_LEN, WHIRLPOOL_PROGRAM_ID};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const OPENBOOK_V1_PROGRAM: &str = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX";
+
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+const LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+const BIN_ARRAY_DISCRIMINATOR: [u8; 8] = [92, 142, 92, 220, 5, 148, 70, 181];
+
+const ONE_SOL: u64 = 1_000_000_000;
+const USDC_150: u64 = 150_000_000;
+
+fn pubkey(s: &str) -> Pubkey {
+    Pubkey::from_str(s).unwrap()
+}
+
+fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_i32(data: &mut [u8], offset: usize, value: i32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_i64(data: &mut [u8], offset: usize, value: i64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u128(data: &mut [u8], offset: usize, value: u128) {
+    data[offset..offset + 16].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_pubkey(data: &mut [u8], offset: usize, key: &Pubkey) {
+    data[offset..offset + 32].copy_from_slice(key.as_ref());
+}
+
+fn token_account(mint: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; 165];
+    put_pubkey(&mut data, 0, mint);
+    put_u64(&mut data, 64, amount);
+    Account { lamports: 2_039_280, data, owner: spl_token::id(), executable: false, rent_epoch: 0 }
+}
+
+fn account(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account { lamports: 1, data, owner, executable: false, rent_epoch: 0 }
+}
+
+/// Decode `pool`, check it asks for every fixture account, apply them and
+/// return its SOL->USDC and USDC->SOL edges
+fn load_edges(address: &Pubkey, pool: &Account, dependents: HashMap<Pubkey, Account>) -> (ExchangeEdge, ExchangeEdge) {
+    let registry = DexRegistry::default();
+    let mut pool = registry.decode_pool(address, pool).unwrap();
+
+    let requested = registry.dependent_accounts(&pool);
+    for key in dependents.keys() {
+        assert!(requested.contains(key), "fixture account {} is not a dependent of {}", key, address);
+    }
+    registry.apply_dependent_accounts(&mut pool, &dependents).unwrap();
+
+    let mut edges = registry.edges_for(&pool, 0).unwrap().into_iter();
+    let sol_to_usdc = edges.next().expect("pool has no edges");
+    let usdc_to_sol = edges.next().expect("pool has one edge");
+    assert_eq!(sol_to_usdc.from_token, pubkey(SOL_MINT));
+    assert_eq!(usdc_to_sol.from_token, pubkey(USDC_MINT));
+    (sol_to_usdc, usdc_to_sol)
+}
+
+fn quote(edge: &ExchangeEdge, amount_in: u64) -> u64 {
+    edge.quoter.as_ref().expect("edge has no quoter").quote_exact_in(amount_in).unwrap()
+}
+
+/// SOL/USDC AMM v4 with 50_000 SOL / 7.5M USDC in the vaults, 0.25% fee and
+/// some PnL still owed to the protocol
+#[test]
+fn test_raydium_amm_quote_fixture() {
+    let address = Pubkey::new_unique();
+    let (coin_vault, pc_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let market = Pubkey::new_unique();
+    let market_program = pubkey(OPENBOOK_V1_PROGRAM);
+    let nonce = Pubkey::find_program_address(&[b"amm authority"], &RAYDIUM_AMM_V4_PROGRAM_ID).1;
+
+    let mut amm = vec![0u8; AMM_INFO_LEN];
+    put_u64(&mut amm, 0, 6); // status: SwapOnly
+    put_u64(&mut amm, 8, nonce as u64);
+    put_u64(&mut amm, 32, 9); // coin decimals
+    put_u64(&mut amm, 40, 6); // pc decimals
+    put_u64(&mut amm, 144, 25); // trade fee
+    put_u64(&mut amm, 152, 10_000);
+    put_u64(&mut amm, 160, 12); // pnl
+    put_u64(&mut amm, 168, 100);
+    put_u64(&mut amm, 176, 25); // swap fee
+    put_u64(&mut amm, 184, 10_000);
+    put_u64(&mut amm, 192, 1_000); // need_take_pnl_coin
+    put_u64(&mut amm, 200, 2_000); // need_take_pnl_pc
+    put_pubkey(&mut amm, 336, &coin_vault);
+    put_pubkey(&mut amm, 368, &pc_vault);
+    put_pubkey(&mut amm, 400, &pubkey(SOL_MINT));
+    put_pubkey(&mut amm, 432, &pubkey(USDC_MINT));
+    put_pubkey(&mut amm, 496, &Pubkey::new_unique()); // open orders
+    put_pubkey(&mut amm, 528, &market);
+    put_pubkey(&mut amm, 560, &market_program);
+    put_pubkey(&mut amm, 592, &Pubkey::new_unique()); // target orders
+
+    let vault_signer_nonce = (0u64..)
+        .find(|nonce| Pubkey::create_program_address(&[market.as_ref(), &nonce.to_le_bytes()], &market_program).is_ok())
+        .unwrap();
+    let mut market_state = vec![0u8; MARKET_STATE_LEN];
+    market_state[0..5].copy_from_slice(b"serum");
+    put_pubkey(&mut market_state, 13, &market);
+    put_u64(&mut market_state, 45, vault_signer_nonce);
+
+    let dependents = HashMap::from([
+        (coin_vault, token_account(&pubkey(SOL_MINT), 50_000 * ONE_SOL)),
+        (pc_vault, token_account(&pubkey(USDC_MINT), 7_500_000_000_000)),
+        (market, account(market_state, market_program)),
+    ]);
+    let (sol_to_usdc, usdc_to_sol) =
+        load_edges(&address, &account(amm, RAYDIUM_AMM_V4_PROGRAM_ID), dependents);
+
+    assert_eq!(quote(&sol_to_usdc, ONE_SOL), 149_622_015);
+    assert_eq!(quote(&usdc_to_sol, USDC_150), 997_480_100);
+}
+
+/// SOL/USDC Whirlpool (tick spacing 64, 0.3% fee) at ~0.15 USDC per lamport
+/// with no initialized tick near the price, so both swaps stay in one range
+#[test]
+fn test_whirlpool_quote_fixture() {
+    const TICK_SPACING: i32 = 64;
+    const TICK_CURRENT: i32 = -18_973;
+    const SQRT_PRICE: u128 = 7_144_387_086_259_561_921;
+    const LIQUIDITY: u128 = 8_000_000_000_000;
+
+    let address = Pubkey::new_unique();
+    let (vault_a, vault_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let mut whirlpool = vec![0u8; WHIRLPOOL_LEN];
+    whirlpool[0..8].copy_from_slice(&WHIRLPOOL_DISCRIMINATOR);
+    put_pubkey(&mut whirlpool, 8, &Pubkey::new_unique()); // whirlpools_config
+    put_u16(&mut whirlpool, 41, TICK_SPACING as u16);
+    put_u16(&mut whirlpool, 45, 3_000); // fee_rate, hundredths of a bp
+    put_u16(&mut whirlpool, 47, 1_300); // protocol_fee_rate
+    put_u128(&mut whirlpool, 49, LIQUIDITY);
+    put_u128(&mut whirlpool, 65, SQRT_PRICE);
+    put_i32(&mut whirlpool, 81, TICK_CURRENT);
+    put_pubkey(&mut whirlpool, 101, &pubkey(SOL_MINT));
+    put_pubkey(&mut whirlpool, 133, &vault_a);
+    put_pubkey(&mut whirlpool, 181, &pubkey(USDC_MINT));
+    put_pubkey(&mut whirlpool, 213, &vault_b);
+
+    let mut dependents = HashMap::from([
+        (vault_a, token_account(&pubkey(SOL_MINT), 60_000 * ONE_SOL)),
+        (vault_b, token_account(&pubkey(USDC_MINT), 9_000_000_000_000)),
+    ]);
+    // Arrays of 88 ticks: the one holding the price and two on either side
+    for start_tick_index in [-33_792, -28_160, -22_528, -16_896, -11_264] {
+        let mut tick_array = vec![0u8; TICK_ARRAY_LEN];
+        tick_array[0..8].copy_from_slice(&TICK_ARRAY_DISCRIMINATOR);
+        put_i32(&mut tick_array, 8, start_tick_index);
+        put_pubkey(&mut tick_array, 12 + 88 * 113, &address);
+        dependents.insert(
+            tick_array_address(&WHIRLPOOL_PROGRAM_ID, &address, start_tick_index),
+            account(tick_array, WHIRLPOOL_PROGRAM_ID),
+        );
+    }
+
+    let (sol_to_usdc, usdc_to_sol) =
+        load_edges(&address, &account(whirlpool, WHIRLPOOL_PROGRAM_ID), dependents);
+
+    assert_eq!(quote(&sol_to_usdc, ONE_SOL), 149_542_523);
+    assert_eq!(quote(&usdc_to_sol, USDC_150), 996_953_602);
+}
+
+/// SOL/USDC DLMM pair (bin step 10, 0.1% base fee) active at bin -1899.
+/// The active bin holds only 50 USDC, so the SOL->USDC swap drains it and
+/// finishes one bin down, paying the variable fee there.
+#[test]
+fn test_meteora_dlmm_quote_fixture() {
+    const ACTIVE_ID: i32 = -1_899;
+
+    let address = Pubkey::new_unique();
+    let (reserve_x, reserve_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let mut lb_pair = vec![0u8; LB_PAIR_LEN];
+    lb_pair[0..8].copy_from_slice(&LB_PAIR_DISCRIMINATOR);
+    put_u16(&mut lb_pair, 8, 10_000); // base_factor
+    put_u16(&mut lb_pair, 10, 30); // filter_period
+    put_u16(&mut lb_pair, 12, 600); // decay_period
+    put_u16(&mut lb_pair, 14, 5_000); // reduction_factor
+    put_u32(&mut lb_pair, 16, 40_000); // variable_fee_control
+    put_u32(&mut lb_pair, 20, 350_000); // max_volatility_accumulator
+    put_i32(&mut lb_pair, 24, -443_636); // min_bin_id
+    put_i32(&mut lb_pair, 28, 443_636); // max_bin_id
+    put_i64(&mut lb_pair, 56, 0); // last_update_timestamp: long past the decay period
+    put_i32(&mut lb_pair, 76, ACTIVE_ID);
+    put_u16(&mut lb_pair, 80, 10); // bin_step
+    put_pubkey(&mut lb_pair, 88, &pubkey(SOL_MINT));
+    put_pubkey(&mut lb_pair, 120, &pubkey(USDC_MINT));
+    put_pubkey(&mut lb_pair, 152, &reserve_x);
+    put_pubkey(&mut lb_pair, 184, &reserve_y);
+    put_pubkey(&mut lb_pair, 552, &Pubkey::new_unique()); // oracle
+
+    let mut dependents = HashMap::from([
+        (reserve_x, token_account(&pubkey(SOL_MINT), 2_000 * ONE_SOL)),
+        (reserve_y, token_account(&pubkey(USDC_MINT), 300_000_000_000)),
+    ]);
+    // Bin arrays -29..=-27 around the active bin; Y below it, X above it
+    for index in [-29i64, -28, -27] {
+        let bit = (index + 512) as usize;
+        let word = 584 + bit / 64 * 8;
+        let bits = u64::from_le_bytes(lb_pair[word..word + 8].try_into().unwrap()) | 1 << (bit % 64);
+        put_u64(&mut lb_pair, word, bits);
+
+        let mut bin_array = vec![0u8; BIN_ARRAY_LEN];
+        bin_array[0..8].copy_from_slice(&BIN_ARRAY_DISCRIMINATOR);
+        put_i64(&mut bin_array, 8, index);
+        put_pubkey(&mut bin_array, 24, &address);
+        for i in 0..70 {
+            let bin_id = (index * 70 + i) as i32;
+            let (amount_x, amount_y) = match bin_id.cmp(&ACTIVE_ID) {
+                std::cmp::Ordering::Less => (0, 1_000_000_000),
+                std::cmp::Ordering::Equal => (5 * ONE_SOL, 50_000_000),
+                std::cmp::Ordering::Greater => (10 * ONE_SOL, 0),
+            };
+            let offset = 56 + i as usize * 144;
+            put_u64(&mut bin_array, offset, amount_x);
+            put_u64(&mut bin_array, offset + 8, amount_y);
+        }
+        dependents.insert(
+            bin_array_address(&METEORA_DLMM_PROGRAM_ID, &address, index),
+            account(bin_array, METEORA_DLMM_PROGRAM_ID),
+        );
+    }
+
+    let (sol_to_usdc, usdc_to_sol) =
+        load_edges(&address, &account(lb_pair, METEORA_DLMM_PROGRAM_ID), dependents);
+
+    assert_eq!(quote(&sol_to_usdc, ONE_SOL), 149_610_522);
+    assert_eq!(quote(&usdc_to_sol, USDC_150), 999_930_649);
+}
@@ -1,8 +1,15 @@
-/// Test to find correct Raydium AMM V4 offsets
+/// Raydium AMM V4 layout tests
+///
+/// Fixture tests build AmmInfo / market / vault accounts with known values and
+/// check decoding, reserve calculation and the swap instruction. The ignored
+/// network test checks the decoder against the live SOL/USDC pool.
 use anyhow::Result;
+use solana_mev_bot::dex::adapter::{DexAdapter, SwapParams, VenueState};
+use solana_mev_bot::dex::raydium::{AmmInfo, MarketKeys, RaydiumAdapter, AMM_INFO_LEN, MARKET_STATE_LEN, RAYDIUM_AMM_V4_PROGRAM_ID};
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::str::FromStr;
-use reqwest;
 use base64::{Engine as _, engine::general_purpose};
 use serial_test::serial;
 
@@ -14,6 +21,242 @@ const EXPECTED_QUOTE_VAULT: &str = "HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz
 const EXPECTED_BASE_MINT: &str = "So11111111111111111111111111111111111111112";
 const EXPECTED_QUOTE_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 const EXPECTED_LP_MINT: &str = "8HoQnePLqPj4M7PUDzfw8e3Ymdwgc7NLGnaTUapubyvu";
+const EXPECTED_AMM_AUTHORITY: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+const OPENBOOK_V1_PROGRAM: &str = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX";
+
+fn pubkey(s: &str) -> Pubkey {
+    Pubkey::from_str(s).unwrap()
+}
+
+fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_pubkey(data: &mut [u8], offset: usize, key: &Pubkey) {
+    data[offset..offset + 32].copy_from_slice(key.as_ref());
+}
+
+/// Nonce of the AMM authority PDA for the mainnet program
+fn authority_nonce() -> u8 {
+    Pubkey::find_program_address(&[b"amm authority"], &RAYDIUM_AMM_V4_PROGRAM_ID).1
+}
+
+/// Vault signer nonce that yields a valid PDA for `market`
+fn vault_signer_nonce(market: &Pubkey, program: &Pubkey) -> u64 {
+    (0u64..).find(|nonce| {
+        Pubkey::create_program_address(&[market.as_ref(), &nonce.to_le_bytes()], program).is_ok()
+    }).unwrap()
+}
+
+struct AmmFixture {
+    data: Vec<u8>,
+    market: Pubkey,
+    open_orders: Pubkey,
+    target_orders: Pubkey,
+}
+
+/// SOL/USDC AmmInfo with known vaults and mints and some pending PnL
+fn amm_fixture() -> AmmFixture {
+    let market = Pubkey::new_unique();
+    let open_orders = Pubkey::new_unique();
+    let target_orders = Pubkey::new_unique();
+
+    let mut data = vec![0u8; AMM_INFO_LEN];
+    put_u64(&mut data, 0, 6); // status: SwapOnly
+    put_u64(&mut data, 8, authority_nonce() as u64);
+    put_u64(&mut data, 32, 9); // coin decimals
+    put_u64(&mut data, 40, 6); // pc decimals
+    put_u64(&mut data, 144, 25); // trade fee
+    put_u64(&mut data, 152, 10_000);
+    put_u64(&mut data, 160, 12); // pnl
+    put_u64(&mut data, 168, 100);
+    put_u64(&mut data, 176, 25); // swap fee
+    put_u64(&mut data, 184, 10_000);
+    put_u64(&mut data, 192, 1_000); // need_take_pnl_coin
+    put_u64(&mut data, 200, 2_000); // need_take_pnl_pc
+    put_pubkey(&mut data, 336, &pubkey(EXPECTED_BASE_VAULT));
+    put_pubkey(&mut data, 368, &pubkey(EXPECTED_QUOTE_VAULT));
+    put_pubkey(&mut data, 400, &pubkey(EXPECTED_BASE_MINT));
+    put_pubkey(&mut data, 432, &pubkey(EXPECTED_QUOTE_MINT));
+    put_pubkey(&mut data, 464, &pubkey(EXPECTED_LP_MINT));
+    put_pubkey(&mut data, 496, &open_orders);
+    put_pubkey(&mut data, 528, &market);
+    put_pubkey(&mut data, 560, &pubkey(OPENBOOK_V1_PROGRAM));
+    put_pubkey(&mut data, 592, &target_orders);
+
+    AmmFixture { data, market, open_orders, target_orders }
+}
+
+fn market_fixture(market: &Pubkey) -> (Vec<u8>, MarketKeys) {
+    let program = pubkey(OPENBOOK_V1_PROGRAM);
+    let nonce = vault_signer_nonce(market, &program);
+    let keys = MarketKeys {
+        bids: Pubkey::new_unique(),
+        asks: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        vault_signer: Pubkey::create_program_address(&[market.as_ref(), &nonce.to_le_bytes()], &program).unwrap(),
+    };
+
+    let mut data = vec![0u8; MARKET_STATE_LEN];
+    data[0..5].copy_from_slice(b"serum");
+    put_pubkey(&mut data, 13, market);
+    put_u64(&mut data, 45, nonce);
+    put_pubkey(&mut data, 117, &keys.coin_vault);
+    put_pubkey(&mut data, 165, &keys.pc_vault);
+    put_pubkey(&mut data, 253, &keys.event_queue);
+    put_pubkey(&mut data, 285, &keys.bids);
+    put_pubkey(&mut data, 317, &keys.asks);
+
+    (data, keys)
+}
+
+fn token_account(mint: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; 165];
+    put_pubkey(&mut data, 0, mint);
+    put_u64(&mut data, 64, amount);
+    Account { lamports: 2_039_280, data, owner: spl_token::id(), executable: false, rent_epoch: 0 }
+}
+
+fn account(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account { lamports: 1, data, owner, executable: false, rent_epoch: 0 }
+}
+
+#[test]
+fn test_decode_amm_info_fixture() {
+    let fixture = amm_fixture();
+    let amm = AmmInfo::decode(&fixture.data).unwrap();
+
+    assert_eq!(amm.status, 6);
+    assert!(amm.swap_enabled());
+    assert_eq!(amm.coin_decimals, 9);
+    assert_eq!(amm.pc_decimals, 6);
+    assert_eq!(amm.fees.trade_fee_numerator, 25);
+    assert_eq!(amm.fees.pnl_numerator, 12);
+    assert_eq!(amm.fees.pnl_denominator, 100);
+    assert_eq!(amm.swap_fee_bps(), 25);
+    assert_eq!(amm.need_take_pnl_coin, 1_000);
+    assert_eq!(amm.need_take_pnl_pc, 2_000);
+    assert_eq!(amm.coin_vault, pubkey(EXPECTED_BASE_VAULT));
+    assert_eq!(amm.pc_vault, pubkey(EXPECTED_QUOTE_VAULT));
+    assert_eq!(amm.coin_mint, pubkey(EXPECTED_BASE_MINT));
+    assert_eq!(amm.pc_mint, pubkey(EXPECTED_QUOTE_MINT));
+    assert_eq!(amm.lp_mint, pubkey(EXPECTED_LP_MINT));
+    assert_eq!(amm.open_orders, fixture.open_orders);
+    assert_eq!(amm.market, fixture.market);
+    assert_eq!(amm.market_program, pubkey(OPENBOOK_V1_PROGRAM));
+    assert_eq!(amm.target_orders, fixture.target_orders);
+}
+
+#[test]
+fn test_amm_authority_pda() {
+    let amm = AmmInfo::decode(&amm_fixture().data).unwrap();
+    let authority = amm.authority(&RAYDIUM_AMM_V4_PROGRAM_ID).unwrap();
+    assert_eq!(authority, pubkey(EXPECTED_AMM_AUTHORITY));
+}
+
+#[test]
+fn test_decode_market_fixture() {
+    let market = Pubkey::new_unique();
+    let (data, expected) = market_fixture(&market);
+
+    let keys = MarketKeys::decode(&market, &pubkey(OPENBOOK_V1_PROGRAM), &data).unwrap();
+    assert_eq!(keys, expected);
+}
+
+#[test]
+fn test_disabled_pool_is_rejected() {
+    let mut fixture = amm_fixture();
+    put_u64(&mut fixture.data, 0, 2); // Disabled
+
+    let adapter = RaydiumAdapter::default();
+    assert!(adapter.decode_pool(&pubkey(RAYDIUM_SOL_USDC), &fixture.data).is_err());
+}
+
+#[test]
+fn test_reserves_are_vault_balances_minus_pending_pnl() {
+    let fixture = amm_fixture();
+    let adapter = RaydiumAdapter::default();
+    let mut pool = adapter.decode_pool(&pubkey(RAYDIUM_SOL_USDC), &fixture.data).unwrap();
+
+    let dependents = adapter.dependent_accounts(&pool);
+    assert_eq!(dependents, vec![pubkey(EXPECTED_BASE_VAULT), pubkey(EXPECTED_QUOTE_VAULT), fixture.market]);
+
+    let (market_data, _) = market_fixture(&fixture.market);
+    let mut accounts = HashMap::new();
+    accounts.insert(pubkey(EXPECTED_BASE_VAULT), token_account(&pubkey(EXPECTED_BASE_MINT), 50_000_000_000));
+    accounts.insert(pubkey(EXPECTED_QUOTE_VAULT), token_account(&pubkey(EXPECTED_QUOTE_MINT), 7_500_000_000));
+    accounts.insert(fixture.market, account(market_data, pubkey(OPENBOOK_V1_PROGRAM)));
+
+    adapter.apply_dependent_accounts(&mut pool, &accounts).unwrap();
+
+    assert_eq!(pool.reserve_a, 50_000_000_000 - 1_000);
+    assert_eq!(pool.reserve_b, 7_500_000_000 - 2_000);
+    assert_eq!(pool.fee_bps, 25);
+
+    // Exact quote: fee rounded up from the input, output rounded down
+    // 1 SOL in: fee = 2_500_000, in after fee = 997_500_000
+    let out = adapter.quote_exact_in(&pool, 1_000_000_000, true).unwrap();
+    let expected = (pool.reserve_b as u128 * 997_500_000 / (pool.reserve_a as u128 + 997_500_000)) as u64;
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_swap_instruction_has_18_accounts() {
+    let fixture = amm_fixture();
+    let adapter = RaydiumAdapter::default();
+    let mut pool = adapter.decode_pool(&pubkey(RAYDIUM_SOL_USDC), &fixture.data).unwrap();
+
+    let (market_data, market_keys) = market_fixture(&fixture.market);
+    let mut accounts = HashMap::new();
+    accounts.insert(pubkey(EXPECTED_BASE_VAULT), token_account(&pubkey(EXPECTED_BASE_MINT), 1_000_000));
+    accounts.insert(pubkey(EXPECTED_QUOTE_VAULT), token_account(&pubkey(EXPECTED_QUOTE_MINT), 1_000_000));
+    accounts.insert(fixture.market, account(market_data, pubkey(OPENBOOK_V1_PROGRAM)));
+    adapter.apply_dependent_accounts(&mut pool, &accounts).unwrap();
+    assert!(matches!(pool.venue, VenueState::Raydium(_)));
+
+    let params = SwapParams {
+        user: Pubkey::new_unique(),
+        user_source: Pubkey::new_unique(),
+        user_destination: Pubkey::new_unique(),
+        amount_in: 1_000,
+        minimum_out: 900,
+        a_to_b: true,
+    };
+    let ix = adapter.build_swap_ix(&pool, &params).unwrap();
+
+    assert_eq!(ix.program_id, RAYDIUM_AMM_V4_PROGRAM_ID);
+    assert_eq!(ix.data[0], 9);
+    assert_eq!(&ix.data[1..9], &1_000u64.to_le_bytes());
+    assert_eq!(&ix.data[9..17], &900u64.to_le_bytes());
+
+    let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(keys, vec![
+        spl_token::id(),
+        pubkey(RAYDIUM_SOL_USDC),
+        pubkey(EXPECTED_AMM_AUTHORITY),
+        fixture.open_orders,
+        fixture.target_orders,
+        pubkey(EXPECTED_BASE_VAULT),
+        pubkey(EXPECTED_QUOTE_VAULT),
+        pubkey(OPENBOOK_V1_PROGRAM),
+        fixture.market,
+        market_keys.bids,
+        market_keys.asks,
+        market_keys.event_queue,
+        market_keys.coin_vault,
+        market_keys.pc_vault,
+        market_keys.vault_signer,
+        params.user_source,
+        params.user_destination,
+        params.user,
+    ]);
+
+    // Only the user wallet signs
+    let signers: Vec<Pubkey> = ix.accounts.iter().filter(|meta| meta.is_signer).map(|meta| meta.pubkey).collect();
+    assert_eq!(signers, vec![params.user]);
+}
 
 #[tokio::test]
 #[serial]
@@ -108,5 +351,14 @@ async fn test_find_raydium_offsets() -> Result<()> {
         println!("❌ LP Mint NOT FOUND");
     }
     
+    // The decoder must agree with the live account
+    let amm = AmmInfo::decode(&data)?;
+    assert_eq!(amm.coin_vault, expected_base_vault);
+    assert_eq!(amm.pc_vault, expected_quote_vault);
+    assert_eq!(amm.coin_mint, expected_base_mint);
+    assert_eq!(amm.pc_mint, expected_quote_mint);
+    assert_eq!(amm.lp_mint, expected_lp_mint);
+    assert_eq!(amm.authority(&RAYDIUM_AMM_V4_PROGRAM_ID)?, pubkey(EXPECTED_AMM_AUTHORITY));
+    
     Ok(())
 }